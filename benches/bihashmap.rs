@@ -0,0 +1,178 @@
+//! Benchmarks comparing `BiHashMap` against the previous implementation, which
+//! kept every value behind an `Rc` shared by two `HashMap`s.
+
+use bimap::BiHashMap;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+const SIZE: u64 = 10_000;
+
+/// A minimal copy of the previous `Rc`-based `BiHashMap`, kept as a baseline.
+mod baseline {
+    use std::{collections::HashMap, hash::Hash, rc::Rc};
+
+    pub struct RcBiHashMap<L, R> {
+        left2right: HashMap<Rc<L>, Rc<R>>,
+        right2left: HashMap<Rc<R>, Rc<L>>,
+    }
+
+    impl<L, R> RcBiHashMap<L, R>
+    where
+        L: Eq + Hash,
+        R: Eq + Hash,
+    {
+        pub fn new() -> Self {
+            Self {
+                left2right: HashMap::new(),
+                right2left: HashMap::new(),
+            }
+        }
+
+        pub fn get_by_left(&self, left: &L) -> Option<&R> {
+            self.left2right.get(left).map(|right| &**right)
+        }
+
+        pub fn get_by_right(&self, right: &R) -> Option<&L> {
+            self.right2left.get(right).map(|left| &**left)
+        }
+
+        pub fn remove_by_left(&mut self, left: &L) -> Option<(L, R)> {
+            self.left2right.remove(left).map(|right_rc| {
+                let left_rc = self.right2left.remove(&right_rc).unwrap();
+                (
+                    Rc::try_unwrap(left_rc).ok().unwrap(),
+                    Rc::try_unwrap(right_rc).ok().unwrap(),
+                )
+            })
+        }
+
+        pub fn insert(&mut self, left: L, right: R) {
+            self.remove_by_left(&left);
+            if let Some(left_rc) = self.right2left.remove(&right) {
+                self.left2right.remove(&left_rc);
+            }
+            let left_rc = Rc::new(left);
+            let right_rc = Rc::new(right);
+            self.left2right.insert(left_rc.clone(), right_rc.clone());
+            self.right2left.insert(right_rc, left_rc);
+        }
+
+        pub fn iter(&self) -> impl Iterator<Item = (&L, &R)> {
+            self.left2right.iter().map(|(l, r)| (&**l, &**r))
+        }
+    }
+
+    // like the previous implementation, cloning copies the values and rehashes
+    // them instead of sharing them between the clones
+    impl<L, R> Clone for RcBiHashMap<L, R>
+    where
+        L: Clone + Eq + Hash,
+        R: Clone + Eq + Hash,
+    {
+        fn clone(&self) -> Self {
+            let mut map = Self {
+                left2right: HashMap::with_capacity(self.left2right.capacity()),
+                right2left: HashMap::with_capacity(self.right2left.capacity()),
+            };
+            for (left, right) in self.iter() {
+                map.insert(left.clone(), right.clone());
+            }
+            map
+        }
+    }
+}
+
+use baseline::RcBiHashMap;
+
+fn indexed() -> BiHashMap<u64, String> {
+    (0..SIZE).map(|i| (i, i.to_string())).collect()
+}
+
+fn rc() -> RcBiHashMap<u64, String> {
+    let mut map = RcBiHashMap::new();
+    for i in 0..SIZE {
+        map.insert(i, i.to_string());
+    }
+    map
+}
+
+fn insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert");
+    group.bench_function("indexed", |b| b.iter(|| black_box(indexed())));
+    group.bench_function("rc", |b| b.iter(|| black_box(rc())));
+    group.finish();
+}
+
+fn lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lookup");
+    let map = indexed();
+    group.bench_function("indexed", |b| {
+        b.iter(|| {
+            for i in 0..SIZE {
+                let right = map.get_by_left(&i).unwrap();
+                black_box(map.get_by_right(right));
+            }
+        })
+    });
+    let map = rc();
+    group.bench_function("rc", |b| {
+        b.iter(|| {
+            for i in 0..SIZE {
+                let right = map.get_by_left(&i).unwrap();
+                black_box(map.get_by_right(right));
+            }
+        })
+    });
+    group.finish();
+}
+
+fn remove(c: &mut Criterion) {
+    let mut group = c.benchmark_group("remove");
+    group.bench_function("indexed", |b| {
+        b.iter_batched(
+            indexed,
+            |mut map| {
+                for i in 0..SIZE {
+                    black_box(map.remove_by_left(&i));
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("rc", |b| {
+        b.iter_batched(
+            rc,
+            |mut map| {
+                for i in 0..SIZE {
+                    black_box(map.remove_by_left(&i));
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn iteration(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iteration");
+    let map = indexed();
+    group.bench_function("indexed", |b| {
+        b.iter(|| map.iter().map(|(l, r)| l + r.len() as u64).sum::<u64>())
+    });
+    let map = rc();
+    group.bench_function("rc", |b| {
+        b.iter(|| map.iter().map(|(l, r)| l + r.len() as u64).sum::<u64>())
+    });
+    group.finish();
+}
+
+fn clone(c: &mut Criterion) {
+    let mut group = c.benchmark_group("clone");
+    let map = indexed();
+    group.bench_function("indexed", |b| b.iter(|| black_box(map.clone())));
+    let map = rc();
+    group.bench_function("rc", |b| b.iter(|| black_box(map.clone())));
+    group.finish();
+}
+
+criterion_group!(benches, insert, lookup, remove, iteration, clone);
+criterion_main!(benches);
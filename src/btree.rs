@@ -1,26 +1,45 @@
 //! A bimap backed by two `BTreeMap`s.
 
-use crate::Overwritten;
+#[cfg(feature = "rayon")]
+mod par;
+
+#[cfg(feature = "rayon")]
+pub use self::par::{IntoParIter, ParIter, ParLeftValues, ParRightValues};
+
+use crate::{
+    compare::{Compare, Natural},
+    debug_assert_committed,
+    mem::{Ref, WeakRef, Wrapper},
+    BatchConflict, BatchInsertError, Collision, InsertError, Overwritten, Reinsert, Undo,
+};
 use alloc::{
     collections::{btree_map, BTreeMap},
-    rc::Rc,
+    vec::Vec,
 };
 use core::{
+    borrow::Borrow,
     cmp::Ordering,
     fmt,
     hash::{Hash, Hasher},
     iter::{Extend, FromIterator, FusedIterator},
-    ops::RangeBounds,
+    marker::PhantomData,
+    mem,
+    ops::{Bound, Deref, DerefMut, RangeBounds},
 };
 
 /// A bimap backed by two `BTreeMap`s.
 ///
+/// The left values are ordered by the comparator `LC` and the right values by
+/// the comparator `RC`. Both default to [`Natural`], which uses the values'
+/// `Ord` implementations; see the [`compare`] module for custom orderings.
+///
 /// See the [module-level documentation] for more details and examples.
 ///
 /// [module-level documentation]: crate
-pub struct BiBTreeMap<L, R> {
-    left2right: BTreeMap<Rc<L>, Rc<R>>,
-    right2left: BTreeMap<Rc<R>, Rc<L>>,
+/// [`compare`]: crate::compare
+pub struct BiBTreeMap<L, R, LC = Natural, RC = Natural> {
+    left2right: BTreeMap<Ref<L, LC>, Ref<R, RC>>,
+    right2left: BTreeMap<Ref<R, RC>, Ref<L, LC>>,
 }
 
 impl<L, R> BiBTreeMap<L, R>
@@ -28,7 +47,11 @@ where
     L: Ord,
     R: Ord,
 {
-    /// Creates an empty `BiBTreeMap`.
+    /// Creates an empty `BiBTreeMap` that orders both sides by their `Ord`
+    /// implementations.
+    ///
+    /// A bimap with [custom comparators] can be created with
+    /// `BiBTreeMap::default()`.
     ///
     /// # Examples
     ///
@@ -37,13 +60,21 @@ where
     ///
     /// let bimap = BiBTreeMap::<char, i32>::new();
     /// ```
+    ///
+    /// [custom comparators]: crate::compare
     pub fn new() -> Self {
         Self {
             left2right: BTreeMap::new(),
             right2left: BTreeMap::new(),
         }
     }
+}
 
+impl<L, R, LC, RC> BiBTreeMap<L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
     /// Returns the number of left-right pairs in the bimap.
     ///
     /// # Examples
@@ -99,6 +130,33 @@ where
         self.right2left.clear();
     }
 
+    /// Removes all pairs from the bimap and returns them in an iterator, in
+    /// ascending order by left value.
+    ///
+    /// The bimap is empty as soon as this method returns, even if the iterator
+    /// is dropped before it is fully consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('b', 1);
+    /// bimap.insert('a', 2);
+    ///
+    /// let pairs: Vec<_> = bimap.drain().collect();
+    /// assert_eq!(pairs, [('a', 2), ('b', 1)]);
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, L, R, LC, RC> {
+        self.right2left.clear();
+        Drain {
+            inner: mem::take(&mut self.left2right).into_iter(),
+            marker: PhantomData,
+        }
+    }
+
     /// Creates an iterator over the left-right pairs in the bimap in ascending
     /// order by left value.
     ///
@@ -118,7 +176,7 @@ where
     ///     println!("({}, {})", left, right);
     /// }
     /// ```
-    pub fn iter(&self) -> Iter<'_, L, R> {
+    pub fn iter(&self) -> Iter<'_, L, R, LC, RC> {
         Iter {
             inner: self.left2right.iter(),
         }
@@ -143,7 +201,7 @@ where
     ///     println!("{}", char_value);
     /// }
     /// ```
-    pub fn left_values(&self) -> LeftValues<'_, L, R> {
+    pub fn left_values(&self) -> LeftValues<'_, L, R, LC, RC> {
         LeftValues {
             inner: self.left2right.iter(),
         }
@@ -168,15 +226,91 @@ where
     ///     println!("{}", int_value);
     /// }
     /// ```
-    pub fn right_values(&self) -> RightValues<'_, L, R> {
+    pub fn right_values(&self) -> RightValues<'_, L, R, LC, RC> {
         RightValues {
             inner: self.right2left.iter(),
         }
     }
 
+    /// Returns the left-right pair with the smallest left value in the bimap,
+    /// or `None` if the bimap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(bimap.first_by_left(), None);
+    /// bimap.insert('a', 2);
+    /// bimap.insert('b', 1);
+    /// assert_eq!(bimap.first_by_left(), Some((&'a', &2)));
+    /// ```
+    pub fn first_by_left(&self) -> Option<(&L, &R)> {
+        self.left2right.first_key_value().map(|(l, r)| (&**l, &**r))
+    }
+
+    /// Returns the left-right pair with the smallest right value in the bimap,
+    /// or `None` if the bimap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(bimap.first_by_right(), None);
+    /// bimap.insert('a', 2);
+    /// bimap.insert('b', 1);
+    /// assert_eq!(bimap.first_by_right(), Some((&'b', &1)));
+    /// ```
+    pub fn first_by_right(&self) -> Option<(&L, &R)> {
+        self.right2left.first_key_value().map(|(r, l)| (&**l, &**r))
+    }
+
+    /// Returns the left-right pair with the largest left value in the bimap,
+    /// or `None` if the bimap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(bimap.last_by_left(), None);
+    /// bimap.insert('a', 2);
+    /// bimap.insert('b', 1);
+    /// assert_eq!(bimap.last_by_left(), Some((&'b', &1)));
+    /// ```
+    pub fn last_by_left(&self) -> Option<(&L, &R)> {
+        self.left2right.last_key_value().map(|(l, r)| (&**l, &**r))
+    }
+
+    /// Returns the left-right pair with the largest right value in the bimap,
+    /// or `None` if the bimap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(bimap.last_by_right(), None);
+    /// bimap.insert('a', 2);
+    /// bimap.insert('b', 1);
+    /// assert_eq!(bimap.last_by_right(), Some((&'a', &2)));
+    /// ```
+    pub fn last_by_right(&self) -> Option<(&L, &R)> {
+        self.right2left.last_key_value().map(|(r, l)| (&**l, &**r))
+    }
+
     /// Returns a reference to the right value corresponding to the given left
     /// value.
     ///
+    /// The left value may be any borrowed form of the bimap's left value
+    /// type, but the ordering on the borrowed form *must* match the ordering
+    /// on the left value type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -187,13 +321,22 @@ where
     /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
     /// assert_eq!(bimap.get_by_left(&'z'), None);
     /// ```
-    pub fn get_by_left(&self, left: &L) -> Option<&R> {
-        self.left2right.get(left).map(|l| &**l)
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.left2right.get(Wrapper::wrap(left)).map(|l| &**l)
     }
 
     /// Returns a reference to the left value corresponding to the given right
     /// value.
     ///
+    /// The right value may be any borrowed form of the bimap's right value
+    /// type, but the ordering on the borrowed form *must* match the ordering
+    /// on the right value type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -204,13 +347,22 @@ where
     /// assert_eq!(bimap.get_by_right(&1), Some(&'a'));
     /// assert_eq!(bimap.get_by_right(&2), None);
     /// ```
-    pub fn get_by_right(&self, right: &R) -> Option<&L> {
-        self.right2left.get(right).map(|r| &**r)
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.right2left.get(Wrapper::wrap(right)).map(|r| &**r)
     }
 
     /// Returns `true` if the bimap contains the given left value and `false`
     /// otherwise.
     ///
+    /// The left value may be any borrowed form of the bimap's left value
+    /// type, but the ordering on the borrowed form *must* match the ordering
+    /// on the left value type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -221,13 +373,22 @@ where
     /// assert!(bimap.contains_left(&'a'));
     /// assert!(!bimap.contains_left(&'b'));
     /// ```
-    pub fn contains_left(&self, left: &L) -> bool {
-        self.left2right.contains_key(left)
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.left2right.contains_key(Wrapper::wrap(left))
     }
 
     /// Returns `true` if the map contains the given right value and `false`
     /// otherwise.
     ///
+    /// The right value may be any borrowed form of the bimap's right value
+    /// type, but the ordering on the borrowed form *must* match the ordering
+    /// on the right value type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -238,8 +399,146 @@ where
     /// assert!(bimap.contains_right(&1));
     /// assert!(!bimap.contains_right(&2));
     /// ```
-    pub fn contains_right(&self, right: &R) -> bool {
-        self.right2left.contains_key(right)
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.right2left.contains_key(Wrapper::wrap(right))
+    }
+
+    /// Returns a guard granting mutable access to the right value corresponding
+    /// to the given left value, or `None` if the bimap doesn't contain it.
+    ///
+    /// Changes made through the guard only take effect when it is committed.
+    /// [`RightMut::commit`] overwrites any other pair with an equal right
+    /// value, just as [`insert`] and [`LeftEntry::and_modify`] would, while
+    /// [`RightMut::commit_no_overwrite`] rejects the change in that case and
+    /// returns the rejected right value, just as [`try_insert`] would. Dropping
+    /// the guard without committing it discards the change, and panics in debug
+    /// builds if the right value was changed.
+    ///
+    /// The original right value is cloned when the guard is created, even if
+    /// the change turns out not to collide, so that it can be restored.
+    ///
+    /// The pair is taken out of the bimap while the guard is alive, so leaking
+    /// the guard with [`mem::forget`] removes the pair from the bimap.
+    ///
+    /// The left value may be any borrowed form of the bimap's left value
+    /// type, but the ordering on the borrowed form *must* match the ordering
+    /// on the left value type.
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    /// [`try_insert`]: BiBTreeMap::try_insert
+    /// [`mem::forget`]: core::mem::forget
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let mut right = bimap.get_mut_by_left(&'a').unwrap();
+    /// *right = 3;
+    /// assert_eq!(right.commit(), None);
+    /// assert_eq!(bimap.get_by_right(&3), Some(&'a'));
+    ///
+    /// // 2 is taken by 'b', so the change is rejected
+    /// let mut right = bimap.get_mut_by_left(&'a').unwrap();
+    /// *right = 2;
+    /// assert_eq!(right.commit_no_overwrite(), Err(2));
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&3));
+    ///
+    /// // or overwrites the pair containing it
+    /// let mut right = bimap.get_mut_by_left(&'a').unwrap();
+    /// *right = 2;
+    /// assert_eq!(right.commit(), Some(('b', 2)));
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    pub fn get_mut_by_left<Q>(&mut self, left: &Q) -> Option<RightMut<'_, L, R, LC, RC>>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+        R: Clone,
+    {
+        let (left_rc, right_rc) = self.left2right.remove_entry(Wrapper::wrap(left))?;
+        self.right2left.remove(&right_rc);
+        // at this point we can safely unwrap because the other pointers are gone
+        let pair = (left_rc.into_inner(), right_rc.into_inner());
+        Some(RightMut {
+            map: self,
+            original: Some(pair.1.clone()),
+            pair: Some(pair),
+        })
+    }
+
+    /// Returns a guard granting mutable access to the left value corresponding
+    /// to the given right value, or `None` if the bimap doesn't contain it.
+    ///
+    /// Changes made through the guard only take effect when it is committed.
+    /// [`LeftMut::commit`] overwrites any other pair with an equal left value,
+    /// just as [`insert`] and [`RightEntry::and_modify`] would, while
+    /// [`LeftMut::commit_no_overwrite`] rejects the change in that case and
+    /// returns the rejected left value, just as [`try_insert`] would. Dropping
+    /// the guard without committing it discards the change, and panics in debug
+    /// builds if the left value was changed.
+    ///
+    /// The original left value is cloned when the guard is created, even if
+    /// the change turns out not to collide, so that it can be restored.
+    ///
+    /// The pair is taken out of the bimap while the guard is alive, so leaking
+    /// the guard with [`mem::forget`] removes the pair from the bimap.
+    ///
+    /// The right value may be any borrowed form of the bimap's right value
+    /// type, but the ordering on the borrowed form *must* match the ordering
+    /// on the right value type.
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    /// [`try_insert`]: BiBTreeMap::try_insert
+    /// [`mem::forget`]: core::mem::forget
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let mut left = bimap.get_mut_by_right(&1).unwrap();
+    /// *left = 'c';
+    /// assert_eq!(left.commit(), None);
+    /// assert_eq!(bimap.get_by_left(&'c'), Some(&1));
+    ///
+    /// // 'b' is taken by 2, so the change is rejected
+    /// let mut left = bimap.get_mut_by_right(&1).unwrap();
+    /// *left = 'b';
+    /// assert_eq!(left.commit_no_overwrite(), Err('b'));
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'c'));
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn get_mut_by_right<Q>(&mut self, right: &Q) -> Option<LeftMut<'_, L, R, LC, RC>>
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+        L: Clone,
+    {
+        let (right_rc, left_rc) = self.right2left.remove_entry(Wrapper::wrap(right))?;
+        self.left2right.remove(&left_rc);
+        // at this point we can safely unwrap because the other pointers are gone
+        let pair = (left_rc.into_inner(), right_rc.into_inner());
+        Some(LeftMut {
+            map: self,
+            original: Some(pair.0.clone()),
+            pair: Some(pair),
+        })
     }
 
     /// Removes the left-right pair corresponding to the given left value.
@@ -247,6 +546,10 @@ where
     /// Returns the previous left-right pair if the map contained the left value
     /// and `None` otherwise.
     ///
+    /// The left value may be any borrowed form of the bimap's left value
+    /// type, but the ordering on the borrowed form *must* match the ordering
+    /// on the left value type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -260,15 +563,17 @@ where
     /// assert_eq!(bimap.remove_by_left(&'b'), Some(('b', 2)));
     /// assert_eq!(bimap.remove_by_left(&'b'), None);
     /// ```
-    pub fn remove_by_left(&mut self, left: &L) -> Option<(L, R)> {
-        self.left2right.remove(left).map(|right_rc| {
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.left2right.remove(Wrapper::wrap(left)).map(|right_rc| {
             // unwrap is safe because we know right2left contains the key (it's a bimap)
             let left_rc = self.right2left.remove(&right_rc).unwrap();
             // at this point we can safely unwrap because the other pointers are gone
-            (
-                Rc::try_unwrap(left_rc).ok().unwrap(),
-                Rc::try_unwrap(right_rc).ok().unwrap(),
-            )
+            (left_rc.into_inner(), right_rc.into_inner())
         })
     }
 
@@ -277,6 +582,10 @@ where
     /// Returns the previous left-right pair if the map contained the right
     /// value and `None` otherwise.
     ///
+    /// The right value may be any borrowed form of the bimap's right value
+    /// type, but the ordering on the borrowed form *must* match the ordering
+    /// on the right value type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -290,15 +599,117 @@ where
     /// assert_eq!(bimap.remove_by_right(&2), Some(('b', 2)));
     /// assert_eq!(bimap.remove_by_right(&2), None);
     /// ```
-    pub fn remove_by_right(&mut self, right: &R) -> Option<(L, R)> {
-        self.right2left.remove(right).map(|left_rc| {
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.right2left.remove(Wrapper::wrap(right)).map(|left_rc| {
             // unwrap is safe because we know left2right contains the key (it's a bimap)
             let right_rc = self.left2right.remove(&left_rc).unwrap();
             // at this point we can safely unwrap because the other pointers are gone
-            (
-                Rc::try_unwrap(left_rc).ok().unwrap(),
-                Rc::try_unwrap(right_rc).ok().unwrap(),
-            )
+            (left_rc.into_inner(), right_rc.into_inner())
+        })
+    }
+
+    /// Removes the left-right pair with the smallest left value from the
+    /// bimap and returns it, or `None` if the bimap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 2);
+    /// bimap.insert('b', 1);
+    ///
+    /// assert_eq!(bimap.pop_first_by_left(), Some(('a', 2)));
+    /// assert_eq!(bimap.len(), 1);
+    /// assert!(!bimap.contains_right(&2));
+    /// ```
+    pub fn pop_first_by_left(&mut self) -> Option<(L, R)> {
+        self.left2right.pop_first().map(|(left_rc, right_rc)| {
+            // unwrap is safe because we know right2left contains the key (it's a bimap)
+            self.right2left.remove(&right_rc).unwrap();
+            // at this point we can safely unwrap because the other pointers are gone
+            (left_rc.into_inner(), right_rc.into_inner())
+        })
+    }
+
+    /// Removes the left-right pair with the smallest right value from the
+    /// bimap and returns it, or `None` if the bimap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 2);
+    /// bimap.insert('b', 1);
+    ///
+    /// assert_eq!(bimap.pop_first_by_right(), Some(('b', 1)));
+    /// assert_eq!(bimap.len(), 1);
+    /// assert!(!bimap.contains_left(&'b'));
+    /// ```
+    pub fn pop_first_by_right(&mut self) -> Option<(L, R)> {
+        self.right2left.pop_first().map(|(right_rc, left_rc)| {
+            // unwrap is safe because we know left2right contains the key (it's a bimap)
+            self.left2right.remove(&left_rc).unwrap();
+            // at this point we can safely unwrap because the other pointers are gone
+            (left_rc.into_inner(), right_rc.into_inner())
+        })
+    }
+
+    /// Removes the left-right pair with the largest left value from the
+    /// bimap and returns it, or `None` if the bimap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 2);
+    /// bimap.insert('b', 1);
+    ///
+    /// assert_eq!(bimap.pop_last_by_left(), Some(('b', 1)));
+    /// assert_eq!(bimap.len(), 1);
+    /// assert!(!bimap.contains_right(&1));
+    /// ```
+    pub fn pop_last_by_left(&mut self) -> Option<(L, R)> {
+        self.left2right.pop_last().map(|(left_rc, right_rc)| {
+            // unwrap is safe because we know right2left contains the key (it's a bimap)
+            self.right2left.remove(&right_rc).unwrap();
+            // at this point we can safely unwrap because the other pointers are gone
+            (left_rc.into_inner(), right_rc.into_inner())
+        })
+    }
+
+    /// Removes the left-right pair with the largest right value from the
+    /// bimap and returns it, or `None` if the bimap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 2);
+    /// bimap.insert('b', 1);
+    ///
+    /// assert_eq!(bimap.pop_last_by_right(), Some(('a', 2)));
+    /// assert_eq!(bimap.len(), 1);
+    /// assert!(!bimap.contains_left(&'a'));
+    /// ```
+    pub fn pop_last_by_right(&mut self) -> Option<(L, R)> {
+        self.right2left.pop_last().map(|(right_rc, left_rc)| {
+            // unwrap is safe because we know left2right contains the key (it's a bimap)
+            self.left2right.remove(&left_rc).unwrap();
+            // at this point we can safely unwrap because the other pointers are gone
+            (left_rc.into_inner(), right_rc.into_inner())
         })
     }
 
@@ -358,7 +769,7 @@ where
             (Some(l_pair), None) => {
                 // since remove_by_left() was called first, it's possible the right value was
                 // removed if a duplicate pair is being inserted
-                if l_pair.1 == right {
+                if RC::compare(&l_pair.1, &right) == Ordering::Equal {
                     Overwritten::Pair(l_pair.0, l_pair.1)
                 } else {
                     Overwritten::Left(l_pair.0, l_pair.1)
@@ -397,48 +808,176 @@ where
         }
     }
 
-    /// Inserts the given left-right pair into the bimap without checking if the
-    /// pair already exists.
-    fn insert_unchecked(&mut self, left: L, right: R) {
-        let left_rc = Rc::new(left);
-        let right_rc = Rc::new(right);
-        self.left2right.insert(left_rc.clone(), right_rc.clone());
-        self.right2left.insert(right_rc, left_rc);
+    /// Inserts the given left-right pair into the bimap without overwriting any
+    /// existing values, reporting which values collided on failure.
+    ///
+    /// Returns `Ok(())` if the pair was successfully inserted into the bimap.
+    /// Otherwise, the bimap is unchanged and an [`InsertError`] is returned
+    /// containing the attempted pair and the existing pairs it collided with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, Collision};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(bimap.try_insert('a', 1), Ok(()));
+    /// assert_eq!(bimap.try_insert('b', 2), Ok(()));
+    ///
+    /// let err = bimap.try_insert('a', 2).unwrap_err();
+    /// assert_eq!(err.pair, ('a', 2));
+    /// assert_eq!(err.collision, Collision::Both((&'a', &1), (&'b', &2)));
+    ///
+    /// let err = bimap.try_insert('c', 1).unwrap_err();
+    /// assert_eq!(err.collision, Collision::Right(&'a', &1));
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "failed to insert 'c' <> 1: the right value is already paired with 'a'"
+    /// );
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn try_insert(&mut self, left: L, right: R) -> Result<(), InsertError<'_, L, R>> {
+        if !self.contains_left(&left) && !self.contains_right(&right) {
+            self.insert_unchecked(left, right);
+            return Ok(());
+        }
+        let l_pair = self
+            .left2right
+            .get_key_value(Wrapper::wrap(&left))
+            .map(|(l, r)| (&**l, &**r));
+        let r_pair = self
+            .right2left
+            .get_key_value(Wrapper::wrap(&right))
+            .map(|(r, l)| (&**l, &**r));
+        Err(InsertError {
+            pair: (left, right),
+            collision: Collision::new(l_pair, r_pair),
+        })
     }
 
-    /// Creates an iterator over the left-right pairs lying within a range of
-    /// left values in the bimap in ascending order by left.
+    /// Inserts all left-right pairs of the given batch into the bimap, or none
+    /// of them if any pair would overwrite an existing value.
     ///
-    /// The iterator element type is `(&L, &R)`.
+    /// The whole batch is validated before the bimap is changed: a pair
+    /// conflicts if its left or right value already exists in the bimap, or if
+    /// an earlier pair of the batch has the same left or right value. On
+    /// failure, the bimap is unchanged and a [`BatchInsertError`] is returned
+    /// that hands back the pairs and lists every conflict.
     ///
     /// # Examples
     ///
     /// ```
-    /// use bimap::BiBTreeMap;
+    /// use bimap::{BatchConflict, BiBTreeMap};
     ///
     /// let mut bimap = BiBTreeMap::new();
     /// bimap.insert('a', 1);
-    /// bimap.insert('b', 2);
-    /// bimap.insert('c', 3);
-    /// bimap.insert('d', 4);
     ///
-    /// for (left, right) in bimap.left_range('b'..'d') {
-    ///     println!("({}, {})", left, right);
-    /// }
+    /// let err = bimap
+    ///     .insert_all_no_overwrite(vec![('b', 2), ('a', 3), ('c', 2)])
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     err.conflicts,
+    ///     [BatchConflict::Left(1), BatchConflict::DuplicateRight(0, 2)]
+    /// );
+    /// assert_eq!(err.pairs, [('b', 2), ('a', 3), ('c', 2)]);
+    /// assert_eq!(bimap.len(), 1);
+    ///
+    /// assert_eq!(bimap.insert_all_no_overwrite(vec![('b', 2), ('c', 3)]), Ok(()));
+    /// assert_eq!(bimap.len(), 3);
     /// ```
-    pub fn left_range<A>(&self, range: A) -> LeftRange<'_, L, R>
+    pub fn insert_all_no_overwrite<I>(&mut self, iter: I) -> Result<(), BatchInsertError<L, R>>
     where
-        A: RangeBounds<L>,
+        I: IntoIterator<Item = (L, R)>,
     {
-        LeftRange {
-            inner: self.left2right.range(range),
+        let pairs: Vec<(L, R)> = iter.into_iter().collect();
+        let mut conflicts = Vec::new();
+        // the index of the first pair of the batch with each left and right value
+        let mut batch_lefts = BTreeMap::new();
+        let mut batch_rights = BTreeMap::new();
+        for (index, (left, right)) in pairs.iter().enumerate() {
+            if self.contains_left(left) {
+                conflicts.push(BatchConflict::Left(index));
+            }
+            if self.contains_right(right) {
+                conflicts.push(BatchConflict::Right(index));
+            }
+            match batch_lefts.entry(Wrapper::<L, LC>::wrap(left)) {
+                btree_map::Entry::Occupied(entry) => {
+                    conflicts.push(BatchConflict::DuplicateLeft(*entry.get(), index));
+                }
+                btree_map::Entry::Vacant(entry) => {
+                    entry.insert(index);
+                }
+            }
+            match batch_rights.entry(Wrapper::<R, RC>::wrap(right)) {
+                btree_map::Entry::Occupied(entry) => {
+                    conflicts.push(BatchConflict::DuplicateRight(*entry.get(), index));
+                }
+                btree_map::Entry::Vacant(entry) => {
+                    entry.insert(index);
+                }
+            }
+        }
+        if !conflicts.is_empty() {
+            return Err(BatchInsertError { pairs, conflicts });
         }
+        for (left, right) in pairs {
+            self.insert_unchecked(left, right);
+        }
+        Ok(())
     }
 
-    /// Creates an iterator over the left-right pairs lying within a range of
-    /// right values in the bimap in ascending order by right.
+    /// Starts a transaction, which returns a guard that modifies the bimap and
+    /// records how to undo each modification.
     ///
-    /// The iterator element type is `(&L, &R)`.
+    /// The modifications are rolled back when the guard is dropped, unless
+    /// [`Transaction::commit`] is called first. Nested transactions can be
+    /// started with [`Transaction::savepoint`] to roll back only part of the
+    /// modifications.
+    ///
+    /// The transaction keeps a copy of every value it may have to restore, so
+    /// its modifying methods require `L: Clone` and `R: Clone`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, Overwritten};
+    ///
+    /// let mut names = BiBTreeMap::new();
+    /// names.insert("alice", 1);
+    /// names.insert("bob", 2);
+    ///
+    /// // rename alice to carol, then fail validation and abandon the batch
+    /// {
+    ///     let mut renames = names.transaction();
+    ///     assert_eq!(renames.remove_by_right(&1), Some(("alice", 1)));
+    ///     assert_eq!(renames.insert("carol", 2), Overwritten::Right("bob", 2));
+    ///     assert_eq!(renames.get_by_left(&"carol"), Some(&2));
+    /// }
+    /// assert_eq!(names.get_by_left(&"alice"), Some(&1));
+    /// assert_eq!(names.get_by_left(&"bob"), Some(&2));
+    /// assert!(!names.contains_left(&"carol"));
+    ///
+    /// // this time, keep the rename
+    /// let mut renames = names.transaction();
+    /// renames.remove_by_right(&1);
+    /// renames.insert("carol", 1);
+    /// renames.commit();
+    /// assert_eq!(names.get_by_right(&1), Some(&"carol"));
+    /// ```
+    pub fn transaction(&mut self) -> Transaction<'_, L, R, LC, RC> {
+        Transaction {
+            bimap: self,
+            log: Vec::new(),
+            parent: None,
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all left-right pairs `(l, r)` such that `f(&l,
+    /// &r)` returns `false`. Pairs are visited in ascending order by left
+    /// value. The bimap stays consistent even if the predicate panics.
     ///
     /// # Examples
     ///
@@ -449,381 +988,3210 @@ where
     /// bimap.insert('a', 1);
     /// bimap.insert('b', 2);
     /// bimap.insert('c', 3);
-    /// bimap.insert('d', 4);
-    ///
-    /// for (left, right) in bimap.right_range(2..4) {
-    ///     println!("({}, {})", left, right);
-    /// }
+    /// bimap.retain(|&l, &r| r >= 2);
+    /// assert_eq!(bimap.len(), 2);
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    /// assert_eq!(bimap.get_by_left(&'c'), Some(&3));
+    /// assert_eq!(bimap.get_by_left(&'a'), None);
     /// ```
-    pub fn right_range<A>(&self, range: A) -> RightRange<'_, L, R>
+    pub fn retain<F>(&mut self, f: F)
     where
-        A: RangeBounds<R>,
+        F: FnMut(&L, &R) -> bool,
     {
-        RightRange {
-            inner: self.right2left.range(range),
-        }
+        let mut f = f;
+        let right2left = &mut self.right2left;
+        self.left2right.retain(|left, right| {
+            // the right-to-left entry is only removed once `f` has returned,
+            // so a panic in `f` leaves both maps untouched
+            f(left, right) || {
+                right2left.remove(right);
+                false
+            }
+        });
+    }
+
+    /// Creates an iterator which uses a predicate to decide whether a
+    /// left-right pair should be removed, yielding the removed pairs.
+    ///
+    /// Pairs are visited in ascending order by left value. If the predicate
+    /// returns `true`, the pair is removed from the bimap and yielded,
+    /// otherwise it stays in the bimap. If the iterator is dropped or leaked
+    /// with [`mem::forget`] before it is fully consumed, the pairs it hasn't
+    /// visited yet are kept. The bimap stays consistent even if the predicate
+    /// panics.
+    ///
+    /// Each pair the iterator visits costs `O(log n)` time, so stopping early
+    /// doesn't pay for the rest of the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap: BiBTreeMap<_, _> = (0..8).map(|i| (i, i * 10)).collect();
+    ///
+    /// let evens: Vec<_> = bimap.extract_if(|&l, _| l % 2 == 0).collect();
+    /// assert_eq!(evens, [(0, 0), (2, 20), (4, 40), (6, 60)]);
+    /// assert_eq!(bimap.len(), 4);
+    /// assert_eq!(bimap.get_by_right(&30), Some(&3));
+    /// ```
+    ///
+    /// [`mem::forget`]: core::mem::forget
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, L, R, LC, RC, F>
+    where
+        F: FnMut(&L, &R) -> bool,
+    {
+        ExtractIf {
+            next: self.left2right.keys().next().map(Ref::downgrade),
+            left2right: &mut self.left2right,
+            right2left: &mut self.right2left,
+            pred,
+        }
+    }
+
+    /// Gets the entry corresponding to the given left value for in-place
+    /// manipulation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// assert_eq!(*bimap.entry_by_left('a').or_insert(5), 1);
+    /// assert_eq!(*bimap.entry_by_left('b').or_insert_with(|| 2), 2);
+    /// assert_eq!(bimap.get_by_right(&2), Some(&'b'));
+    /// ```
+    pub fn entry_by_left(&mut self, left: L) -> LeftEntry<'_, L, R, LC, RC> {
+        if self.contains_left(&left) {
+            LeftEntry::Occupied(OccupiedLeftEntry { map: self, left })
+        } else {
+            LeftEntry::Vacant(VacantLeftEntry { map: self, left })
+        }
+    }
+
+    /// Gets the entry corresponding to the given right value for in-place
+    /// manipulation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// assert_eq!(*bimap.entry_by_right(1).or_insert('z'), 'a');
+    /// assert_eq!(*bimap.entry_by_right(2).or_insert_with(|| 'b'), 'b');
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    /// ```
+    pub fn entry_by_right(&mut self, right: R) -> RightEntry<'_, L, R, LC, RC> {
+        if self.contains_right(&right) {
+            RightEntry::Occupied(OccupiedRightEntry { map: self, right })
+        } else {
+            RightEntry::Vacant(VacantRightEntry { map: self, right })
+        }
+    }
+
+    /// Inserts the given left-right pair into the bimap without checking if the
+    /// pair already exists.
+    fn insert_unchecked(&mut self, left: L, right: R) {
+        let left_rc = Ref::new(left);
+        let right_rc = Ref::new(right);
+        self.left2right.insert(left_rc.clone(), right_rc.clone());
+        self.right2left.insert(right_rc, left_rc);
+    }
+
+    /// Inserts the given left-right pair into the bimap without checking if the
+    /// pair already exists, and returns references to the inserted values.
+    fn insert_unchecked_ref(&mut self, left: L, right: R) -> (&L, &R) {
+        let left_rc = Ref::new(left);
+        let right_rc = Ref::new(right);
+        self.left2right.insert(left_rc.clone(), right_rc.clone());
+        self.right2left.insert(right_rc, left_rc.clone());
+        // unwrap is safe because the pair was just inserted
+        let (left_ref, right_ref) = self.left2right.get_key_value(&left_rc).unwrap();
+        (&**left_ref, &**right_ref)
+    }
+
+    /// Creates an iterator over the left-right pairs lying within a range of
+    /// left values in the bimap in ascending order by left.
+    ///
+    /// The iterator element type is `(&L, &R)`.
+    ///
+    /// The range may be given in any borrowed form of the bimap's left value
+    /// type, but the ordering on the borrowed form *must* match the ordering on
+    /// the left value type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.insert('d', 4);
+    ///
+    /// for (left, right) in bimap.left_range('b'..'d') {
+    ///     println!("({}, {})", left, right);
+    /// }
+    /// ```
+    pub fn left_range<Q, A>(&self, range: A) -> LeftRange<'_, L, R, LC, RC>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+        A: RangeBounds<Q>,
+    {
+        LeftRange {
+            inner: self
+                .left2right
+                .range::<Wrapper<Q, _>, _>(wrap_bounds(&range)),
+        }
+    }
+
+    /// Creates an iterator over the left-right pairs lying within a range of
+    /// right values in the bimap in ascending order by right.
+    ///
+    /// The iterator element type is `(&L, &R)`.
+    ///
+    /// The range may be given in any borrowed form of the bimap's right value
+    /// type, but the ordering on the borrowed form *must* match the ordering on
+    /// the right value type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.insert('d', 4);
+    ///
+    /// for (left, right) in bimap.right_range(2..4) {
+    ///     println!("({}, {})", left, right);
+    /// }
+    /// ```
+    pub fn right_range<Q, A>(&self, range: A) -> RightRange<'_, L, R, LC, RC>
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+        A: RangeBounds<Q>,
+    {
+        RightRange {
+            inner: self
+                .right2left
+                .range::<Wrapper<Q, _>, _>(wrap_bounds(&range)),
+        }
+    }
+
+    /// Removes the left-right pairs lying within a range of left values from
+    /// the bimap and returns them in a new bimap.
+    ///
+    /// The range may be given in any borrowed form of the bimap's left value
+    /// type, but the ordering on the borrowed form *must* match the ordering on
+    /// the left value type.
+    ///
+    /// Removing `m` of the bimap's `n` pairs takes `O(m log n)` time when `m`
+    /// is small compared with `n`, and `O(n)` time otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+    ///
+    /// let removed = bimap.remove_left_range(3..7);
+    /// assert_eq!(removed.len(), 4);
+    /// assert_eq!(removed.get_by_right(&30), Some(&3));
+    /// assert_eq!(bimap.len(), 6);
+    /// assert_eq!(bimap.get_by_right(&30), None);
+    /// ```
+    pub fn remove_left_range<Q, A>(&mut self, range: A) -> BiBTreeMap<L, R, LC, RC>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+        A: RangeBounds<Q>,
+    {
+        let left2right = split_off_range(&mut self.left2right, &range);
+        let right2left = extract_by_value(&mut self.right2left, &left2right, |left| {
+            range_contains::<_, LC, _>(&range, (**left).borrow())
+        });
+        BiBTreeMap {
+            left2right,
+            right2left,
+        }
+    }
+
+    /// Removes the left-right pairs lying within a range of right values from
+    /// the bimap and returns them in a new bimap.
+    ///
+    /// The range may be given in any borrowed form of the bimap's right value
+    /// type, but the ordering on the borrowed form *must* match the ordering on
+    /// the right value type.
+    ///
+    /// Removing `m` of the bimap's `n` pairs takes `O(m log n)` time when `m`
+    /// is small compared with `n`, and `O(n)` time otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+    ///
+    /// let removed = bimap.remove_right_range(..=20);
+    /// assert_eq!(removed.len(), 3);
+    /// assert_eq!(removed.get_by_left(&2), Some(&20));
+    /// assert_eq!(bimap.len(), 7);
+    /// assert_eq!(bimap.get_by_left(&2), None);
+    /// ```
+    pub fn remove_right_range<Q, A>(&mut self, range: A) -> BiBTreeMap<L, R, LC, RC>
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+        A: RangeBounds<Q>,
+    {
+        let right2left = split_off_range(&mut self.right2left, &range);
+        let left2right = extract_by_value(&mut self.left2right, &right2left, |right| {
+            range_contains::<_, RC, _>(&range, (**right).borrow())
+        });
+        BiBTreeMap {
+            left2right,
+            right2left,
+        }
+    }
+
+    /// Splits the bimap in two at the given left value. Returns everything
+    /// after the given left value, including the left value itself.
+    ///
+    /// The left value may be any borrowed form of the bimap's left value type,
+    /// but the ordering on the borrowed form *must* match the ordering on the
+    /// left value type.
+    ///
+    /// Removing `m` of the bimap's `n` pairs takes `O(m log n)` time when `m`
+    /// is small compared with `n`, and `O(n)` time otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 3);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 1);
+    ///
+    /// let split = bimap.split_off_left(&'b');
+    /// assert_eq!(bimap.len(), 1);
+    /// assert_eq!(bimap.get_by_right(&3), Some(&'a'));
+    /// assert_eq!(split.len(), 2);
+    /// assert_eq!(split.get_by_right(&1), Some(&'c'));
+    /// ```
+    pub fn split_off_left<Q>(&mut self, left: &Q) -> BiBTreeMap<L, R, LC, RC>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+    {
+        let left2right = self.left2right.split_off(Wrapper::wrap(left));
+        let right2left = extract_by_value(&mut self.right2left, &left2right, |other| {
+            LC::compare((**other).borrow(), left) != Ordering::Less
+        });
+        BiBTreeMap {
+            left2right,
+            right2left,
+        }
+    }
+
+    /// Splits the bimap in two at the given right value. Returns everything
+    /// after the given right value, including the right value itself.
+    ///
+    /// The right value may be any borrowed form of the bimap's right value
+    /// type, but the ordering on the borrowed form *must* match the ordering on
+    /// the right value type.
+    ///
+    /// Removing `m` of the bimap's `n` pairs takes `O(m log n)` time when `m`
+    /// is small compared with `n`, and `O(n)` time otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 3);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 1);
+    ///
+    /// let split = bimap.split_off_right(&2);
+    /// assert_eq!(bimap.len(), 1);
+    /// assert_eq!(bimap.get_by_left(&'c'), Some(&1));
+    /// assert_eq!(split.len(), 2);
+    /// assert_eq!(split.get_by_left(&'a'), Some(&3));
+    /// ```
+    pub fn split_off_right<Q>(&mut self, right: &Q) -> BiBTreeMap<L, R, LC, RC>
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+    {
+        let right2left = self.right2left.split_off(Wrapper::wrap(right));
+        let left2right = extract_by_value(&mut self.left2right, &right2left, |other| {
+            RC::compare((**other).borrow(), right) != Ordering::Less
+        });
+        BiBTreeMap {
+            left2right,
+            right2left,
+        }
+    }
+
+    /// Moves all pairs from `other` into `self`, leaving `other` empty.
+    ///
+    /// Pairs from `other` overwrite any conflicting pairs in `self`, exactly
+    /// as if they were inserted with [`insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut a = BiBTreeMap::new();
+    /// a.insert('a', 1);
+    /// a.insert('b', 2);
+    ///
+    /// let mut b = BiBTreeMap::new();
+    /// b.insert('b', 3);
+    /// b.insert('c', 4);
+    ///
+    /// a.append(&mut b);
+    /// assert!(b.is_empty());
+    /// assert_eq!(a.len(), 3);
+    /// assert_eq!(a.get_by_left(&'b'), Some(&3));
+    /// assert_eq!(a.get_by_right(&2), None);
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    pub fn append(&mut self, other: &mut BiBTreeMap<L, R, LC, RC>) {
+        for (left, right) in other.iter() {
+            self.remove_by_left(left);
+            self.remove_by_right(right);
+        }
+        self.left2right.append(&mut other.left2right);
+        self.right2left.append(&mut other.right2left);
+    }
+
+    /// Returns a view of the bimap with its left and right values swapped.
+    ///
+    /// The view shares the bimap's storage, so creating one is free.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let inverse = bimap.inverse();
+    /// assert_eq!(inverse.get_by_left(&1), Some(&'a'));
+    /// assert_eq!(inverse.get_by_right(&'b'), Some(&2));
+    /// ```
+    pub fn inverse(&self) -> Inverse<'_, L, R, LC, RC> {
+        Inverse { map: self }
+    }
+
+    /// Returns a mutable view of the bimap with its left and right values
+    /// swapped.
+    ///
+    /// The view shares the bimap's storage, so changes made through it are
+    /// visible in the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// bimap.inverse_mut().insert(2, 'b');
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    /// ```
+    pub fn inverse_mut(&mut self) -> InverseMut<'_, L, R, LC, RC> {
+        InverseMut { map: self }
+    }
+
+    /// Converts the bimap into its inverse, whose left values are this bimap's
+    /// right values and vice versa.
+    ///
+    /// No values are moved or compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let inverse: BiBTreeMap<i32, char> = bimap.into_inverse();
+    /// assert_eq!(inverse.get_by_left(&1), Some(&'a'));
+    /// assert_eq!(inverse.get_by_right(&'b'), Some(&2));
+    /// ```
+    pub fn into_inverse(self) -> BiBTreeMap<R, L, RC, LC> {
+        BiBTreeMap {
+            left2right: self.right2left,
+            right2left: self.left2right,
+        }
+    }
+}
+
+/// A bound on a borrowed value that can be used to query the internal maps.
+type WrappedBound<'a, Q, C> = Bound<&'a Wrapper<Q, C>>;
+
+/// Converts the bounds of a range of borrowed values into bounds that can be
+/// used to query the internal maps.
+fn wrap_bounds<Q, C, A>(range: &A) -> (WrappedBound<'_, Q, C>, WrappedBound<'_, Q, C>)
+where
+    Q: ?Sized,
+    A: RangeBounds<Q>,
+{
+    fn wrap_bound<Q: ?Sized, C>(bound: Bound<&Q>) -> WrappedBound<'_, Q, C> {
+        match bound {
+            Bound::Included(value) => Bound::Included(Wrapper::wrap(value)),
+            Bound::Excluded(value) => Bound::Excluded(Wrapper::wrap(value)),
+            Bound::Unbounded => Bound::Unbounded,
+        }
+    }
+    (
+        wrap_bound(range.start_bound()),
+        wrap_bound(range.end_bound()),
+    )
+}
+
+/// Returns `true` if a borrowed value lies within a range, comparing values
+/// with the comparator `C`.
+fn range_contains<Q, C, A>(range: &A, value: &Q) -> bool
+where
+    Q: ?Sized,
+    C: Compare<Q>,
+    A: RangeBounds<Q>,
+{
+    let after_start = match range.start_bound() {
+        Bound::Included(start) => C::compare(start, value) != Ordering::Greater,
+        Bound::Excluded(start) => C::compare(start, value) == Ordering::Less,
+        Bound::Unbounded => true,
+    };
+    let before_end = match range.end_bound() {
+        Bound::Included(end) => C::compare(value, end) != Ordering::Greater,
+        Bound::Excluded(end) => C::compare(value, end) == Ordering::Less,
+        Bound::Unbounded => true,
+    };
+    after_start && before_end
+}
+
+/// Removes the entries whose keys lie within a range of borrowed values from
+/// one of the internal maps and returns them in a new map.
+fn split_off_range<K, C, V, Q, A>(
+    map: &mut BTreeMap<Ref<K, C>, V>,
+    range: &A,
+) -> BTreeMap<Ref<K, C>, V>
+where
+    K: Borrow<Q>,
+    C: Compare<K> + Compare<Q>,
+    Q: ?Sized,
+    A: RangeBounds<Q>,
+{
+    let mut removed = match range.start_bound() {
+        Bound::Included(start) => map.split_off(Wrapper::wrap(start)),
+        Bound::Excluded(start) => {
+            let mut removed = map.split_off(Wrapper::wrap(start));
+            if let Some((key, value)) = removed.remove_entry(Wrapper::wrap(start)) {
+                map.insert(key, value);
+            }
+            removed
+        }
+        Bound::Unbounded => mem::take(map),
+    };
+    let mut after = match range.end_bound() {
+        Bound::Included(end) => {
+            let mut after = removed.split_off(Wrapper::wrap(end));
+            if let Some((key, value)) = after.remove_entry(Wrapper::wrap(end)) {
+                removed.insert(key, value);
+            }
+            after
+        }
+        Bound::Excluded(end) => removed.split_off(Wrapper::wrap(end)),
+        Bound::Unbounded => BTreeMap::new(),
+    };
+    map.append(&mut after);
+    removed
+}
+
+/// Removes the entries whose values satisfy a predicate from one of the
+/// internal maps and returns them in a new map.
+///
+/// `removed` holds the same pairs, already split off from the other internal
+/// map. When there are few of them, they are removed from `map` one at a time
+/// in `O(m log n)` time, otherwise `map` is rebuilt in `O(n)` time.
+fn extract_by_value<K, KC, V, VC, F>(
+    map: &mut BTreeMap<Ref<K, KC>, Ref<V, VC>>,
+    removed: &BTreeMap<Ref<V, VC>, Ref<K, KC>>,
+    pred: F,
+) -> BTreeMap<Ref<K, KC>, Ref<V, VC>>
+where
+    KC: Compare<K>,
+    F: Fn(&Ref<V, VC>) -> bool,
+{
+    let log = (usize::BITS - map.len().leading_zeros()) as usize;
+    if removed.len().saturating_mul(log) < map.len() {
+        return removed
+            .values()
+            .filter_map(|key| map.remove_entry(key))
+            .collect();
+    }
+    let mut extracted = Vec::new();
+    map.retain(|key, value| {
+        if pred(value) {
+            // the references held by the map are dropped once this returns
+            extracted.push((key.clone(), value.clone()));
+            false
+        } else {
+            true
+        }
+    });
+    // the entries are visited in order, so building the map is linear
+    extracted.into_iter().collect()
+}
+
+impl<L, R, LC, RC> Clone for BiBTreeMap<L, R, LC, RC>
+where
+    L: Clone,
+    LC: Compare<L>,
+    R: Clone,
+    RC: Compare<R>,
+{
+    fn clone(&self) -> BiBTreeMap<L, R, LC, RC> {
+        self.iter().map(|(l, r)| (l.clone(), r.clone())).collect()
+    }
+}
+
+impl<L, R, LC, RC> fmt::Debug for BiBTreeMap<L, R, LC, RC>
+where
+    L: fmt::Debug,
+    LC: Compare<L>,
+    R: fmt::Debug,
+    RC: Compare<R>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{")?;
+        for (i, (left, right)) in self.left2right.iter().enumerate() {
+            let comma = if i == 0 { "" } else { ", " };
+            write!(f, "{}{:?} <> {:?}", comma, left, right)?;
+        }
+        write!(f, "}}")?;
+        Ok(())
+    }
+}
+
+impl<L, R, LC, RC> Default for BiBTreeMap<L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    fn default() -> BiBTreeMap<L, R, LC, RC> {
+        BiBTreeMap {
+            left2right: BTreeMap::default(),
+            right2left: BTreeMap::default(),
+        }
+    }
+}
+
+impl<L, R, LC, RC> Eq for BiBTreeMap<L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+}
+
+impl<L, R, LC, RC> FromIterator<(L, R)> for BiBTreeMap<L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    fn from_iter<I>(iter: I) -> BiBTreeMap<L, R, LC, RC>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let mut bimap = BiBTreeMap::default();
+        for (left, right) in iter {
+            bimap.insert(left, right);
+        }
+        bimap
+    }
+}
+
+impl<'a, L, R, LC, RC> IntoIterator for &'a BiBTreeMap<L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R, LC, RC>;
+
+    fn into_iter(self) -> Iter<'a, L, R, LC, RC> {
+        self.iter()
+    }
+}
+
+impl<L, R, LC, RC> IntoIterator for BiBTreeMap<L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    type Item = (L, R);
+    type IntoIter = IntoIter<L, R, LC, RC>;
+
+    fn into_iter(self) -> IntoIter<L, R, LC, RC> {
+        IntoIter {
+            inner: self.left2right.into_iter(),
+        }
+    }
+}
+
+impl<L, R, LC, RC> Extend<(L, R)> for BiBTreeMap<L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(move |(l, r)| {
+            self.insert(l, r);
+        });
+    }
+}
+
+impl<L, R, LC, RC> Ord for BiBTreeMap<L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.left2right.cmp(&other.left2right)
+    }
+}
+
+impl<L, R, LC, RC> PartialEq for BiBTreeMap<L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.left2right == other.left2right
+    }
+}
+
+impl<L, R, LC, RC> PartialOrd for BiBTreeMap<L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<L, R> Hash for BiBTreeMap<L, R>
+where
+    L: Hash,
+    R: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.left2right.hash(state);
+    }
+}
+
+/// An owning iterator over the left-right pairs in a `BiBTreeMap`.
+pub struct IntoIter<L, R, LC = Natural, RC = Natural> {
+    inner: btree_map::IntoIter<Ref<L, LC>, Ref<R, RC>>,
+}
+
+impl<L, R, LC, RC> DoubleEndedIterator for IntoIter<L, R, LC, RC> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // unwraps are safe because right2left is gone
+        self.inner
+            .next_back()
+            .map(|(l, r)| (l.into_inner(), r.into_inner()))
+    }
+}
+
+impl<L, R, LC, RC> ExactSizeIterator for IntoIter<L, R, LC, RC> {}
+
+impl<L, R, LC, RC> FusedIterator for IntoIter<L, R, LC, RC> {}
+
+impl<L, R, LC, RC> Iterator for IntoIter<L, R, LC, RC> {
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // unwraps are safe because right2left is gone
+        self.inner
+            .next()
+            .map(|(l, r)| (l.into_inner(), r.into_inner()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the left-right pairs in a `BiBTreeMap`.
+///
+/// This struct is created by the [`iter`] method of `BiBTreeMap`.
+///
+/// [`iter`]: BiBTreeMap::iter
+pub struct Iter<'a, L, R, LC = Natural, RC = Natural> {
+    inner: btree_map::Iter<'a, Ref<L, LC>, Ref<R, RC>>,
+}
+
+impl<'a, L, R, LC, RC> DoubleEndedIterator for Iter<'a, L, R, LC, RC> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(l, r)| (&**l, &**r))
+    }
+}
+
+impl<'a, L, R, LC, RC> ExactSizeIterator for Iter<'a, L, R, LC, RC> {}
+
+impl<'a, L, R, LC, RC> FusedIterator for Iter<'a, L, R, LC, RC> {}
+
+impl<'a, L, R, LC, RC> Iterator for Iter<'a, L, R, LC, RC> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(l, r)| (&**l, &**r))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the left values in a `BiBTreeMap`.
+///
+/// This struct is created by the [`left_values`] method of `BiBTreeMap`.
+///
+/// [`left_values`]: BiBTreeMap::left_values
+pub struct LeftValues<'a, L, R, LC = Natural, RC = Natural> {
+    inner: btree_map::Iter<'a, Ref<L, LC>, Ref<R, RC>>,
+}
+
+impl<'a, L, R, LC, RC> DoubleEndedIterator for LeftValues<'a, L, R, LC, RC> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(l, _)| &**l)
+    }
+}
+
+impl<'a, L, R, LC, RC> ExactSizeIterator for LeftValues<'a, L, R, LC, RC> {}
+
+impl<'a, L, R, LC, RC> FusedIterator for LeftValues<'a, L, R, LC, RC> {}
+
+impl<'a, L, R, LC, RC> Iterator for LeftValues<'a, L, R, LC, RC> {
+    type Item = &'a L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(l, _)| &**l)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the right values in a `BiBTreeMap`.
+///
+/// This struct is created by the [`right_values`] method of `BiBTreeMap`.
+///
+/// [`right_values`]: BiBTreeMap::right_values
+pub struct RightValues<'a, L, R, LC = Natural, RC = Natural> {
+    inner: btree_map::Iter<'a, Ref<R, RC>, Ref<L, LC>>,
+}
+
+impl<'a, L, R, LC, RC> DoubleEndedIterator for RightValues<'a, L, R, LC, RC> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(r, _)| &**r)
+    }
+}
+
+impl<'a, L, R, LC, RC> ExactSizeIterator for RightValues<'a, L, R, LC, RC> {}
+
+impl<'a, L, R, LC, RC> FusedIterator for RightValues<'a, L, R, LC, RC> {}
+
+impl<'a, L, R, LC, RC> Iterator for RightValues<'a, L, R, LC, RC> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(r, _)| &**r)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A draining iterator over the left-right pairs in a `BiBTreeMap`.
+///
+/// This struct is created by the [`drain`] method of `BiBTreeMap`.
+///
+/// [`drain`]: BiBTreeMap::drain
+pub struct Drain<'a, L, R, LC = Natural, RC = Natural> {
+    inner: btree_map::IntoIter<Ref<L, LC>, Ref<R, RC>>,
+    marker: PhantomData<&'a mut BiBTreeMap<L, R, LC, RC>>,
+}
+
+impl<'a, L, R, LC, RC> DoubleEndedIterator for Drain<'a, L, R, LC, RC> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // unwraps are safe because right2left has been cleared
+        self.inner
+            .next_back()
+            .map(|(l, r)| (l.into_inner(), r.into_inner()))
+    }
+}
+
+impl<'a, L, R, LC, RC> ExactSizeIterator for Drain<'a, L, R, LC, RC> {}
+
+impl<'a, L, R, LC, RC> FusedIterator for Drain<'a, L, R, LC, RC> {}
+
+impl<'a, L, R, LC, RC> Iterator for Drain<'a, L, R, LC, RC> {
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // unwraps are safe because right2left has been cleared
+        self.inner
+            .next()
+            .map(|(l, r)| (l.into_inner(), r.into_inner()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator which uses a predicate to decide whether to remove a
+/// left-right pair from a `BiBTreeMap`.
+///
+/// This struct is created by the [`extract_if`] method of `BiBTreeMap`.
+///
+/// [`extract_if`]: BiBTreeMap::extract_if
+pub struct ExtractIf<'a, L, R, LC, RC, F> {
+    left2right: &'a mut BTreeMap<Ref<L, LC>, Ref<R, RC>>,
+    right2left: &'a mut BTreeMap<Ref<R, RC>, Ref<L, LC>>,
+    // the left value of the next pair to visit, which is only weakly
+    // referenced so that leaking the iterator doesn't leave it shared
+    next: Option<WeakRef<L, LC>>,
+    pred: F,
+}
+
+impl<'a, L, R, LC, RC, F> FusedIterator for ExtractIf<'a, L, R, LC, RC, F>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+    F: FnMut(&L, &R) -> bool,
+{
+}
+
+impl<'a, L, R, LC, RC, F> Iterator for ExtractIf<'a, L, R, LC, RC, F>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+    F: FnMut(&L, &R) -> bool,
+{
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        extract_next(
+            self.left2right,
+            self.right2left,
+            &mut self.next,
+            &mut self.pred,
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.left2right.len()))
+    }
+}
+
+/// Visits the pairs of `forward` from the one at `next` onwards, and removes
+/// the first pair matching `pred` from both maps.
+///
+/// `next` is moved to the pair after each visited pair before `pred` is
+/// called, so a panicking predicate leaves the maps unchanged. Each visited
+/// pair costs `O(log n)` time.
+fn extract_next<A, B, AC, BC, F>(
+    forward: &mut BTreeMap<Ref<A, AC>, Ref<B, BC>>,
+    backward: &mut BTreeMap<Ref<B, BC>, Ref<A, AC>>,
+    next: &mut Option<WeakRef<A, AC>>,
+    mut pred: F,
+) -> Option<(A, B)>
+where
+    AC: Compare<A>,
+    BC: Compare<B>,
+    F: FnMut(&A, &B) -> bool,
+{
+    loop {
+        // the pair at `next` is still in the map, since only the iterator
+        // holding `next` can remove it
+        let key = next.take()?.upgrade()?;
+        let matched = {
+            let mut range =
+                forward.range::<Ref<A, AC>, _>((Bound::Included(&key), Bound::Unbounded));
+            let (a, b) = range.next()?;
+            *next = range.next().map(|(a, _)| a.downgrade());
+            pred(a, b)
+        };
+        if matched {
+            let (a, b) = forward.remove_entry(&key)?;
+            drop(key);
+            backward.remove(&b);
+            return Some((a.into_inner(), b.into_inner()));
+        }
+    }
+}
+
+/// An iterator over a range of left-right pairs in a `BiBTreeMap`.
+///
+/// This struct is created by the [`left_range`] method of `BiBTreeMap`.
+///
+/// [`left_range`]: BiBTreeMap::left_range
+pub struct LeftRange<'a, L, R, LC = Natural, RC = Natural> {
+    inner: btree_map::Range<'a, Ref<L, LC>, Ref<R, RC>>,
+}
+
+impl<'a, L, R, LC, RC> fmt::Debug for LeftRange<'a, L, R, LC, RC>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LeftRange")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<'a, L, R, LC, RC> DoubleEndedIterator for LeftRange<'a, L, R, LC, RC> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(l, r)| (&**l, &**r))
+    }
+}
+
+impl<'a, L, R, LC, RC> ExactSizeIterator for LeftRange<'a, L, R, LC, RC> {}
+
+impl<'a, L, R, LC, RC> FusedIterator for LeftRange<'a, L, R, LC, RC> {}
+
+impl<'a, L, R, LC, RC> Iterator for LeftRange<'a, L, R, LC, RC> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(l, r)| (&**l, &**r))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over a range of left-right pairs in a `BiBTreeMap`.
+///
+/// This struct is created by the [`right_range`] method of `BiBTreeMap`.
+///
+/// [`right_range`]: BiBTreeMap::right_range
+pub struct RightRange<'a, L, R, LC = Natural, RC = Natural> {
+    inner: btree_map::Range<'a, Ref<R, RC>, Ref<L, LC>>,
+}
+
+impl<'a, L, R, LC, RC> fmt::Debug for RightRange<'a, L, R, LC, RC>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RightRange")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<'a, L, R, LC, RC> DoubleEndedIterator for RightRange<'a, L, R, LC, RC> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(r, l)| (&**l, &**r))
+    }
+}
+
+impl<'a, L, R, LC, RC> ExactSizeIterator for RightRange<'a, L, R, LC, RC> {}
+
+impl<'a, L, R, LC, RC> FusedIterator for RightRange<'a, L, R, LC, RC> {}
+
+impl<'a, L, R, LC, RC> Iterator for RightRange<'a, L, R, LC, RC> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(r, l)| (&**l, &**r))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A view into a single left value in a `BiBTreeMap`, which may either be
+/// vacant or occupied.
+///
+/// This enum is created by the [`entry_by_left`] method of `BiBTreeMap`.
+///
+/// [`entry_by_left`]: BiBTreeMap::entry_by_left
+pub enum LeftEntry<'a, L, R, LC = Natural, RC = Natural> {
+    /// An occupied entry.
+    Occupied(OccupiedLeftEntry<'a, L, R, LC, RC>),
+
+    /// A vacant entry.
+    Vacant(VacantLeftEntry<'a, L, R, LC, RC>),
+}
+
+impl<'a, L, R, LC, RC> LeftEntry<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    /// Returns a reference to this entry's left value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::<char, i32>::new();
+    /// assert_eq!(bimap.entry_by_left('a').key(), &'a');
+    /// ```
+    pub fn key(&self) -> &L {
+        match self {
+            LeftEntry::Occupied(entry) => entry.key(),
+            LeftEntry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Ensures the entry's left value is in the bimap by inserting the given
+    /// right value if the entry is vacant, and returns a reference to the
+    /// right value in the entry.
+    ///
+    /// If the entry is vacant and the right value already exists in the bimap,
+    /// its previous left-right pair is overwritten, as with [`insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(*bimap.entry_by_left('a').or_insert(1), 1);
+    /// assert_eq!(*bimap.entry_by_left('a').or_insert(2), 1);
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    pub fn or_insert(self, right: R) -> &'a R {
+        self.or_insert_with(|| right)
+    }
+
+    /// Ensures the entry's left value is in the bimap by inserting the result
+    /// of the given function if the entry is vacant, and returns a reference to
+    /// the right value in the entry.
+    ///
+    /// If the entry is vacant and the computed right value already exists in
+    /// the bimap, its previous left-right pair is overwritten, as with
+    /// [`insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(*bimap.entry_by_left('a').or_insert_with(|| 1), 1);
+    /// assert_eq!(*bimap.entry_by_left('a').or_insert_with(|| unreachable!()), 1);
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    pub fn or_insert_with<F>(self, default: F) -> &'a R
+    where
+        F: FnOnce() -> R,
+    {
+        match self {
+            LeftEntry::Occupied(entry) => entry.into_ref(),
+            LeftEntry::Vacant(entry) => entry.insert_ref(default()),
+        }
+    }
+
+    /// Provides in-place mutable access to the right value of an occupied
+    /// entry before any potential inserts into the bimap.
+    ///
+    /// The modified right value is re-indexed once `f` returns. If it is equal
+    /// to the right value of a different pair in the bimap, that pair is
+    /// overwritten, as with [`insert`].
+    ///
+    /// If `f` panics, the pair is inserted back into the bimap with whatever
+    /// changes `f` made to its right value before panicking.
+    ///
+    /// To reject a colliding right value instead, as [`try_insert`] rejects
+    /// colliding pairs, use [`get_mut_by_left`] and
+    /// [`RightMut::commit_no_overwrite`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// bimap.entry_by_left('a').and_modify(|r| *r += 10).or_insert(0);
+    /// bimap.entry_by_left('b').and_modify(|r| *r += 10).or_insert(0);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&11));
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&0));
+    /// assert_eq!(bimap.get_by_right(&1), None);
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    /// [`try_insert`]: BiBTreeMap::try_insert
+    /// [`get_mut_by_left`]: BiBTreeMap::get_mut_by_left
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut R),
+    {
+        match self {
+            LeftEntry::Occupied(mut entry) => {
+                entry.modify(f);
+                LeftEntry::Occupied(entry)
+            }
+            LeftEntry::Vacant(entry) => LeftEntry::Vacant(entry),
+        }
+    }
+}
+
+/// A view into an occupied left value in a `BiBTreeMap`.
+///
+/// This struct is part of the [`LeftEntry`] enum.
+pub struct OccupiedLeftEntry<'a, L, R, LC = Natural, RC = Natural> {
+    map: &'a mut BiBTreeMap<L, R, LC, RC>,
+    left: L,
+}
+
+impl<'a, L, R, LC, RC> OccupiedLeftEntry<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    /// Returns a reference to the left value stored in the bimap.
+    pub fn key(&self) -> &L {
+        // unwrap is safe because the entry is occupied
+        self.map
+            .left2right
+            .get_key_value(Wrapper::wrap(&self.left))
+            .unwrap()
+            .0
+    }
+
+    /// Returns a reference to the right value corresponding to the entry's
+    /// left value.
+    pub fn get(&self) -> &R {
+        // unwrap is safe because the entry is occupied
+        self.map.get_by_left(&self.left).unwrap()
+    }
+
+    /// Converts the entry into a reference to its right value with the
+    /// lifetime of the bimap.
+    pub fn into_ref(self) -> &'a R {
+        let map = self.map;
+        // unwrap is safe because the entry is occupied
+        map.get_by_left(&self.left).unwrap()
+    }
+
+    /// Removes the entry's left-right pair from the bimap and returns it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{btree::LeftEntry, BiBTreeMap};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let LeftEntry::Occupied(entry) = bimap.entry_by_left('a') {
+    ///     assert_eq!(entry.remove(), ('a', 1));
+    /// }
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn remove(self) -> (L, R) {
+        // unwrap is safe because the entry is occupied
+        self.map.remove_by_left(&self.left).unwrap()
+    }
+
+    /// Inserts the entry's left value with the given right value, replacing
+    /// the entry's previous left-right pair.
+    ///
+    /// The return value is the same as that of [`insert`], so it indicates any
+    /// other pair that was overwritten because it contained the right value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{btree::LeftEntry, BiBTreeMap, Overwritten};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// if let LeftEntry::Occupied(entry) = bimap.entry_by_left('a') {
+    ///     assert_eq!(entry.insert(2), Overwritten::Both(('a', 1), ('b', 2)));
+    /// }
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&2));
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    pub fn insert(self, right: R) -> Overwritten<L, R> {
+        self.map.insert(self.left, right)
+    }
+
+    fn modify<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut R),
+    {
+        // unwrap is safe because the entry is occupied
+        let pair = self.map.remove_by_left(&self.left).unwrap();
+        // the pair is inserted back when the guard is dropped, even if `f`
+        // panics
+        let mut guard = Reinsert::new(&mut *self.map, pair, |map, left, right| {
+            map.insert(left, right);
+        });
+        f(guard.pair_mut().1);
+    }
+}
+
+/// A view into a vacant left value in a `BiBTreeMap`.
+///
+/// This struct is part of the [`LeftEntry`] enum.
+pub struct VacantLeftEntry<'a, L, R, LC = Natural, RC = Natural> {
+    map: &'a mut BiBTreeMap<L, R, LC, RC>,
+    left: L,
+}
+
+impl<'a, L, R, LC, RC> VacantLeftEntry<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    /// Returns a reference to the left value that would be inserted.
+    pub fn key(&self) -> &L {
+        &self.left
+    }
+
+    /// Takes ownership of the left value that would be inserted.
+    pub fn into_key(self) -> L {
+        self.left
+    }
+
+    /// Returns the left-right pair that would be overwritten if the given right
+    /// value was inserted into this entry, or `None` if there is no collision.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{btree::LeftEntry, BiBTreeMap};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let LeftEntry::Vacant(entry) = bimap.entry_by_left('b') {
+    ///     assert_eq!(entry.collision(&1), Some((&'a', &1)));
+    ///     assert_eq!(entry.collision(&2), None);
+    /// }
+    /// ```
+    pub fn collision<Q>(&self, right: &Q) -> Option<(&L, &R)>
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.map
+            .right2left
+            .get_key_value(Wrapper::wrap(right))
+            .map(|(r, l)| (&**l, &**r))
+    }
+
+    /// Inserts the entry's left value with the given right value.
+    ///
+    /// The return value is the same as that of [`insert`]. Since the left value
+    /// is vacant, it is either `Overwritten::Neither` or
+    /// `Overwritten::Right`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{btree::LeftEntry, BiBTreeMap, Overwritten};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let LeftEntry::Vacant(entry) = bimap.entry_by_left('b') {
+    ///     assert_eq!(entry.insert(1), Overwritten::Right('a', 1));
+    /// }
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&1));
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    pub fn insert(self, right: R) -> Overwritten<L, R> {
+        self.map.insert(self.left, right)
+    }
+
+    /// Inserts the entry's left value with the given right value without
+    /// overwriting any existing pairs.
+    ///
+    /// Returns `Ok(())` if the pair was inserted. If the right value already
+    /// exists in the bimap, `Err((left, right))` is returned with the attempted
+    /// left-right pair and the bimap is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{btree::LeftEntry, BiBTreeMap};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let LeftEntry::Vacant(entry) = bimap.entry_by_left('b') {
+    ///     assert_eq!(entry.insert_no_overwrite(1), Err(('b', 1)));
+    /// }
+    /// ```
+    pub fn insert_no_overwrite(self, right: R) -> Result<(), (L, R)> {
+        self.map.insert_no_overwrite(self.left, right)
+    }
+
+    fn insert_ref(self, right: R) -> &'a R {
+        let map = self.map;
+        map.remove_by_right(&right);
+        map.insert_unchecked_ref(self.left, right).1
+    }
+}
+
+/// A view into a single right value in a `BiBTreeMap`, which may either be
+/// vacant or occupied.
+///
+/// This enum is created by the [`entry_by_right`] method of `BiBTreeMap`.
+///
+/// [`entry_by_right`]: BiBTreeMap::entry_by_right
+pub enum RightEntry<'a, L, R, LC = Natural, RC = Natural> {
+    /// An occupied entry.
+    Occupied(OccupiedRightEntry<'a, L, R, LC, RC>),
+
+    /// A vacant entry.
+    Vacant(VacantRightEntry<'a, L, R, LC, RC>),
+}
+
+impl<'a, L, R, LC, RC> RightEntry<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    /// Returns a reference to this entry's right value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::<char, i32>::new();
+    /// assert_eq!(bimap.entry_by_right(1).key(), &1);
+    /// ```
+    pub fn key(&self) -> &R {
+        match self {
+            RightEntry::Occupied(entry) => entry.key(),
+            RightEntry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Ensures the entry's right value is in the bimap by inserting the given
+    /// left value if the entry is vacant, and returns a reference to the left
+    /// value in the entry.
+    ///
+    /// If the entry is vacant and the left value already exists in the bimap,
+    /// its previous left-right pair is overwritten, as with [`insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(*bimap.entry_by_right(1).or_insert('a'), 'a');
+    /// assert_eq!(*bimap.entry_by_right(1).or_insert('b'), 'a');
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    pub fn or_insert(self, left: L) -> &'a L {
+        self.or_insert_with(|| left)
+    }
+
+    /// Ensures the entry's right value is in the bimap by inserting the result
+    /// of the given function if the entry is vacant, and returns a reference to
+    /// the left value in the entry.
+    ///
+    /// If the entry is vacant and the computed left value already exists in the
+    /// bimap, its previous left-right pair is overwritten, as with [`insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(*bimap.entry_by_right(1).or_insert_with(|| 'a'), 'a');
+    /// assert_eq!(*bimap.entry_by_right(1).or_insert_with(|| unreachable!()), 'a');
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    pub fn or_insert_with<F>(self, default: F) -> &'a L
+    where
+        F: FnOnce() -> L,
+    {
+        match self {
+            RightEntry::Occupied(entry) => entry.into_ref(),
+            RightEntry::Vacant(entry) => entry.insert_ref(default()),
+        }
+    }
+
+    /// Provides in-place mutable access to the left value of an occupied entry
+    /// before any potential inserts into the bimap.
+    ///
+    /// The modified left value is re-indexed once `f` returns. If it is equal
+    /// to the left value of a different pair in the bimap, that pair is
+    /// overwritten, as with [`insert`].
+    ///
+    /// If `f` panics, the pair is inserted back into the bimap with whatever
+    /// changes `f` made to its left value before panicking.
+    ///
+    /// To reject a colliding left value instead, as [`try_insert`] rejects
+    /// colliding pairs, use [`get_mut_by_right`] and
+    /// [`LeftMut::commit_no_overwrite`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert(1, 'a');
+    ///
+    /// bimap.entry_by_right('a').and_modify(|l| *l += 10).or_insert(0);
+    /// bimap.entry_by_right('b').and_modify(|l| *l += 10).or_insert(0);
+    /// assert_eq!(bimap.get_by_right(&'a'), Some(&11));
+    /// assert_eq!(bimap.get_by_right(&'b'), Some(&0));
+    /// assert_eq!(bimap.get_by_left(&1), None);
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    /// [`try_insert`]: BiBTreeMap::try_insert
+    /// [`get_mut_by_right`]: BiBTreeMap::get_mut_by_right
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut L),
+    {
+        match self {
+            RightEntry::Occupied(mut entry) => {
+                entry.modify(f);
+                RightEntry::Occupied(entry)
+            }
+            RightEntry::Vacant(entry) => RightEntry::Vacant(entry),
+        }
+    }
+}
+
+/// A view into an occupied right value in a `BiBTreeMap`.
+///
+/// This struct is part of the [`RightEntry`] enum.
+pub struct OccupiedRightEntry<'a, L, R, LC = Natural, RC = Natural> {
+    map: &'a mut BiBTreeMap<L, R, LC, RC>,
+    right: R,
+}
+
+impl<'a, L, R, LC, RC> OccupiedRightEntry<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    /// Returns a reference to the right value stored in the bimap.
+    pub fn key(&self) -> &R {
+        // unwrap is safe because the entry is occupied
+        self.map
+            .right2left
+            .get_key_value(Wrapper::wrap(&self.right))
+            .unwrap()
+            .0
+    }
+
+    /// Returns a reference to the left value corresponding to the entry's
+    /// right value.
+    pub fn get(&self) -> &L {
+        // unwrap is safe because the entry is occupied
+        self.map.get_by_right(&self.right).unwrap()
+    }
+
+    /// Converts the entry into a reference to its left value with the lifetime
+    /// of the bimap.
+    pub fn into_ref(self) -> &'a L {
+        let map = self.map;
+        // unwrap is safe because the entry is occupied
+        map.get_by_right(&self.right).unwrap()
+    }
+
+    /// Removes the entry's left-right pair from the bimap and returns it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{btree::RightEntry, BiBTreeMap};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let RightEntry::Occupied(entry) = bimap.entry_by_right(1) {
+    ///     assert_eq!(entry.remove(), ('a', 1));
+    /// }
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn remove(self) -> (L, R) {
+        // unwrap is safe because the entry is occupied
+        self.map.remove_by_right(&self.right).unwrap()
+    }
+
+    /// Inserts the entry's right value with the given left value, replacing
+    /// the entry's previous left-right pair.
+    ///
+    /// The return value is the same as that of [`insert`], so it indicates any
+    /// other pair that was overwritten because it contained the left value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{btree::RightEntry, BiBTreeMap, Overwritten};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// if let RightEntry::Occupied(entry) = bimap.entry_by_right(1) {
+    ///     assert_eq!(entry.insert('b'), Overwritten::Both(('b', 2), ('a', 1)));
+    /// }
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'b'));
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    pub fn insert(self, left: L) -> Overwritten<L, R> {
+        self.map.insert(left, self.right)
+    }
+
+    fn modify<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut L),
+    {
+        // unwrap is safe because the entry is occupied
+        let pair = self.map.remove_by_right(&self.right).unwrap();
+        // the pair is inserted back when the guard is dropped, even if `f`
+        // panics
+        let mut guard = Reinsert::new(&mut *self.map, pair, |map, left, right| {
+            map.insert(left, right);
+        });
+        f(guard.pair_mut().0);
+    }
+}
+
+/// A view into a vacant right value in a `BiBTreeMap`.
+///
+/// This struct is part of the [`RightEntry`] enum.
+pub struct VacantRightEntry<'a, L, R, LC = Natural, RC = Natural> {
+    map: &'a mut BiBTreeMap<L, R, LC, RC>,
+    right: R,
+}
+
+impl<'a, L, R, LC, RC> VacantRightEntry<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    /// Returns a reference to the right value that would be inserted.
+    pub fn key(&self) -> &R {
+        &self.right
+    }
+
+    /// Takes ownership of the right value that would be inserted.
+    pub fn into_key(self) -> R {
+        self.right
+    }
+
+    /// Returns the left-right pair that would be overwritten if the given left
+    /// value was inserted into this entry, or `None` if there is no collision.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{btree::RightEntry, BiBTreeMap};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let RightEntry::Vacant(entry) = bimap.entry_by_right(2) {
+    ///     assert_eq!(entry.collision(&'a'), Some((&'a', &1)));
+    ///     assert_eq!(entry.collision(&'b'), None);
+    /// }
+    /// ```
+    pub fn collision<Q>(&self, left: &Q) -> Option<(&L, &R)>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.map
+            .left2right
+            .get_key_value(Wrapper::wrap(left))
+            .map(|(l, r)| (&**l, &**r))
+    }
+
+    /// Inserts the entry's right value with the given left value.
+    ///
+    /// The return value is the same as that of [`insert`]. Since the right
+    /// value is vacant, it is either `Overwritten::Neither` or
+    /// `Overwritten::Left`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{btree::RightEntry, BiBTreeMap, Overwritten};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let RightEntry::Vacant(entry) = bimap.entry_by_right(2) {
+    ///     assert_eq!(entry.insert('a'), Overwritten::Left('a', 1));
+    /// }
+    /// assert_eq!(bimap.get_by_right(&2), Some(&'a'));
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    pub fn insert(self, left: L) -> Overwritten<L, R> {
+        self.map.insert(left, self.right)
+    }
+
+    /// Inserts the entry's right value with the given left value without
+    /// overwriting any existing pairs.
+    ///
+    /// Returns `Ok(())` if the pair was inserted. If the left value already
+    /// exists in the bimap, `Err((left, right))` is returned with the attempted
+    /// left-right pair and the bimap is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{btree::RightEntry, BiBTreeMap};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let RightEntry::Vacant(entry) = bimap.entry_by_right(2) {
+    ///     assert_eq!(entry.insert_no_overwrite('a'), Err(('a', 2)));
+    /// }
+    /// ```
+    pub fn insert_no_overwrite(self, left: L) -> Result<(), (L, R)> {
+        self.map.insert_no_overwrite(left, self.right)
+    }
+
+    fn insert_ref(self, left: L) -> &'a L {
+        let map = self.map;
+        map.remove_by_left(&left);
+        map.insert_unchecked_ref(left, self.right).0
+    }
+}
+
+/// A guard granting mutable access to a right value in a `BiBTreeMap`.
+///
+/// This struct is created by the [`get_mut_by_left`] method of `BiBTreeMap`.
+/// Changes are only applied when the guard is committed, and discarded when it
+/// is dropped; see [`get_mut_by_left`] for how collisions are handled.
+///
+/// [`get_mut_by_left`]: BiBTreeMap::get_mut_by_left
+#[must_use = "changes made through the guard are discarded unless it is committed"]
+pub struct RightMut<'a, L, R, LC = Natural, RC = Natural>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    map: &'a mut BiBTreeMap<L, R, LC, RC>,
+    // only taken by `finish`
+    pair: Option<(L, R)>,
+    // the right value when the guard was created, only taken by `finish`
+    original: Option<R>,
+}
+
+impl<'a, L, R, LC, RC> RightMut<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    /// Returns a reference to the left value of the pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_mut_by_left(&'a').unwrap().left(), &'a');
+    /// ```
+    pub fn left(&self) -> &L {
+        // unwrap is safe because the pair is only taken when the guard is consumed
+        &self.pair.as_ref().unwrap().0
+    }
+
+    /// Re-inserts the pair, removing any other pair with an equal right value
+    /// from the bimap.
+    ///
+    /// Returns the removed pair, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let mut right = bimap.get_mut_by_left(&'a').unwrap();
+    /// *right = 3;
+    /// assert_eq!(right.commit(), None);
+    ///
+    /// let mut right = bimap.get_mut_by_left(&'a').unwrap();
+    /// *right = 2;
+    /// assert_eq!(right.commit(), Some(('b', 2)));
+    /// assert_eq!(bimap.get_by_right(&2), Some(&'a'));
+    /// ```
+    pub fn commit(mut self) -> Option<(L, R)> {
+        // overwriting never fails
+        self.finish(true).ok().flatten()
+    }
+
+    /// Re-inserts the pair unless another pair has an equal right value.
+    ///
+    /// Returns `Ok(())` if the pair was re-inserted. Otherwise, the pair is
+    /// re-inserted with its original right value and the rejected one is
+    /// returned as `Err(right)`, so neither pair is affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let mut right = bimap.get_mut_by_left(&'a').unwrap();
+    /// *right = 2;
+    /// assert_eq!(right.commit_no_overwrite(), Err(2));
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.get_by_right(&2), Some(&'b'));
+    /// ```
+    pub fn commit_no_overwrite(mut self) -> Result<(), R> {
+        self.finish(false).map(|_| ())
+    }
+
+    /// Re-inserts the pair. If another pair has an equal right value, that
+    /// pair is removed and returned if `overwrite` is `true`, and the pair is
+    /// re-inserted with its original right value otherwise, returning the
+    /// rejected one.
+    fn finish(&mut self, overwrite: bool) -> Result<Option<(L, R)>, R> {
+        let (left, mut right) = match self.pair.take() {
+            Some(pair) => pair,
+            None => return Ok(None),
+        };
+        // unwrap is safe because the original value is taken with the pair
+        let original = self.original.take().unwrap();
+        if overwrite {
+            let removed = self.map.remove_by_right(&right);
+            self.map.insert_unchecked(left, right);
+            Ok(removed)
+        } else if self.map.contains_right(&right) {
+            let rejected = mem::replace(&mut right, original);
+            self.map.insert_unchecked(left, right);
+            Err(rejected)
+        } else {
+            self.map.insert_unchecked(left, right);
+            Ok(None)
+        }
+    }
+}
+
+impl<'a, L, R, LC, RC> fmt::Debug for RightMut<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    R: fmt::Debug,
+    RC: Compare<R>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<'a, L, R, LC, RC> Deref for RightMut<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        // unwrap is safe because the pair is only taken when the guard is consumed
+        &self.pair.as_ref().unwrap().1
+    }
+}
+
+impl<'a, L, R, LC, RC> DerefMut for RightMut<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    fn deref_mut(&mut self) -> &mut R {
+        // unwrap is safe because the pair is only taken when the guard is consumed
+        &mut self.pair.as_mut().unwrap().1
+    }
+}
+
+impl<'a, L, R, LC, RC> Drop for RightMut<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    fn drop(&mut self) {
+        // uncommitted changes are discarded
+        if let Some((left, right)) = self.pair.take() {
+            // unwrap is safe because the original value is taken with the pair
+            let original = self.original.take().unwrap();
+            let changed = RC::compare(&right, &original) != Ordering::Equal;
+            self.map.insert_unchecked(left, original);
+            debug_assert_committed(changed, "RightMut");
+        }
+    }
+}
+
+/// A guard granting mutable access to a left value in a `BiBTreeMap`.
+///
+/// This struct is created by the [`get_mut_by_right`] method of `BiBTreeMap`.
+/// Changes are only applied when the guard is committed, and discarded when it
+/// is dropped; see [`get_mut_by_right`] for how collisions are handled.
+///
+/// [`get_mut_by_right`]: BiBTreeMap::get_mut_by_right
+#[must_use = "changes made through the guard are discarded unless it is committed"]
+pub struct LeftMut<'a, L, R, LC = Natural, RC = Natural>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    map: &'a mut BiBTreeMap<L, R, LC, RC>,
+    // only taken by `finish`
+    pair: Option<(L, R)>,
+    // the left value when the guard was created, only taken by `finish`
+    original: Option<L>,
+}
+
+impl<'a, L, R, LC, RC> LeftMut<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    /// Returns a reference to the right value of the pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_mut_by_right(&1).unwrap().right(), &1);
+    /// ```
+    pub fn right(&self) -> &R {
+        // unwrap is safe because the pair is only taken when the guard is consumed
+        &self.pair.as_ref().unwrap().1
+    }
+
+    /// Re-inserts the pair, removing any other pair with an equal left value
+    /// from the bimap.
+    ///
+    /// Returns the removed pair, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let mut left = bimap.get_mut_by_right(&1).unwrap();
+    /// *left = 'b';
+    /// assert_eq!(left.commit(), Some(('b', 2)));
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&1));
+    /// ```
+    pub fn commit(mut self) -> Option<(L, R)> {
+        // overwriting never fails
+        self.finish(true).ok().flatten()
+    }
+
+    /// Re-inserts the pair unless another pair has an equal left value.
+    ///
+    /// Returns `Ok(())` if the pair was re-inserted. Otherwise, the pair is
+    /// re-inserted with its original left value and the rejected one is
+    /// returned as `Err(left)`, so neither pair is affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let mut left = bimap.get_mut_by_right(&1).unwrap();
+    /// *left = 'b';
+    /// assert_eq!(left.commit_no_overwrite(), Err('b'));
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+    ///
+    /// let mut left = bimap.get_mut_by_right(&1).unwrap();
+    /// *left = 'c';
+    /// assert_eq!(left.commit_no_overwrite(), Ok(()));
+    /// assert_eq!(bimap.get_by_left(&'c'), Some(&1));
+    /// ```
+    pub fn commit_no_overwrite(mut self) -> Result<(), L> {
+        self.finish(false).map(|_| ())
+    }
+
+    /// Re-inserts the pair. If another pair has an equal left value, that
+    /// pair is removed and returned if `overwrite` is `true`, and the pair is
+    /// re-inserted with its original left value otherwise, returning the
+    /// rejected one.
+    fn finish(&mut self, overwrite: bool) -> Result<Option<(L, R)>, L> {
+        let (mut left, right) = match self.pair.take() {
+            Some(pair) => pair,
+            None => return Ok(None),
+        };
+        // unwrap is safe because the original value is taken with the pair
+        let original = self.original.take().unwrap();
+        if overwrite {
+            let removed = self.map.remove_by_left(&left);
+            self.map.insert_unchecked(left, right);
+            Ok(removed)
+        } else if self.map.contains_left(&left) {
+            let rejected = mem::replace(&mut left, original);
+            self.map.insert_unchecked(left, right);
+            Err(rejected)
+        } else {
+            self.map.insert_unchecked(left, right);
+            Ok(None)
+        }
+    }
+}
+
+impl<'a, L, R, LC, RC> fmt::Debug for LeftMut<'a, L, R, LC, RC>
+where
+    L: fmt::Debug,
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<'a, L, R, LC, RC> Deref for LeftMut<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    type Target = L;
+
+    fn deref(&self) -> &L {
+        // unwrap is safe because the pair is only taken when the guard is consumed
+        &self.pair.as_ref().unwrap().0
+    }
+}
+
+impl<'a, L, R, LC, RC> DerefMut for LeftMut<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    fn deref_mut(&mut self) -> &mut L {
+        // unwrap is safe because the pair is only taken when the guard is consumed
+        &mut self.pair.as_mut().unwrap().0
+    }
+}
+
+impl<'a, L, R, LC, RC> Drop for LeftMut<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    fn drop(&mut self) {
+        // uncommitted changes are discarded
+        if let Some((left, right)) = self.pair.take() {
+            // unwrap is safe because the original value is taken with the pair
+            let original = self.original.take().unwrap();
+            let changed = LC::compare(&left, &original) != Ordering::Equal;
+            self.map.insert_unchecked(original, right);
+            debug_assert_committed(changed, "LeftMut");
+        }
+    }
+}
+
+/// A transaction on a `BiBTreeMap`, which rolls back its modifications when it
+/// is dropped unless it is committed.
+///
+/// Its modifying methods return the same owned values as the corresponding
+/// methods of `BiBTreeMap`. To be able to undo them, the transaction keeps a
+/// copy of the inserted left values and of the overwritten and removed pairs,
+/// so they require `L: Clone`, and `R: Clone` for the methods that may
+/// overwrite or remove pairs.
+///
+/// This struct is created by the [`transaction`] method of `BiBTreeMap`, and by
+/// the [`savepoint`] method of a transaction. It dereferences to the bimap, so
+/// the bimap can be read as usual while the transaction is in progress.
+///
+/// [`transaction`]: BiBTreeMap::transaction
+/// [`savepoint`]: Transaction::savepoint
+pub struct Transaction<'a, L, R, LC = Natural, RC = Natural>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    bimap: &'a mut BiBTreeMap<L, R, LC, RC>,
+    // the modifications made by this transaction, in order
+    log: Vec<Undo<L, R>>,
+    // the log of the enclosing transaction, if this is a savepoint
+    parent: Option<&'a mut Vec<Undo<L, R>>>,
+}
+
+impl<'a, L, R, LC, RC> Transaction<'a, L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    /// Inserts the given left-right pair into the bimap like
+    /// [`BiBTreeMap::insert`], and returns the overwritten pairs, if any.
+    ///
+    /// A copy of the left value and of the overwritten pairs is kept to undo
+    /// the insertion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, Overwritten};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut transaction = bimap.transaction();
+    /// assert_eq!(transaction.insert('b', 2), Overwritten::Neither);
+    /// assert_eq!(transaction.insert('a', 3), Overwritten::Left('a', 1));
+    /// assert_eq!(transaction.insert('b', 3), Overwritten::Both(('b', 2), ('a', 3)));
+    /// drop(transaction);
+    ///
+    /// assert_eq!(bimap.len(), 1);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        let overwritten = self.bimap.insert(left.clone(), right);
+        self.log.push(match overwritten {
+            Overwritten::Neither => Undo::Inserted(left),
+            ref overwritten => Undo::Overwritten(overwritten.clone()),
+        });
+        overwritten
+    }
+
+    /// Inserts the given left-right pair into the bimap like
+    /// [`BiBTreeMap::insert_no_overwrite`], failing if either value is already
+    /// in the bimap.
+    ///
+    /// A copy of the left value is kept to undo the insertion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut transaction = bimap.transaction();
+    /// assert_eq!(transaction.insert_no_overwrite('a', 2), Err(('a', 2)));
+    /// assert_eq!(transaction.insert_no_overwrite('b', 2), Ok(()));
+    /// transaction.commit();
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)>
+    where
+        L: Clone,
+    {
+        self.bimap.insert_no_overwrite(left.clone(), right)?;
+        self.log.push(Undo::Inserted(left));
+        Ok(())
+    }
+
+    /// Removes the left-right pair corresponding to the given left value like
+    /// [`BiBTreeMap::remove_by_left`], and returns it.
+    ///
+    /// A copy of the pair is kept to undo the removal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut transaction = bimap.transaction();
+    /// assert_eq!(transaction.remove_by_left(&'a'), Some(('a', 1)));
+    /// assert_eq!(transaction.remove_by_left(&'a'), None);
+    /// assert!(transaction.is_empty());
+    /// drop(transaction);
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+    {
+        let (left, right) = self.bimap.remove_by_left(left)?;
+        self.log.push(Undo::Removed(left.clone(), right.clone()));
+        Some((left, right))
     }
-}
 
-impl<L, R> Clone for BiBTreeMap<L, R>
-where
-    L: Clone + Ord,
-    R: Clone + Ord,
-{
-    fn clone(&self) -> BiBTreeMap<L, R> {
-        self.iter().map(|(l, r)| (l.clone(), r.clone())).collect()
+    /// Removes the left-right pair corresponding to the given right value
+    /// like [`BiBTreeMap::remove_by_right`], and returns it.
+    ///
+    /// A copy of the pair is kept to undo the removal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut transaction = bimap.transaction();
+    /// assert_eq!(transaction.remove_by_right(&1), Some(('a', 1)));
+    /// assert_eq!(transaction.remove_by_right(&1), None);
+    /// assert!(transaction.is_empty());
+    /// drop(transaction);
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+    {
+        let (left, right) = self.bimap.remove_by_right(right)?;
+        self.log.push(Undo::Removed(left.clone(), right.clone()));
+        Some((left, right))
+    }
+
+    /// Starts a nested transaction.
+    ///
+    /// Rolling back the nested transaction only undoes its own modifications.
+    /// Committing it hands them over to this transaction, which can still
+    /// roll them back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// let mut transaction = bimap.transaction();
+    /// transaction.insert('a', 1);
+    ///
+    /// let mut savepoint = transaction.savepoint();
+    /// savepoint.insert('b', 2);
+    /// savepoint.rollback();
+    ///
+    /// let mut savepoint = transaction.savepoint();
+    /// savepoint.insert('c', 3);
+    /// savepoint.commit();
+    ///
+    /// transaction.commit();
+    /// assert_eq!(bimap.len(), 2);
+    /// assert!(!bimap.contains_left(&'b'));
+    /// ```
+    pub fn savepoint(&mut self) -> Transaction<'_, L, R, LC, RC> {
+        Transaction {
+            bimap: self.bimap,
+            log: Vec::new(),
+            parent: Some(&mut self.log),
+        }
+    }
+
+    /// Keeps the modifications made by the transaction.
+    ///
+    /// If this is a nested transaction, its modifications are handed over to
+    /// the enclosing transaction instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// let mut transaction = bimap.transaction();
+    /// transaction.insert('a', 1);
+    /// transaction.commit();
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn commit(mut self) {
+        match self.parent.take() {
+            Some(parent) => parent.append(&mut self.log),
+            None => self.log.clear(),
+        }
     }
+
+    /// Undoes the modifications made by the transaction, which is the same
+    /// as dropping it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut transaction = bimap.transaction();
+    /// transaction.remove_by_left(&'a');
+    /// transaction.rollback();
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn rollback(self) {}
 }
 
-impl<L, R> fmt::Debug for BiBTreeMap<L, R>
+impl<'a, L, R, LC, RC> Deref for Transaction<'a, L, R, LC, RC>
 where
-    L: fmt::Debug + Ord,
-    R: fmt::Debug + Ord,
+    LC: Compare<L>,
+    RC: Compare<R>,
 {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{{")?;
-        for (i, (left, right)) in self.left2right.iter().enumerate() {
-            let comma = if i == 0 { "" } else { ", " };
-            write!(f, "{}{:?} <> {:?}", comma, left, right)?;
-        }
-        write!(f, "}}")?;
-        Ok(())
+    type Target = BiBTreeMap<L, R, LC, RC>;
+
+    fn deref(&self) -> &BiBTreeMap<L, R, LC, RC> {
+        self.bimap
     }
 }
 
-impl<L, R> Default for BiBTreeMap<L, R>
+impl<'a, L, R, LC, RC> Drop for Transaction<'a, L, R, LC, RC>
 where
-    L: Ord,
-    R: Ord,
+    LC: Compare<L>,
+    RC: Compare<R>,
 {
-    fn default() -> BiBTreeMap<L, R> {
-        BiBTreeMap {
-            left2right: BTreeMap::default(),
-            right2left: BTreeMap::default(),
+    fn drop(&mut self) {
+        while let Some(undo) = self.log.pop() {
+            undo.undo(
+                self.bimap,
+                |bimap, left, right| {
+                    bimap.insert(left, right);
+                },
+                |bimap, left| {
+                    bimap.remove_by_left(left);
+                },
+            );
         }
     }
 }
 
-impl<L, R> Eq for BiBTreeMap<L, R>
-where
-    L: Ord,
-    R: Ord,
-{
+/// A view of a `BiBTreeMap<L, R, LC, RC>` as a bimap from `R` to `L`.
+///
+/// This struct is created by the [`inverse`] method of `BiBTreeMap`. Its left
+/// values are the right values of the underlying bimap and vice versa.
+///
+/// The view offers the read-only methods of `BiBTreeMap` with the sides
+/// swapped.
+///
+/// [`inverse`]: BiBTreeMap::inverse
+pub struct Inverse<'a, L, R, LC = Natural, RC = Natural> {
+    map: &'a BiBTreeMap<L, R, LC, RC>,
 }
 
-impl<L, R> FromIterator<(L, R)> for BiBTreeMap<L, R>
+impl<'a, L, R, LC, RC> Inverse<'a, L, R, LC, RC>
 where
-    L: Ord,
-    R: Ord,
+    LC: Compare<L>,
+    RC: Compare<R>,
 {
-    fn from_iter<I>(iter: I) -> BiBTreeMap<L, R>
+    /// Returns the bimap this view was created from, with its left and right
+    /// values in their original orientation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let inverse = bimap.inverse();
+    /// assert_eq!(inverse.get_by_left(&1), Some(&'a'));
+    /// assert_eq!(inverse.as_map().get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn as_map(&self) -> &'a BiBTreeMap<L, R, LC, RC> {
+        self.map
+    }
+
+    /// Returns the number of left-right pairs in the bimap.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Creates an iterator over the left-right pairs in the inverse bimap in
+    /// ascending order by left value.
+    ///
+    /// The iterator element type is `(&R, &L)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let pairs: Vec<_> = bimap.inverse().iter().collect();
+    /// assert_eq!(pairs, [(&1, &'a')]);
+    /// ```
+    pub fn iter(&self) -> Iter<'a, R, L, RC, LC> {
+        Iter {
+            inner: self.map.right2left.iter(),
+        }
+    }
+
+    /// Creates an iterator over the left values in the inverse bimap, which are
+    /// the right values of the underlying bimap, in ascending order.
+    pub fn left_values(&self) -> LeftValues<'a, R, L, RC, LC> {
+        LeftValues {
+            inner: self.map.right2left.iter(),
+        }
+    }
+
+    /// Creates an iterator over the right values in the inverse bimap, which
+    /// are the left values of the underlying bimap, in ascending order.
+    pub fn right_values(&self) -> RightValues<'a, R, L, RC, LC> {
+        RightValues {
+            inner: self.map.left2right.iter(),
+        }
+    }
+
+    /// Returns the left-right pair with the smallest left value in the inverse
+    /// bimap, or `None` if the bimap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 2);
+    /// bimap.insert('b', 1);
+    /// assert_eq!(bimap.inverse().first_by_left(), Some((&1, &'b')));
+    /// ```
+    pub fn first_by_left(&self) -> Option<(&'a R, &'a L)> {
+        self.map.first_by_right().map(|(l, r)| (r, l))
+    }
+
+    /// Returns the left-right pair with the smallest right value in the
+    /// inverse bimap, or `None` if the bimap is empty.
+    pub fn first_by_right(&self) -> Option<(&'a R, &'a L)> {
+        self.map.first_by_left().map(|(l, r)| (r, l))
+    }
+
+    /// Returns the left-right pair with the largest left value in the inverse
+    /// bimap, or `None` if the bimap is empty.
+    pub fn last_by_left(&self) -> Option<(&'a R, &'a L)> {
+        self.map.last_by_right().map(|(l, r)| (r, l))
+    }
+
+    /// Returns the left-right pair with the largest right value in the inverse
+    /// bimap, or `None` if the bimap is empty.
+    pub fn last_by_right(&self) -> Option<(&'a R, &'a L)> {
+        self.map.last_by_left().map(|(l, r)| (r, l))
+    }
+
+    /// Creates an iterator over the left-right pairs lying within a range of
+    /// left values in the inverse bimap in ascending order by left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    ///
+    /// let pairs: Vec<_> = bimap.inverse().left_range(2..).collect();
+    /// assert_eq!(pairs, [(&2, &'b'), (&3, &'c')]);
+    /// ```
+    pub fn left_range<Q, A>(&self, range: A) -> LeftRange<'a, R, L, RC, LC>
     where
-        I: IntoIterator<Item = (L, R)>,
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+        A: RangeBounds<Q>,
     {
-        let mut bimap = BiBTreeMap::new();
-        for (left, right) in iter {
-            bimap.insert(left, right);
+        LeftRange {
+            inner: self
+                .map
+                .right2left
+                .range::<Wrapper<Q, _>, _>(wrap_bounds(&range)),
         }
-        bimap
     }
-}
 
-impl<'a, L, R> IntoIterator for &'a BiBTreeMap<L, R>
-where
-    L: Ord,
-    R: Ord,
-{
-    type Item = (&'a L, &'a R);
-    type IntoIter = Iter<'a, L, R>;
+    /// Creates an iterator over the left-right pairs lying within a range of
+    /// right values in the inverse bimap in ascending order by right.
+    pub fn right_range<Q, A>(&self, range: A) -> RightRange<'a, R, L, RC, LC>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+        A: RangeBounds<Q>,
+    {
+        RightRange {
+            inner: self
+                .map
+                .left2right
+                .range::<Wrapper<Q, _>, _>(wrap_bounds(&range)),
+        }
+    }
 
-    fn into_iter(self) -> Iter<'a, L, R> {
-        self.iter()
+    /// Returns a reference to the right value corresponding to the given left
+    /// value of the inverse bimap.
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&'a L>
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.map.get_by_right(left)
     }
-}
 
-impl<L, R> IntoIterator for BiBTreeMap<L, R>
-where
-    L: Ord,
-    R: Ord,
-{
-    type Item = (L, R);
-    type IntoIter = IntoIter<L, R>;
+    /// Returns a reference to the left value corresponding to the given right
+    /// value of the inverse bimap.
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&'a R>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.map.get_by_left(right)
+    }
 
-    fn into_iter(self) -> IntoIter<L, R> {
-        IntoIter {
-            inner: self.left2right.into_iter(),
-        }
+    /// Returns `true` if the inverse bimap contains the given left value and
+    /// `false` otherwise.
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.map.contains_right(left)
+    }
+
+    /// Returns `true` if the inverse bimap contains the given right value and
+    /// `false` otherwise.
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.map.contains_left(right)
     }
 }
 
-impl<L, R> Extend<(L, R)> for BiBTreeMap<L, R>
-where
-    L: Ord,
-    R: Ord,
-{
-    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
-        iter.into_iter().for_each(move |(l, r)| {
-            self.insert(l, r);
-        });
+impl<'a, L, R, LC, RC> Clone for Inverse<'a, L, R, LC, RC> {
+    fn clone(&self) -> Self {
+        *self
     }
 }
 
-impl<L, R> Ord for BiBTreeMap<L, R>
+impl<'a, L, R, LC, RC> Copy for Inverse<'a, L, R, LC, RC> {}
+
+impl<'a, L, R, LC, RC> fmt::Debug for Inverse<'a, L, R, LC, RC>
 where
-    L: Ord,
-    R: Ord,
+    L: fmt::Debug,
+    R: fmt::Debug,
 {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.left2right.cmp(&other.left2right)
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{")?;
+        for (i, (left, right)) in self.map.right2left.iter().enumerate() {
+            let comma = if i == 0 { "" } else { ", " };
+            write!(f, "{}{:?} <> {:?}", comma, left, right)?;
+        }
+        write!(f, "}}")?;
+        Ok(())
     }
 }
 
-impl<L, R> PartialEq for BiBTreeMap<L, R>
+impl<'a, L, R, LC, RC> IntoIterator for Inverse<'a, L, R, LC, RC>
 where
-    L: Ord,
-    R: Ord,
+    LC: Compare<L>,
+    RC: Compare<R>,
 {
-    fn eq(&self, other: &Self) -> bool {
-        self.left2right == other.left2right
+    type Item = (&'a R, &'a L);
+    type IntoIter = Iter<'a, R, L, RC, LC>;
+
+    fn into_iter(self) -> Iter<'a, R, L, RC, LC> {
+        self.iter()
     }
 }
 
-impl<L, R> PartialOrd for BiBTreeMap<L, R>
+/// A mutable view of a `BiBTreeMap<L, R, LC, RC>` as a bimap from `R` to `L`.
+///
+/// This struct is created by the [`inverse_mut`] method of `BiBTreeMap`. Its
+/// left values are the right values of the underlying bimap and vice versa.
+///
+/// The view offers the methods of `BiBTreeMap` with the sides swapped, except
+/// for [`transaction`], which is only available on the underlying bimap
+/// through [`as_map_mut`]. The entries and guards it returns are those of the
+/// underlying bimap, so the pairs they hand back are in its orientation.
+///
+/// [`inverse_mut`]: BiBTreeMap::inverse_mut
+/// [`transaction`]: BiBTreeMap::transaction
+/// [`as_map_mut`]: InverseMut::as_map_mut
+pub struct InverseMut<'a, L, R, LC = Natural, RC = Natural> {
+    map: &'a mut BiBTreeMap<L, R, LC, RC>,
+}
+
+impl<'a, L, R, LC, RC> InverseMut<'a, L, R, LC, RC>
 where
-    L: Ord,
-    R: Ord,
+    LC: Compare<L>,
+    RC: Compare<R>,
 {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.left2right.partial_cmp(&other.left2right)
+    /// Returns the bimap this view was created from, with its left and right
+    /// values in their original orientation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let inverse = bimap.inverse_mut();
+    /// assert_eq!(inverse.as_map().get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn as_map(&self) -> &BiBTreeMap<L, R, LC, RC> {
+        self.map
+    }
+
+    /// Returns the bimap this view was created from mutably, with its left
+    /// and right values in their original orientation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut inverse = bimap.inverse_mut();
+    /// inverse.as_map_mut().insert('b', 2);
+    /// assert_eq!(inverse.get_by_left(&2), Some(&'b'));
+    /// ```
+    pub fn as_map_mut(&mut self) -> &mut BiBTreeMap<L, R, LC, RC> {
+        self.map
     }
-}
 
-impl<L, R> Hash for BiBTreeMap<L, R>
-where
-    L: Hash,
-    R: Hash,
-{
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.left2right.hash(state);
+    /// Returns a shared view of the inverse bimap.
+    pub fn as_inverse(&self) -> Inverse<'_, L, R, LC, RC> {
+        self.map.inverse()
     }
-}
-
-/// An owning iterator over the left-right pairs in a `BiBTreeMap`.
-pub struct IntoIter<L, R> {
-    inner: btree_map::IntoIter<Rc<L>, Rc<R>>,
-}
 
-impl<L, R> DoubleEndedIterator for IntoIter<L, R> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        // unwraps are safe because right2left is gone
-        self.inner.next_back().map(|(l, r)| {
-            (
-                Rc::try_unwrap(l).ok().unwrap(),
-                Rc::try_unwrap(r).ok().unwrap(),
-            )
-        })
+    /// Returns the number of left-right pairs in the bimap.
+    pub fn len(&self) -> usize {
+        self.map.len()
     }
-}
-
-impl<L, R> ExactSizeIterator for IntoIter<L, R> {}
-
-impl<L, R> FusedIterator for IntoIter<L, R> {}
 
-impl<L, R> Iterator for IntoIter<L, R> {
-    type Item = (L, R);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        // unwraps are safe because right2left is gone
-        self.inner.next().map(|(l, r)| {
-            (
-                Rc::try_unwrap(l).ok().unwrap(),
-                Rc::try_unwrap(r).ok().unwrap(),
-            )
-        })
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.inner.size_hint()
+    /// Removes all left-right pairs from the bimap.
+    pub fn clear(&mut self) {
+        self.map.clear()
     }
-}
 
-/// An iterator over the left-right pairs in a `BiBTreeMap`.
-///
-/// This struct is created by the [`iter`] method of `BiBTreeMap`.
-///
-/// [`iter`]: BiBTreeMap::iter
-pub struct Iter<'a, L, R> {
-    inner: btree_map::Iter<'a, Rc<L>, Rc<R>>,
-}
+    /// Removes all pairs from the bimap and returns them in an iterator, in
+    /// the inverse orientation and ascending order by left value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 2);
+    /// bimap.insert('b', 1);
+    ///
+    /// let pairs: Vec<_> = bimap.inverse_mut().drain().collect();
+    /// assert_eq!(pairs, [(1, 'b'), (2, 'a')]);
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, R, L, RC, LC> {
+        self.map.left2right.clear();
+        Drain {
+            inner: mem::take(&mut self.map.right2left).into_iter(),
+            marker: PhantomData,
+        }
+    }
 
-impl<'a, L, R> DoubleEndedIterator for Iter<'a, L, R> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        self.inner.next_back().map(|(l, r)| (&**l, &**r))
+    /// Creates an iterator over the left-right pairs in the inverse bimap in
+    /// ascending order by left value.
+    ///
+    /// The iterator element type is `(&R, &L)`.
+    pub fn iter(&self) -> Iter<'_, R, L, RC, LC> {
+        self.as_inverse().iter()
     }
-}
 
-impl<'a, L, R> ExactSizeIterator for Iter<'a, L, R> {}
+    /// Creates an iterator over the left values in the inverse bimap, which are
+    /// the right values of the underlying bimap, in ascending order.
+    pub fn left_values(&self) -> LeftValues<'_, R, L, RC, LC> {
+        self.as_inverse().left_values()
+    }
 
-impl<'a, L, R> FusedIterator for Iter<'a, L, R> {}
+    /// Creates an iterator over the right values in the inverse bimap, which
+    /// are the left values of the underlying bimap, in ascending order.
+    pub fn right_values(&self) -> RightValues<'_, R, L, RC, LC> {
+        self.as_inverse().right_values()
+    }
 
-impl<'a, L, R> Iterator for Iter<'a, L, R> {
-    type Item = (&'a L, &'a R);
+    /// Returns the left-right pair with the smallest left value in the inverse
+    /// bimap, or `None` if the bimap is empty.
+    pub fn first_by_left(&self) -> Option<(&R, &L)> {
+        self.as_inverse().first_by_left()
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|(l, r)| (&**l, &**r))
+    /// Returns the left-right pair with the smallest right value in the
+    /// inverse bimap, or `None` if the bimap is empty.
+    pub fn first_by_right(&self) -> Option<(&R, &L)> {
+        self.as_inverse().first_by_right()
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.inner.size_hint()
+    /// Returns the left-right pair with the largest left value in the inverse
+    /// bimap, or `None` if the bimap is empty.
+    pub fn last_by_left(&self) -> Option<(&R, &L)> {
+        self.as_inverse().last_by_left()
     }
-}
 
-/// An iterator over the left values in a `BiBTreeMap`.
-///
-/// This struct is created by the [`left_values`] method of `BiBTreeMap`.
-///
-/// [`left_values`]: BiBTreeMap::left_values
-pub struct LeftValues<'a, L, R> {
-    inner: btree_map::Iter<'a, Rc<L>, Rc<R>>,
-}
+    /// Returns the left-right pair with the largest right value in the inverse
+    /// bimap, or `None` if the bimap is empty.
+    pub fn last_by_right(&self) -> Option<(&R, &L)> {
+        self.as_inverse().last_by_right()
+    }
 
-impl<'a, L, R> DoubleEndedIterator for LeftValues<'a, L, R> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        self.inner.next_back().map(|(l, _)| &**l)
+    /// Creates an iterator over the left-right pairs lying within a range of
+    /// left values in the inverse bimap in ascending order by left.
+    pub fn left_range<Q, A>(&self, range: A) -> LeftRange<'_, R, L, RC, LC>
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+        A: RangeBounds<Q>,
+    {
+        self.as_inverse().left_range(range)
     }
-}
 
-impl<'a, L, R> ExactSizeIterator for LeftValues<'a, L, R> {}
+    /// Creates an iterator over the left-right pairs lying within a range of
+    /// right values in the inverse bimap in ascending order by right.
+    pub fn right_range<Q, A>(&self, range: A) -> RightRange<'_, R, L, RC, LC>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+        A: RangeBounds<Q>,
+    {
+        self.as_inverse().right_range(range)
+    }
 
-impl<'a, L, R> FusedIterator for LeftValues<'a, L, R> {}
+    /// Returns a reference to the right value corresponding to the given left
+    /// value of the inverse bimap.
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.map.get_by_right(left)
+    }
 
-impl<'a, L, R> Iterator for LeftValues<'a, L, R> {
-    type Item = &'a L;
+    /// Returns a reference to the left value corresponding to the given right
+    /// value of the inverse bimap.
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.map.get_by_left(right)
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|(l, _)| &**l)
+    /// Returns `true` if the inverse bimap contains the given left value and
+    /// `false` otherwise.
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.map.contains_right(left)
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.inner.size_hint()
+    /// Returns `true` if the inverse bimap contains the given right value and
+    /// `false` otherwise.
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.map.contains_left(right)
     }
-}
 
-/// An iterator over the right values in a `BiBTreeMap`.
-///
-/// This struct is created by the [`right_values`] method of `BiBTreeMap`.
-///
-/// [`right_values`]: BiBTreeMap::right_values
-pub struct RightValues<'a, L, R> {
-    inner: btree_map::Iter<'a, Rc<R>, Rc<L>>,
-}
+    /// Returns a guard that gives mutable access to the right value
+    /// corresponding to the given left value of the inverse bimap.
+    ///
+    /// The guard is the one returned by [`BiBTreeMap::get_mut_by_right`], so
+    /// its changes are only applied once it is committed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut inverse = bimap.inverse_mut();
+    /// let mut right = inverse.get_mut_by_left(&1).unwrap();
+    /// *right = 'b';
+    /// assert_eq!(right.commit_no_overwrite(), Ok(()));
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&1));
+    /// ```
+    pub fn get_mut_by_left<Q>(&mut self, left: &Q) -> Option<LeftMut<'_, L, R, LC, RC>>
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+        L: Clone,
+    {
+        self.map.get_mut_by_right(left)
+    }
 
-impl<'a, L, R> DoubleEndedIterator for RightValues<'a, L, R> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        self.inner.next_back().map(|(r, _)| &**r)
+    /// Returns a guard that gives mutable access to the left value
+    /// corresponding to the given right value of the inverse bimap.
+    ///
+    /// The guard is the one returned by [`BiBTreeMap::get_mut_by_left`], so
+    /// its changes are only applied once it is committed.
+    pub fn get_mut_by_right<Q>(&mut self, right: &Q) -> Option<RightMut<'_, L, R, LC, RC>>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+        R: Clone,
+    {
+        self.map.get_mut_by_left(right)
     }
-}
 
-impl<'a, L, R> ExactSizeIterator for RightValues<'a, L, R> {}
+    /// Gets the entry corresponding to the given left value of the inverse
+    /// bimap for in-place manipulation.
+    ///
+    /// This is the entry returned by [`BiBTreeMap::entry_by_right`], so the
+    /// pairs it hands back are in the orientation of the underlying bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.inverse_mut().entry_by_left(1).or_insert('a');
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn entry_by_left(&mut self, left: R) -> RightEntry<'_, L, R, LC, RC> {
+        self.map.entry_by_right(left)
+    }
 
-impl<'a, L, R> FusedIterator for RightValues<'a, L, R> {}
+    /// Gets the entry corresponding to the given right value of the inverse
+    /// bimap for in-place manipulation.
+    ///
+    /// This is the entry returned by [`BiBTreeMap::entry_by_left`], so the
+    /// pairs it hands back are in the orientation of the underlying bimap.
+    pub fn entry_by_right(&mut self, right: L) -> LeftEntry<'_, L, R, LC, RC> {
+        self.map.entry_by_left(right)
+    }
 
-impl<'a, L, R> Iterator for RightValues<'a, L, R> {
-    type Item = &'a R;
+    /// Removes and returns the left-right pair with the smallest left value in
+    /// the inverse bimap, or `None` if the bimap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 2);
+    /// bimap.insert('b', 1);
+    ///
+    /// assert_eq!(bimap.inverse_mut().pop_first_by_left(), Some((1, 'b')));
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    pub fn pop_first_by_left(&mut self) -> Option<(R, L)> {
+        self.map.pop_first_by_right().map(|(l, r)| (r, l))
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|(r, _)| &**r)
+    /// Removes and returns the left-right pair with the smallest right value in
+    /// the inverse bimap, or `None` if the bimap is empty.
+    pub fn pop_first_by_right(&mut self) -> Option<(R, L)> {
+        self.map.pop_first_by_left().map(|(l, r)| (r, l))
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.inner.size_hint()
+    /// Removes and returns the left-right pair with the largest left value in
+    /// the inverse bimap, or `None` if the bimap is empty.
+    pub fn pop_last_by_left(&mut self) -> Option<(R, L)> {
+        self.map.pop_last_by_right().map(|(l, r)| (r, l))
     }
-}
 
-/// An iterator over a range of left-right pairs in a `BiBTreeMap`.
-///
-/// This struct is created by the [`left_range`] method of `BiBTreeMap`.
-///
-/// [`left_range`]: BiBTreeMap::left_range
-#[derive(Debug)]
-pub struct LeftRange<'a, L, R> {
-    inner: btree_map::Range<'a, Rc<L>, Rc<R>>,
-}
+    /// Removes and returns the left-right pair with the largest right value in
+    /// the inverse bimap, or `None` if the bimap is empty.
+    pub fn pop_last_by_right(&mut self) -> Option<(R, L)> {
+        self.map.pop_last_by_left().map(|(l, r)| (r, l))
+    }
 
-impl<'a, L, R> DoubleEndedIterator for LeftRange<'a, L, R> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        self.inner.next_back().map(|(l, r)| (&**l, &**r))
+    /// Removes the left-right pair corresponding to the given left value of the
+    /// inverse bimap.
+    ///
+    /// Returns the previous pair, in the inverse orientation, if the bimap
+    /// contained the value and `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// assert_eq!(bimap.inverse_mut().remove_by_left(&1), Some((1, 'a')));
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(R, L)>
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.map.remove_by_right(left).map(|(l, r)| (r, l))
     }
-}
 
-impl<'a, L, R> ExactSizeIterator for LeftRange<'a, L, R> {}
+    /// Removes the left-right pair corresponding to the given right value of
+    /// the inverse bimap.
+    ///
+    /// Returns the previous pair, in the inverse orientation, if the bimap
+    /// contained the value and `None` otherwise.
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(R, L)>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.map.remove_by_left(right).map(|(l, r)| (r, l))
+    }
 
-impl<'a, L, R> FusedIterator for LeftRange<'a, L, R> {}
+    /// Inserts the given left-right pair into the inverse bimap.
+    ///
+    /// Returns the overwritten pairs, in the inverse orientation. See
+    /// [`BiBTreeMap::insert`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, Overwritten};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut inverse = bimap.inverse_mut();
+    /// assert_eq!(inverse.insert(1, 'b'), Overwritten::Left(1, 'a'));
+    /// assert_eq!(inverse.insert(2, 'b'), Overwritten::Right(1, 'b'));
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    /// ```
+    pub fn insert(&mut self, left: R, right: L) -> Overwritten<R, L> {
+        self.map.insert(right, left).inverse()
+    }
 
-impl<'a, L, R> Iterator for LeftRange<'a, L, R> {
-    type Item = (&'a L, &'a R);
+    /// Inserts the given left-right pair into the inverse bimap without
+    /// overwriting any existing values.
+    ///
+    /// If either value exists in the bimap, `Err((left, right))` is returned
+    /// with the attempted pair and the bimap is unchanged.
+    pub fn insert_no_overwrite(&mut self, left: R, right: L) -> Result<(), (R, L)> {
+        self.map
+            .insert_no_overwrite(right, left)
+            .map_err(|(l, r)| (r, l))
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|(l, r)| (&**l, &**r))
+    /// Inserts the given left-right pair into the inverse bimap if neither
+    /// value is already in it.
+    ///
+    /// Otherwise the bimap is unchanged, and an [`InsertError`] in the inverse
+    /// orientation is returned. See [`BiBTreeMap::try_insert`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, Collision};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut inverse = bimap.inverse_mut();
+    /// let error = inverse.try_insert(1, 'b').unwrap_err();
+    /// assert_eq!(error.pair, (1, 'b'));
+    /// assert_eq!(error.collision, Collision::Left(&1, &'a'));
+    /// ```
+    pub fn try_insert(&mut self, left: R, right: L) -> Result<(), InsertError<'_, R, L>> {
+        self.map
+            .try_insert(right, left)
+            .map_err(InsertError::inverse)
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.inner.size_hint()
+    /// Inserts all left-right pairs of the given batch into the inverse bimap,
+    /// or none of them if any pair would overwrite an existing value.
+    ///
+    /// On failure, the [`BatchInsertError`] is in the inverse orientation. See
+    /// [`BiBTreeMap::insert_all_no_overwrite`] for details.
+    pub fn insert_all_no_overwrite<I>(&mut self, iter: I) -> Result<(), BatchInsertError<R, L>>
+    where
+        I: IntoIterator<Item = (R, L)>,
+    {
+        self.map
+            .insert_all_no_overwrite(iter.into_iter().map(|(r, l)| (l, r)))
+            .map_err(BatchInsertError::inverse)
     }
-}
 
-/// An iterator over a range of left-right pairs in a `BiBTreeMap`.
-///
-/// This struct is created by the [`right_range`] method of `BiBTreeMap`.
-///
-/// [`right_range`]: BiBTreeMap::right_range
-#[derive(Debug)]
-pub struct RightRange<'a, L, R> {
-    inner: btree_map::Range<'a, Rc<R>, Rc<L>>,
-}
+    /// Retains only the pairs of the inverse bimap for which `f` returns
+    /// `true`.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&R, &L) -> bool,
+    {
+        let mut f = f;
+        self.map.retain(|l, r| f(r, l))
+    }
 
-impl<'a, L, R> DoubleEndedIterator for RightRange<'a, L, R> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        self.inner.next_back().map(|(r, l)| (&**l, &**r))
+    /// Creates an iterator which uses a predicate to decide whether a pair of
+    /// the inverse bimap should be removed, yielding the removed pairs in the
+    /// inverse orientation and ascending order by left value. See
+    /// [`BiBTreeMap::extract_if`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap: BiBTreeMap<_, _> = (0..4).map(|i| (i, i * 10)).collect();
+    ///
+    /// let large: Vec<_> = bimap.inverse_mut().extract_if(|&r, _| r >= 20).collect();
+    /// assert_eq!(large, [(20, 2), (30, 3)]);
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, R, L, RC, LC, F>
+    where
+        F: FnMut(&R, &L) -> bool,
+    {
+        ExtractIf {
+            next: self.map.right2left.keys().next().map(Ref::downgrade),
+            left2right: &mut self.map.right2left,
+            right2left: &mut self.map.left2right,
+            pred,
+        }
     }
-}
 
-impl<'a, L, R> ExactSizeIterator for RightRange<'a, L, R> {}
+    /// Removes the left-right pairs lying within a range of left values from
+    /// the inverse bimap and returns them in a new bimap. See
+    /// [`BiBTreeMap::remove_right_range`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+    ///
+    /// let removed = bimap.inverse_mut().remove_left_range(30..70);
+    /// assert_eq!(removed.len(), 4);
+    /// assert_eq!(removed.get_by_left(&30), Some(&3));
+    /// assert_eq!(bimap.len(), 6);
+    /// ```
+    pub fn remove_left_range<Q, A>(&mut self, range: A) -> BiBTreeMap<R, L, RC, LC>
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+        A: RangeBounds<Q>,
+    {
+        self.map.remove_right_range(range).into_inverse()
+    }
 
-impl<'a, L, R> FusedIterator for RightRange<'a, L, R> {}
+    /// Removes the left-right pairs lying within a range of right values from
+    /// the inverse bimap and returns them in a new bimap. See
+    /// [`BiBTreeMap::remove_left_range`] for details.
+    pub fn remove_right_range<Q, A>(&mut self, range: A) -> BiBTreeMap<R, L, RC, LC>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+        A: RangeBounds<Q>,
+    {
+        self.map.remove_left_range(range).into_inverse()
+    }
 
-impl<'a, L, R> Iterator for RightRange<'a, L, R> {
-    type Item = (&'a L, &'a R);
+    /// Splits the inverse bimap in two at the given left value. Returns
+    /// everything after the given left value, including the left value
+    /// itself. See [`BiBTreeMap::split_off_right`] for details.
+    pub fn split_off_left<Q>(&mut self, left: &Q) -> BiBTreeMap<R, L, RC, LC>
+    where
+        R: Borrow<Q>,
+        RC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.map.split_off_right(left).into_inverse()
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|(r, l)| (&**l, &**r))
+    /// Splits the inverse bimap in two at the given right value. Returns
+    /// everything after the given right value, including the right value
+    /// itself. See [`BiBTreeMap::split_off_left`] for details.
+    pub fn split_off_right<Q>(&mut self, right: &Q) -> BiBTreeMap<R, L, RC, LC>
+    where
+        L: Borrow<Q>,
+        LC: Compare<Q>,
+        Q: ?Sized,
+    {
+        self.map.split_off_left(right).into_inverse()
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.inner.size_hint()
+    /// Moves all pairs from `other` into the inverse bimap, leaving `other`
+    /// empty. See [`BiBTreeMap::append`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut other = BiBTreeMap::new();
+    /// other.insert(2, 'b');
+    ///
+    /// bimap.inverse_mut().append(&mut other);
+    /// assert!(other.is_empty());
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    /// ```
+    pub fn append(&mut self, other: &mut BiBTreeMap<R, L, RC, LC>) {
+        for (right, left) in other.iter() {
+            self.map.remove_by_left(left);
+            self.map.remove_by_right(right);
+        }
+        self.map.right2left.append(&mut other.left2right);
+        self.map.left2right.append(&mut other.right2left);
     }
 }
-// safe because internal Rcs are not exposed by the api and the reference counts
-// only change in methods with &mut self
-unsafe impl<L, R> Send for BiBTreeMap<L, R>
-where
-    L: Send,
-    R: Send,
-{
-}
-unsafe impl<L, R> Sync for BiBTreeMap<L, R>
+
+impl<'a, L, R, LC, RC> fmt::Debug for InverseMut<'a, L, R, LC, RC>
 where
-    L: Sync,
-    R: Sync,
+    L: fmt::Debug,
+    R: fmt::Debug,
 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Inverse { map: &*self.map }.fmt(f)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::compare::Reverse;
 
     #[cfg(not(feature = "std"))]
-    use alloc::vec::Vec;
+    use alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    #[test]
+    fn send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<BiBTreeMap<String, i32>>();
+        assert_send_sync::<BiBTreeMap<String, i32, Reverse, Reverse>>();
+    }
 
     #[test]
     fn clone() {
@@ -935,8 +4303,8 @@ mod tests {
 
     #[test]
     fn cmp() {
-        let bimap = BiBTreeMap::from_iter(vec![('a', 2)]);
-        let bimap2 = BiBTreeMap::from_iter(vec![('b', 1)]);
+        let bimap: BiBTreeMap<_, _> = BiBTreeMap::from_iter(vec![('a', 2)]);
+        let bimap2: BiBTreeMap<_, _> = BiBTreeMap::from_iter(vec![('b', 1)]);
 
         assert_eq!(bimap.partial_cmp(&bimap2), Some(Ordering::Less));
         assert_eq!(bimap.cmp(&bimap2), Ordering::Less);
@@ -1088,7 +4456,7 @@ mod tests {
 
     #[test]
     fn clear() {
-        let mut bimap = BiBTreeMap::from_iter(vec![('a', 1)]);
+        let mut bimap: BiBTreeMap<_, _> = BiBTreeMap::from_iter(vec![('a', 1)]);
         assert_eq!(bimap.len(), 1);
         assert!(!bimap.is_empty());
 
@@ -1100,7 +4468,7 @@ mod tests {
 
     #[test]
     fn get_contains() {
-        let bimap = BiBTreeMap::from_iter(vec![('a', 1)]);
+        let bimap: BiBTreeMap<_, _> = BiBTreeMap::from_iter(vec![('a', 1)]);
 
         assert_eq!(bimap.get_by_left(&'a'), Some(&1));
         assert!(bimap.contains_left(&'a'));
@@ -1162,4 +4530,859 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn entry_by_left() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+
+        match bimap.entry_by_left('a') {
+            LeftEntry::Occupied(entry) => {
+                assert_eq!(entry.key(), &'a');
+                assert_eq!(entry.get(), &1);
+            }
+            LeftEntry::Vacant(_) => panic!("entry should be occupied"),
+        }
+
+        match bimap.entry_by_left('b') {
+            LeftEntry::Occupied(_) => panic!("entry should be vacant"),
+            LeftEntry::Vacant(entry) => {
+                assert_eq!(entry.collision(&1), Some((&'a', &1)));
+                assert_eq!(entry.insert(1), Overwritten::Right('a', 1));
+            }
+        }
+        assert_eq!(bimap.len(), 1);
+
+        // or_insert overwrites a colliding right value like insert does
+        assert_eq!(*bimap.entry_by_left('c').or_insert(1), 1);
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.get_by_right(&1), Some(&'c'));
+
+        // and_modify keeps both directions in sync
+        bimap.entry_by_left('c').and_modify(|r| *r = 5);
+        assert_eq!(bimap.get_by_left(&'c'), Some(&5));
+        assert_eq!(bimap.get_by_right(&5), Some(&'c'));
+        assert_eq!(bimap.get_by_right(&1), None);
+    }
+
+    #[test]
+    fn entry_by_right() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+
+        match bimap.entry_by_right(1) {
+            RightEntry::Occupied(entry) => assert_eq!(entry.remove(), ('a', 1)),
+            RightEntry::Vacant(_) => panic!("entry should be occupied"),
+        }
+        assert!(bimap.is_empty());
+
+        assert_eq!(*bimap.entry_by_right(2).or_insert_with(|| 'b'), 'b');
+        match bimap.entry_by_right(3) {
+            RightEntry::Occupied(_) => panic!("entry should be vacant"),
+            RightEntry::Vacant(entry) => {
+                assert_eq!(entry.insert_no_overwrite('b'), Err(('b', 3)));
+            }
+        }
+        assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn and_modify_panic() {
+        let mut bimap: BiBTreeMap<_, _> = (0..5).map(|i| (i, i * 10)).collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            bimap.entry_by_left(2).and_modify(|r| {
+                *r += 1;
+                panic!("modify");
+            });
+        }));
+        assert!(result.is_err());
+        assert_eq!(bimap.len(), 5);
+        assert_eq!(bimap.get_by_left(&2), Some(&21));
+        assert_eq!(bimap.get_by_right(&21), Some(&2));
+        assert_eq!(bimap.get_by_right(&20), None);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            bimap.entry_by_right(30).and_modify(|_| panic!("modify"));
+        }));
+        assert!(result.is_err());
+        assert_eq!(bimap.len(), 5);
+        assert_eq!(bimap.get_by_right(&30), Some(&3));
+        assert_eq!(bimap.get_by_left(&3), Some(&30));
+        assert_consistent(&bimap);
+    }
+    #[test]
+    fn borrow_string_str() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert(String::from("a"), String::from("b"));
+
+        assert_eq!(bimap.get_by_left("a"), Some(&String::from("b")));
+        assert_eq!(bimap.get_by_right("b"), Some(&String::from("a")));
+        assert!(bimap.contains_left("a"));
+        assert!(bimap.contains_right("b"));
+        assert!(!bimap.contains_left("b"));
+        assert!(!bimap.contains_right("a"));
+
+        assert_eq!(
+            bimap.remove_by_left("a"),
+            Some((String::from("a"), String::from("b")))
+        );
+        bimap.insert(String::from("a"), String::from("b"));
+        assert_eq!(
+            bimap.remove_by_right("b"),
+            Some((String::from("a"), String::from("b")))
+        );
+        assert!(bimap.is_empty());
+    }
+
+    #[test]
+    fn borrow_vec_slice() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert(vec![1, 2], vec!['a', 'b']);
+
+        assert_eq!(bimap.get_by_left(&[1, 2][..]), Some(&vec!['a', 'b']));
+        assert_eq!(bimap.get_by_right(&['a', 'b'][..]), Some(&vec![1, 2]));
+        assert!(bimap.contains_left(&[1, 2][..]));
+        assert!(bimap.contains_right(&['a', 'b'][..]));
+        assert_eq!(
+            bimap.remove_by_left(&[1, 2][..]),
+            Some((vec![1, 2], vec!['a', 'b']))
+        );
+        assert!(bimap.is_empty());
+    }
+
+    #[test]
+    fn borrow_range() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert(String::from("a"), 3);
+        bimap.insert(String::from("b"), 2);
+        bimap.insert(String::from("c"), 1);
+
+        let left_range = bimap
+            .left_range::<str, _>((Bound::Included("b"), Bound::Unbounded))
+            .map(|(l, r)| (l.as_str(), *r))
+            .collect::<Vec<_>>();
+        assert_eq!(left_range, vec![("b", 2), ("c", 1)]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn borrow_pathbuf_path() {
+        use std::path::{Path, PathBuf};
+
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert(PathBuf::from("/usr/bin"), 1);
+
+        assert_eq!(bimap.get_by_left(Path::new("/usr/bin")), Some(&1));
+        assert!(bimap.contains_left(Path::new("/usr/bin")));
+        assert!(!bimap.contains_left(Path::new("/usr/lib")));
+        assert_eq!(
+            bimap.remove_by_left(Path::new("/usr/bin")),
+            Some((PathBuf::from("/usr/bin"), 1))
+        );
+        assert!(bimap.is_empty());
+    }
+
+    #[test]
+    fn inverse() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 3);
+        bimap.insert('b', 2);
+        bimap.insert('c', 1);
+        let inverse = bimap.inverse();
+
+        assert_eq!(inverse.len(), 3);
+        assert_eq!(inverse.get_by_left(&1), Some(&'c'));
+        assert_eq!(inverse.get_by_right(&'a'), Some(&3));
+        assert!(inverse.contains_left(&2));
+        assert!(!inverse.contains_right(&'d'));
+
+        let pairs: Vec<_> = inverse.iter().collect();
+        assert_eq!(pairs, [(&1, &'c'), (&2, &'b'), (&3, &'a')]);
+        let lefts: Vec<_> = inverse.left_values().collect();
+        assert_eq!(lefts, [&1, &2, &3]);
+        let rights: Vec<_> = inverse.right_values().collect();
+        assert_eq!(rights, [&'a', &'b', &'c']);
+        let left_range: Vec<_> = inverse.left_range(2..).collect();
+        assert_eq!(left_range, [(&2, &'b'), (&3, &'a')]);
+        let right_range: Vec<_> = inverse.right_range(..'c').collect();
+        assert_eq!(right_range, [(&3, &'a'), (&2, &'b')]);
+
+        assert_eq!(inverse.as_map(), &bimap);
+        assert_eq!(format!("{:?}", inverse), "{1 <> 'c', 2 <> 'b', 3 <> 'a'}");
+    }
+
+    #[test]
+    fn inverse_mut() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        let mut inverse = bimap.inverse_mut();
+        assert_eq!(inverse.insert(4, 'd'), Overwritten::Neither);
+        assert_eq!(
+            inverse.insert(1, 'b'),
+            Overwritten::Both((1, 'a'), (2, 'b'))
+        );
+        assert_eq!(inverse.insert_no_overwrite(3, 'e'), Err((3, 'e')));
+        assert_eq!(inverse.remove_by_left(&3), Some((3, 'c')));
+        assert_eq!(inverse.remove_by_right(&'d'), Some((4, 'd')));
+        let left_range: Vec<_> = inverse.left_range(..).collect();
+        assert_eq!(left_range, [(&1, &'b')]);
+
+        inverse.insert(5, 'e');
+        assert_eq!(bimap.get_by_left(&'e'), Some(&5));
+        assert_eq!(bimap.len(), 2);
+    }
+
+    #[test]
+    fn inverse_mut_forwarding() {
+        let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, 9 - i)).collect();
+        let mut inverse = bimap.inverse_mut();
+
+        assert_eq!(inverse.first_by_left(), Some((&0, &9)));
+        assert_eq!(inverse.first_by_right(), Some((&9, &0)));
+        assert_eq!(inverse.last_by_left(), Some((&9, &0)));
+        assert_eq!(inverse.last_by_right(), Some((&0, &9)));
+        assert_eq!(inverse.pop_first_by_left(), Some((0, 9)));
+        assert_eq!(inverse.pop_last_by_right(), Some((1, 8)));
+        assert_eq!(inverse.pop_first_by_right(), Some((9, 0)));
+        assert_eq!(inverse.pop_last_by_left(), Some((8, 1)));
+
+        let removed = inverse.remove_left_range(2..4);
+        assert_consistent(&removed);
+        let removed: Vec<_> = removed.into_iter().collect();
+        assert_eq!(removed, [(2, 7), (3, 6)]);
+        let split = inverse.split_off_right(&5);
+        assert_consistent(&split);
+        assert_eq!(split.into_iter().collect::<Vec<_>>(), [(4, 5)]);
+        assert_eq!(inverse.remove_right_range(..2), BiBTreeMap::new());
+        assert_eq!(inverse.split_off_left(&10), BiBTreeMap::new());
+
+        let mut extract = inverse.extract_if(|&r, _| r % 2 == 1);
+        assert_eq!(extract.next(), Some((5, 4)));
+        mem::forget(extract);
+        assert_eq!(inverse.len(), 2);
+        assert_consistent(inverse.as_map());
+        assert_eq!(inverse.remove_by_left(&6), Some((6, 3)));
+
+        let mut other = BiBTreeMap::new();
+        other.insert(6, 100);
+        other.insert(50, 2);
+        inverse.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(
+            inverse.iter().map(|(&l, &r)| (l, r)).collect::<Vec<_>>(),
+            [(6, 100), (50, 2)]
+        );
+
+        assert_eq!(inverse.entry_by_left(6).or_insert(0), &100);
+        let mut right = inverse.get_mut_by_left(&50).unwrap();
+        *right = 3;
+        assert_eq!(right.commit_no_overwrite(), Ok(()));
+        let mut left = inverse.get_mut_by_right(&100).unwrap();
+        *left = 8;
+        assert_eq!(left.commit_no_overwrite(), Ok(()));
+        inverse.retain(|_, &r| r != 3);
+        assert_eq!(inverse.drain().collect::<Vec<_>>(), [(8, 100)]);
+        assert!(bimap.is_empty());
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn inverse_mut_insert_errors() {
+        let bimap: BiBTreeMap<_, _> = (0..5).map(|i| (i, i * 10)).collect();
+        for &(left, right) in &[(10, 2), (10, 1), (50, 3), (20, 9)] {
+            let mut expected = bimap.clone().into_inverse();
+            let expected = expected.try_insert(left, right).unwrap_err().into_owned();
+            let mut actual = bimap.clone();
+            let mut inverse = actual.inverse_mut();
+            let actual = inverse.try_insert(left, right).unwrap_err().into_owned();
+            assert_eq!(actual, expected);
+        }
+
+        let batch = vec![
+            (50, 5),
+            (10, 6),
+            (60, 1),
+            (70, 7),
+            (70, 8),
+            (0, 9),
+            (80, 7),
+            (10, 2),
+        ];
+        let mut expected = bimap.clone().into_inverse();
+        let mut actual = bimap.clone();
+        assert_eq!(
+            actual.inverse_mut().insert_all_no_overwrite(batch.clone()),
+            expected.insert_all_no_overwrite(batch)
+        );
+        assert_eq!(actual, bimap);
+        assert_eq!(
+            actual
+                .inverse_mut()
+                .insert_all_no_overwrite(vec![(50, 5), (60, 6)]),
+            Ok(())
+        );
+        assert_eq!(actual.get_by_left(&6), Some(&60));
+    }
+
+    #[test]
+    fn into_inverse() {
+        let bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, 10 - i)).collect();
+        let inverse = bimap.clone().into_inverse();
+
+        let pairs: Vec<_> = inverse.iter().map(|(&l, &r)| (l, r)).collect();
+        let expected: Vec<_> = (1..=10).map(|i| (i, 10 - i)).collect();
+        assert_eq!(pairs, expected);
+        assert_eq!(inverse.into_inverse(), bimap);
+    }
+
+    #[test]
+    fn get_mut_by_left() {
+        let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+
+        assert!(bimap.get_mut_by_left(&10).is_none());
+        let mut right = bimap.get_mut_by_left(&0).unwrap();
+        *right += 5;
+        assert_eq!(right.commit_no_overwrite(), Ok(()));
+        assert_eq!(bimap.get_by_left(&0), Some(&5));
+        assert_eq!(bimap.get_by_right(&5), Some(&0));
+        assert!(!bimap.contains_right(&0));
+
+        // a collision is rejected without affecting either pair
+        let mut right = bimap.get_mut_by_left(&0).unwrap();
+        *right = 90;
+        assert_eq!(right.commit_no_overwrite(), Err(90));
+        assert_eq!(bimap.len(), 10);
+        assert_eq!(bimap.get_by_left(&0), Some(&5));
+        assert_eq!(bimap.get_by_right(&5), Some(&0));
+        assert_eq!(bimap.get_by_right(&90), Some(&9));
+
+        let mut right = bimap.get_mut_by_left(&1).unwrap();
+        assert_eq!(right.left(), &1);
+        *right = 20;
+        assert_eq!(right.commit(), Some((2, 20)));
+        assert_eq!(bimap.len(), 9);
+
+        let mut right = bimap.get_mut_by_left(&1).unwrap();
+        *right = 30;
+        assert_eq!(right.commit_no_overwrite(), Err(30));
+        assert_eq!(bimap.len(), 9);
+        assert_eq!(bimap.get_by_left(&1), Some(&20));
+        assert_eq!(bimap.get_by_right(&20), Some(&1));
+        assert_eq!(bimap.get_by_right(&30), Some(&3));
+
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn get_mut_by_right() {
+        let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i * 10, i)).collect();
+
+        assert!(bimap.get_mut_by_right(&10).is_none());
+        let mut left = bimap.get_mut_by_right(&0).unwrap();
+        *left += 5;
+        assert_eq!(left.commit_no_overwrite(), Ok(()));
+        assert_eq!(bimap.get_by_right(&0), Some(&5));
+        assert_eq!(bimap.get_by_left(&5), Some(&0));
+
+        // a collision is rejected without affecting either pair
+        let mut left = bimap.get_mut_by_right(&0).unwrap();
+        *left = 90;
+        assert_eq!(left.commit_no_overwrite(), Err(90));
+        assert_eq!(bimap.len(), 10);
+        assert_eq!(bimap.get_by_right(&0), Some(&5));
+        assert_eq!(bimap.get_by_left(&90), Some(&9));
+
+        let mut left = bimap.get_mut_by_right(&1).unwrap();
+        assert_eq!(left.right(), &1);
+        *left = 20;
+        assert_eq!(left.commit(), Some((20, 2)));
+        assert_eq!(bimap.len(), 9);
+
+        let mut left = bimap.get_mut_by_right(&1).unwrap();
+        *left = 30;
+        assert_eq!(left.commit_no_overwrite(), Err(30));
+        assert_eq!(bimap.len(), 9);
+        assert_eq!(bimap.get_by_right(&1), Some(&20));
+        assert_eq!(bimap.get_by_left(&20), Some(&1));
+        assert_eq!(bimap.get_by_left(&30), Some(&3));
+
+        assert_consistent(&bimap);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn get_mut_dropped_without_commit() {
+        let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+
+        // dropping an unchanged guard is fine
+        drop(bimap.get_mut_by_left(&0).unwrap());
+
+        // a changed guard is discarded, and panics in debug builds
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            *bimap.get_mut_by_left(&0).unwrap() = 5;
+        }));
+        assert_eq!(result.is_err(), cfg!(debug_assertions));
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            *bimap.get_mut_by_right(&90).unwrap() = 1;
+        }));
+        assert_eq!(result.is_err(), cfg!(debug_assertions));
+
+        assert_eq!(bimap.len(), 10);
+        assert_eq!(bimap.get_by_left(&0), Some(&0));
+        assert_eq!(bimap.get_by_right(&90), Some(&9));
+        assert_consistent(&bimap);
+    }
+
+    /// Checks that every pair can be found from both of its values.
+    fn assert_consistent<L, R, LC, RC>(bimap: &BiBTreeMap<L, R, LC, RC>)
+    where
+        L: fmt::Debug + PartialEq,
+        R: fmt::Debug + PartialEq,
+        LC: Compare<L>,
+        RC: Compare<R>,
+    {
+        assert_eq!(bimap.right2left.len(), bimap.len());
+        for (left, right) in bimap {
+            assert_eq!(bimap.get_by_left(left), Some(right));
+            assert_eq!(bimap.get_by_right(right), Some(left));
+        }
+    }
+
+    #[test]
+    fn retain() {
+        let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, 10 - i)).collect();
+        let mut seen = Vec::new();
+        bimap.retain(|&l, &r| {
+            seen.push(l);
+            r % 2 == 0
+        });
+        assert_eq!(seen, (0..10).collect::<Vec<_>>());
+        assert_eq!(bimap.len(), 5);
+        assert!(bimap.right_values().all(|r| r % 2 == 0));
+        assert_consistent(&bimap);
+
+        // removed pairs are dropped, so reinserting them doesn't overwrite
+        assert_eq!(bimap.insert(1, 9), Overwritten::Neither);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn retain_panic() {
+        let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            bimap.retain(|&l, _| {
+                assert!(l < 5);
+                l % 2 == 1
+            });
+        }));
+        assert!(result.is_err());
+        assert_eq!(bimap.len(), 7);
+        assert_eq!(
+            bimap.left_values().copied().collect::<Vec<_>>(),
+            [1, 3, 5, 6, 7, 8, 9]
+        );
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn drain() {
+        let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+        let pairs: Vec<_> = bimap.drain().collect();
+        assert_eq!(pairs, (0..10).map(|i| (i, i * 10)).collect::<Vec<_>>());
+        assert!(bimap.is_empty());
+        assert_consistent(&bimap);
+
+        bimap.extend((0..10).map(|i| (i, i * 10)));
+        let mut drain = bimap.drain();
+        assert_eq!(drain.len(), 10);
+        assert_eq!(drain.next(), Some((0, 0)));
+        assert_eq!(drain.next_back(), Some((9, 90)));
+        drop(drain);
+        assert!(bimap.is_empty());
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn extract_if() {
+        let mut bimap: BiBTreeMap<_, _> = (0..100).map(|i| (i, i * 10)).collect();
+        let extracted: Vec<_> = bimap.extract_if(|&l, _| l % 3 == 0).collect();
+        assert_eq!(
+            extracted,
+            (0..100)
+                .filter(|l| l % 3 == 0)
+                .map(|i| (i, i * 10))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(bimap.len(), 66);
+        assert!(bimap.left_values().all(|l| l % 3 != 0));
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn extract_if_dropped_early() {
+        let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+        let mut extract = bimap.extract_if(|&l, _| l % 2 == 0);
+        assert_eq!(extract.next(), Some((0, 0)));
+        assert_eq!(extract.next(), Some((2, 20)));
+        drop(extract);
+        assert_eq!(bimap.len(), 8);
+        assert_eq!(
+            bimap.left_values().copied().collect::<Vec<_>>(),
+            [1, 3, 4, 5, 6, 7, 8, 9]
+        );
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn extract_if_leaked() {
+        let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+        let mut extract = bimap.extract_if(|&l, _| l % 2 == 0);
+        assert_eq!(extract.next(), Some((0, 0)));
+        mem::forget(extract);
+        assert_eq!(bimap.len(), 9);
+        assert_consistent(&bimap);
+        assert_eq!(bimap.remove_by_right(&10), Some((1, 10)));
+        assert_eq!(bimap.remove_by_left(&2), Some((2, 20)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn extract_if_panic() {
+        let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            bimap
+                .extract_if(|&l, _| {
+                    assert!(l < 5);
+                    l % 2 == 0
+                })
+                .for_each(drop);
+        }));
+        assert!(result.is_err());
+        assert_eq!(bimap.len(), 7);
+        assert_eq!(
+            bimap.left_values().copied().collect::<Vec<_>>(),
+            [1, 3, 5, 6, 7, 8, 9]
+        );
+        assert_consistent(&bimap);
+        assert_eq!(bimap.remove_by_right(&50), Some((5, 50)));
+    }
+
+    /// Checks that two bimaps are consistent and removes every pair from them,
+    /// which panics if a value is still shared with another bimap.
+    fn assert_split(bimap: &mut BiBTreeMap<i32, i32>, expected: &[i32]) {
+        assert_consistent(bimap);
+        assert_eq!(bimap.left_values().copied().collect::<Vec<_>>(), expected);
+        for &left in expected {
+            assert_eq!(bimap.remove_by_left(&left), Some((left, -left)));
+        }
+    }
+
+    #[test]
+    fn remove_ranges() {
+        let bounds = [
+            Bound::Included(3),
+            Bound::Excluded(3),
+            Bound::Included(6),
+            Bound::Excluded(6),
+            Bound::Included(20),
+            Bound::Unbounded,
+        ];
+        for &start in &bounds {
+            for &end in &bounds {
+                let range = (start, end);
+                let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, -i)).collect();
+                let mut removed = bimap.remove_left_range(range);
+                let (inside, outside): (Vec<_>, Vec<_>) = (0..10).partition(|i| range.contains(i));
+                assert_split(&mut removed, &inside);
+                assert_split(&mut bimap, &outside);
+
+                let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (-i, i)).collect();
+                let mut removed = bimap.remove_right_range(range).into_inverse();
+                assert_split(&mut removed, &inside);
+                assert_split(&mut bimap.into_inverse(), &outside);
+            }
+        }
+    }
+
+    #[test]
+    fn split_off() {
+        for split in -1..=10 {
+            let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (i, -i)).collect();
+            let mut after = bimap.split_off_left(&split);
+            let (before_values, after_values): (Vec<_>, Vec<_>) = (0..10).partition(|&i| i < split);
+            assert_split(&mut after, &after_values);
+            assert_split(&mut bimap, &before_values);
+
+            let mut bimap: BiBTreeMap<_, _> = (0..10).map(|i| (-i, i)).collect();
+            let mut after = bimap.split_off_right(&split).into_inverse();
+            assert_split(&mut after, &after_values);
+            assert_split(&mut bimap.into_inverse(), &before_values);
+        }
+    }
+
+    #[test]
+    fn remove_ranges_large() {
+        // small ranges remove pairs one at a time, large ones rebuild the map
+        for &(start, end) in &[(500, 505), (0, 1000), (10, 900), (995, 2000)] {
+            let mut bimap: BiBTreeMap<_, _> = (0..1000).map(|i| (i, -i)).collect();
+            let mut removed = bimap.remove_left_range(start..end);
+            let (inside, outside): (Vec<_>, Vec<_>) =
+                (0..1000).partition(|i| (start..end).contains(i));
+            assert_split(&mut removed, &inside);
+            assert_split(&mut bimap, &outside);
+
+            let mut bimap: BiBTreeMap<_, _> = (0..1000).map(|i| (-i, i)).collect();
+            let mut after = bimap.split_off_right(&start).into_inverse();
+            let (before, after_values): (Vec<_>, Vec<_>) = (0..1000).partition(|&i| i < start);
+            assert_split(&mut after, &after_values);
+            assert_split(&mut bimap.into_inverse(), &before);
+        }
+    }
+
+    #[test]
+    fn split_off_borrowed() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert(String::from("a"), String::from("z"));
+        bimap.insert(String::from("b"), String::from("y"));
+        let split = bimap.split_off_left("b");
+        assert_eq!(split.get_by_right("y").map(String::as_str), Some("b"));
+        let split = bimap.split_off_right("z");
+        assert_eq!(split.get_by_left("a").map(String::as_str), Some("z"));
+        assert!(bimap.is_empty());
+    }
+
+    #[test]
+    fn append() {
+        let mut a: BiBTreeMap<_, _> = (0..5).map(|i| (i, -i)).collect();
+        let mut b: BiBTreeMap<_, _> = (10..15).map(|i| (i, -i)).collect();
+        a.append(&mut b);
+        assert!(b.is_empty());
+        assert_consistent(&b);
+        assert_split(&mut a, &[0, 1, 2, 3, 4, 10, 11, 12, 13, 14]);
+
+        let mut a: BiBTreeMap<_, _> = (0..5).map(|i| (i, i)).collect();
+        let mut b = BiBTreeMap::new();
+        b.insert(1, 2);
+        b.insert(7, 4);
+        a.append(&mut b);
+        assert!(b.is_empty());
+        assert_consistent(&a);
+        assert_eq!(
+            a.iter().map(|(&l, &r)| (l, r)).collect::<Vec<_>>(),
+            [(0, 0), (1, 2), (3, 3), (7, 4)]
+        );
+    }
+
+    #[test]
+    fn first_and_last() {
+        let mut bimap = BiBTreeMap::new();
+        assert_eq!(bimap.first_by_left(), None);
+        assert_eq!(bimap.last_by_right(), None);
+        assert_eq!(bimap.pop_first_by_left(), None);
+        assert_eq!(bimap.pop_last_by_right(), None);
+
+        bimap.extend(vec![('a', 3), ('b', 1), ('c', 4), ('d', 2)]);
+        assert_eq!(bimap.first_by_left(), Some((&'a', &3)));
+        assert_eq!(bimap.last_by_left(), Some((&'d', &2)));
+        assert_eq!(bimap.first_by_right(), Some((&'b', &1)));
+        assert_eq!(bimap.last_by_right(), Some((&'c', &4)));
+
+        assert_eq!(bimap.pop_first_by_right(), Some(('b', 1)));
+        assert_eq!(bimap.pop_last_by_left(), Some(('d', 2)));
+        assert_consistent(&bimap);
+        assert_eq!(bimap.pop_last_by_right(), Some(('c', 4)));
+        assert_eq!(bimap.pop_first_by_left(), Some(('a', 3)));
+        assert!(bimap.is_empty());
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn get_mut_debug() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+
+        assert_eq!(format!("{:?}", bimap.get_mut_by_left(&'a').unwrap()), "1");
+        assert_eq!(format!("{:?}", bimap.get_mut_by_right(&1).unwrap()), "'a'");
+    }
+
+    /// Orders strings by their lowercase ASCII forms.
+    struct CaseInsensitive;
+
+    impl Compare<str> for CaseInsensitive {
+        fn compare(a: &str, b: &str) -> Ordering {
+            let a = a.bytes().map(|c| c.to_ascii_lowercase());
+            let b = b.bytes().map(|c| c.to_ascii_lowercase());
+            a.cmp(b)
+        }
+    }
+
+    impl Compare<String> for CaseInsensitive {
+        fn compare(a: &String, b: &String) -> Ordering {
+            <Self as Compare<str>>::compare(a, b)
+        }
+    }
+
+    #[test]
+    fn custom_comparators() {
+        let mut bimap = BiBTreeMap::<String, i32, CaseInsensitive, Reverse>::default();
+        for (i, name) in ["b", "D", "a", "C"].iter().enumerate() {
+            bimap.insert(name.to_string(), i as i32);
+        }
+        assert_consistent(&bimap);
+
+        let lefts: Vec<_> = bimap.left_values().map(String::as_str).collect();
+        assert_eq!(lefts, ["a", "b", "C", "D"]);
+        let rights: Vec<_> = bimap.right_values().copied().collect();
+        assert_eq!(rights, [3, 2, 1, 0]);
+        let pairs: Vec<_> = bimap.iter().map(|(l, &r)| (l.as_str(), r)).collect();
+        assert_eq!(pairs, [("a", 2), ("b", 0), ("C", 3), ("D", 1)]);
+
+        // borrowed lookups go through the comparator
+        assert_eq!(bimap.get_by_left("c"), Some(&3));
+        assert_eq!(bimap.get_by_left(&"A".to_string()), Some(&2));
+        assert!(bimap.contains_left("d"));
+        assert!(!bimap.contains_left("e"));
+
+        // ranges and the first and last pairs follow the comparators
+        let range: Vec<_> = bimap
+            .left_range::<str, _>((Bound::Included("B"), Bound::Excluded("d")))
+            .map(|(l, _)| l.as_str())
+            .collect();
+        assert_eq!(range, ["b", "C"]);
+        let range: Vec<_> = bimap.right_range(&2..=&0).map(|(_, &r)| r).collect();
+        assert_eq!(range, [2, 1, 0]);
+        assert_eq!(bimap.first_by_right(), Some((&"C".to_string(), &3)));
+        assert_eq!(bimap.last_by_left(), Some((&"D".to_string(), &1)));
+
+        // values that compare equal overwrite each other
+        assert_eq!(
+            bimap.insert("A".to_string(), 2),
+            Overwritten::Pair("a".to_string(), 2)
+        );
+        assert_eq!(
+            bimap.insert("c".to_string(), 4),
+            Overwritten::Left("C".to_string(), 3)
+        );
+        assert_eq!(bimap.len(), 4);
+        assert_consistent(&bimap);
+
+        let removed =
+            bimap.remove_left_range::<str, _>((Bound::Included("B"), Bound::Included("C")));
+        assert_consistent(&bimap);
+        assert_consistent(&removed);
+        let removed: Vec<_> = removed.into_iter().collect();
+        assert_eq!(removed, [("b".to_string(), 0), ("c".to_string(), 4)]);
+
+        let split = bimap.split_off_right(&1);
+        assert_consistent(&split);
+        assert_eq!(
+            bimap.into_iter().collect::<Vec<_>>(),
+            [("A".to_string(), 2)]
+        );
+        assert_eq!(
+            split.into_iter().collect::<Vec<_>>(),
+            [("D".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn custom_comparators_inverse() {
+        let bimap: BiBTreeMap<_, _, Reverse, Natural> = (0..5).map(|i| (i, i * 10)).collect();
+        let inverse = bimap.inverse();
+        let lefts: Vec<_> = inverse.left_values().copied().collect();
+        assert_eq!(lefts, [0, 10, 20, 30, 40]);
+
+        let inverse = bimap.into_inverse();
+        assert_consistent(&inverse);
+        let rights: Vec<_> = inverse.right_values().copied().collect();
+        assert_eq!(rights, [4, 3, 2, 1, 0]);
+        let range: Vec<_> = inverse.right_range(3..).map(|(&l, _)| l).collect();
+        assert_eq!(range, [30, 20, 10, 0]);
+    }
+
+    #[test]
+    fn try_insert() {
+        let mut bimap = BiBTreeMap::new();
+        assert_eq!(bimap.try_insert('a', 1), Ok(()));
+        assert_eq!(bimap.try_insert('b', 2), Ok(()));
+
+        let cases = [
+            (('a', 1), Collision::Pair(&'a', &1)),
+            (('a', 3), Collision::Left(&'a', &1)),
+            (('c', 2), Collision::Right(&'b', &2)),
+            (('b', 1), Collision::Both((&'b', &2), (&'a', &1))),
+        ];
+        for (pair, collision) in cases {
+            assert_eq!(
+                bimap.try_insert(pair.0, pair.1),
+                Err(InsertError { pair, collision })
+            );
+        }
+        assert_eq!(bimap.len(), 2);
+        assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+        assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    }
+
+    #[test]
+    fn insert_all_no_overwrite() {
+        let mut bimap: BiBTreeMap<_, _> = vec![(0, 'a'), (1, 'b')].into_iter().collect();
+        let batch = vec![(2, 'c'), (1, 'b'), (3, 'c'), (2, 'd'), (4, 'e'), (4, 'e')];
+        let err = bimap.insert_all_no_overwrite(batch.clone()).unwrap_err();
+        assert_eq!(err.pairs, batch);
+        assert_eq!(
+            err.conflicts,
+            [
+                BatchConflict::Left(1),
+                BatchConflict::Right(1),
+                BatchConflict::DuplicateRight(0, 2),
+                BatchConflict::DuplicateLeft(0, 3),
+                BatchConflict::DuplicateLeft(4, 5),
+                BatchConflict::DuplicateRight(4, 5),
+            ]
+        );
+        assert_eq!(bimap.len(), 2);
+        assert!(!bimap.contains_left(&2));
+
+        assert_eq!(bimap.insert_all_no_overwrite(Vec::new()), Ok(()));
+        assert_eq!(bimap.insert_all_no_overwrite((2..10).zip('c'..)), Ok(()));
+        assert_eq!(bimap.len(), 10);
+        assert_eq!(bimap.get_by_left(&9), Some(&'j'));
+        assert_eq!(bimap.get_by_right(&'c'), Some(&2));
+    }
+
+    #[test]
+    fn transaction() {
+        let mut bimap: BiBTreeMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+        let original = bimap.clone();
+
+        let mut transaction = bimap.transaction();
+        assert_eq!(
+            transaction.insert('a', 2),
+            Overwritten::Both(('a', 1), ('b', 2))
+        );
+        assert_eq!(transaction.insert('c', 3), Overwritten::Pair('c', 3));
+        assert_eq!(transaction.insert_no_overwrite('c', 4), Err(('c', 4)));
+        assert_eq!(transaction.remove_by_right(&3), Some(('c', 3)));
+        {
+            let mut savepoint = transaction.savepoint();
+            assert_eq!(savepoint.insert_no_overwrite('d', 4), Ok(()));
+            assert_eq!(savepoint.remove_by_left(&'a'), Some(('a', 2)));
+            assert_eq!(savepoint.len(), 1);
+        }
+        assert_eq!(transaction.iter().collect::<Vec<_>>(), [(&'a', &2)]);
+        drop(transaction);
+        assert_eq!(bimap, original);
+
+        let mut transaction = bimap.transaction();
+        let mut savepoint = transaction.savepoint();
+        savepoint.insert('z', 26);
+        savepoint.commit();
+        transaction.commit();
+        assert_eq!(bimap.len(), 4);
+        assert_eq!(bimap.last_by_left(), Some((&'z', &26)));
+    }
 }
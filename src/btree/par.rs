@@ -0,0 +1,254 @@
+//! Parallel iteration over a `BiBTreeMap` using [Rayon].
+//!
+//! `BTreeMap`s can't be split efficiently, so the parallel iterators first
+//! collect references to the values into a vector, just like Rayon's own
+//! parallel iterators over `BTreeMap`s do.
+//!
+//! [Rayon]: https://docs.rs/rayon/
+
+use super::BiBTreeMap;
+use crate::compare::Compare;
+use alloc::vec::Vec;
+use rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator};
+
+impl<L, R, LC, RC> BiBTreeMap<L, R, LC, RC>
+where
+    L: Sync,
+    R: Sync,
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    /// Creates a parallel iterator over the left-right pairs in the bimap in
+    /// ascending order by left value.
+    ///
+    /// The iterator element type is `(&L, &R)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let bimap: BiBTreeMap<_, _> = (0..1000).map(|i| (i, i * 2)).collect();
+    /// let sum: i32 = bimap.par_iter().map(|(l, r)| l + r).sum();
+    /// assert_eq!(sum, 3 * 999 * 1000 / 2);
+    /// ```
+    pub fn par_iter(&self) -> ParIter<'_, L, R> {
+        ParIter {
+            inner: self.iter().collect::<Vec<_>>().into_par_iter(),
+        }
+    }
+
+    /// Creates a parallel iterator over the left values in the bimap in
+    /// ascending order.
+    ///
+    /// The iterator element type is `&L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let bimap: BiBTreeMap<_, _> = (0..1000).map(|i| (i, i * 2)).collect();
+    /// assert_eq!(bimap.par_left_values().max(), Some(&999));
+    /// ```
+    pub fn par_left_values(&self) -> ParLeftValues<'_, L> {
+        ParLeftValues {
+            inner: self.left_values().collect::<Vec<_>>().into_par_iter(),
+        }
+    }
+
+    /// Creates a parallel iterator over the right values in the bimap in
+    /// ascending order.
+    ///
+    /// The iterator element type is `&R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let bimap: BiBTreeMap<_, _> = (0..1000).map(|i| (i, i * 2)).collect();
+    /// assert_eq!(bimap.par_right_values().max(), Some(&1998));
+    /// ```
+    pub fn par_right_values(&self) -> ParRightValues<'_, R> {
+        ParRightValues {
+            inner: self.right_values().collect::<Vec<_>>().into_par_iter(),
+        }
+    }
+}
+
+/// Collects the left-right pairs of a parallel iterator into a bimap.
+///
+/// See the [`ParallelExtend`] implementation for which parts run in parallel.
+impl<L, R, LC, RC> FromParallelIterator<(L, R)> for BiBTreeMap<L, R, LC, RC>
+where
+    L: Send,
+    R: Send,
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    fn from_par_iter<I>(par_iter: I) -> BiBTreeMap<L, R, LC, RC>
+    where
+        I: IntoParallelIterator<Item = (L, R)>,
+    {
+        let mut bimap = BiBTreeMap::default();
+        bimap.par_extend(par_iter);
+        bimap
+    }
+}
+
+/// Extends a bimap with the left-right pairs of a parallel iterator.
+///
+/// Only producing the pairs runs in parallel. They are then inserted one at a
+/// time, in the order of the parallel iterator, so conflicting pairs overwrite
+/// each other exactly as they would with the sequential `Extend`
+/// implementation: the last pair wins.
+impl<L, R, LC, RC> ParallelExtend<(L, R)> for BiBTreeMap<L, R, LC, RC>
+where
+    L: Send,
+    R: Send,
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = (L, R)>,
+    {
+        // collecting preserves the order of the pairs, which decides how
+        // conflicts are resolved
+        let pairs: Vec<_> = par_iter.into_par_iter().collect();
+        self.extend(pairs);
+    }
+}
+
+impl<'a, L, R, LC, RC> IntoParallelIterator for &'a BiBTreeMap<L, R, LC, RC>
+where
+    L: Sync,
+    R: Sync,
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    type Item = (&'a L, &'a R);
+    type Iter = ParIter<'a, L, R>;
+
+    fn into_par_iter(self) -> ParIter<'a, L, R> {
+        self.par_iter()
+    }
+}
+
+impl<L, R, LC, RC> IntoParallelIterator for BiBTreeMap<L, R, LC, RC>
+where
+    L: Send,
+    R: Send,
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    type Item = (L, R);
+    type Iter = IntoParIter<L, R>;
+
+    fn into_par_iter(self) -> IntoParIter<L, R> {
+        IntoParIter {
+            inner: self.into_iter().collect::<Vec<_>>().into_par_iter(),
+        }
+    }
+}
+
+/// A parallel iterator over the left-right pairs in a `BiBTreeMap`.
+///
+/// This struct is created by the [`par_iter`] method of `BiBTreeMap`.
+///
+/// [`par_iter`]: BiBTreeMap::par_iter
+#[derive(Debug)]
+pub struct ParIter<'a, L, R> {
+    inner: rayon::vec::IntoIter<(&'a L, &'a R)>,
+}
+
+delegate_indexed!(ParIter<'a, L, R>: (&'a L, &'a R), Sync);
+
+/// A parallel iterator over the left values in a `BiBTreeMap`.
+///
+/// This struct is created by the [`par_left_values`] method of `BiBTreeMap`.
+///
+/// [`par_left_values`]: BiBTreeMap::par_left_values
+#[derive(Debug)]
+pub struct ParLeftValues<'a, L> {
+    inner: rayon::vec::IntoIter<&'a L>,
+}
+
+delegate_indexed!(ParLeftValues<'a, L>: &'a L, Sync);
+
+/// A parallel iterator over the right values in a `BiBTreeMap`.
+///
+/// This struct is created by the [`par_right_values`] method of `BiBTreeMap`.
+///
+/// [`par_right_values`]: BiBTreeMap::par_right_values
+#[derive(Debug)]
+pub struct ParRightValues<'a, R> {
+    inner: rayon::vec::IntoIter<&'a R>,
+}
+
+delegate_indexed!(ParRightValues<'a, R>: &'a R, Sync);
+
+/// An owning parallel iterator over the left-right pairs in a `BiBTreeMap`.
+#[derive(Debug)]
+pub struct IntoParIter<L, R> {
+    inner: rayon::vec::IntoIter<(L, R)>,
+}
+
+delegate_indexed!(IntoParIter<L, R>: (L, R), Send);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator};
+
+    /// Pairs with many conflicting left and right values.
+    fn conflicting_pairs() -> Vec<(u32, u32)> {
+        (0..10_000).map(|i| (i % 1000, (i * 7) % 1500)).collect()
+    }
+
+    #[test]
+    fn par_iter() {
+        let bimap: BiBTreeMap<_, _> = (0..1000).map(|i| (i, 2000 - i)).collect();
+
+        let pairs: Vec<_> = bimap.par_iter().collect();
+        assert_eq!(pairs, bimap.iter().collect::<Vec<_>>());
+        assert_eq!(bimap.par_iter().len(), 1000);
+
+        let lefts: Vec<_> = bimap.par_left_values().collect();
+        assert_eq!(lefts, bimap.left_values().collect::<Vec<_>>());
+        let rights: Vec<_> = bimap.par_right_values().collect();
+        assert_eq!(rights, bimap.right_values().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn into_par_iter() {
+        let bimap: BiBTreeMap<_, _> = (0..1000).map(|i| (i, i * 2)).collect();
+
+        let pairs: Vec<_> = (&bimap).into_par_iter().collect();
+        assert_eq!(pairs, bimap.iter().collect::<Vec<_>>());
+        let owned: Vec<_> = bimap.clone().into_par_iter().collect();
+        assert_eq!(owned, bimap.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn from_par_iter_matches_sequential() {
+        let pairs = conflicting_pairs();
+        let sequential: BiBTreeMap<_, _> = pairs.iter().copied().collect();
+        let parallel: BiBTreeMap<_, _> = pairs.par_iter().copied().collect();
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn par_extend_matches_sequential() {
+        let pairs = conflicting_pairs();
+        let mut sequential: BiBTreeMap<_, _> = (0..500).map(|i| (i, i)).collect();
+        let mut parallel = sequential.clone();
+        sequential.extend(pairs.iter().copied());
+        parallel.par_extend(pairs.par_iter().copied());
+        assert_eq!(parallel, sequential);
+    }
+}
@@ -0,0 +1,118 @@
+//! Comparators for ordering the values of a [`BiBTreeMap`] by something other
+//! than their `Ord` implementations.
+//!
+//! A `BiBTreeMap<L, R, LC, RC>` orders its left values with the comparator
+//! `LC` and its right values with the comparator `RC`, both of which default
+//! to [`Natural`]. Lookups, ranges and iteration all follow these orderings.
+//!
+//! Comparators are types rather than values, since the internal `BTreeMap`s
+//! of a bimap can only order their keys through a trait implementation.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::{
+//!     compare::{Compare, Reverse},
+//!     BiBTreeMap,
+//! };
+//! use std::cmp::Ordering;
+//!
+//! /// Orders strings by their lowercase ASCII forms.
+//! struct CaseInsensitive;
+//!
+//! impl Compare<str> for CaseInsensitive {
+//!     fn compare(a: &str, b: &str) -> Ordering {
+//!         let a = a.bytes().map(|c| c.to_ascii_lowercase());
+//!         let b = b.bytes().map(|c| c.to_ascii_lowercase());
+//!         a.cmp(b)
+//!     }
+//! }
+//!
+//! impl Compare<String> for CaseInsensitive {
+//!     fn compare(a: &String, b: &String) -> Ordering {
+//!         <Self as Compare<str>>::compare(a, b)
+//!     }
+//! }
+//!
+//! let mut bimap = BiBTreeMap::<String, u32, CaseInsensitive, Reverse>::default();
+//! bimap.insert("Apple".to_string(), 1);
+//! bimap.insert("banana".to_string(), 2);
+//! bimap.insert("Cherry".to_string(), 3);
+//!
+//! // left values are looked up case-insensitively
+//! assert_eq!(bimap.get_by_left("APPLE"), Some(&1));
+//! assert!(bimap.contains_left("cherry"));
+//!
+//! // right values are ordered from largest to smallest
+//! let rights: Vec<_> = bimap.right_values().copied().collect();
+//! assert_eq!(rights, [3, 2, 1]);
+//! ```
+//!
+//! [`BiBTreeMap`]: crate::BiBTreeMap
+
+use core::{cmp::Ordering, marker::PhantomData};
+
+/// A total order on values of type `T`.
+///
+/// Implementations must be consistent with each other for all the borrowed
+/// forms a value can be looked up by: if `L: Borrow<Q>`, comparing two values
+/// of type `L` must give the same result as comparing their borrowed forms.
+pub trait Compare<T: ?Sized> {
+    /// Compares two values.
+    fn compare(a: &T, b: &T) -> Ordering;
+}
+
+/// The comparator that orders values by their `Ord` implementations.
+///
+/// This is the default comparator of both sides of a [`BiBTreeMap`].
+///
+/// [`BiBTreeMap`]: crate::BiBTreeMap
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Natural;
+
+impl<T> Compare<T> for Natural
+where
+    T: Ord + ?Sized,
+{
+    fn compare(a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// The comparator that reverses the order of another comparator, which
+/// defaults to [`Natural`].
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{compare::Reverse, BiBTreeMap};
+///
+/// let bimap: BiBTreeMap<_, _, Reverse> = (0..4).map(|i| (i, i * 10)).collect();
+/// let lefts: Vec<_> = bimap.left_values().copied().collect();
+/// assert_eq!(lefts, [3, 2, 1, 0]);
+/// ```
+pub struct Reverse<C = Natural>(PhantomData<C>);
+
+impl<T, C> Compare<T> for Reverse<C>
+where
+    T: ?Sized,
+    C: Compare<T>,
+{
+    fn compare(a: &T, b: &T) -> Ordering {
+        C::compare(b, a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn natural_and_reverse() {
+        assert_eq!(Natural::compare(&1, &2), Ordering::Less);
+        assert_eq!(Natural::compare("b", "a"), Ordering::Greater);
+        assert_eq!(<Reverse>::compare(&1, &2), Ordering::Greater);
+        assert_eq!(<Reverse>::compare("b", "b"), Ordering::Equal);
+        assert_eq!(<Reverse<Reverse>>::compare(&1, &2), Ordering::Less);
+    }
+}
@@ -1,23 +1,47 @@
-//! A bimap backed by two `HashMap`s.
+//! A bimap backed by two hash tables.
 
-use crate::Overwritten;
+#[cfg(feature = "rayon")]
+mod par;
+
+#[cfg(feature = "rayon")]
+pub use self::par::{IntoParIter, ParIter, ParLeftValues, ParRightValues};
+
+use crate::{
+    debug_assert_committed, BatchConflict, BatchInsertError, Collision, InsertError, Overwritten,
+    Reinsert, Undo,
+};
+use hashbrown::{hash_table, HashTable};
 use std::{
-    collections::{hash_map, HashMap},
-    fmt,
-    hash::{BuildHasher, Hash},
-    iter::{Extend, FromIterator, FusedIterator},
-    ops::Deref,
-    rc::Rc,
+    borrow::Borrow,
+    cmp::Ordering,
+    collections::{self, hash_map},
+    error, fmt,
+    hash::{BuildHasher, Hash, Hasher},
+    iter::{self, Extend, FromIterator, FusedIterator},
+    marker::PhantomData,
+    mem,
+    ops::{Deref, DerefMut},
+    slice, vec,
 };
 
-/// A bimap backed by two `HashMap`s.
+/// A bimap backed by two hash tables.
+///
+/// Each left-right pair is stored exactly once, in a dense vector of pairs.
+/// The two hash tables only hold indices into that vector: one is keyed by the
+/// hashes of the left values and the other by the hashes of the right values.
 ///
 /// See the [module-level documentation] for more details and examples.
 ///
 /// [module-level documentation]: crate
+#[derive(Clone)]
 pub struct BiHashMap<L, R, LS = hash_map::RandomState, RS = hash_map::RandomState> {
-    left2right: HashMap<Rc<L>, Rc<R>, LS>,
-    right2left: HashMap<Rc<R>, Rc<L>, RS>,
+    // the pair at index `i` is `(lefts[i], rights[i])`
+    lefts: Vec<L>,
+    rights: Vec<R>,
+    left2right: HashTable<usize>,
+    right2left: HashTable<usize>,
+    hash_builder_left: LS,
+    hash_builder_right: RS,
 }
 
 impl<L, R> BiHashMap<L, R, hash_map::RandomState, hash_map::RandomState>
@@ -35,10 +59,7 @@ where
     /// let bimap = BiHashMap::<char, i32>::new();
     /// ```
     pub fn new() -> Self {
-        Self {
-            left2right: HashMap::new(),
-            right2left: HashMap::new(),
-        }
+        Self::with_hashers(hash_map::RandomState::new(), hash_map::RandomState::new())
     }
 
     /// Creates a new empty `BiHashMap` with the given capacity.
@@ -52,10 +73,11 @@ where
     /// assert!(bimap.capacity() >= 10);
     /// ```
     pub fn with_capacity(capacity: usize) -> Self {
-        Self {
-            left2right: HashMap::with_capacity(capacity),
-            right2left: HashMap::with_capacity(capacity),
-        }
+        Self::with_capacity_and_hashers(
+            capacity,
+            hash_map::RandomState::new(),
+            hash_map::RandomState::new(),
+        )
     }
 }
 
@@ -78,7 +100,7 @@ where
     /// assert_eq!(bimap.len(), 3);
     /// ```
     pub fn len(&self) -> usize {
-        self.left2right.len()
+        self.lefts.len()
     }
 
     /// Returns `true` if the bimap contains no left-right pairs, and `false`
@@ -97,7 +119,7 @@ where
     /// assert!(bimap.is_empty());
     /// ```
     pub fn is_empty(&self) -> bool {
-        self.left2right.is_empty()
+        self.lefts.is_empty()
     }
 
     /// Returns a lower bound on the number of left-right pairs the `BiHashMap`
@@ -112,7 +134,11 @@ where
     /// assert!(bimap.capacity() >= 10);
     /// ```
     pub fn capacity(&self) -> usize {
-        self.left2right.capacity().min(self.right2left.capacity())
+        self.lefts
+            .capacity()
+            .min(self.rights.capacity())
+            .min(self.left2right.capacity())
+            .min(self.right2left.capacity())
     }
 
     /// Removes all left-right pairs from the bimap.
@@ -130,8 +156,38 @@ where
     /// assert!(bimap.len() == 0);
     /// ```
     pub fn clear(&mut self) {
+        self.lefts.clear();
+        self.rights.clear();
+        self.left2right.clear();
+        self.right2left.clear();
+    }
+
+    /// Removes all pairs from the bimap and returns them in an iterator, in
+    /// arbitrary order.
+    ///
+    /// The bimap is empty as soon as this method returns, even if the iterator
+    /// is dropped before it is fully consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let mut pairs: Vec<_> = bimap.drain().collect();
+    /// pairs.sort();
+    /// assert_eq!(pairs, [('a', 1), ('b', 2)]);
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, L, R> {
         self.left2right.clear();
         self.right2left.clear();
+        Drain {
+            inner: self.lefts.drain(..).zip(self.rights.drain(..)),
+        }
     }
 
     /// Creates an iterator over the left-right pairs in the bimap in arbitrary
@@ -155,7 +211,7 @@ where
     /// ```
     pub fn iter(&self) -> Iter<'_, L, R> {
         Iter {
-            inner: self.left2right.iter(),
+            inner: self.lefts.iter().zip(self.rights.iter()),
         }
     }
 
@@ -180,7 +236,8 @@ where
     /// ```
     pub fn left_values(&self) -> LeftValues<'_, L, R> {
         LeftValues {
-            inner: self.left2right.iter(),
+            inner: self.lefts.iter(),
+            marker: PhantomData,
         }
     }
 
@@ -205,7 +262,79 @@ where
     /// ```
     pub fn right_values(&self) -> RightValues<'_, L, R> {
         RightValues {
-            inner: self.right2left.iter(),
+            inner: self.rights.iter(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a view of the bimap with its left and right values swapped.
+    ///
+    /// The view shares the bimap's storage, so creating one is free.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let inverse = bimap.inverse();
+    /// assert_eq!(inverse.get_by_left(&1), Some(&'a'));
+    /// assert_eq!(inverse.get_by_right(&'b'), Some(&2));
+    /// ```
+    pub fn inverse(&self) -> Inverse<'_, L, R, LS, RS> {
+        Inverse { map: self }
+    }
+
+    /// Returns a mutable view of the bimap with its left and right values
+    /// swapped.
+    ///
+    /// The view shares the bimap's storage, so changes made through it are
+    /// visible in the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// bimap.inverse_mut().insert(2, 'b');
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    /// ```
+    pub fn inverse_mut(&mut self) -> InverseMut<'_, L, R, LS, RS> {
+        InverseMut { map: self }
+    }
+
+    /// Converts the bimap into its inverse, whose left values are this bimap's
+    /// right values and vice versa.
+    ///
+    /// No values are moved or rehashed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let inverse: BiHashMap<i32, char> = bimap.into_inverse();
+    /// assert_eq!(inverse.get_by_left(&1), Some(&'a'));
+    /// assert_eq!(inverse.get_by_right(&'b'), Some(&2));
+    /// ```
+    pub fn into_inverse(self) -> BiHashMap<R, L, RS, LS> {
+        BiHashMap {
+            lefts: self.rights,
+            rights: self.lefts,
+            left2right: self.right2left,
+            right2left: self.left2right,
+            hash_builder_left: self.hash_builder_right,
+            hash_builder_right: self.hash_builder_left,
         }
     }
 }
@@ -233,8 +362,12 @@ where
     /// ```
     pub fn with_hashers(hash_builder_left: LS, hash_builder_right: RS) -> Self {
         Self {
-            left2right: HashMap::with_hasher(hash_builder_left),
-            right2left: HashMap::with_hasher(hash_builder_right),
+            lefts: Vec::new(),
+            rights: Vec::new(),
+            left2right: HashTable::new(),
+            right2left: HashTable::new(),
+            hash_builder_left,
+            hash_builder_right,
         }
     }
 
@@ -259,14 +392,130 @@ where
         hash_builder_right: RS,
     ) -> Self {
         Self {
-            left2right: HashMap::with_capacity_and_hasher(capacity, hash_builder_left),
-            right2left: HashMap::with_capacity_and_hasher(capacity, hash_builder_right),
+            lefts: Vec::with_capacity(capacity),
+            rights: Vec::with_capacity(capacity),
+            left2right: HashTable::with_capacity(capacity),
+            right2left: HashTable::with_capacity(capacity),
+            hash_builder_left,
+            hash_builder_right,
         }
     }
 
+    /// Reserves capacity for at least `additional` more left-right pairs to
+    /// be inserted into the bimap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::<char, i32>::new();
+    /// bimap.reserve(10);
+    /// assert!(bimap.capacity() >= 10);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.lefts.reserve(additional);
+        self.rights.reserve(additional);
+        self.left2right
+            .reserve(additional, rehasher(&self.lefts, &self.hash_builder_left));
+        self.right2left
+            .reserve(additional, rehasher(&self.rights, &self.hash_builder_right));
+    }
+
+    /// Tries to reserve capacity for at least `additional` more left-right
+    /// pairs to be inserted into the bimap.
+    ///
+    /// Unlike [`reserve`], this method returns an error instead of panicking
+    /// or aborting if the capacity overflows or the allocator reports a
+    /// failure. The bimap is left intact, although some of the capacity may
+    /// already have been reserved.
+    ///
+    /// [`reserve`]: BiHashMap::reserve
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::<char, i32>::new();
+    /// assert!(bimap.try_reserve(10).is_ok());
+    /// assert!(bimap.capacity() >= 10);
+    /// assert!(bimap.try_reserve(usize::MAX).is_err());
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.lefts
+            .try_reserve(additional)
+            .map_err(TryReserveError::vec)?;
+        self.rights
+            .try_reserve(additional)
+            .map_err(TryReserveError::vec)?;
+        self.left2right
+            .try_reserve(additional, rehasher(&self.lefts, &self.hash_builder_left))
+            .map_err(TryReserveError::table)?;
+        self.right2left
+            .try_reserve(additional, rehasher(&self.rights, &self.hash_builder_right))
+            .map_err(TryReserveError::table)
+    }
+
+    /// Shrinks the capacity of the bimap as much as possible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::<char, i32>::with_capacity(100);
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.shrink_to_fit();
+    /// assert!(bimap.capacity() >= 2);
+    /// assert!(bimap.capacity() < 100);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to(0);
+    }
+
+    /// Shrinks the capacity of the bimap with a lower bound.
+    ///
+    /// The capacity will remain at least as large as both the length and the
+    /// given lower bound.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::<char, i32>::with_capacity(100);
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.shrink_to(10);
+    /// assert!(bimap.capacity() >= 10);
+    /// assert!(bimap.capacity() < 100);
+    /// bimap.shrink_to(0);
+    /// assert!(bimap.capacity() >= 2);
+    /// ```
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.lefts.shrink_to(min_capacity);
+        self.rights.shrink_to(min_capacity);
+        self.left2right
+            .shrink_to(min_capacity, rehasher(&self.lefts, &self.hash_builder_left));
+        self.right2left.shrink_to(
+            min_capacity,
+            rehasher(&self.rights, &self.hash_builder_right),
+        );
+    }
+
     /// Returns a reference to the right value corresponding to the given left
     /// value.
     ///
+    /// The left value may be any borrowed form of the bimap's left value
+    /// type, but `Hash` and `Eq` on the borrowed form *must* match those for
+    /// the left value type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -277,13 +526,21 @@ where
     /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
     /// assert_eq!(bimap.get_by_left(&'z'), None);
     /// ```
-    pub fn get_by_left(&self, left: &L) -> Option<&R> {
-        self.left2right.get(left).map(Deref::deref)
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_left(left).map(|index| &self.rights[index])
     }
 
     /// Returns a reference to the left value corresponding to the given right
     /// value.
     ///
+    /// The right value may be any borrowed form of the bimap's right value
+    /// type, but `Hash` and `Eq` on the borrowed form *must* match those for
+    /// the right value type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -294,13 +551,21 @@ where
     /// assert_eq!(bimap.get_by_right(&1), Some(&'a'));
     /// assert_eq!(bimap.get_by_right(&2), None);
     /// ```
-    pub fn get_by_right(&self, right: &R) -> Option<&L> {
-        self.right2left.get(right).map(Deref::deref)
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_right(right).map(|index| &self.lefts[index])
     }
 
     /// Returns `true` if the bimap contains the given left value and `false`
     /// otherwise.
     ///
+    /// The left value may be any borrowed form of the bimap's left value
+    /// type, but `Hash` and `Eq` on the borrowed form *must* match those for
+    /// the left value type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -311,13 +576,21 @@ where
     /// assert!(bimap.contains_left(&'a'));
     /// assert!(!bimap.contains_left(&'b'));
     /// ```
-    pub fn contains_left(&self, left: &L) -> bool {
-        self.left2right.contains_key(left)
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_left(left).is_some()
     }
 
     /// Returns `true` if the map contains the given right value and `false`
     /// otherwise.
     ///
+    /// The right value may be any borrowed form of the bimap's right value
+    /// type, but `Hash` and `Eq` on the borrowed form *must* match those for
+    /// the right value type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -328,8 +601,131 @@ where
     /// assert!(bimap.contains_right(&1));
     /// assert!(!bimap.contains_right(&2));
     /// ```
-    pub fn contains_right(&self, right: &R) -> bool {
-        self.right2left.contains_key(right)
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_right(right).is_some()
+    }
+
+    /// Returns a guard granting mutable access to the right value corresponding
+    /// to the given left value, or `None` if the bimap doesn't contain it.
+    ///
+    /// Changes made through the guard only take effect when it is committed.
+    /// [`RightMut::commit`] overwrites any other pair with an equal right
+    /// value, just as [`insert`] and [`LeftEntry::and_modify`] would, while
+    /// [`RightMut::commit_no_overwrite`] rejects the change in that case and
+    /// returns the rejected right value, just as [`try_insert`] would. Dropping
+    /// the guard without committing it discards the change, and panics in debug
+    /// builds if the right value was changed.
+    ///
+    /// The original right value is cloned when the guard is created, even if
+    /// the change turns out not to collide, so that it can be restored.
+    ///
+    /// The left value may be any borrowed form of the bimap's left value
+    /// type, but `Hash` and `Eq` on the borrowed form *must* match those for
+    /// the left value type.
+    ///
+    /// [`insert`]: BiHashMap::insert
+    /// [`try_insert`]: BiHashMap::try_insert
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let mut right = bimap.get_mut_by_left(&'a').unwrap();
+    /// *right = 3;
+    /// assert_eq!(right.commit(), None);
+    /// assert_eq!(bimap.get_by_right(&3), Some(&'a'));
+    ///
+    /// // 2 is taken by 'b', so the change is rejected
+    /// let mut right = bimap.get_mut_by_left(&'a').unwrap();
+    /// *right = 2;
+    /// assert_eq!(right.commit_no_overwrite(), Err(2));
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&3));
+    ///
+    /// // or overwrites the pair containing it
+    /// let mut right = bimap.get_mut_by_left(&'a').unwrap();
+    /// *right = 2;
+    /// assert_eq!(right.commit(), Some(('b', 2)));
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    pub fn get_mut_by_left<Q>(&mut self, left: &Q) -> Option<RightMut<'_, L, R, LS, RS>>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        R: Clone,
+    {
+        let index = self.find_left(left)?;
+        let original = Some(self.rights[index].clone());
+        Some(RightMut {
+            map: self,
+            index,
+            original,
+        })
+    }
+
+    /// Returns a guard granting mutable access to the left value corresponding
+    /// to the given right value, or `None` if the bimap doesn't contain it.
+    ///
+    /// Changes made through the guard only take effect when it is committed.
+    /// [`LeftMut::commit`] overwrites any other pair with an equal left value,
+    /// just as [`insert`] and [`RightEntry::and_modify`] would, while
+    /// [`LeftMut::commit_no_overwrite`] rejects the change in that case and
+    /// returns the rejected left value, just as [`try_insert`] would. Dropping
+    /// the guard without committing it discards the change, and panics in debug
+    /// builds if the left value was changed.
+    ///
+    /// The original left value is cloned when the guard is created, even if
+    /// the change turns out not to collide, so that it can be restored.
+    ///
+    /// The right value may be any borrowed form of the bimap's right value
+    /// type, but `Hash` and `Eq` on the borrowed form *must* match those for
+    /// the right value type.
+    ///
+    /// [`insert`]: BiHashMap::insert
+    /// [`try_insert`]: BiHashMap::try_insert
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let mut left = bimap.get_mut_by_right(&1).unwrap();
+    /// *left = 'c';
+    /// assert_eq!(left.commit(), None);
+    /// assert_eq!(bimap.get_by_left(&'c'), Some(&1));
+    ///
+    /// // 'b' is taken by 2, so the change is rejected
+    /// let mut left = bimap.get_mut_by_right(&1).unwrap();
+    /// *left = 'b';
+    /// assert_eq!(left.commit_no_overwrite(), Err('b'));
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'c'));
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn get_mut_by_right<Q>(&mut self, right: &Q) -> Option<LeftMut<'_, L, R, LS, RS>>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        L: Clone,
+    {
+        let index = self.find_right(right)?;
+        let original = Some(self.lefts[index].clone());
+        Some(LeftMut {
+            map: self,
+            index,
+            original,
+        })
     }
 
     /// Removes the left-right pair corresponding to the given left value.
@@ -337,6 +733,10 @@ where
     /// Returns the previous left-right pair if the map contained the left value
     /// and `None` otherwise.
     ///
+    /// The left value may be any borrowed form of the bimap's left value
+    /// type, but `Hash` and `Eq` on the borrowed form *must* match those for
+    /// the left value type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -350,16 +750,12 @@ where
     /// assert_eq!(bimap.remove_by_left(&'b'), Some(('b', 2)));
     /// assert_eq!(bimap.remove_by_left(&'b'), None);
     /// ```
-    pub fn remove_by_left(&mut self, left: &L) -> Option<(L, R)> {
-        self.left2right.remove(left).map(|right_rc| {
-            // unwrap is safe because we know right2left contains the key (it's a bimap)
-            let left_rc = self.right2left.remove(&right_rc).unwrap();
-            // at this point we can safely unwrap because the other pointers are gone
-            (
-                Rc::try_unwrap(left_rc).ok().unwrap(),
-                Rc::try_unwrap(right_rc).ok().unwrap(),
-            )
-        })
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_left(left).map(|index| self.remove_index(index))
     }
 
     /// Removes the left-right pair corresponding to the given right value.
@@ -367,6 +763,10 @@ where
     /// Returns the previous left-right pair if the map contained the right
     /// value and `None` otherwise.
     ///
+    /// The right value may be any borrowed form of the bimap's right value
+    /// type, but `Hash` and `Eq` on the borrowed form *must* match those for
+    /// the right value type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -380,16 +780,12 @@ where
     /// assert_eq!(bimap.remove_by_right(&2), Some(('b', 2)));
     /// assert_eq!(bimap.remove_by_right(&2), None);
     /// ```
-    pub fn remove_by_right(&mut self, right: &R) -> Option<(L, R)> {
-        self.right2left.remove(right).map(|left_rc| {
-            // unwrap is safe because we know left2right contains the key (it's a bimap)
-            let right_rc = self.left2right.remove(&left_rc).unwrap();
-            // at this point we can safely unwrap because the other pointers are gone
-            (
-                Rc::try_unwrap(left_rc).ok().unwrap(),
-                Rc::try_unwrap(right_rc).ok().unwrap(),
-            )
-        })
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_right(right).map(|index| self.remove_index(index))
     }
 
     /// Inserts the given left-right pair into the bimap.
@@ -442,7 +838,35 @@ where
     /// assert_eq!(bimap.len(), 1); // {'a' <> 2}
     /// ```
     pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
-        let retval = match (self.remove_by_left(&left), self.remove_by_right(&right)) {
+        let left_hash = self.hash_builder_left.hash_one(&left);
+        let right_hash = self.hash_builder_right.hash_one(&right);
+        self.insert_hashed(left, right, left_hash, right_hash)
+    }
+
+    /// Inserts the given left-right pair into the bimap like [`insert`], given
+    /// the hashes of its values.
+    ///
+    /// [`insert`]: BiHashMap::insert
+    fn insert_hashed(
+        &mut self,
+        left: L,
+        right: R,
+        left_hash: u64,
+        right_hash: u64,
+    ) -> Overwritten<L, R> {
+        let lefts = &self.lefts;
+        let l_index = self
+            .left2right
+            .find(left_hash, |&index| lefts[index] == left)
+            .copied();
+        let l_pair = l_index.map(|index| self.remove_index(index));
+        let rights = &self.rights;
+        let r_index = self
+            .right2left
+            .find(right_hash, |&index| rights[index] == right)
+            .copied();
+        let r_pair = r_index.map(|index| self.remove_index(index));
+        let retval = match (l_pair, r_pair) {
             (None, None) => Overwritten::Neither,
             (None, Some(r_pair)) => Overwritten::Right(r_pair.0, r_pair.1),
             (Some(l_pair), None) => {
@@ -456,7 +880,7 @@ where
             }
             (Some(l_pair), Some(r_pair)) => Overwritten::Both(l_pair, r_pair),
         };
-        self.insert_unchecked(left, right);
+        self.insert_unchecked_hashed(left, right, left_hash, right_hash);
         retval
     }
 
@@ -487,6 +911,178 @@ where
         }
     }
 
+    /// Inserts the given left-right pair into the bimap without overwriting any
+    /// existing values, reporting which values collided on failure.
+    ///
+    /// Returns `Ok(())` if the pair was successfully inserted into the bimap.
+    /// Otherwise, the bimap is unchanged and an [`InsertError`] is returned
+    /// containing the attempted pair and the existing pairs it collided with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, Collision};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// assert_eq!(bimap.try_insert('a', 1), Ok(()));
+    /// assert_eq!(bimap.try_insert('b', 2), Ok(()));
+    ///
+    /// let err = bimap.try_insert('a', 2).unwrap_err();
+    /// assert_eq!(err.pair, ('a', 2));
+    /// assert_eq!(err.collision, Collision::Both((&'a', &1), (&'b', &2)));
+    ///
+    /// let err = bimap.try_insert('c', 1).unwrap_err();
+    /// assert_eq!(err.collision, Collision::Right(&'a', &1));
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "failed to insert 'c' <> 1: the right value is already paired with 'a'"
+    /// );
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn try_insert(&mut self, left: L, right: R) -> Result<(), InsertError<'_, L, R>> {
+        let (l_index, r_index) = match (self.find_left(&left), self.find_right(&right)) {
+            (None, None) => {
+                self.insert_unchecked(left, right);
+                return Ok(());
+            }
+            indices => indices,
+        };
+        let (lefts, rights) = (&self.lefts, &self.rights);
+        let pair_at = |index: usize| (&lefts[index], &rights[index]);
+        Err(InsertError {
+            pair: (left, right),
+            collision: Collision::new(l_index.map(pair_at), r_index.map(pair_at)),
+        })
+    }
+
+    /// Inserts all left-right pairs of the given batch into the bimap, or none
+    /// of them if any pair would overwrite an existing value.
+    ///
+    /// The whole batch is validated before the bimap is changed: a pair
+    /// conflicts if its left or right value already exists in the bimap, or if
+    /// an earlier pair of the batch has the same left or right value. On
+    /// failure, the bimap is unchanged and a [`BatchInsertError`] is returned
+    /// that hands back the pairs and lists every conflict.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BatchConflict, BiHashMap};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let err = bimap
+    ///     .insert_all_no_overwrite(vec![('b', 2), ('a', 3), ('c', 2)])
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     err.conflicts,
+    ///     [BatchConflict::Left(1), BatchConflict::DuplicateRight(0, 2)]
+    /// );
+    /// assert_eq!(err.pairs, [('b', 2), ('a', 3), ('c', 2)]);
+    /// assert_eq!(bimap.len(), 1);
+    ///
+    /// assert_eq!(bimap.insert_all_no_overwrite(vec![('b', 2), ('c', 3)]), Ok(()));
+    /// assert_eq!(bimap.len(), 3);
+    /// ```
+    pub fn insert_all_no_overwrite<I>(&mut self, iter: I) -> Result<(), BatchInsertError<L, R>>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let pairs: Vec<(L, R)> = iter.into_iter().collect();
+        let mut conflicts = Vec::new();
+        // the index of the first pair of the batch with each left and right value
+        let mut batch_lefts = HashTable::<usize>::with_capacity(pairs.len());
+        let mut batch_rights = HashTable::<usize>::with_capacity(pairs.len());
+        let (hash_left, hash_right) = (&self.hash_builder_left, &self.hash_builder_right);
+        for (index, (left, right)) in pairs.iter().enumerate() {
+            if self.contains_left(left) {
+                conflicts.push(BatchConflict::Left(index));
+            }
+            if self.contains_right(right) {
+                conflicts.push(BatchConflict::Right(index));
+            }
+            match batch_lefts.entry(
+                hash_left.hash_one(left),
+                |&i| pairs[i].0 == *left,
+                |&i| hash_left.hash_one(&pairs[i].0),
+            ) {
+                hash_table::Entry::Occupied(entry) => {
+                    conflicts.push(BatchConflict::DuplicateLeft(*entry.get(), index));
+                }
+                hash_table::Entry::Vacant(entry) => {
+                    entry.insert(index);
+                }
+            }
+            match batch_rights.entry(
+                hash_right.hash_one(right),
+                |&i| pairs[i].1 == *right,
+                |&i| hash_right.hash_one(&pairs[i].1),
+            ) {
+                hash_table::Entry::Occupied(entry) => {
+                    conflicts.push(BatchConflict::DuplicateRight(*entry.get(), index));
+                }
+                hash_table::Entry::Vacant(entry) => {
+                    entry.insert(index);
+                }
+            }
+        }
+        if !conflicts.is_empty() {
+            return Err(BatchInsertError { pairs, conflicts });
+        }
+        self.reserve(pairs.len());
+        for (left, right) in pairs {
+            self.insert_unchecked(left, right);
+        }
+        Ok(())
+    }
+
+    /// Starts a transaction, which returns a guard that modifies the bimap and
+    /// records how to undo each modification.
+    ///
+    /// The modifications are rolled back when the guard is dropped, unless
+    /// [`Transaction::commit`] is called first. Nested transactions can be
+    /// started with [`Transaction::savepoint`] to roll back only part of the
+    /// modifications.
+    ///
+    /// The transaction keeps a copy of every value it may have to restore, so
+    /// its modifying methods require `L: Clone` and `R: Clone`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, Overwritten};
+    ///
+    /// let mut names = BiHashMap::new();
+    /// names.insert("alice", 1);
+    /// names.insert("bob", 2);
+    ///
+    /// // rename alice to carol, then fail validation and abandon the batch
+    /// {
+    ///     let mut renames = names.transaction();
+    ///     assert_eq!(renames.remove_by_right(&1), Some(("alice", 1)));
+    ///     assert_eq!(renames.insert("carol", 2), Overwritten::Right("bob", 2));
+    ///     assert_eq!(renames.get_by_left(&"carol"), Some(&2));
+    /// }
+    /// assert_eq!(names.get_by_left(&"alice"), Some(&1));
+    /// assert_eq!(names.get_by_left(&"bob"), Some(&2));
+    /// assert!(!names.contains_left(&"carol"));
+    ///
+    /// // this time, keep the rename
+    /// let mut renames = names.transaction();
+    /// renames.remove_by_right(&1);
+    /// renames.insert("carol", 1);
+    /// renames.commit();
+    /// assert_eq!(names.get_by_right(&1), Some(&"carol"));
+    /// ```
+    pub fn transaction(&mut self) -> Transaction<'_, L, R, LS, RS> {
+        Transaction {
+            bimap: self,
+            log: Vec::new(),
+            parent: None,
+        }
+    }
+
     /// Retains only the elements specified by the predicate.
     ///
     /// In other words, remove all left-right pairs `(l, r)` such that `f(&l,
@@ -512,77 +1108,264 @@ where
         F: FnMut(&L, &R) -> bool,
     {
         let mut f = f;
-        let right2left = &mut self.right2left;
-        self.left2right.retain(|l, r| {
-            let to_retain = f(l, r);
-            if !to_retain {
-                right2left.remove(r);
+        let mut index = 0;
+        while index < self.len() {
+            if f(&self.lefts[index], &self.rights[index]) {
+                index += 1;
+            } else {
+                // the last pair is moved into `index`, so it's checked next
+                self.remove_index(index);
             }
-            to_retain
-        });
-    }
-
-    /// Inserts the given left-right pair into the bimap without checking if the
-    /// pair already exists.
-    fn insert_unchecked(&mut self, left: L, right: R) {
-        let left_rc = Rc::new(left);
-        let right_rc = Rc::new(right);
-        self.left2right.insert(left_rc.clone(), right_rc.clone());
-        self.right2left.insert(right_rc, left_rc);
+        }
     }
-}
 
-impl<L, R, LS, RS> Clone for BiHashMap<L, R, LS, RS>
-where
-    L: Clone + Eq + Hash,
-    R: Clone + Eq + Hash,
-    LS: BuildHasher + Clone,
-    RS: BuildHasher + Clone,
-{
-    fn clone(&self) -> BiHashMap<L, R, LS, RS> {
-        let mut new_bimap = BiHashMap::with_capacity_and_hashers(
-            self.capacity(),
-            self.left2right.hasher().clone(),
-            self.right2left.hasher().clone(),
+    /// Creates an iterator which uses a predicate to decide whether a
+    /// left-right pair should be removed, yielding the removed pairs.
+    ///
+    /// Pairs are visited in arbitrary order. If the predicate returns `true`,
+    /// the pair is removed from the bimap and yielded, otherwise it stays in
+    /// the bimap. If the iterator is dropped before it is fully consumed, the
+    /// pairs it hasn't visited yet are kept. The bimap stays consistent even if
+    /// the predicate panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap: BiHashMap<_, _> = (0..8).map(|i| (i, i * 10)).collect();
+    ///
+    /// let mut evens: Vec<_> = bimap.extract_if(|&l, _| l % 2 == 0).collect();
+    /// evens.sort();
+    /// assert_eq!(evens, [(0, 0), (2, 20), (4, 40), (6, 60)]);
+    /// assert_eq!(bimap.len(), 4);
+    /// assert_eq!(bimap.get_by_right(&30), Some(&3));
+    /// ```
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, L, R, LS, RS, F>
+    where
+        F: FnMut(&L, &R) -> bool,
+    {
+        ExtractIf {
+            map: self,
+            index: 0,
+            pred,
+        }
+    }
+
+    /// Gets the entry corresponding to the given left value for in-place
+    /// manipulation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// assert_eq!(*bimap.entry_by_left('a').or_insert(5), 1);
+    /// assert_eq!(*bimap.entry_by_left('b').or_insert_with(|| 2), 2);
+    /// assert_eq!(bimap.get_by_right(&2), Some(&'b'));
+    /// ```
+    pub fn entry_by_left(&mut self, left: L) -> LeftEntry<'_, L, R, LS, RS> {
+        match self.find_left(&left) {
+            Some(index) => LeftEntry::Occupied(OccupiedLeftEntry {
+                map: self,
+                left,
+                index,
+            }),
+            None => LeftEntry::Vacant(VacantLeftEntry { map: self, left }),
+        }
+    }
+
+    /// Gets the entry corresponding to the given right value for in-place
+    /// manipulation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// assert_eq!(*bimap.entry_by_right(1).or_insert('z'), 'a');
+    /// assert_eq!(*bimap.entry_by_right(2).or_insert_with(|| 'b'), 'b');
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    /// ```
+    pub fn entry_by_right(&mut self, right: R) -> RightEntry<'_, L, R, LS, RS> {
+        match self.find_right(&right) {
+            Some(index) => RightEntry::Occupied(OccupiedRightEntry {
+                map: self,
+                right,
+                index,
+            }),
+            None => RightEntry::Vacant(VacantRightEntry { map: self, right }),
+        }
+    }
+
+    /// Returns the index of the pair containing the given left value.
+    fn find_left<Q>(&self, left: &Q) -> Option<usize>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder_left.hash_one(left);
+        let lefts = &self.lefts;
+        self.left2right
+            .find(hash, |&index| lefts[index].borrow() == left)
+            .copied()
+    }
+
+    /// Returns the index of the pair containing the given right value.
+    fn find_right<Q>(&self, right: &Q) -> Option<usize>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder_right.hash_one(right);
+        let rights = &self.rights;
+        self.right2left
+            .find(hash, |&index| rights[index].borrow() == right)
+            .copied()
+    }
+
+    /// Inserts the given left-right pair into the bimap without checking if the
+    /// pair already exists, and returns the index of the new pair.
+    fn insert_unchecked(&mut self, left: L, right: R) -> usize {
+        let left_hash = self.hash_builder_left.hash_one(&left);
+        let right_hash = self.hash_builder_right.hash_one(&right);
+        self.insert_unchecked_hashed(left, right, left_hash, right_hash)
+    }
+
+    /// Inserts the given left-right pair into the bimap like
+    /// `insert_unchecked`, given the hashes of its values.
+    fn insert_unchecked_hashed(
+        &mut self,
+        left: L,
+        right: R,
+        left_hash: u64,
+        right_hash: u64,
+    ) -> usize {
+        let index = self.lefts.len();
+        self.lefts.push(left);
+        self.rights.push(right);
+        self.left2right.insert_unique(
+            left_hash,
+            index,
+            rehasher(&self.lefts, &self.hash_builder_left),
+        );
+        self.right2left.insert_unique(
+            right_hash,
+            index,
+            rehasher(&self.rights, &self.hash_builder_right),
         );
-        for (l, r) in self.iter() {
-            new_bimap.insert(l.clone(), r.clone());
+        index
+    }
+
+    /// Removes the pair at the given index from the bimap and returns it.
+    ///
+    /// The last pair is moved into the vacated index to keep the pairs dense.
+    fn remove_index(&mut self, index: usize) -> (L, R) {
+        let left_hash = self.hash_builder_left.hash_one(&self.lefts[index]);
+        let right_hash = self.hash_builder_right.hash_one(&self.rights[index]);
+        erase_index(&mut self.left2right, left_hash, index);
+        erase_index(&mut self.right2left, right_hash, index);
+
+        let last = self.len() - 1;
+        if index != last {
+            let left_hash = self.hash_builder_left.hash_one(&self.lefts[last]);
+            let right_hash = self.hash_builder_right.hash_one(&self.rights[last]);
+            replace_index(&mut self.left2right, left_hash, last, index);
+            replace_index(&mut self.right2left, right_hash, last, index);
+        }
+        (
+            self.lefts.swap_remove(index),
+            self.rights.swap_remove(index),
+        )
+    }
+
+    /// Visits the pairs from `index` onwards, and removes and returns the first
+    /// pair matching `pred`. Every pair before `index` is kept.
+    fn extract_next<F>(&mut self, index: &mut usize, mut pred: F) -> Option<(L, R)>
+    where
+        F: FnMut(&L, &R) -> bool,
+    {
+        while *index < self.len() {
+            if pred(&self.lefts[*index], &self.rights[*index]) {
+                // the last pair is moved into `index`, so it's checked next
+                return Some(self.remove_index(*index));
+            }
+            *index += 1;
         }
-        new_bimap
+        None
     }
 }
 
+/// Returns a function that recomputes the hash of the value at an index, for
+/// use when a hash table of indices is resized.
+pub(crate) fn rehasher<'a, T, S>(
+    values: &'a [T],
+    hash_builder: &'a S,
+) -> impl Fn(&usize) -> u64 + 'a
+where
+    T: Hash,
+    S: BuildHasher,
+{
+    move |&index| hash_builder.hash_one(&values[index])
+}
+
+/// Removes an index from a hash table of indices.
+pub(crate) fn erase_index(table: &mut HashTable<usize>, hash: u64, index: usize) {
+    // unwrap is safe because every pair is indexed in both tables
+    table
+        .find_entry(hash, |&i| i == index)
+        .ok()
+        .unwrap()
+        .remove();
+}
+
+/// Replaces an index in a hash table of indices.
+pub(crate) fn replace_index(table: &mut HashTable<usize>, hash: u64, old: usize, new: usize) {
+    // unwrap is safe because every pair is indexed in both tables
+    *table.find_mut(hash, |&i| i == old).unwrap() = new;
+}
+
 impl<L, R, LS, RS> fmt::Debug for BiHashMap<L, R, LS, RS>
 where
     L: fmt::Debug,
     R: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        struct EntryDebugger<'a, L, R> {
-            left: &'a L,
-            right: &'a R,
-        }
-        impl<'a, L, R> fmt::Debug for EntryDebugger<'a, L, R>
-        where
-            L: fmt::Debug,
-            R: fmt::Debug,
-        {
-            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                self.left.fmt(f)?;
-                write!(f, " <> ")?;
-                self.right.fmt(f)
-            }
-        }
         f.debug_set()
             .entries(
-                self.left2right
+                self.lefts
                     .iter()
+                    .zip(&self.rights)
                     .map(|(left, right)| EntryDebugger { left, right }),
             )
             .finish()
     }
 }
 
+/// Formats a left-right pair as `left <> right`.
+pub(crate) struct EntryDebugger<'a, L, R> {
+    pub(crate) left: &'a L,
+    pub(crate) right: &'a R,
+}
+
+impl<'a, L, R> fmt::Debug for EntryDebugger<'a, L, R>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.left.fmt(f)?;
+        write!(f, " <> ")?;
+        self.right.fmt(f)
+    }
+}
+
 impl<L, R, LS, RS> Default for BiHashMap<L, R, LS, RS>
 where
     L: Eq + Hash,
@@ -591,10 +1374,7 @@ where
     RS: BuildHasher + Default,
 {
     fn default() -> BiHashMap<L, R, LS, RS> {
-        BiHashMap {
-            left2right: HashMap::default(),
-            right2left: HashMap::default(),
-        }
+        BiHashMap::with_hashers(LS::default(), RS::default())
     }
 }
 
@@ -657,7 +1437,7 @@ where
 
     fn into_iter(self) -> IntoIter<L, R> {
         IntoIter {
-            inner: self.left2right.into_iter(),
+            inner: self.lefts.into_iter().zip(self.rights),
         }
     }
 }
@@ -676,6 +1456,30 @@ where
     }
 }
 
+/// Orders bimaps like [`BiBTreeMap`](crate::BiBTreeMap)s holding the same
+/// pairs, by lexicographically comparing their pairs sorted by left value.
+///
+/// Since a `BiHashMap` is unordered, every comparison sorts the pairs of both
+/// bimaps first, which takes `O(n log n)` time and allocates.
+impl<L, R, LS, RS> Ord for BiHashMap<L, R, LS, RS>
+where
+    L: Ord + Hash,
+    R: Ord + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        sorted_pairs(self).cmp(&sorted_pairs(other))
+    }
+}
+
+/// Collects the pairs of a bimap sorted by left value, which is unique.
+fn sorted_pairs<L: Ord, R, LS, RS>(bimap: &BiHashMap<L, R, LS, RS>) -> Vec<(&L, &R)> {
+    let mut pairs: Vec<_> = bimap.lefts.iter().zip(&bimap.rights).collect();
+    pairs.sort_unstable_by_key(|&(left, _)| left);
+    pairs
+}
+
 impl<L, R, LS, RS> PartialEq for BiHashMap<L, R, LS, RS>
 where
     L: Eq + Hash,
@@ -684,13 +1488,112 @@ where
     RS: BuildHasher,
 {
     fn eq(&self, other: &Self) -> bool {
-        self.left2right == other.left2right
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(left, right)| other.get_by_left(left) == Some(right))
+    }
+}
+
+impl<L, R, LS, RS> PartialOrd for BiHashMap<L, R, LS, RS>
+where
+    L: Ord + Hash,
+    R: Ord + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Hashes a bimap independently of the order of its pairs, its capacity and
+/// its hashers, so that bimaps that compare equal hash equally.
+///
+/// Each pair is hashed on its own with a fixed hasher, and the pair hashes are
+/// combined with a wrapping sum before being fed to `state`.
+impl<L, R, LS, RS> Hash for BiHashMap<L, R, LS, RS>
+where
+    L: Hash,
+    R: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let sum = self
+            .lefts
+            .iter()
+            .zip(&self.rights)
+            .map(|pair| {
+                // unlike `RandomState`, `DefaultHasher::new` always uses the
+                // same keys
+                let mut hasher = hash_map::DefaultHasher::new();
+                pair.hash(&mut hasher);
+                hasher.finish()
+            })
+            .fold(0u64, u64::wrapping_add);
+        state.write_usize(self.lefts.len());
+        state.write_u64(sum);
+    }
+}
+
+/// The error type for the [`try_reserve`] method of a `BiHashMap`.
+///
+/// [`try_reserve`]: BiHashMap::try_reserve
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TryReserveError {
+    kind: TryReserveErrorKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum TryReserveErrorKind {
+    // the vectors of left or right values couldn't grow
+    Vec(collections::TryReserveError),
+    CapacityOverflow,
+    AllocError,
+}
+
+impl TryReserveError {
+    fn vec(error: collections::TryReserveError) -> Self {
+        TryReserveError {
+            kind: TryReserveErrorKind::Vec(error),
+        }
+    }
+
+    fn table(error: hashbrown::TryReserveError) -> Self {
+        let kind = match error {
+            hashbrown::TryReserveError::CapacityOverflow => TryReserveErrorKind::CapacityOverflow,
+            hashbrown::TryReserveError::AllocError { .. } => TryReserveErrorKind::AllocError,
+        };
+        TryReserveError { kind }
+    }
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            TryReserveErrorKind::Vec(error) => error.fmt(f),
+            TryReserveErrorKind::CapacityOverflow => f.write_str(
+                "memory allocation failed because the computed capacity exceeded the \
+                 collection's maximum",
+            ),
+            TryReserveErrorKind::AllocError => f.write_str(
+                "memory allocation failed because the memory allocator returned an error",
+            ),
+        }
+    }
+}
+
+impl error::Error for TryReserveError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.kind {
+            TryReserveErrorKind::Vec(error) => Some(error),
+            _ => None,
+        }
     }
 }
 
 /// An owning iterator over the left-right pairs in a `BiHashMap`.
 pub struct IntoIter<L, R> {
-    inner: hash_map::IntoIter<Rc<L>, Rc<R>>,
+    inner: iter::Zip<vec::IntoIter<L>, vec::IntoIter<R>>,
 }
 
 impl<L, R> ExactSizeIterator for IntoIter<L, R> {}
@@ -701,12 +1604,7 @@ impl<L, R> Iterator for IntoIter<L, R> {
     type Item = (L, R);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|(l, r)| {
-            (
-                Rc::try_unwrap(l).ok().unwrap(),
-                Rc::try_unwrap(r).ok().unwrap(),
-            )
-        })
+        self.inner.next()
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -720,7 +1618,7 @@ impl<L, R> Iterator for IntoIter<L, R> {
 ///
 /// [`iter`]: BiHashMap::iter
 pub struct Iter<'a, L, R> {
-    inner: hash_map::Iter<'a, Rc<L>, Rc<R>>,
+    inner: iter::Zip<slice::Iter<'a, L>, slice::Iter<'a, R>>,
 }
 
 impl<'a, L, R> ExactSizeIterator for Iter<'a, L, R> {}
@@ -731,9 +1629,7 @@ impl<'a, L, R> Iterator for Iter<'a, L, R> {
     type Item = (&'a L, &'a R);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner
-            .next()
-            .map(|(l, r)| (Deref::deref(l), Deref::deref(r)))
+        self.inner.next()
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -747,7 +1643,8 @@ impl<'a, L, R> Iterator for Iter<'a, L, R> {
 ///
 /// [`left_values`]: BiHashMap::left_values
 pub struct LeftValues<'a, L, R> {
-    inner: hash_map::Iter<'a, Rc<L>, Rc<R>>,
+    inner: slice::Iter<'a, L>,
+    marker: PhantomData<&'a R>,
 }
 
 impl<'a, L, R> ExactSizeIterator for LeftValues<'a, L, R> {}
@@ -758,7 +1655,7 @@ impl<'a, L, R> Iterator for LeftValues<'a, L, R> {
     type Item = &'a L;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|(l, _)| Deref::deref(l))
+        self.inner.next()
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -772,7 +1669,8 @@ impl<'a, L, R> Iterator for LeftValues<'a, L, R> {
 ///
 /// [`right_values`]: BiHashMap::right_values
 pub struct RightValues<'a, L, R> {
-    inner: hash_map::Iter<'a, Rc<R>, Rc<L>>,
+    inner: slice::Iter<'a, R>,
+    marker: PhantomData<&'a L>,
 }
 
 impl<'a, L, R> ExactSizeIterator for RightValues<'a, L, R> {}
@@ -783,31 +1681,2044 @@ impl<'a, L, R> Iterator for RightValues<'a, L, R> {
     type Item = &'a R;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|(r, _)| Deref::deref(r))
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A draining iterator over the left-right pairs in a `BiHashMap`.
+///
+/// This struct is created by the [`drain`] method of `BiHashMap`.
+///
+/// [`drain`]: BiHashMap::drain
+pub struct Drain<'a, L, R> {
+    inner: iter::Zip<vec::Drain<'a, L>, vec::Drain<'a, R>>,
+}
+
+impl<'a, L, R> DoubleEndedIterator for Drain<'a, L, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, L, R> ExactSizeIterator for Drain<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for Drain<'a, L, R> {}
+
+impl<'a, L, R> Iterator for Drain<'a, L, R> {
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator which uses a predicate to decide whether to remove a
+/// left-right pair from a `BiHashMap`.
+///
+/// This struct is created by the [`extract_if`] method of `BiHashMap`.
+///
+/// [`extract_if`]: BiHashMap::extract_if
+pub struct ExtractIf<'a, L, R, LS, RS, F> {
+    map: &'a mut BiHashMap<L, R, LS, RS>,
+    // every pair before `index` has been kept
+    index: usize,
+    pred: F,
+}
+
+impl<'a, L, R, LS, RS, F> FusedIterator for ExtractIf<'a, L, R, LS, RS, F>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+    F: FnMut(&L, &R) -> bool,
+{
+}
+
+impl<'a, L, R, LS, RS, F> Iterator for ExtractIf<'a, L, R, LS, RS, F>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+    F: FnMut(&L, &R) -> bool,
+{
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.map.extract_next(&mut self.index, &mut self.pred)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.map.len() - self.index))
+    }
+}
+
+/// An iterator which uses a predicate to decide whether to remove a
+/// left-right pair from the inverse of a `BiHashMap`.
+///
+/// This struct is created by the [`extract_if`] method of `InverseMut`.
+///
+/// [`extract_if`]: InverseMut::extract_if
+pub struct InverseExtractIf<'a, L, R, LS, RS, F> {
+    map: &'a mut BiHashMap<L, R, LS, RS>,
+    // every pair before `index` has been kept
+    index: usize,
+    pred: F,
+}
+
+impl<'a, L, R, LS, RS, F> FusedIterator for InverseExtractIf<'a, L, R, LS, RS, F>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+    F: FnMut(&R, &L) -> bool,
+{
+}
+
+impl<'a, L, R, LS, RS, F> Iterator for InverseExtractIf<'a, L, R, LS, RS, F>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+    F: FnMut(&R, &L) -> bool,
+{
+    type Item = (R, L);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pred = &mut self.pred;
+        self.map
+            .extract_next(&mut self.index, |l, r| pred(r, l))
+            .map(|(l, r)| (r, l))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.map.len() - self.index))
+    }
+}
+
+/// A view into a single left value in a `BiHashMap`, which may either be
+/// vacant or occupied.
+///
+/// This enum is created by the [`entry_by_left`] method of `BiHashMap`.
+///
+/// [`entry_by_left`]: BiHashMap::entry_by_left
+pub enum LeftEntry<'a, L, R, LS, RS> {
+    /// An occupied entry.
+    Occupied(OccupiedLeftEntry<'a, L, R, LS, RS>),
+
+    /// A vacant entry.
+    Vacant(VacantLeftEntry<'a, L, R, LS, RS>),
+}
+
+impl<'a, L, R, LS, RS> LeftEntry<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Returns a reference to this entry's left value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::<char, i32>::new();
+    /// assert_eq!(bimap.entry_by_left('a').key(), &'a');
+    /// ```
+    pub fn key(&self) -> &L {
+        match self {
+            LeftEntry::Occupied(entry) => entry.key(),
+            LeftEntry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Ensures the entry's left value is in the bimap by inserting the given
+    /// right value if the entry is vacant, and returns a reference to the
+    /// right value in the entry.
+    ///
+    /// If the entry is vacant and the right value already exists in the bimap,
+    /// its previous left-right pair is overwritten, as with [`insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// assert_eq!(*bimap.entry_by_left('a').or_insert(1), 1);
+    /// assert_eq!(*bimap.entry_by_left('a').or_insert(2), 1);
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    pub fn or_insert(self, right: R) -> &'a R {
+        self.or_insert_with(|| right)
+    }
+
+    /// Ensures the entry's left value is in the bimap by inserting the result
+    /// of the given function if the entry is vacant, and returns a reference to
+    /// the right value in the entry.
+    ///
+    /// If the entry is vacant and the computed right value already exists in
+    /// the bimap, its previous left-right pair is overwritten, as with
+    /// [`insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// assert_eq!(*bimap.entry_by_left('a').or_insert_with(|| 1), 1);
+    /// assert_eq!(*bimap.entry_by_left('a').or_insert_with(|| unreachable!()), 1);
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    pub fn or_insert_with<F>(self, default: F) -> &'a R
+    where
+        F: FnOnce() -> R,
+    {
+        match self {
+            LeftEntry::Occupied(entry) => entry.into_ref(),
+            LeftEntry::Vacant(entry) => entry.insert_ref(default()),
+        }
+    }
+
+    /// Provides in-place mutable access to the right value of an occupied
+    /// entry before any potential inserts into the bimap.
+    ///
+    /// The modified right value is re-indexed once `f` returns. If it is equal
+    /// to the right value of a different pair in the bimap, that pair is
+    /// overwritten, as with [`insert`].
+    ///
+    /// If `f` panics, the pair is inserted back into the bimap with whatever
+    /// changes `f` made to its right value before panicking.
+    ///
+    /// To reject a colliding right value instead, as [`try_insert`] rejects
+    /// colliding pairs, use [`get_mut_by_left`] and
+    /// [`RightMut::commit_no_overwrite`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// bimap.entry_by_left('a').and_modify(|r| *r += 10).or_insert(0);
+    /// bimap.entry_by_left('b').and_modify(|r| *r += 10).or_insert(0);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&11));
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&0));
+    /// assert_eq!(bimap.get_by_right(&1), None);
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    /// [`try_insert`]: BiHashMap::try_insert
+    /// [`get_mut_by_left`]: BiHashMap::get_mut_by_left
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut R),
+    {
+        match self {
+            LeftEntry::Occupied(mut entry) => {
+                entry.modify(f);
+                LeftEntry::Occupied(entry)
+            }
+            LeftEntry::Vacant(entry) => LeftEntry::Vacant(entry),
+        }
+    }
+}
+
+/// A view into an occupied left value in a `BiHashMap`.
+///
+/// This struct is part of the [`LeftEntry`] enum.
+pub struct OccupiedLeftEntry<'a, L, R, LS, RS> {
+    map: &'a mut BiHashMap<L, R, LS, RS>,
+    left: L,
+    index: usize,
+}
+
+impl<'a, L, R, LS, RS> OccupiedLeftEntry<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Returns a reference to the left value stored in the bimap.
+    pub fn key(&self) -> &L {
+        &self.map.lefts[self.index]
+    }
+
+    /// Returns a reference to the right value corresponding to the entry's
+    /// left value.
+    pub fn get(&self) -> &R {
+        &self.map.rights[self.index]
+    }
+
+    /// Converts the entry into a reference to its right value with the
+    /// lifetime of the bimap.
+    pub fn into_ref(self) -> &'a R {
+        &self.map.rights[self.index]
+    }
+
+    /// Removes the entry's left-right pair from the bimap and returns it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{hash::LeftEntry, BiHashMap};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let LeftEntry::Occupied(entry) = bimap.entry_by_left('a') {
+    ///     assert_eq!(entry.remove(), ('a', 1));
+    /// }
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn remove(self) -> (L, R) {
+        self.map.remove_index(self.index)
+    }
+
+    /// Inserts the entry's left value with the given right value, replacing
+    /// the entry's previous left-right pair.
+    ///
+    /// The return value is the same as that of [`insert`], so it indicates any
+    /// other pair that was overwritten because it contained the right value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{hash::LeftEntry, BiHashMap, Overwritten};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// if let LeftEntry::Occupied(entry) = bimap.entry_by_left('a') {
+    ///     assert_eq!(entry.insert(2), Overwritten::Both(('a', 1), ('b', 2)));
+    /// }
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&2));
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    pub fn insert(self, right: R) -> Overwritten<L, R> {
+        self.map.insert(self.left, right)
+    }
+
+    fn modify<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut R),
+    {
+        let pair = self.map.remove_index(self.index);
+        // the pair is inserted back when the guard is dropped, even if `f`
+        // panics
+        let mut guard = Reinsert::new(&mut *self.map, pair, |map, left, right| {
+            map.insert(left, right);
+        });
+        f(guard.pair_mut().1);
+        drop(guard);
+        // unwrap is safe because the left value was just inserted
+        self.index = self.map.find_left(&self.left).unwrap();
+    }
+}
+
+/// A view into a vacant left value in a `BiHashMap`.
+///
+/// This struct is part of the [`LeftEntry`] enum.
+pub struct VacantLeftEntry<'a, L, R, LS, RS> {
+    map: &'a mut BiHashMap<L, R, LS, RS>,
+    left: L,
+}
+
+impl<'a, L, R, LS, RS> VacantLeftEntry<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Returns a reference to the left value that would be inserted.
+    pub fn key(&self) -> &L {
+        &self.left
+    }
+
+    /// Takes ownership of the left value that would be inserted.
+    pub fn into_key(self) -> L {
+        self.left
+    }
+
+    /// Returns the left-right pair that would be overwritten if the given right
+    /// value was inserted into this entry, or `None` if there is no collision.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{hash::LeftEntry, BiHashMap};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let LeftEntry::Vacant(entry) = bimap.entry_by_left('b') {
+    ///     assert_eq!(entry.collision(&1), Some((&'a', &1)));
+    ///     assert_eq!(entry.collision(&2), None);
+    /// }
+    /// ```
+    pub fn collision<Q>(&self, right: &Q) -> Option<(&L, &R)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map
+            .find_right(right)
+            .map(|index| (&self.map.lefts[index], &self.map.rights[index]))
+    }
+
+    /// Inserts the entry's left value with the given right value.
+    ///
+    /// The return value is the same as that of [`insert`]. Since the left value
+    /// is vacant, it is either `Overwritten::Neither` or
+    /// `Overwritten::Right`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{hash::LeftEntry, BiHashMap, Overwritten};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let LeftEntry::Vacant(entry) = bimap.entry_by_left('b') {
+    ///     assert_eq!(entry.insert(1), Overwritten::Right('a', 1));
+    /// }
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&1));
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    pub fn insert(self, right: R) -> Overwritten<L, R> {
+        self.map.insert(self.left, right)
+    }
+
+    /// Inserts the entry's left value with the given right value without
+    /// overwriting any existing pairs.
+    ///
+    /// Returns `Ok(())` if the pair was inserted. If the right value already
+    /// exists in the bimap, `Err((left, right))` is returned with the attempted
+    /// left-right pair and the bimap is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{hash::LeftEntry, BiHashMap};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let LeftEntry::Vacant(entry) = bimap.entry_by_left('b') {
+    ///     assert_eq!(entry.insert_no_overwrite(1), Err(('b', 1)));
+    /// }
+    /// ```
+    pub fn insert_no_overwrite(self, right: R) -> Result<(), (L, R)> {
+        self.map.insert_no_overwrite(self.left, right)
+    }
+
+    fn insert_ref(self, right: R) -> &'a R {
+        let map = self.map;
+        map.remove_by_right(&right);
+        let index = map.insert_unchecked(self.left, right);
+        &map.rights[index]
+    }
+}
+
+/// A view into a single right value in a `BiHashMap`, which may either be
+/// vacant or occupied.
+///
+/// This enum is created by the [`entry_by_right`] method of `BiHashMap`.
+///
+/// [`entry_by_right`]: BiHashMap::entry_by_right
+pub enum RightEntry<'a, L, R, LS, RS> {
+    /// An occupied entry.
+    Occupied(OccupiedRightEntry<'a, L, R, LS, RS>),
+
+    /// A vacant entry.
+    Vacant(VacantRightEntry<'a, L, R, LS, RS>),
+}
+
+impl<'a, L, R, LS, RS> RightEntry<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Returns a reference to this entry's right value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::<char, i32>::new();
+    /// assert_eq!(bimap.entry_by_right(1).key(), &1);
+    /// ```
+    pub fn key(&self) -> &R {
+        match self {
+            RightEntry::Occupied(entry) => entry.key(),
+            RightEntry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Ensures the entry's right value is in the bimap by inserting the given
+    /// left value if the entry is vacant, and returns a reference to the left
+    /// value in the entry.
+    ///
+    /// If the entry is vacant and the left value already exists in the bimap,
+    /// its previous left-right pair is overwritten, as with [`insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// assert_eq!(*bimap.entry_by_right(1).or_insert('a'), 'a');
+    /// assert_eq!(*bimap.entry_by_right(1).or_insert('b'), 'a');
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    pub fn or_insert(self, left: L) -> &'a L {
+        self.or_insert_with(|| left)
+    }
+
+    /// Ensures the entry's right value is in the bimap by inserting the result
+    /// of the given function if the entry is vacant, and returns a reference to
+    /// the left value in the entry.
+    ///
+    /// If the entry is vacant and the computed left value already exists in the
+    /// bimap, its previous left-right pair is overwritten, as with [`insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// assert_eq!(*bimap.entry_by_right(1).or_insert_with(|| 'a'), 'a');
+    /// assert_eq!(*bimap.entry_by_right(1).or_insert_with(|| unreachable!()), 'a');
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    pub fn or_insert_with<F>(self, default: F) -> &'a L
+    where
+        F: FnOnce() -> L,
+    {
+        match self {
+            RightEntry::Occupied(entry) => entry.into_ref(),
+            RightEntry::Vacant(entry) => entry.insert_ref(default()),
+        }
+    }
+
+    /// Provides in-place mutable access to the left value of an occupied entry
+    /// before any potential inserts into the bimap.
+    ///
+    /// The modified left value is re-indexed once `f` returns. If it is equal
+    /// to the left value of a different pair in the bimap, that pair is
+    /// overwritten, as with [`insert`].
+    ///
+    /// If `f` panics, the pair is inserted back into the bimap with whatever
+    /// changes `f` made to its left value before panicking.
+    ///
+    /// To reject a colliding left value instead, as [`try_insert`] rejects
+    /// colliding pairs, use [`get_mut_by_right`] and
+    /// [`LeftMut::commit_no_overwrite`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert(1, 'a');
+    ///
+    /// bimap.entry_by_right('a').and_modify(|l| *l += 10).or_insert(0);
+    /// bimap.entry_by_right('b').and_modify(|l| *l += 10).or_insert(0);
+    /// assert_eq!(bimap.get_by_right(&'a'), Some(&11));
+    /// assert_eq!(bimap.get_by_right(&'b'), Some(&0));
+    /// assert_eq!(bimap.get_by_left(&1), None);
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    /// [`try_insert`]: BiHashMap::try_insert
+    /// [`get_mut_by_right`]: BiHashMap::get_mut_by_right
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut L),
+    {
+        match self {
+            RightEntry::Occupied(mut entry) => {
+                entry.modify(f);
+                RightEntry::Occupied(entry)
+            }
+            RightEntry::Vacant(entry) => RightEntry::Vacant(entry),
+        }
+    }
+}
+
+/// A view into an occupied right value in a `BiHashMap`.
+///
+/// This struct is part of the [`RightEntry`] enum.
+pub struct OccupiedRightEntry<'a, L, R, LS, RS> {
+    map: &'a mut BiHashMap<L, R, LS, RS>,
+    right: R,
+    index: usize,
+}
+
+impl<'a, L, R, LS, RS> OccupiedRightEntry<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Returns a reference to the right value stored in the bimap.
+    pub fn key(&self) -> &R {
+        &self.map.rights[self.index]
+    }
+
+    /// Returns a reference to the left value corresponding to the entry's
+    /// right value.
+    pub fn get(&self) -> &L {
+        &self.map.lefts[self.index]
+    }
+
+    /// Converts the entry into a reference to its left value with the lifetime
+    /// of the bimap.
+    pub fn into_ref(self) -> &'a L {
+        &self.map.lefts[self.index]
+    }
+
+    /// Removes the entry's left-right pair from the bimap and returns it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{hash::RightEntry, BiHashMap};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let RightEntry::Occupied(entry) = bimap.entry_by_right(1) {
+    ///     assert_eq!(entry.remove(), ('a', 1));
+    /// }
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn remove(self) -> (L, R) {
+        self.map.remove_index(self.index)
+    }
+
+    /// Inserts the entry's right value with the given left value, replacing
+    /// the entry's previous left-right pair.
+    ///
+    /// The return value is the same as that of [`insert`], so it indicates any
+    /// other pair that was overwritten because it contained the left value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{hash::RightEntry, BiHashMap, Overwritten};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// if let RightEntry::Occupied(entry) = bimap.entry_by_right(1) {
+    ///     assert_eq!(entry.insert('b'), Overwritten::Both(('b', 2), ('a', 1)));
+    /// }
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'b'));
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    pub fn insert(self, left: L) -> Overwritten<L, R> {
+        self.map.insert(left, self.right)
+    }
+
+    fn modify<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut L),
+    {
+        let pair = self.map.remove_index(self.index);
+        // the pair is inserted back when the guard is dropped, even if `f`
+        // panics
+        let mut guard = Reinsert::new(&mut *self.map, pair, |map, left, right| {
+            map.insert(left, right);
+        });
+        f(guard.pair_mut().0);
+        drop(guard);
+        // unwrap is safe because the right value was just inserted
+        self.index = self.map.find_right(&self.right).unwrap();
+    }
+}
+
+/// A view into a vacant right value in a `BiHashMap`.
+///
+/// This struct is part of the [`RightEntry`] enum.
+pub struct VacantRightEntry<'a, L, R, LS, RS> {
+    map: &'a mut BiHashMap<L, R, LS, RS>,
+    right: R,
+}
+
+impl<'a, L, R, LS, RS> VacantRightEntry<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Returns a reference to the right value that would be inserted.
+    pub fn key(&self) -> &R {
+        &self.right
+    }
+
+    /// Takes ownership of the right value that would be inserted.
+    pub fn into_key(self) -> R {
+        self.right
+    }
+
+    /// Returns the left-right pair that would be overwritten if the given left
+    /// value was inserted into this entry, or `None` if there is no collision.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{hash::RightEntry, BiHashMap};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let RightEntry::Vacant(entry) = bimap.entry_by_right(2) {
+    ///     assert_eq!(entry.collision(&'a'), Some((&'a', &1)));
+    ///     assert_eq!(entry.collision(&'b'), None);
+    /// }
+    /// ```
+    pub fn collision<Q>(&self, left: &Q) -> Option<(&L, &R)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map
+            .find_left(left)
+            .map(|index| (&self.map.lefts[index], &self.map.rights[index]))
+    }
+
+    /// Inserts the entry's right value with the given left value.
+    ///
+    /// The return value is the same as that of [`insert`]. Since the right
+    /// value is vacant, it is either `Overwritten::Neither` or
+    /// `Overwritten::Left`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{hash::RightEntry, BiHashMap, Overwritten};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let RightEntry::Vacant(entry) = bimap.entry_by_right(2) {
+    ///     assert_eq!(entry.insert('a'), Overwritten::Left('a', 1));
+    /// }
+    /// assert_eq!(bimap.get_by_right(&2), Some(&'a'));
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    pub fn insert(self, left: L) -> Overwritten<L, R> {
+        self.map.insert(left, self.right)
+    }
+
+    /// Inserts the entry's right value with the given left value without
+    /// overwriting any existing pairs.
+    ///
+    /// Returns `Ok(())` if the pair was inserted. If the left value already
+    /// exists in the bimap, `Err((left, right))` is returned with the attempted
+    /// left-right pair and the bimap is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{hash::RightEntry, BiHashMap};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// if let RightEntry::Vacant(entry) = bimap.entry_by_right(2) {
+    ///     assert_eq!(entry.insert_no_overwrite('a'), Err(('a', 2)));
+    /// }
+    /// ```
+    pub fn insert_no_overwrite(self, left: L) -> Result<(), (L, R)> {
+        self.map.insert_no_overwrite(left, self.right)
+    }
+
+    fn insert_ref(self, left: L) -> &'a L {
+        let map = self.map;
+        map.remove_by_left(&left);
+        let index = map.insert_unchecked(left, self.right);
+        &map.lefts[index]
+    }
+}
+
+/// A guard granting mutable access to a right value in a `BiHashMap`.
+///
+/// This struct is created by the [`get_mut_by_left`] method of `BiHashMap`.
+/// Changes are only applied when the guard is committed, and discarded when it
+/// is dropped; see [`get_mut_by_left`] for how collisions are handled.
+///
+/// [`get_mut_by_left`]: BiHashMap::get_mut_by_left
+#[must_use = "changes made through the guard are discarded unless it is committed"]
+pub struct RightMut<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    map: &'a mut BiHashMap<L, R, LS, RS>,
+    index: usize,
+    // the right value when the guard was created, only taken by `finish`
+    original: Option<R>,
+}
+
+impl<'a, L, R, LS, RS> RightMut<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Returns a reference to the left value of the pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_mut_by_left(&'a').unwrap().left(), &'a');
+    /// ```
+    pub fn left(&self) -> &L {
+        &self.map.lefts[self.index]
+    }
+
+    /// Re-indexes the right value, removing any other pair with an equal right
+    /// value from the bimap.
+    ///
+    /// Returns the removed pair, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let mut right = bimap.get_mut_by_left(&'a').unwrap();
+    /// *right = 3;
+    /// assert_eq!(right.commit(), None);
+    ///
+    /// let mut right = bimap.get_mut_by_left(&'a').unwrap();
+    /// *right = 2;
+    /// assert_eq!(right.commit(), Some(('b', 2)));
+    /// assert_eq!(bimap.get_by_right(&2), Some(&'a'));
+    /// ```
+    pub fn commit(mut self) -> Option<(L, R)> {
+        // overwriting never fails
+        self.finish(true).ok().flatten()
+    }
+
+    /// Re-indexes the right value unless another pair has an equal right
+    /// value.
+    ///
+    /// Returns `Ok(())` if the right value was re-indexed. Otherwise, the
+    /// original right value is restored and the rejected one is returned as
+    /// `Err(right)`, so neither pair is affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let mut right = bimap.get_mut_by_left(&'a').unwrap();
+    /// *right = 2;
+    /// assert_eq!(right.commit_no_overwrite(), Err(2));
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.get_by_right(&2), Some(&'b'));
+    /// ```
+    pub fn commit_no_overwrite(mut self) -> Result<(), R> {
+        self.finish(false).map(|_| ())
+    }
+
+    /// Re-indexes the right value. If another pair has an equal right value,
+    /// that pair is removed and returned if `overwrite` is `true`, and the
+    /// original right value is restored otherwise, returning the rejected one.
+    fn finish(&mut self, overwrite: bool) -> Result<Option<(L, R)>, R> {
+        let original = match self.original.take() {
+            Some(original) => original,
+            None => return Ok(None),
+        };
+        let map = &mut *self.map;
+        let index = self.index;
+        let hash = map.hash_builder_right.hash_one(&map.rights[index]);
+        let rights = &map.rights;
+        let collision = map
+            .right2left
+            .find(hash, |&i| i != index && rights[i] == rights[index])
+            .copied();
+        if collision.is_some() && !overwrite {
+            // the index is still stored under the hash of the original value
+            return Err(mem::replace(&mut map.rights[index], original));
+        }
+
+        let original_hash = map.hash_builder_right.hash_one(&original);
+        erase_index(&mut map.right2left, original_hash, index);
+        // the index is indexed again even if there is a collision, so that
+        // removing the other pair leaves both tables consistent
+        map.right2left
+            .insert_unique(hash, index, rehasher(&map.rights, &map.hash_builder_right));
+        Ok(collision.map(|other| map.remove_index(other)))
+    }
+}
+
+impl<'a, L, R, LS, RS> fmt::Debug for RightMut<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: fmt::Debug + Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.map.rights[self.index].fmt(f)
+    }
+}
+
+impl<'a, L, R, LS, RS> Deref for RightMut<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        &self.map.rights[self.index]
+    }
+}
+
+impl<'a, L, R, LS, RS> DerefMut for RightMut<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn deref_mut(&mut self) -> &mut R {
+        &mut self.map.rights[self.index]
+    }
+}
+
+impl<'a, L, R, LS, RS> Drop for RightMut<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn drop(&mut self) {
+        // uncommitted changes are discarded
+        if let Some(original) = self.original.take() {
+            let right = mem::replace(&mut self.map.rights[self.index], original);
+            debug_assert_committed(right != self.map.rights[self.index], "RightMut");
+        }
+    }
+}
+
+/// A guard granting mutable access to a left value in a `BiHashMap`.
+///
+/// This struct is created by the [`get_mut_by_right`] method of `BiHashMap`.
+/// Changes are only applied when the guard is committed, and discarded when it
+/// is dropped; see [`get_mut_by_right`] for how collisions are handled.
+///
+/// [`get_mut_by_right`]: BiHashMap::get_mut_by_right
+#[must_use = "changes made through the guard are discarded unless it is committed"]
+pub struct LeftMut<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    map: &'a mut BiHashMap<L, R, LS, RS>,
+    index: usize,
+    // the left value when the guard was created, only taken by `finish`
+    original: Option<L>,
+}
+
+impl<'a, L, R, LS, RS> LeftMut<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Returns a reference to the right value of the pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_mut_by_right(&1).unwrap().right(), &1);
+    /// ```
+    pub fn right(&self) -> &R {
+        &self.map.rights[self.index]
+    }
+
+    /// Re-indexes the left value, removing any other pair with an equal left
+    /// value from the bimap.
+    ///
+    /// Returns the removed pair, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let mut left = bimap.get_mut_by_right(&1).unwrap();
+    /// *left = 'b';
+    /// assert_eq!(left.commit(), Some(('b', 2)));
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&1));
+    /// ```
+    pub fn commit(mut self) -> Option<(L, R)> {
+        // overwriting never fails
+        self.finish(true).ok().flatten()
+    }
+
+    /// Re-indexes the left value unless another pair has an equal left value.
+    ///
+    /// Returns `Ok(())` if the left value was re-indexed. Otherwise, the
+    /// original left value is restored and the rejected one is returned as
+    /// `Err(left)`, so neither pair is affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let mut left = bimap.get_mut_by_right(&1).unwrap();
+    /// *left = 'b';
+    /// assert_eq!(left.commit_no_overwrite(), Err('b'));
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+    ///
+    /// let mut left = bimap.get_mut_by_right(&1).unwrap();
+    /// *left = 'c';
+    /// assert_eq!(left.commit_no_overwrite(), Ok(()));
+    /// assert_eq!(bimap.get_by_left(&'c'), Some(&1));
+    /// ```
+    pub fn commit_no_overwrite(mut self) -> Result<(), L> {
+        self.finish(false).map(|_| ())
+    }
+
+    /// Re-indexes the left value. If another pair has an equal left value,
+    /// that pair is removed and returned if `overwrite` is `true`, and the
+    /// original left value is restored otherwise, returning the rejected one.
+    fn finish(&mut self, overwrite: bool) -> Result<Option<(L, R)>, L> {
+        let original = match self.original.take() {
+            Some(original) => original,
+            None => return Ok(None),
+        };
+        let map = &mut *self.map;
+        let index = self.index;
+        let hash = map.hash_builder_left.hash_one(&map.lefts[index]);
+        let lefts = &map.lefts;
+        let collision = map
+            .left2right
+            .find(hash, |&i| i != index && lefts[i] == lefts[index])
+            .copied();
+        if collision.is_some() && !overwrite {
+            // the index is still stored under the hash of the original value
+            return Err(mem::replace(&mut map.lefts[index], original));
+        }
+
+        let original_hash = map.hash_builder_left.hash_one(&original);
+        erase_index(&mut map.left2right, original_hash, index);
+        // the index is indexed again even if there is a collision, so that
+        // removing the other pair leaves both tables consistent
+        map.left2right
+            .insert_unique(hash, index, rehasher(&map.lefts, &map.hash_builder_left));
+        Ok(collision.map(|other| map.remove_index(other)))
+    }
+}
+
+impl<'a, L, R, LS, RS> fmt::Debug for LeftMut<'a, L, R, LS, RS>
+where
+    L: fmt::Debug + Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.map.lefts[self.index].fmt(f)
+    }
+}
+
+impl<'a, L, R, LS, RS> Deref for LeftMut<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    type Target = L;
+
+    fn deref(&self) -> &L {
+        &self.map.lefts[self.index]
+    }
+}
+
+impl<'a, L, R, LS, RS> DerefMut for LeftMut<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn deref_mut(&mut self) -> &mut L {
+        &mut self.map.lefts[self.index]
+    }
+}
+
+impl<'a, L, R, LS, RS> Drop for LeftMut<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn drop(&mut self) {
+        // uncommitted changes are discarded
+        if let Some(original) = self.original.take() {
+            let left = mem::replace(&mut self.map.lefts[self.index], original);
+            debug_assert_committed(left != self.map.lefts[self.index], "LeftMut");
+        }
+    }
+}
+
+/// A transaction on a `BiHashMap`, which rolls back its modifications when it
+/// is dropped unless it is committed.
+///
+/// Its modifying methods return the same owned values as the corresponding
+/// methods of `BiHashMap`. To be able to undo them, the transaction keeps a
+/// copy of the inserted left values and of the overwritten and removed pairs,
+/// so they require `L: Clone`, and `R: Clone` for the methods that may
+/// overwrite or remove pairs.
+///
+/// This struct is created by the [`transaction`] method of `BiHashMap`, and by
+/// the [`savepoint`] method of a transaction. It dereferences to the bimap, so
+/// the bimap can be read as usual while the transaction is in progress.
+///
+/// [`transaction`]: BiHashMap::transaction
+/// [`savepoint`]: Transaction::savepoint
+pub struct Transaction<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    bimap: &'a mut BiHashMap<L, R, LS, RS>,
+    // the modifications made by this transaction, in order
+    log: Vec<Undo<L, R>>,
+    // the log of the enclosing transaction, if this is a savepoint
+    parent: Option<&'a mut Vec<Undo<L, R>>>,
+}
+
+impl<'a, L, R, LS, RS> Transaction<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Inserts the given left-right pair into the bimap like
+    /// [`BiHashMap::insert`], and returns the overwritten pairs, if any.
+    ///
+    /// A copy of the left value and of the overwritten pairs is kept to undo
+    /// the insertion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, Overwritten};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut transaction = bimap.transaction();
+    /// assert_eq!(transaction.insert('b', 2), Overwritten::Neither);
+    /// assert_eq!(transaction.insert('a', 3), Overwritten::Left('a', 1));
+    /// assert_eq!(transaction.insert('b', 3), Overwritten::Both(('b', 2), ('a', 3)));
+    /// drop(transaction);
+    ///
+    /// assert_eq!(bimap.len(), 1);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        let overwritten = self.bimap.insert(left.clone(), right);
+        self.log.push(match overwritten {
+            Overwritten::Neither => Undo::Inserted(left),
+            ref overwritten => Undo::Overwritten(overwritten.clone()),
+        });
+        overwritten
+    }
+
+    /// Inserts the given left-right pair into the bimap like
+    /// [`BiHashMap::insert_no_overwrite`], failing if either value is already
+    /// in the bimap.
+    ///
+    /// A copy of the left value is kept to undo the insertion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut transaction = bimap.transaction();
+    /// assert_eq!(transaction.insert_no_overwrite('a', 2), Err(('a', 2)));
+    /// assert_eq!(transaction.insert_no_overwrite('b', 2), Ok(()));
+    /// transaction.commit();
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)>
+    where
+        L: Clone,
+    {
+        self.bimap.insert_no_overwrite(left.clone(), right)?;
+        self.log.push(Undo::Inserted(left));
+        Ok(())
+    }
+
+    /// Removes the left-right pair corresponding to the given left value like
+    /// [`BiHashMap::remove_by_left`], and returns it.
+    ///
+    /// A copy of the pair is kept to undo the removal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut transaction = bimap.transaction();
+    /// assert_eq!(transaction.remove_by_left(&'a'), Some(('a', 1)));
+    /// assert_eq!(transaction.remove_by_left(&'a'), None);
+    /// assert!(transaction.is_empty());
+    /// drop(transaction);
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let (left, right) = self.bimap.remove_by_left(left)?;
+        self.log.push(Undo::Removed(left.clone(), right.clone()));
+        Some((left, right))
+    }
+
+    /// Removes the left-right pair corresponding to the given right value
+    /// like [`BiHashMap::remove_by_right`], and returns it.
+    ///
+    /// A copy of the pair is kept to undo the removal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut transaction = bimap.transaction();
+    /// assert_eq!(transaction.remove_by_right(&1), Some(('a', 1)));
+    /// assert_eq!(transaction.remove_by_right(&1), None);
+    /// assert!(transaction.is_empty());
+    /// drop(transaction);
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let (left, right) = self.bimap.remove_by_right(right)?;
+        self.log.push(Undo::Removed(left.clone(), right.clone()));
+        Some((left, right))
+    }
+
+    /// Starts a nested transaction.
+    ///
+    /// Rolling back the nested transaction only undoes its own modifications.
+    /// Committing it hands them over to this transaction, which can still
+    /// roll them back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// let mut transaction = bimap.transaction();
+    /// transaction.insert('a', 1);
+    ///
+    /// let mut savepoint = transaction.savepoint();
+    /// savepoint.insert('b', 2);
+    /// savepoint.rollback();
+    ///
+    /// let mut savepoint = transaction.savepoint();
+    /// savepoint.insert('c', 3);
+    /// savepoint.commit();
+    ///
+    /// transaction.commit();
+    /// assert_eq!(bimap.len(), 2);
+    /// assert!(!bimap.contains_left(&'b'));
+    /// ```
+    pub fn savepoint(&mut self) -> Transaction<'_, L, R, LS, RS> {
+        Transaction {
+            bimap: self.bimap,
+            log: Vec::new(),
+            parent: Some(&mut self.log),
+        }
+    }
+
+    /// Keeps the modifications made by the transaction.
+    ///
+    /// If this is a nested transaction, its modifications are handed over to
+    /// the enclosing transaction instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// let mut transaction = bimap.transaction();
+    /// transaction.insert('a', 1);
+    /// transaction.commit();
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn commit(mut self) {
+        match self.parent.take() {
+            Some(parent) => parent.append(&mut self.log),
+            None => self.log.clear(),
+        }
+    }
+
+    /// Undoes the modifications made by the transaction, which is the same
+    /// as dropping it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut transaction = bimap.transaction();
+    /// transaction.remove_by_left(&'a');
+    /// transaction.rollback();
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn rollback(self) {}
+}
+
+impl<'a, L, R, LS, RS> Deref for Transaction<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    type Target = BiHashMap<L, R, LS, RS>;
+
+    fn deref(&self) -> &BiHashMap<L, R, LS, RS> {
+        self.bimap
+    }
+}
+
+impl<'a, L, R, LS, RS> Drop for Transaction<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn drop(&mut self) {
+        while let Some(undo) = self.log.pop() {
+            undo.undo(
+                self.bimap,
+                |bimap, left, right| {
+                    bimap.insert(left, right);
+                },
+                |bimap, left| {
+                    bimap.remove_by_left(left);
+                },
+            );
+        }
+    }
+}
+
+/// A view of a `BiHashMap<L, R>` as a bimap from `R` to `L`.
+///
+/// This struct is created by the [`inverse`] method of `BiHashMap`. Its left
+/// values are the right values of the underlying bimap and vice versa.
+///
+/// The view offers the read-only methods of `BiHashMap` with the sides
+/// swapped.
+///
+/// [`inverse`]: BiHashMap::inverse
+pub struct Inverse<'a, L, R, LS, RS> {
+    map: &'a BiHashMap<L, R, LS, RS>,
+}
+
+impl<'a, L, R, LS, RS> Inverse<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Returns the bimap this view was created from, with its left and right
+    /// values in their original orientation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let inverse = bimap.inverse();
+    /// assert_eq!(inverse.get_by_left(&1), Some(&'a'));
+    /// assert_eq!(inverse.as_map().get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn as_map(&self) -> &'a BiHashMap<L, R, LS, RS> {
+        self.map
+    }
+
+    /// Returns the number of left-right pairs in the bimap.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns a lower bound on the number of left-right pairs the bimap can
+    /// store without reallocating memory.
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Creates an iterator over the left-right pairs in the inverse bimap in
+    /// arbitrary order.
+    ///
+    /// The iterator element type is `(&R, &L)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let pairs: Vec<_> = bimap.inverse().iter().collect();
+    /// assert_eq!(pairs, [(&1, &'a')]);
+    /// ```
+    pub fn iter(&self) -> Iter<'a, R, L> {
+        Iter {
+            inner: self.map.rights.iter().zip(self.map.lefts.iter()),
+        }
+    }
+
+    /// Creates an iterator over the left values in the inverse bimap, which are
+    /// the right values of the underlying bimap, in arbitrary order.
+    pub fn left_values(&self) -> LeftValues<'a, R, L> {
+        LeftValues {
+            inner: self.map.rights.iter(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Creates an iterator over the right values in the inverse bimap, which
+    /// are the left values of the underlying bimap, in arbitrary order.
+    pub fn right_values(&self) -> RightValues<'a, R, L> {
+        RightValues {
+            inner: self.map.lefts.iter(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the right value corresponding to the given left
+    /// value of the inverse bimap.
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&'a L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get_by_right(left)
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value of the inverse bimap.
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&'a R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get_by_left(right)
+    }
+
+    /// Returns `true` if the inverse bimap contains the given left value and
+    /// `false` otherwise.
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.contains_right(left)
+    }
+
+    /// Returns `true` if the inverse bimap contains the given right value and
+    /// `false` otherwise.
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.contains_left(right)
+    }
+}
+
+impl<'a, L, R, LS, RS> Clone for Inverse<'a, L, R, LS, RS> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, L, R, LS, RS> Copy for Inverse<'a, L, R, LS, RS> {}
+
+impl<'a, L, R, LS, RS> fmt::Debug for Inverse<'a, L, R, LS, RS>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set()
+            .entries(
+                self.map
+                    .rights
+                    .iter()
+                    .zip(&self.map.lefts)
+                    .map(|(left, right)| EntryDebugger { left, right }),
+            )
+            .finish()
+    }
+}
+
+impl<'a, L, R, LS, RS> IntoIterator for Inverse<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    type Item = (&'a R, &'a L);
+    type IntoIter = Iter<'a, R, L>;
+
+    fn into_iter(self) -> Iter<'a, R, L> {
+        self.iter()
+    }
+}
+
+/// A mutable view of a `BiHashMap<L, R>` as a bimap from `R` to `L`.
+///
+/// This struct is created by the [`inverse_mut`] method of `BiHashMap`. Its
+/// left values are the right values of the underlying bimap and vice versa.
+///
+/// The view offers the methods of `BiHashMap` with the sides swapped, except
+/// for [`transaction`], which is only available on the underlying bimap
+/// through [`as_map_mut`]. The entries and guards it returns are those of the
+/// underlying bimap, so the pairs they hand back are in its orientation.
+///
+/// [`inverse_mut`]: BiHashMap::inverse_mut
+/// [`transaction`]: BiHashMap::transaction
+/// [`as_map_mut`]: InverseMut::as_map_mut
+pub struct InverseMut<'a, L, R, LS, RS> {
+    map: &'a mut BiHashMap<L, R, LS, RS>,
+}
+
+impl<'a, L, R, LS, RS> InverseMut<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Returns the bimap this view was created from, with its left and right
+    /// values in their original orientation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let inverse = bimap.inverse_mut();
+    /// assert_eq!(inverse.as_map().get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn as_map(&self) -> &BiHashMap<L, R, LS, RS> {
+        self.map
+    }
+
+    /// Returns the bimap this view was created from mutably, with its left
+    /// and right values in their original orientation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut inverse = bimap.inverse_mut();
+    /// inverse.as_map_mut().insert('b', 2);
+    /// assert_eq!(inverse.get_by_left(&2), Some(&'b'));
+    /// ```
+    pub fn as_map_mut(&mut self) -> &mut BiHashMap<L, R, LS, RS> {
+        self.map
+    }
+
+    /// Returns a shared view of the inverse bimap.
+    pub fn as_inverse(&self) -> Inverse<'_, L, R, LS, RS> {
+        self.map.inverse()
+    }
+
+    /// Returns the number of left-right pairs in the bimap.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns a lower bound on the number of left-right pairs the bimap can
+    /// store without reallocating memory.
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Removes all left-right pairs from the bimap.
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    /// Removes all pairs from the bimap and returns them in an iterator, in
+    /// the inverse orientation and arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let pairs: Vec<_> = bimap.inverse_mut().drain().collect();
+    /// assert_eq!(pairs, [(1, 'a')]);
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, R, L> {
+        self.map.left2right.clear();
+        self.map.right2left.clear();
+        Drain {
+            inner: self.map.rights.drain(..).zip(self.map.lefts.drain(..)),
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more left-right pairs. See
+    /// [`BiHashMap::reserve`] for details.
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more left-right
+    /// pairs. See [`BiHashMap::try_reserve`] for details.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.map.try_reserve(additional)
+    }
+
+    /// Shrinks the capacity of the bimap as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit()
+    }
+
+    /// Shrinks the capacity of the bimap with a lower limit.
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.map.shrink_to(min_capacity)
+    }
+
+    /// Creates an iterator over the left-right pairs in the inverse bimap in
+    /// arbitrary order.
+    ///
+    /// The iterator element type is `(&R, &L)`.
+    pub fn iter(&self) -> Iter<'_, R, L> {
+        self.as_inverse().iter()
+    }
+
+    /// Creates an iterator over the left values in the inverse bimap, which are
+    /// the right values of the underlying bimap, in arbitrary order.
+    pub fn left_values(&self) -> LeftValues<'_, R, L> {
+        self.as_inverse().left_values()
+    }
+
+    /// Creates an iterator over the right values in the inverse bimap, which
+    /// are the left values of the underlying bimap, in arbitrary order.
+    pub fn right_values(&self) -> RightValues<'_, R, L> {
+        self.as_inverse().right_values()
+    }
+
+    /// Returns a reference to the right value corresponding to the given left
+    /// value of the inverse bimap.
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get_by_right(left)
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value of the inverse bimap.
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.get_by_left(right)
+    }
+
+    /// Returns `true` if the inverse bimap contains the given left value and
+    /// `false` otherwise.
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.contains_right(left)
+    }
+
+    /// Returns `true` if the inverse bimap contains the given right value and
+    /// `false` otherwise.
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.contains_left(right)
+    }
+
+    /// Returns a guard that gives mutable access to the right value
+    /// corresponding to the given left value of the inverse bimap.
+    ///
+    /// The guard is the one returned by [`BiHashMap::get_mut_by_right`], so its
+    /// changes are only applied once it is committed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut inverse = bimap.inverse_mut();
+    /// let mut right = inverse.get_mut_by_left(&1).unwrap();
+    /// *right = 'b';
+    /// assert_eq!(right.commit_no_overwrite(), Ok(()));
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&1));
+    /// ```
+    pub fn get_mut_by_left<Q>(&mut self, left: &Q) -> Option<LeftMut<'_, L, R, LS, RS>>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        L: Clone,
+    {
+        self.map.get_mut_by_right(left)
+    }
+
+    /// Returns a guard that gives mutable access to the left value
+    /// corresponding to the given right value of the inverse bimap.
+    ///
+    /// The guard is the one returned by [`BiHashMap::get_mut_by_left`], so its
+    /// changes are only applied once it is committed.
+    pub fn get_mut_by_right<Q>(&mut self, right: &Q) -> Option<RightMut<'_, L, R, LS, RS>>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        R: Clone,
+    {
+        self.map.get_mut_by_left(right)
+    }
+
+    /// Gets the entry corresponding to the given left value of the inverse
+    /// bimap for in-place manipulation.
+    ///
+    /// This is the entry returned by [`BiHashMap::entry_by_right`], so the
+    /// pairs it hands back are in the orientation of the underlying bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.inverse_mut().entry_by_left(1).or_insert('a');
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn entry_by_left(&mut self, left: R) -> RightEntry<'_, L, R, LS, RS> {
+        self.map.entry_by_right(left)
+    }
+
+    /// Gets the entry corresponding to the given right value of the inverse
+    /// bimap for in-place manipulation.
+    ///
+    /// This is the entry returned by [`BiHashMap::entry_by_left`], so the
+    /// pairs it hands back are in the orientation of the underlying bimap.
+    pub fn entry_by_right(&mut self, right: L) -> LeftEntry<'_, L, R, LS, RS> {
+        self.map.entry_by_left(right)
+    }
+
+    /// Removes the left-right pair corresponding to the given left value of the
+    /// inverse bimap.
+    ///
+    /// Returns the previous pair, in the inverse orientation, if the bimap
+    /// contained the value and `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// assert_eq!(bimap.inverse_mut().remove_by_left(&1), Some((1, 'a')));
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(R, L)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.remove_by_right(left).map(|(l, r)| (r, l))
+    }
+
+    /// Removes the left-right pair corresponding to the given right value of
+    /// the inverse bimap.
+    ///
+    /// Returns the previous pair, in the inverse orientation, if the bimap
+    /// contained the value and `None` otherwise.
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(R, L)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.map.remove_by_left(right).map(|(l, r)| (r, l))
+    }
+
+    /// Inserts the given left-right pair into the inverse bimap.
+    ///
+    /// Returns the overwritten pairs, in the inverse orientation. See
+    /// [`BiHashMap::insert`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, Overwritten};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut inverse = bimap.inverse_mut();
+    /// assert_eq!(inverse.insert(1, 'b'), Overwritten::Left(1, 'a'));
+    /// assert_eq!(inverse.insert(2, 'b'), Overwritten::Right(1, 'b'));
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    /// ```
+    pub fn insert(&mut self, left: R, right: L) -> Overwritten<R, L> {
+        self.map.insert(right, left).inverse()
+    }
+
+    /// Inserts the given left-right pair into the inverse bimap without
+    /// overwriting any existing values.
+    ///
+    /// If either value exists in the bimap, `Err((left, right))` is returned
+    /// with the attempted pair and the bimap is unchanged.
+    pub fn insert_no_overwrite(&mut self, left: R, right: L) -> Result<(), (R, L)> {
+        self.map
+            .insert_no_overwrite(right, left)
+            .map_err(|(l, r)| (r, l))
+    }
+
+    /// Inserts the given left-right pair into the inverse bimap if neither
+    /// value is already in it.
+    ///
+    /// Otherwise the bimap is unchanged, and an [`InsertError`] in the inverse
+    /// orientation is returned. See [`BiHashMap::try_insert`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, Collision};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut inverse = bimap.inverse_mut();
+    /// let error = inverse.try_insert(1, 'b').unwrap_err();
+    /// assert_eq!(error.pair, (1, 'b'));
+    /// assert_eq!(error.collision, Collision::Left(&1, &'a'));
+    /// ```
+    pub fn try_insert(&mut self, left: R, right: L) -> Result<(), InsertError<'_, R, L>> {
+        self.map
+            .try_insert(right, left)
+            .map_err(InsertError::inverse)
+    }
+
+    /// Inserts all left-right pairs of the given batch into the inverse bimap,
+    /// or none of them if any pair would overwrite an existing value.
+    ///
+    /// On failure, the [`BatchInsertError`] is in the inverse orientation. See
+    /// [`BiHashMap::insert_all_no_overwrite`] for details.
+    pub fn insert_all_no_overwrite<I>(&mut self, iter: I) -> Result<(), BatchInsertError<R, L>>
+    where
+        I: IntoIterator<Item = (R, L)>,
+    {
+        self.map
+            .insert_all_no_overwrite(iter.into_iter().map(|(r, l)| (l, r)))
+            .map_err(BatchInsertError::inverse)
+    }
+
+    /// Retains only the pairs of the inverse bimap for which `f` returns
+    /// `true`.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&R, &L) -> bool,
+    {
+        let mut f = f;
+        self.map.retain(|l, r| f(r, l))
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.inner.size_hint()
+    /// Creates an iterator which uses a predicate to decide whether a pair of
+    /// the inverse bimap should be removed, yielding the removed pairs in the
+    /// inverse orientation. See [`BiHashMap::extract_if`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap: BiHashMap<_, _> = (0..4).map(|i| (i, i * 10)).collect();
+    ///
+    /// let mut large: Vec<_> = bimap.inverse_mut().extract_if(|&r, _| r >= 20).collect();
+    /// large.sort();
+    /// assert_eq!(large, [(20, 2), (30, 3)]);
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn extract_if<F>(&mut self, pred: F) -> InverseExtractIf<'_, L, R, LS, RS, F>
+    where
+        F: FnMut(&R, &L) -> bool,
+    {
+        InverseExtractIf {
+            map: self.map,
+            index: 0,
+            pred,
+        }
     }
 }
 
-// safe because internal Rcs are not exposed by the api and the reference counts
-// only change in methods with &mut self
-unsafe impl<L, R, LS, RS> Send for BiHashMap<L, R, LS, RS>
-where
-    L: Send,
-    R: Send,
-    LS: Send,
-    RS: Send,
-{
-}
-unsafe impl<L, R, LS, RS> Sync for BiHashMap<L, R, LS, RS>
+impl<'a, L, R, LS, RS> fmt::Debug for InverseMut<'a, L, R, LS, RS>
 where
-    L: Sync,
-    R: Sync,
-    LS: Sync,
-    RS: Sync,
+    L: fmt::Debug,
+    R: fmt::Debug,
 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Inverse { map: &*self.map }.fmt(f)
+    }
 }
 
 #[cfg(test)]
@@ -1043,4 +3954,775 @@ mod tests {
         assert_eq!(bimap.len(), 1);
         assert_eq!(i, 3);
     }
+
+    /// Checks that every pair can be found from both of its values.
+    fn assert_consistent<L, R>(bimap: &BiHashMap<L, R>)
+    where
+        L: Eq + Hash + fmt::Debug,
+        R: Eq + Hash + fmt::Debug,
+    {
+        assert_eq!(bimap.left2right.len(), bimap.len());
+        assert_eq!(bimap.right2left.len(), bimap.len());
+        for (left, right) in bimap {
+            assert_eq!(bimap.get_by_left(left), Some(right));
+            assert_eq!(bimap.get_by_right(right), Some(left));
+        }
+    }
+
+    #[test]
+    fn retain_panic() {
+        let mut bimap: BiHashMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut calls = 0;
+            bimap.retain(|_, _| {
+                calls += 1;
+                assert!(calls < 6);
+                calls % 2 == 0
+            });
+        }));
+        assert!(result.is_err());
+        assert_eq!(bimap.len(), 7);
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn drain() {
+        let mut bimap: BiHashMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+        let mut pairs: Vec<_> = bimap.drain().collect();
+        pairs.sort_unstable();
+        assert_eq!(pairs, (0..10).map(|i| (i, i * 10)).collect::<Vec<_>>());
+        assert!(bimap.is_empty());
+        assert_consistent(&bimap);
+
+        bimap.extend((0..10).map(|i| (i, i * 10)));
+        let mut drain = bimap.drain();
+        assert_eq!(drain.len(), 10);
+        drain.next();
+        drain.next_back();
+        drop(drain);
+        assert!(bimap.is_empty());
+        assert_consistent(&bimap);
+
+        bimap.insert(1, 2);
+        assert_eq!(bimap.get_by_right(&2), Some(&1));
+    }
+
+    #[test]
+    fn extract_if() {
+        let mut bimap: BiHashMap<_, _> = (0..100).map(|i| (i, i * 10)).collect();
+        let mut extracted: Vec<_> = bimap.extract_if(|&l, _| l % 3 == 0).collect();
+        extracted.sort_unstable();
+        assert_eq!(
+            extracted,
+            (0..100)
+                .filter(|l| l % 3 == 0)
+                .map(|i| (i, i * 10))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(bimap.len(), 66);
+        assert!(bimap.left_values().all(|l| l % 3 != 0));
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn extract_if_calls_pred_once() {
+        let mut bimap: BiHashMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+        let mut seen = Vec::new();
+        let extracted = bimap
+            .extract_if(|&l, _| {
+                seen.push(l);
+                l < 5
+            })
+            .count();
+        assert_eq!(extracted, 5);
+        seen.sort_unstable();
+        assert_eq!(seen, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn extract_if_dropped_early() {
+        let mut bimap: BiHashMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+        assert_eq!(bimap.extract_if(|_, _| true).take(2).count(), 2);
+        assert_eq!(bimap.len(), 8);
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn extract_if_panic() {
+        let mut bimap: BiHashMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut calls = 0;
+            bimap
+                .extract_if(|_, _| {
+                    calls += 1;
+                    assert!(calls < 6);
+                    calls % 2 == 0
+                })
+                .for_each(drop);
+        }));
+        assert!(result.is_err());
+        assert_eq!(bimap.len(), 8);
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn remove_keeps_indices_consistent() {
+        let mut bimap: BiHashMap<_, _> = (0..100).map(|i| (i, i * 10)).collect();
+
+        // removing from the front moves the last pair into the vacated slot
+        for i in 0..50 {
+            assert_eq!(bimap.remove_by_left(&i), Some((i, i * 10)));
+        }
+        for i in 50..75 {
+            assert_eq!(bimap.remove_by_right(&(i * 10)), Some((i, i * 10)));
+        }
+        assert_eq!(bimap.len(), 25);
+        for i in 75..100 {
+            assert_eq!(bimap.get_by_left(&i), Some(&(i * 10)));
+            assert_eq!(bimap.get_by_right(&(i * 10)), Some(&i));
+        }
+
+        bimap.retain(|l, _| l % 2 == 0);
+        assert_eq!(bimap.len(), 12);
+        for i in 75..100 {
+            assert_eq!(bimap.contains_left(&i), i % 2 == 0);
+            assert_eq!(bimap.contains_right(&(i * 10)), i % 2 == 0);
+        }
+    }
+
+    #[test]
+    fn entry_by_left() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+
+        match bimap.entry_by_left('a') {
+            LeftEntry::Occupied(entry) => {
+                assert_eq!(entry.key(), &'a');
+                assert_eq!(entry.get(), &1);
+            }
+            LeftEntry::Vacant(_) => panic!("entry should be occupied"),
+        }
+
+        match bimap.entry_by_left('b') {
+            LeftEntry::Occupied(_) => panic!("entry should be vacant"),
+            LeftEntry::Vacant(entry) => {
+                assert_eq!(entry.collision(&1), Some((&'a', &1)));
+                assert_eq!(entry.insert(1), Overwritten::Right('a', 1));
+            }
+        }
+        assert_eq!(bimap.len(), 1);
+
+        // or_insert overwrites a colliding right value like insert does
+        assert_eq!(*bimap.entry_by_left('c').or_insert(1), 1);
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.get_by_right(&1), Some(&'c'));
+
+        // and_modify keeps both directions in sync
+        bimap.entry_by_left('c').and_modify(|r| *r = 5);
+        assert_eq!(bimap.get_by_left(&'c'), Some(&5));
+        assert_eq!(bimap.get_by_right(&5), Some(&'c'));
+        assert_eq!(bimap.get_by_right(&1), None);
+    }
+
+    #[test]
+    fn entry_by_right() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+
+        match bimap.entry_by_right(1) {
+            RightEntry::Occupied(entry) => assert_eq!(entry.remove(), ('a', 1)),
+            RightEntry::Vacant(_) => panic!("entry should be occupied"),
+        }
+        assert!(bimap.is_empty());
+
+        assert_eq!(*bimap.entry_by_right(2).or_insert_with(|| 'b'), 'b');
+        match bimap.entry_by_right(3) {
+            RightEntry::Occupied(_) => panic!("entry should be vacant"),
+            RightEntry::Vacant(entry) => {
+                assert_eq!(entry.insert_no_overwrite('b'), Err(('b', 3)));
+            }
+        }
+        assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    }
+
+    #[test]
+    fn and_modify_panic() {
+        let mut bimap: BiHashMap<_, _> = (0..5).map(|i| (i, i * 10)).collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            bimap.entry_by_left(2).and_modify(|r| {
+                *r += 1;
+                panic!("modify");
+            });
+        }));
+        assert!(result.is_err());
+        assert_eq!(bimap.len(), 5);
+        assert_eq!(bimap.get_by_left(&2), Some(&21));
+        assert_eq!(bimap.get_by_right(&21), Some(&2));
+        assert_eq!(bimap.get_by_right(&20), None);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            bimap.entry_by_right(30).and_modify(|_| panic!("modify"));
+        }));
+        assert!(result.is_err());
+        assert_eq!(bimap.len(), 5);
+        assert_eq!(bimap.get_by_right(&30), Some(&3));
+        assert_eq!(bimap.get_by_left(&3), Some(&30));
+        assert_consistent(&bimap);
+    }
+    #[test]
+    fn borrow_string_str() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert(String::from("a"), String::from("b"));
+
+        assert_eq!(bimap.get_by_left("a"), Some(&String::from("b")));
+        assert_eq!(bimap.get_by_right("b"), Some(&String::from("a")));
+        assert!(bimap.contains_left("a"));
+        assert!(bimap.contains_right("b"));
+        assert!(!bimap.contains_left("b"));
+        assert!(!bimap.contains_right("a"));
+
+        assert_eq!(
+            bimap.remove_by_left("a"),
+            Some((String::from("a"), String::from("b")))
+        );
+        bimap.insert(String::from("a"), String::from("b"));
+        assert_eq!(
+            bimap.remove_by_right("b"),
+            Some((String::from("a"), String::from("b")))
+        );
+        assert!(bimap.is_empty());
+    }
+
+    #[test]
+    fn borrow_vec_slice() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert(vec![1, 2], vec!['a', 'b']);
+
+        assert_eq!(bimap.get_by_left(&[1, 2][..]), Some(&vec!['a', 'b']));
+        assert_eq!(bimap.get_by_right(&['a', 'b'][..]), Some(&vec![1, 2]));
+        assert!(bimap.contains_left(&[1, 2][..]));
+        assert!(bimap.contains_right(&['a', 'b'][..]));
+        assert_eq!(
+            bimap.remove_by_left(&[1, 2][..]),
+            Some((vec![1, 2], vec!['a', 'b']))
+        );
+        assert!(bimap.is_empty());
+    }
+
+    #[test]
+    fn borrow_pathbuf_path() {
+        use std::path::{Path, PathBuf};
+
+        let mut bimap = BiHashMap::new();
+        bimap.insert(PathBuf::from("/usr/bin"), 1);
+
+        assert_eq!(bimap.get_by_left(Path::new("/usr/bin")), Some(&1));
+        assert!(bimap.contains_left(Path::new("/usr/bin")));
+        assert!(!bimap.contains_left(Path::new("/usr/lib")));
+        assert_eq!(
+            bimap.remove_by_left(Path::new("/usr/bin")),
+            Some((PathBuf::from("/usr/bin"), 1))
+        );
+        assert!(bimap.is_empty());
+    }
+
+    #[test]
+    fn inverse() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        let inverse = bimap.inverse();
+
+        assert_eq!(inverse.len(), 2);
+        assert_eq!(inverse.get_by_left(&1), Some(&'a'));
+        assert_eq!(inverse.get_by_right(&'b'), Some(&2));
+        assert!(inverse.contains_left(&2));
+        assert!(!inverse.contains_right(&'c'));
+
+        let mut pairs: Vec<_> = inverse.iter().collect();
+        pairs.sort();
+        assert_eq!(pairs, [(&1, &'a'), (&2, &'b')]);
+        let mut lefts: Vec<_> = inverse.left_values().collect();
+        lefts.sort();
+        assert_eq!(lefts, [&1, &2]);
+        let mut rights: Vec<_> = inverse.right_values().collect();
+        rights.sort();
+        assert_eq!(rights, [&'a', &'b']);
+
+        assert_eq!(inverse.as_map(), &bimap);
+    }
+
+    #[test]
+    fn inverse_debug() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+
+        assert_eq!(format!("{:?}", bimap.inverse()), "{1 <> 'a'}");
+        assert_eq!(format!("{:?}", bimap.inverse_mut()), "{1 <> 'a'}");
+    }
+
+    #[test]
+    fn inverse_mut() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        let mut inverse = bimap.inverse_mut();
+        assert_eq!(inverse.insert(4, 'd'), Overwritten::Neither);
+        assert_eq!(
+            inverse.insert(1, 'b'),
+            Overwritten::Both((1, 'a'), (2, 'b'))
+        );
+        assert_eq!(inverse.insert_no_overwrite(3, 'e'), Err((3, 'e')));
+        assert_eq!(inverse.remove_by_left(&3), Some((3, 'c')));
+        assert_eq!(inverse.remove_by_right(&'d'), Some((4, 'd')));
+        inverse.retain(|&left, _| left != 1);
+        assert!(inverse.is_empty());
+
+        inverse.insert(5, 'e');
+        assert_eq!(bimap.get_by_left(&'e'), Some(&5));
+        assert_eq!(bimap.len(), 1);
+    }
+
+    #[test]
+    fn inverse_mut_forwarding() {
+        let mut bimap: BiHashMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+        let mut inverse = bimap.inverse_mut();
+
+        inverse.reserve(10);
+        assert!(inverse.capacity() >= 20);
+        assert!(inverse.as_inverse().capacity() >= 20);
+
+        assert_eq!(inverse.entry_by_left(10).or_insert(11), &1);
+        inverse.entry_by_left(100).or_insert(10);
+        let mut right = inverse.get_mut_by_left(&20).unwrap();
+        *right = 12;
+        assert_eq!(right.commit_no_overwrite(), Ok(()));
+        let mut left = inverse.get_mut_by_right(&3).unwrap();
+        *left = 13;
+        assert_eq!(left.commit_no_overwrite(), Ok(()));
+        assert_eq!(inverse.get_by_left(&10), Some(&1));
+        assert_eq!(inverse.get_by_left(&100), Some(&10));
+        assert_eq!(inverse.get_by_left(&20), Some(&12));
+        assert_eq!(inverse.get_by_right(&3), Some(&13));
+
+        let mut extracted: Vec<_> = inverse.extract_if(|&r, _| r >= 60).collect();
+        extracted.sort_unstable();
+        assert_eq!(extracted, [(60, 6), (70, 7), (80, 8), (90, 9), (100, 10)]);
+        assert_eq!(inverse.len(), 6);
+        assert_consistent(inverse.as_map());
+
+        let mut drained: Vec<_> = inverse.drain().collect();
+        drained.sort_unstable();
+        assert_eq!(
+            drained,
+            [(0, 0), (10, 1), (13, 3), (20, 12), (40, 4), (50, 5)]
+        );
+        inverse.shrink_to_fit();
+        assert!(bimap.is_empty());
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn inverse_mut_insert_errors() {
+        let bimap: BiHashMap<_, _> = (0..5).map(|i| (i, i * 10)).collect();
+        for &(left, right) in &[(10, 2), (10, 1), (50, 3), (20, 9)] {
+            let mut expected = bimap.clone().into_inverse();
+            let expected = expected.try_insert(left, right).unwrap_err().into_owned();
+            let mut actual = bimap.clone();
+            let mut inverse = actual.inverse_mut();
+            let actual = inverse.try_insert(left, right).unwrap_err().into_owned();
+            assert_eq!(actual, expected);
+        }
+
+        let batch = vec![
+            (50, 5),
+            (10, 6),
+            (60, 1),
+            (70, 7),
+            (70, 8),
+            (0, 9),
+            (80, 7),
+            (10, 2),
+        ];
+        let mut expected = bimap.clone().into_inverse();
+        let mut actual = bimap.clone();
+        assert_eq!(
+            actual.inverse_mut().insert_all_no_overwrite(batch.clone()),
+            expected.insert_all_no_overwrite(batch)
+        );
+        assert_eq!(actual, bimap);
+        assert_eq!(
+            actual
+                .inverse_mut()
+                .insert_all_no_overwrite(vec![(50, 5), (60, 6)]),
+            Ok(())
+        );
+        assert_eq!(actual.get_by_left(&6), Some(&60));
+    }
+
+    #[test]
+    fn into_inverse() {
+        let bimap: BiHashMap<_, _> = (0..10).map(|i| (i, i.to_string())).collect();
+        let mut inverse = bimap.clone().into_inverse();
+
+        assert_eq!(inverse.len(), 10);
+        for i in 0..10 {
+            assert_eq!(inverse.get_by_left(&i.to_string()), Some(&i));
+            assert_eq!(inverse.get_by_right(&i), Some(&i.to_string()));
+        }
+        inverse.remove_by_left("3");
+        assert_eq!(
+            inverse.insert(String::from("a"), 0),
+            Overwritten::Right(String::from("0"), 0)
+        );
+        assert_eq!(inverse.into_inverse().len(), 9);
+    }
+
+    #[test]
+    fn get_mut_by_left() {
+        let mut bimap: BiHashMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+
+        assert!(bimap.get_mut_by_left(&10).is_none());
+        let mut right = bimap.get_mut_by_left(&0).unwrap();
+        *right += 5;
+        assert_eq!(right.commit_no_overwrite(), Ok(()));
+        assert_eq!(bimap.get_by_left(&0), Some(&5));
+        assert_eq!(bimap.get_by_right(&5), Some(&0));
+        assert!(!bimap.contains_right(&0));
+
+        // a collision is rejected without affecting either pair
+        let mut right = bimap.get_mut_by_left(&0).unwrap();
+        *right = 90;
+        assert_eq!(right.commit_no_overwrite(), Err(90));
+        assert_eq!(bimap.len(), 10);
+        assert_eq!(bimap.get_by_left(&0), Some(&5));
+        assert_eq!(bimap.get_by_right(&5), Some(&0));
+        assert_eq!(bimap.get_by_right(&90), Some(&9));
+
+        let mut right = bimap.get_mut_by_left(&1).unwrap();
+        assert_eq!(right.left(), &1);
+        *right = 20;
+        assert_eq!(right.commit(), Some((2, 20)));
+        assert_eq!(bimap.len(), 9);
+
+        let mut right = bimap.get_mut_by_left(&1).unwrap();
+        *right = 30;
+        assert_eq!(right.commit_no_overwrite(), Err(30));
+        assert_eq!(bimap.len(), 9);
+        assert_eq!(bimap.get_by_left(&1), Some(&20));
+        assert_eq!(bimap.get_by_right(&20), Some(&1));
+        assert_eq!(bimap.get_by_right(&30), Some(&3));
+
+        for (left, right) in bimap.clone() {
+            assert_eq!(bimap.get_by_left(&left), Some(&right));
+            assert_eq!(bimap.get_by_right(&right), Some(&left));
+        }
+    }
+
+    #[test]
+    fn get_mut_by_right() {
+        let mut bimap: BiHashMap<_, _> = (0..10).map(|i| (i * 10, i)).collect();
+
+        assert!(bimap.get_mut_by_right(&10).is_none());
+        let mut left = bimap.get_mut_by_right(&0).unwrap();
+        *left += 5;
+        assert_eq!(left.commit_no_overwrite(), Ok(()));
+        assert_eq!(bimap.get_by_right(&0), Some(&5));
+        assert_eq!(bimap.get_by_left(&5), Some(&0));
+
+        // a collision is rejected without affecting either pair
+        let mut left = bimap.get_mut_by_right(&0).unwrap();
+        *left = 90;
+        assert_eq!(left.commit_no_overwrite(), Err(90));
+        assert_eq!(bimap.len(), 10);
+        assert_eq!(bimap.get_by_right(&0), Some(&5));
+        assert_eq!(bimap.get_by_left(&90), Some(&9));
+
+        let mut left = bimap.get_mut_by_right(&1).unwrap();
+        assert_eq!(left.right(), &1);
+        *left = 20;
+        assert_eq!(left.commit(), Some((20, 2)));
+        assert_eq!(bimap.len(), 9);
+
+        let mut left = bimap.get_mut_by_right(&1).unwrap();
+        *left = 30;
+        assert_eq!(left.commit_no_overwrite(), Err(30));
+        assert_eq!(bimap.len(), 9);
+        assert_eq!(bimap.get_by_right(&1), Some(&20));
+        assert_eq!(bimap.get_by_left(&20), Some(&1));
+        assert_eq!(bimap.get_by_left(&30), Some(&3));
+
+        for (left, right) in bimap.clone() {
+            assert_eq!(bimap.get_by_left(&left), Some(&right));
+            assert_eq!(bimap.get_by_right(&right), Some(&left));
+        }
+    }
+
+    #[test]
+    fn get_mut_dropped_without_commit() {
+        let mut bimap: BiHashMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+
+        // dropping an unchanged guard is fine
+        drop(bimap.get_mut_by_left(&0).unwrap());
+
+        // a changed guard is discarded, and panics in debug builds
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            *bimap.get_mut_by_left(&0).unwrap() = 5;
+        }));
+        assert_eq!(result.is_err(), cfg!(debug_assertions));
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            *bimap.get_mut_by_right(&90).unwrap() = 1;
+        }));
+        assert_eq!(result.is_err(), cfg!(debug_assertions));
+
+        assert_eq!(bimap.len(), 10);
+        assert_eq!(bimap.get_by_left(&0), Some(&0));
+        assert_eq!(bimap.get_by_right(&90), Some(&9));
+        assert_consistent(&bimap);
+    }
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn hash() {
+        use std::collections::HashSet;
+
+        let mut hashset = HashSet::new();
+        hashset.insert(BiHashMap::new());
+        hashset.insert(BiHashMap::from_iter(iter::once((0, '0'))));
+        hashset.insert(BiHashMap::from_iter(vec![(0, '0'), (0, '1'), (1, '0')]));
+        hashset.insert(BiHashMap::from_iter(vec![(1, '0'), (0, '1'), (0, '0')]));
+        hashset.insert(BiHashMap::from_iter(vec![(0, '0'), (0, '1'), (1, '0')]));
+
+        assert_eq!(
+            hashset,
+            HashSet::from_iter(vec![
+                BiHashMap::new(),
+                BiHashMap::from_iter(iter::once((0, '0'))),
+                BiHashMap::from_iter(vec![(0, '0'), (1, '0'), (0, '1')]),
+            ])
+        );
+    }
+
+    #[test]
+    fn ord() {
+        let a: BiHashMap<_, _> = vec![(1, 'a'), (2, 'b')].into_iter().collect();
+        let b: BiHashMap<_, _> = vec![(2, 'b'), (1, 'c')].into_iter().collect();
+        let c: BiHashMap<_, _> = vec![(2, 'b'), (1, 'a'), (3, 'c')].into_iter().collect();
+
+        assert!(a < b);
+        assert!(a < c);
+        assert!(c < b);
+        assert!(BiHashMap::new() < a);
+        assert_eq!(a.cmp(&a.clone()), Ordering::Equal);
+    }
+
+    #[test]
+    fn hash_is_independent_of_order_and_capacity() {
+        fn prop(pairs: Vec<(u8, u16)>, rotation: usize, capacity: u8, noise: Vec<u8>) -> bool {
+            let bimap: BiHashMap<_, _> = pairs.into_iter().collect();
+            let mut pairs: Vec<_> = bimap.iter().map(|(&l, &r)| (l, r)).collect();
+
+            // the same pairs in another order, with a different capacity
+            if !pairs.is_empty() {
+                let mid = rotation % pairs.len();
+                pairs.rotate_left(mid);
+            }
+            pairs.reverse();
+            let mut other = BiHashMap::with_capacity(capacity.into());
+            other.extend(pairs);
+
+            // pairs that are inserted and removed again leave no trace
+            for &left in &noise {
+                if !bimap.contains_left(&left) {
+                    other.insert_no_overwrite(left, u16::MAX).ok();
+                    other.remove_by_left(&left);
+                }
+            }
+
+            bimap == other && hash_of(&bimap) == hash_of(&other)
+        }
+        quickcheck::quickcheck(prop as fn(Vec<(u8, u16)>, usize, u8, Vec<u8>) -> bool);
+    }
+
+    #[test]
+    fn ord_matches_btree() {
+        fn prop(a: Vec<(u8, u8)>, b: Vec<(u8, u8)>) -> bool {
+            let (hash_a, hash_b): (BiHashMap<_, _>, BiHashMap<_, _>) =
+                (a.iter().copied().collect(), b.iter().copied().collect());
+            let (btree_a, btree_b): (crate::BiBTreeMap<_, _>, crate::BiBTreeMap<_, _>) =
+                (a.into_iter().collect(), b.into_iter().collect());
+            hash_a.cmp(&hash_b) == btree_a.cmp(&btree_b)
+                && (hash_a == hash_b) == (hash_a.cmp(&hash_b) == Ordering::Equal)
+        }
+        quickcheck::quickcheck(prop as fn(Vec<(u8, u8)>, Vec<(u8, u8)>) -> bool);
+    }
+
+    #[test]
+    fn reserve_and_shrink() {
+        let mut bimap: BiHashMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+        bimap.reserve(100);
+        assert!(bimap.capacity() >= 110);
+        assert!(bimap.try_reserve(200).is_ok());
+        assert!(bimap.capacity() >= 210);
+
+        bimap.shrink_to(50);
+        assert!(bimap.capacity() >= 50);
+        bimap.shrink_to_fit();
+        assert!(bimap.capacity() >= 10);
+        assert!(bimap.capacity() < 50);
+
+        // the indices survive rehashing
+        for i in 0..10 {
+            assert_eq!(bimap.get_by_left(&i), Some(&(i * 10)));
+            assert_eq!(bimap.get_by_right(&(i * 10)), Some(&i));
+        }
+    }
+
+    #[test]
+    fn try_reserve_error() {
+        use std::error::Error;
+
+        let mut bimap: BiHashMap<_, _> = (0..10).map(|i| (i, i)).collect();
+        let err = bimap.try_reserve(usize::MAX).unwrap_err();
+        assert!(err.to_string().starts_with("memory allocation failed"));
+        assert!(err.source().is_some());
+        assert_eq!(bimap.len(), 10);
+        assert_eq!(bimap.get_by_left(&3), Some(&3));
+
+        // the vectors can hold this many zero-sized values, but the tables can't
+        let mut bimap = BiHashMap::<(), ()>::new();
+        let err = bimap.try_reserve(usize::MAX).unwrap_err();
+        assert!(err.to_string().contains("capacity exceeded"));
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn try_insert() {
+        let mut bimap = BiHashMap::new();
+        assert_eq!(bimap.try_insert('a', 1), Ok(()));
+        assert_eq!(bimap.try_insert('b', 2), Ok(()));
+
+        let cases = [
+            (('a', 1), Collision::Pair(&'a', &1)),
+            (('a', 3), Collision::Left(&'a', &1)),
+            (('c', 2), Collision::Right(&'b', &2)),
+            (('b', 1), Collision::Both((&'b', &2), (&'a', &1))),
+        ];
+        for (pair, collision) in cases {
+            assert_eq!(
+                bimap.try_insert(pair.0, pair.1),
+                Err(InsertError { pair, collision })
+            );
+        }
+        assert_eq!(bimap.len(), 2);
+        assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+        assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    }
+
+    #[test]
+    fn insert_all_no_overwrite() {
+        let mut bimap: BiHashMap<_, _> = vec![(0, 'a'), (1, 'b')].into_iter().collect();
+        let batch = vec![(2, 'c'), (1, 'b'), (3, 'c'), (2, 'd'), (4, 'e'), (4, 'e')];
+        let err = bimap.insert_all_no_overwrite(batch.clone()).unwrap_err();
+        assert_eq!(err.pairs, batch);
+        assert_eq!(
+            err.conflicts,
+            [
+                BatchConflict::Left(1),
+                BatchConflict::Right(1),
+                BatchConflict::DuplicateRight(0, 2),
+                BatchConflict::DuplicateLeft(0, 3),
+                BatchConflict::DuplicateLeft(4, 5),
+                BatchConflict::DuplicateRight(4, 5),
+            ]
+        );
+        assert_eq!(bimap.len(), 2);
+        assert!(!bimap.contains_left(&2));
+
+        assert_eq!(bimap.insert_all_no_overwrite(Vec::new()), Ok(()));
+        assert_eq!(bimap.insert_all_no_overwrite((2..10).zip('c'..)), Ok(()));
+        assert_eq!(bimap.len(), 10);
+        assert_eq!(bimap.get_by_left(&9), Some(&'j'));
+        assert_eq!(bimap.get_by_right(&'c'), Some(&2));
+    }
+
+    #[test]
+    fn transaction_rollback_restores_bimap() {
+        fn prop(pairs: Vec<(u8, u8)>, ops: Vec<(u8, u8, u8)>, commit: bool) -> bool {
+            let original: BiHashMap<_, _> = pairs.into_iter().collect();
+            let mut expected = original.clone();
+            let mut bimap = original.clone();
+            let mut transaction = bimap.transaction();
+            for (op, left, right) in ops {
+                let matches = match op % 4 {
+                    0 => transaction.insert(left, right) == expected.insert(left, right),
+                    1 => {
+                        transaction.insert_no_overwrite(left, right)
+                            == expected.insert_no_overwrite(left, right)
+                    }
+                    2 => transaction.remove_by_left(&left) == expected.remove_by_left(&left),
+                    _ => transaction.remove_by_right(&right) == expected.remove_by_right(&right),
+                };
+                if !matches {
+                    return false;
+                }
+            }
+            if *transaction != expected {
+                return false;
+            }
+            if commit {
+                transaction.commit();
+                bimap == expected
+            } else {
+                drop(transaction);
+                bimap == original && bimap.iter().all(|(l, r)| bimap.get_by_right(r) == Some(l))
+            }
+        }
+        quickcheck::quickcheck(prop as fn(Vec<(u8, u8)>, Vec<(u8, u8, u8)>, bool) -> bool);
+    }
+
+    #[test]
+    fn transaction_savepoints() {
+        let mut bimap: BiHashMap<_, _> = vec![('a', 1), ('b', 2)].into_iter().collect();
+        let original = bimap.clone();
+
+        let mut transaction = bimap.transaction();
+        transaction.insert('c', 3);
+        {
+            let mut outer = transaction.savepoint();
+            outer.remove_by_left(&'a');
+            {
+                let mut inner = outer.savepoint();
+                inner.insert('b', 3);
+                assert_eq!(inner.len(), 1);
+            }
+            assert_eq!(outer.get_by_left(&'b'), Some(&2));
+            assert_eq!(outer.get_by_left(&'c'), Some(&3));
+
+            let mut inner = outer.savepoint();
+            inner.insert('d', 4);
+            inner.commit();
+            outer.commit();
+        }
+        assert_eq!(transaction.len(), 3);
+        assert!(!transaction.contains_left(&'a'));
+        transaction.rollback();
+        assert_eq!(bimap, original);
+
+        let mut transaction = bimap.transaction();
+        transaction.savepoint().insert('e', 5);
+        let mut savepoint = transaction.savepoint();
+        savepoint.insert('a', 2);
+        savepoint.commit();
+        transaction.commit();
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.get_by_left(&'a'), Some(&2));
+    }
 }
@@ -0,0 +1,262 @@
+//! Parallel iteration over a `BiHashMap` using [Rayon].
+//!
+//! [Rayon]: https://docs.rs/rayon/
+
+use super::BiHashMap;
+use rayon::iter::{
+    FromParallelIterator, IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator,
+    ParallelExtend, ParallelIterator, Zip,
+};
+use std::hash::{BuildHasher, Hash};
+
+impl<L, R, LS, RS> BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash + Sync,
+    R: Eq + Hash + Sync,
+{
+    /// Creates a parallel iterator over the left-right pairs in the bimap.
+    ///
+    /// The iterator element type is `(&L, &R)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let bimap: BiHashMap<_, _> = (0..1000).map(|i| (i, i * 2)).collect();
+    /// let sum: i32 = bimap.par_iter().map(|(l, r)| l + r).sum();
+    /// assert_eq!(sum, 3 * 999 * 1000 / 2);
+    /// ```
+    pub fn par_iter(&self) -> ParIter<'_, L, R> {
+        ParIter {
+            inner: self.lefts.par_iter().zip(self.rights.par_iter()),
+        }
+    }
+
+    /// Creates a parallel iterator over the left values in the bimap.
+    ///
+    /// The iterator element type is `&L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let bimap: BiHashMap<_, _> = (0..1000).map(|i| (i, i * 2)).collect();
+    /// assert_eq!(bimap.par_left_values().max(), Some(&999));
+    /// ```
+    pub fn par_left_values(&self) -> ParLeftValues<'_, L> {
+        ParLeftValues {
+            inner: self.lefts.par_iter(),
+        }
+    }
+
+    /// Creates a parallel iterator over the right values in the bimap.
+    ///
+    /// The iterator element type is `&R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let bimap: BiHashMap<_, _> = (0..1000).map(|i| (i, i * 2)).collect();
+    /// assert_eq!(bimap.par_right_values().max(), Some(&1998));
+    /// ```
+    pub fn par_right_values(&self) -> ParRightValues<'_, R> {
+        ParRightValues {
+            inner: self.rights.par_iter(),
+        }
+    }
+}
+
+/// Collects the left-right pairs of a parallel iterator into a bimap.
+///
+/// See the [`ParallelExtend`] implementation for which parts run in parallel.
+impl<L, R, LS, RS> FromParallelIterator<(L, R)> for BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash + Send,
+    R: Eq + Hash + Send,
+    LS: BuildHasher + Default + Sync,
+    RS: BuildHasher + Default + Sync,
+{
+    fn from_par_iter<I>(par_iter: I) -> BiHashMap<L, R, LS, RS>
+    where
+        I: IntoParallelIterator<Item = (L, R)>,
+    {
+        let mut bimap = BiHashMap::default();
+        bimap.par_extend(par_iter);
+        bimap
+    }
+}
+
+/// Extends a bimap with the left-right pairs of a parallel iterator.
+///
+/// Producing the pairs and hashing their values run in parallel, but the pairs
+/// are then inserted one at a time, in the order of the parallel iterator. So
+/// conflicting pairs overwrite each other exactly as they would with the
+/// sequential `Extend` implementation: the last pair wins.
+impl<L, R, LS, RS> ParallelExtend<(L, R)> for BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash + Send,
+    R: Eq + Hash + Send,
+    LS: BuildHasher + Sync,
+    RS: BuildHasher + Sync,
+{
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = (L, R)>,
+    {
+        let (hash_left, hash_right) = (&self.hash_builder_left, &self.hash_builder_right);
+        // collecting preserves the order of the pairs, which decides how
+        // conflicts are resolved
+        let pairs: Vec<_> = par_iter
+            .into_par_iter()
+            .map(|(left, right)| {
+                let hashes = (hash_left.hash_one(&left), hash_right.hash_one(&right));
+                (left, right, hashes)
+            })
+            .collect();
+        self.lefts.reserve(pairs.len());
+        self.rights.reserve(pairs.len());
+        for (left, right, (left_hash, right_hash)) in pairs {
+            self.insert_hashed(left, right, left_hash, right_hash);
+        }
+    }
+}
+
+impl<'a, L, R, LS, RS> IntoParallelIterator for &'a BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash + Sync,
+    R: Eq + Hash + Sync,
+{
+    type Item = (&'a L, &'a R);
+    type Iter = ParIter<'a, L, R>;
+
+    fn into_par_iter(self) -> ParIter<'a, L, R> {
+        self.par_iter()
+    }
+}
+
+impl<L, R, LS, RS> IntoParallelIterator for BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash + Send,
+    R: Eq + Hash + Send,
+{
+    type Item = (L, R);
+    type Iter = IntoParIter<L, R>;
+
+    fn into_par_iter(self) -> IntoParIter<L, R> {
+        IntoParIter {
+            inner: self.lefts.into_par_iter().zip(self.rights.into_par_iter()),
+        }
+    }
+}
+
+/// A parallel iterator over the left-right pairs in a `BiHashMap`.
+///
+/// This struct is created by the [`par_iter`] method of `BiHashMap`.
+///
+/// [`par_iter`]: BiHashMap::par_iter
+#[derive(Debug)]
+pub struct ParIter<'a, L, R> {
+    inner: Zip<rayon::slice::Iter<'a, L>, rayon::slice::Iter<'a, R>>,
+}
+
+delegate_indexed!(ParIter<'a, L, R>: (&'a L, &'a R), Sync);
+
+/// A parallel iterator over the left values in a `BiHashMap`.
+///
+/// This struct is created by the [`par_left_values`] method of `BiHashMap`.
+///
+/// [`par_left_values`]: BiHashMap::par_left_values
+#[derive(Debug)]
+pub struct ParLeftValues<'a, L> {
+    inner: rayon::slice::Iter<'a, L>,
+}
+
+delegate_indexed!(ParLeftValues<'a, L>: &'a L, Sync);
+
+/// A parallel iterator over the right values in a `BiHashMap`.
+///
+/// This struct is created by the [`par_right_values`] method of `BiHashMap`.
+///
+/// [`par_right_values`]: BiHashMap::par_right_values
+#[derive(Debug)]
+pub struct ParRightValues<'a, R> {
+    inner: rayon::slice::Iter<'a, R>,
+}
+
+delegate_indexed!(ParRightValues<'a, R>: &'a R, Sync);
+
+/// An owning parallel iterator over the left-right pairs in a `BiHashMap`.
+#[derive(Debug)]
+pub struct IntoParIter<L, R> {
+    inner: Zip<rayon::vec::IntoIter<L>, rayon::vec::IntoIter<R>>,
+}
+
+delegate_indexed!(IntoParIter<L, R>: (L, R), Send);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pairs with many conflicting left and right values.
+    fn conflicting_pairs() -> Vec<(u32, u32)> {
+        (0..10_000).map(|i| (i % 1000, (i * 7) % 1500)).collect()
+    }
+
+    #[test]
+    fn par_iter() {
+        let bimap: BiHashMap<_, _> = (0..1000).map(|i| (i, i * 2)).collect();
+
+        let mut pairs: Vec<_> = bimap.par_iter().collect();
+        pairs.sort();
+        let mut expected: Vec<_> = bimap.iter().collect();
+        expected.sort();
+        assert_eq!(pairs, expected);
+        assert_eq!(bimap.par_iter().len(), 1000);
+
+        let mut lefts: Vec<_> = bimap.par_left_values().copied().collect();
+        lefts.sort();
+        assert_eq!(lefts, (0..1000).collect::<Vec<_>>());
+        let mut rights: Vec<_> = bimap.par_right_values().copied().collect();
+        rights.sort();
+        assert_eq!(rights, (0..1000).map(|i| i * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn into_par_iter() {
+        let bimap: BiHashMap<_, _> = (0..1000).map(|i| (i, i.to_string())).collect();
+
+        let mut pairs: Vec<_> = (&bimap).into_par_iter().collect();
+        pairs.sort();
+        assert_eq!(pairs.len(), 1000);
+        let mut owned: Vec<_> = bimap.clone().into_par_iter().collect();
+        owned.sort();
+        let mut expected: Vec<_> = bimap.into_iter().collect();
+        expected.sort();
+        assert_eq!(owned, expected);
+    }
+
+    #[test]
+    fn from_par_iter_matches_sequential() {
+        let pairs = conflicting_pairs();
+        let sequential: BiHashMap<_, _> = pairs.iter().copied().collect();
+        let parallel: BiHashMap<_, _> = pairs.par_iter().copied().collect();
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn par_extend_matches_sequential() {
+        let pairs = conflicting_pairs();
+        let mut sequential: BiHashMap<_, _> = (0..500).map(|i| (i, i)).collect();
+        let mut parallel = sequential.clone();
+        sequential.extend(pairs.iter().copied());
+        parallel.par_extend(pairs.par_iter().copied());
+        assert_eq!(parallel, sequential);
+    }
+}
@@ -0,0 +1,1333 @@
+//! A bimap that preserves the insertion order of its pairs.
+//!
+//! A [`BiIndexMap`] is laid out like a [`BiHashMap`]: the pairs are stored
+//! densely in two vectors, and two hash tables map each value to the position
+//! of its pair. Unlike a `BiHashMap`, it keeps the pairs in the order they were
+//! inserted in, so iteration is deterministic and every pair can be addressed
+//! by its position.
+//!
+//! Removing a pair comes in two flavors, like in [`Vec`]: `swap_remove_*`
+//! moves the last pair into the vacated position in constant time, while
+//! `shift_remove_*` shifts all following pairs down to preserve the order,
+//! which takes linear time.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::BiIndexMap;
+//!
+//! let mut bimap = BiIndexMap::new();
+//! bimap.insert('c', 3);
+//! bimap.insert('a', 1);
+//! bimap.insert('b', 2);
+//!
+//! // pairs are iterated over in insertion order
+//! let pairs: Vec<_> = bimap.iter().collect();
+//! assert_eq!(pairs, [(&'c', &3), (&'a', &1), (&'b', &2)]);
+//!
+//! // and can be accessed by position
+//! assert_eq!(bimap.get_index(1), Some((&'a', &1)));
+//! assert_eq!(bimap.index_of_right(&2), Some(2));
+//!
+//! bimap.sort_by_left();
+//! assert_eq!(bimap.left_values().collect::<String>(), "abc");
+//! ```
+//!
+//! [`BiHashMap`]: crate::BiHashMap
+
+use crate::{
+    hash::{erase_index, rehasher, replace_index, EntryDebugger},
+    Overwritten,
+};
+use hashbrown::HashTable;
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    collections::hash_map,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::{self, Extend, FromIterator, FusedIterator},
+    mem,
+    ops::Range,
+    slice, vec,
+};
+
+/// A bimap that preserves the insertion order of its pairs.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// [module-level documentation]: crate::index
+pub struct BiIndexMap<L, R, LS = hash_map::RandomState, RS = hash_map::RandomState> {
+    // the pair at position `i` is `(lefts[i], rights[i])`
+    lefts: Vec<L>,
+    rights: Vec<R>,
+    left2right: HashTable<usize>,
+    right2left: HashTable<usize>,
+    hash_builder_left: LS,
+    hash_builder_right: RS,
+}
+
+impl<L, R> BiIndexMap<L, R, hash_map::RandomState, hash_map::RandomState>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    /// Creates an empty `BiIndexMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let bimap = BiIndexMap::<char, i32>::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hashers(hash_map::RandomState::new(), hash_map::RandomState::new())
+    }
+
+    /// Creates a new empty `BiIndexMap` with the given capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let bimap = BiIndexMap::<char, i32>::with_capacity(10);
+    /// assert!(bimap.capacity() >= 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hashers(
+            capacity,
+            hash_map::RandomState::new(),
+            hash_map::RandomState::new(),
+        )
+    }
+}
+
+impl<L, R, LS, RS> BiIndexMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    /// Returns the number of left-right pairs in the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.lefts.len()
+    }
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// assert!(bimap.is_empty());
+    /// bimap.insert('a', 1);
+    /// assert!(!bimap.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.lefts.is_empty()
+    }
+
+    /// Returns a lower bound on the number of left-right pairs the bimap can
+    /// store without reallocating memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let bimap = BiIndexMap::<char, i32>::with_capacity(10);
+    /// assert!(bimap.capacity() >= 10);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.lefts
+            .capacity()
+            .min(self.rights.capacity())
+            .min(self.left2right.capacity())
+            .min(self.right2left.capacity())
+    }
+
+    /// Removes all left-right pairs from the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.clear();
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.lefts.clear();
+        self.rights.clear();
+        self.left2right.clear();
+        self.right2left.clear();
+    }
+
+    /// Creates an iterator over the left-right pairs in the bimap in order.
+    ///
+    /// The iterator element type is `(&L, &R)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('b', 2);
+    /// bimap.insert('a', 1);
+    ///
+    /// let pairs: Vec<_> = bimap.iter().collect();
+    /// assert_eq!(pairs, [(&'b', &2), (&'a', &1)]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, L, R> {
+        Iter {
+            inner: self.lefts.iter().zip(self.rights.iter()),
+        }
+    }
+
+    /// Creates an iterator over the left values in the bimap in order.
+    ///
+    /// The iterator element type is `&L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('b', 2);
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.left_values().collect::<Vec<_>>(), [&'b', &'a']);
+    /// ```
+    pub fn left_values(&self) -> LeftValues<'_, L> {
+        LeftValues {
+            inner: self.lefts.iter(),
+        }
+    }
+
+    /// Creates an iterator over the right values in the bimap in order.
+    ///
+    /// The iterator element type is `&R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('b', 2);
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.right_values().collect::<Vec<_>>(), [&2, &1]);
+    /// ```
+    pub fn right_values(&self) -> RightValues<'_, R> {
+        RightValues {
+            inner: self.rights.iter(),
+        }
+    }
+
+    /// Returns the left-right pair at the given position, or `None` if the
+    /// position is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// assert_eq!(bimap.get_index(1), Some((&'b', &2)));
+    /// assert_eq!(bimap.get_index(2), None);
+    /// ```
+    pub fn get_index(&self, index: usize) -> Option<(&L, &R)> {
+        Some((self.lefts.get(index)?, &self.rights[index]))
+    }
+}
+
+impl<L, R, LS, RS> BiIndexMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Creates a new empty `BiIndexMap` using `hash_builder_left` to hash left
+    /// values and `hash_builder_right` to hash right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let s_left = RandomState::new();
+    /// let s_right = RandomState::new();
+    /// let mut bimap = BiIndexMap::<char, i32, _, _>::with_hashers(s_left, s_right);
+    /// bimap.insert('a', 42);
+    /// ```
+    pub fn with_hashers(hash_builder_left: LS, hash_builder_right: RS) -> Self {
+        Self::with_capacity_and_hashers(0, hash_builder_left, hash_builder_right)
+    }
+
+    /// Creates a new empty `BiIndexMap` with the given capacity, using
+    /// `hash_builder_left` to hash left values and `hash_builder_right` to
+    /// hash right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let s_left = RandomState::new();
+    /// let s_right = RandomState::new();
+    /// let bimap = BiIndexMap::<char, i32, _, _>::with_capacity_and_hashers(10, s_left, s_right);
+    /// assert!(bimap.capacity() >= 10);
+    /// ```
+    pub fn with_capacity_and_hashers(
+        capacity: usize,
+        hash_builder_left: LS,
+        hash_builder_right: RS,
+    ) -> Self {
+        Self {
+            lefts: Vec::with_capacity(capacity),
+            rights: Vec::with_capacity(capacity),
+            left2right: HashTable::with_capacity(capacity),
+            right2left: HashTable::with_capacity(capacity),
+            hash_builder_left,
+            hash_builder_right,
+        }
+    }
+
+    /// Returns a reference to the right value corresponding to the given left
+    /// value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.get_by_left(&'z'), None);
+    /// ```
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of_left(left).map(|index| &self.rights[index])
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+    /// assert_eq!(bimap.get_by_right(&2), None);
+    /// ```
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of_right(right).map(|index| &self.lefts[index])
+    }
+
+    /// Returns `true` if the bimap contains the given left value and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// assert!(bimap.contains_left(&'a'));
+    /// assert!(!bimap.contains_left(&'b'));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of_left(left).is_some()
+    }
+
+    /// Returns `true` if the bimap contains the given right value and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// assert!(bimap.contains_right(&1));
+    /// assert!(!bimap.contains_right(&2));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index_of_right(right).is_some()
+    }
+
+    /// Returns the position of the pair containing the given left value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// assert_eq!(bimap.index_of_left(&'b'), Some(1));
+    /// assert_eq!(bimap.index_of_left(&'c'), None);
+    /// ```
+    pub fn index_of_left<Q>(&self, left: &Q) -> Option<usize>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder_left.hash_one(left);
+        let lefts = &self.lefts;
+        self.left2right
+            .find(hash, |&index| lefts[index].borrow() == left)
+            .copied()
+    }
+
+    /// Returns the position of the pair containing the given right value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// assert_eq!(bimap.index_of_right(&2), Some(1));
+    /// assert_eq!(bimap.index_of_right(&3), None);
+    /// ```
+    pub fn index_of_right<Q>(&self, right: &Q) -> Option<usize>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder_right.hash_one(right);
+        let rights = &self.rights;
+        self.right2left
+            .find(hash, |&index| rights[index].borrow() == right)
+            .copied()
+    }
+
+    /// Inserts the given left-right pair into the bimap.
+    ///
+    /// Returns an enum `Overwritten` representing any left-right pairs that
+    /// were overwritten by the call to `insert`, like
+    /// [`BiHashMap::insert`](crate::BiHashMap::insert).
+    ///
+    /// A new pair is appended at the end. A pair that overwrites others takes
+    /// the position of the pair of its left value, or of its right value if
+    /// the left value wasn't in the bimap. If both values were in separate
+    /// pairs, the pair of the right value is removed as if by
+    /// [`shift_remove_index`].
+    ///
+    /// [`shift_remove_index`]: BiIndexMap::shift_remove_index
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiIndexMap, Overwritten};
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
+    /// assert_eq!(bimap.insert('b', 2), Overwritten::Neither);
+    /// assert_eq!(bimap.insert('c', 3), Overwritten::Neither);
+    ///
+    /// // the new pair takes the position of ('b', 2)
+    /// assert_eq!(bimap.insert('b', 4), Overwritten::Left('b', 2));
+    /// assert_eq!(bimap.get_index(1), Some((&'b', &4)));
+    ///
+    /// // ('c', 3) is removed and the later pairs shift down
+    /// assert_eq!(bimap.insert('a', 3), Overwritten::Both(('a', 1), ('c', 3)));
+    /// let pairs: Vec<_> = bimap.iter().collect();
+    /// assert_eq!(pairs, [(&'a', &3), (&'b', &4)]);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        match (self.index_of_left(&left), self.index_of_right(&right)) {
+            (None, None) => {
+                self.push_unchecked(left, right);
+                Overwritten::Neither
+            }
+            (Some(l_index), Some(r_index)) if l_index == r_index => {
+                let (l, r) = self.replace_at(l_index, left, right);
+                Overwritten::Pair(l, r)
+            }
+            (Some(l_index), None) => {
+                let (l, r) = self.replace_at(l_index, left, right);
+                Overwritten::Left(l, r)
+            }
+            (None, Some(r_index)) => {
+                let (l, r) = self.replace_at(r_index, left, right);
+                Overwritten::Right(l, r)
+            }
+            (Some(l_index), Some(r_index)) => {
+                // unwrap is safe because the index was just found
+                let r_pair = self.shift_remove_index(r_index).unwrap();
+                let l_index = if r_index < l_index {
+                    l_index - 1
+                } else {
+                    l_index
+                };
+                let l_pair = self.replace_at(l_index, left, right);
+                Overwritten::Both(l_pair, r_pair)
+            }
+        }
+    }
+
+    /// Inserts the given left-right pair at the end of the bimap without
+    /// overwriting any existing values.
+    ///
+    /// Returns `Ok(())` if the pair was successfully inserted into the bimap.
+    /// If either value exists in the map, `Err((left, right)` is returned
+    /// with the attempted left-right pair and the map is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// assert_eq!(bimap.insert_no_overwrite('a', 1), Ok(()));
+    /// assert_eq!(bimap.insert_no_overwrite('b', 2), Ok(()));
+    /// assert_eq!(bimap.insert_no_overwrite('a', 3), Err(('a', 3)));
+    /// assert_eq!(bimap.insert_no_overwrite('c', 2), Err(('c', 2)));
+    /// ```
+    pub fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        if self.contains_left(&left) || self.contains_right(&right) {
+            Err((left, right))
+        } else {
+            self.push_unchecked(left, right);
+            Ok(())
+        }
+    }
+
+    /// Removes the left-right pair corresponding to the given left value by
+    /// moving the last pair into its position.
+    ///
+    /// This takes constant time but perturbs the order of the last pair. Use
+    /// [`shift_remove_by_left`] to preserve the order.
+    ///
+    /// [`shift_remove_by_left`]: BiIndexMap::shift_remove_by_left
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// assert_eq!(bimap.swap_remove_by_left(&'a'), Some(('a', 1)));
+    /// assert_eq!(bimap.swap_remove_by_left(&'a'), None);
+    /// assert_eq!(bimap.left_values().collect::<String>(), "cb");
+    /// ```
+    pub fn swap_remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let index = self.index_of_left(left)?;
+        self.swap_remove_index(index)
+    }
+
+    /// Removes the left-right pair corresponding to the given right value by
+    /// moving the last pair into its position.
+    ///
+    /// This takes constant time but perturbs the order of the last pair. Use
+    /// [`shift_remove_by_right`] to preserve the order.
+    ///
+    /// [`shift_remove_by_right`]: BiIndexMap::shift_remove_by_right
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// assert_eq!(bimap.swap_remove_by_right(&1), Some(('a', 1)));
+    /// assert_eq!(bimap.swap_remove_by_right(&1), None);
+    /// assert_eq!(bimap.left_values().collect::<String>(), "cb");
+    /// ```
+    pub fn swap_remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let index = self.index_of_right(right)?;
+        self.swap_remove_index(index)
+    }
+
+    /// Removes the left-right pair corresponding to the given left value by
+    /// shifting all following pairs down.
+    ///
+    /// This preserves the order of the remaining pairs but takes linear time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// assert_eq!(bimap.shift_remove_by_left(&'a'), Some(('a', 1)));
+    /// assert_eq!(bimap.shift_remove_by_left(&'a'), None);
+    /// assert_eq!(bimap.left_values().collect::<String>(), "bc");
+    /// ```
+    pub fn shift_remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let index = self.index_of_left(left)?;
+        self.shift_remove_index(index)
+    }
+
+    /// Removes the left-right pair corresponding to the given right value by
+    /// shifting all following pairs down.
+    ///
+    /// This preserves the order of the remaining pairs but takes linear time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// assert_eq!(bimap.shift_remove_by_right(&1), Some(('a', 1)));
+    /// assert_eq!(bimap.shift_remove_by_right(&1), None);
+    /// assert_eq!(bimap.left_values().collect::<String>(), "bc");
+    /// ```
+    pub fn shift_remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let index = self.index_of_right(right)?;
+        self.shift_remove_index(index)
+    }
+
+    /// Removes the left-right pair at the given position by moving the last
+    /// pair into its position, or returns `None` if the position is out of
+    /// bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// assert_eq!(bimap.swap_remove_index(0), Some(('a', 1)));
+    /// assert_eq!(bimap.swap_remove_index(2), None);
+    /// assert_eq!(bimap.get_index(0), Some((&'c', &3)));
+    /// ```
+    pub fn swap_remove_index(&mut self, index: usize) -> Option<(L, R)> {
+        if index >= self.len() {
+            return None;
+        }
+        let (left_hash, right_hash) = self.hashes(index);
+        erase_index(&mut self.left2right, left_hash, index);
+        erase_index(&mut self.right2left, right_hash, index);
+
+        let last = self.len() - 1;
+        if index != last {
+            let (left_hash, right_hash) = self.hashes(last);
+            replace_index(&mut self.left2right, left_hash, last, index);
+            replace_index(&mut self.right2left, right_hash, last, index);
+        }
+        Some((
+            self.lefts.swap_remove(index),
+            self.rights.swap_remove(index),
+        ))
+    }
+
+    /// Removes the left-right pair at the given position by shifting all
+    /// following pairs down, or returns `None` if the position is out of
+    /// bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// assert_eq!(bimap.shift_remove_index(0), Some(('a', 1)));
+    /// assert_eq!(bimap.shift_remove_index(2), None);
+    /// assert_eq!(bimap.get_index(0), Some((&'b', &2)));
+    /// ```
+    pub fn shift_remove_index(&mut self, index: usize) -> Option<(L, R)> {
+        if index >= self.len() {
+            return None;
+        }
+        let (left_hash, right_hash) = self.hashes(index);
+        erase_index(&mut self.left2right, left_hash, index);
+        erase_index(&mut self.right2left, right_hash, index);
+        self.shift_down(index + 1..self.len());
+        Some((self.lefts.remove(index), self.rights.remove(index)))
+    }
+
+    /// Moves the left-right pair at position `from` to position `to`,
+    /// shifting the pairs in between.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either position is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = "abcd".chars().zip(1..).collect();
+    /// bimap.move_index(0, 2);
+    /// assert_eq!(bimap.left_values().collect::<String>(), "bcad");
+    /// bimap.move_index(3, 0);
+    /// assert_eq!(bimap.left_values().collect::<String>(), "dbca");
+    /// assert_eq!(bimap.index_of_right(&1), Some(3));
+    /// ```
+    pub fn move_index(&mut self, from: usize, to: usize) {
+        let len = self.len();
+        assert!(
+            from < len && to < len,
+            "move_index out of bounds: the len is {} but the positions are {} and {}",
+            len,
+            from,
+            to
+        );
+        if from == to {
+            return;
+        }
+        // park the moved pair on a position no other pair can have
+        let (left_hash, right_hash) = self.hashes(from);
+        replace_index(&mut self.left2right, left_hash, from, usize::MAX);
+        replace_index(&mut self.right2left, right_hash, from, usize::MAX);
+        if from < to {
+            self.shift_down(from + 1..to + 1);
+            self.lefts[from..=to].rotate_left(1);
+            self.rights[from..=to].rotate_left(1);
+        } else {
+            self.shift_up(to..from);
+            self.lefts[to..=from].rotate_right(1);
+            self.rights[to..=from].rotate_right(1);
+        }
+        replace_index(&mut self.left2right, left_hash, usize::MAX, to);
+        replace_index(&mut self.right2left, right_hash, usize::MAX, to);
+    }
+
+    /// Sorts the left-right pairs in the bimap by their left values.
+    ///
+    /// The sort is stable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('b', 1), ('c', 3), ('a', 2)].into_iter().collect();
+    /// bimap.sort_by_left();
+    /// assert_eq!(bimap.left_values().collect::<String>(), "abc");
+    /// ```
+    pub fn sort_by_left(&mut self)
+    where
+        L: Ord,
+    {
+        self.sort_by(|l1, _, l2, _| l1.cmp(l2));
+    }
+
+    /// Sorts the left-right pairs in the bimap by their right values.
+    ///
+    /// The sort is stable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('b', 1), ('c', 3), ('a', 2)].into_iter().collect();
+    /// bimap.sort_by_right();
+    /// assert_eq!(bimap.left_values().collect::<String>(), "bac");
+    /// ```
+    pub fn sort_by_right(&mut self)
+    where
+        R: Ord,
+    {
+        self.sort_by(|_, r1, _, r2| r1.cmp(r2));
+    }
+
+    /// Sorts the left-right pairs in the bimap with the given comparison
+    /// function, which receives the left and right values of two pairs.
+    ///
+    /// The sort is stable. The bimap is unchanged if the comparison function
+    /// panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('b', 1), ('c', 3), ('a', 2)].into_iter().collect();
+    /// bimap.sort_by(|_, r1, _, r2| r2.cmp(r1));
+    /// assert_eq!(bimap.left_values().collect::<String>(), "cab");
+    /// ```
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&L, &R, &L, &R) -> Ordering,
+    {
+        let (lefts, rights) = (&self.lefts, &self.rights);
+        let mut order: Vec<usize> = (0..self.len()).collect();
+        order.sort_by(|&a, &b| compare(&lefts[a], &rights[a], &lefts[b], &rights[b]));
+
+        // move the pair at `order[i]` to position `i`, one cycle at a time
+        for start in 0..order.len() {
+            let mut index = start;
+            loop {
+                let source = mem::replace(&mut order[index], index);
+                if source == start {
+                    break;
+                }
+                self.lefts.swap(index, source);
+                self.rights.swap(index, source);
+                index = source;
+            }
+        }
+
+        self.left2right.clear();
+        self.right2left.clear();
+        for index in 0..self.len() {
+            let (left_hash, right_hash) = self.hashes(index);
+            self.left2right.insert_unique(
+                left_hash,
+                index,
+                rehasher(&self.lefts, &self.hash_builder_left),
+            );
+            self.right2left.insert_unique(
+                right_hash,
+                index,
+                rehasher(&self.rights, &self.hash_builder_right),
+            );
+        }
+    }
+
+    /// Returns the hashes of the left and right value at the given position.
+    fn hashes(&self, index: usize) -> (u64, u64) {
+        (
+            self.hash_builder_left.hash_one(&self.lefts[index]),
+            self.hash_builder_right.hash_one(&self.rights[index]),
+        )
+    }
+
+    /// Appends the given left-right pair without checking if either value is
+    /// already in the bimap.
+    fn push_unchecked(&mut self, left: L, right: R) {
+        let index = self.len();
+        let left_hash = self.hash_builder_left.hash_one(&left);
+        let right_hash = self.hash_builder_right.hash_one(&right);
+        self.lefts.push(left);
+        self.rights.push(right);
+        self.left2right.insert_unique(
+            left_hash,
+            index,
+            rehasher(&self.lefts, &self.hash_builder_left),
+        );
+        self.right2left.insert_unique(
+            right_hash,
+            index,
+            rehasher(&self.rights, &self.hash_builder_right),
+        );
+    }
+
+    /// Replaces the left-right pair at the given position, which must not
+    /// conflict with any other pair, and returns the previous pair.
+    fn replace_at(&mut self, index: usize, left: L, right: R) -> (L, R) {
+        let (left_hash, right_hash) = self.hashes(index);
+        erase_index(&mut self.left2right, left_hash, index);
+        erase_index(&mut self.right2left, right_hash, index);
+        let previous = (
+            mem::replace(&mut self.lefts[index], left),
+            mem::replace(&mut self.rights[index], right),
+        );
+        let (left_hash, right_hash) = self.hashes(index);
+        self.left2right.insert_unique(
+            left_hash,
+            index,
+            rehasher(&self.lefts, &self.hash_builder_left),
+        );
+        self.right2left.insert_unique(
+            right_hash,
+            index,
+            rehasher(&self.rights, &self.hash_builder_right),
+        );
+        previous
+    }
+
+    /// Updates the tables for the pairs at the given positions moving one
+    /// position down. The position below the range must be vacant in both
+    /// tables.
+    fn shift_down(&mut self, range: Range<usize>) {
+        for index in range {
+            let (left_hash, right_hash) = self.hashes(index);
+            replace_index(&mut self.left2right, left_hash, index, index - 1);
+            replace_index(&mut self.right2left, right_hash, index, index - 1);
+        }
+    }
+
+    /// Updates the tables for the pairs at the given positions moving one
+    /// position up. The position above the range must be vacant in both
+    /// tables.
+    fn shift_up(&mut self, range: Range<usize>) {
+        for index in range.rev() {
+            let (left_hash, right_hash) = self.hashes(index);
+            replace_index(&mut self.left2right, left_hash, index, index + 1);
+            replace_index(&mut self.right2left, right_hash, index, index + 1);
+        }
+    }
+}
+
+impl<L, R, LS, RS> Clone for BiIndexMap<L, R, LS, RS>
+where
+    L: Clone,
+    R: Clone,
+    LS: Clone,
+    RS: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            lefts: self.lefts.clone(),
+            rights: self.rights.clone(),
+            left2right: self.left2right.clone(),
+            right2left: self.right2left.clone(),
+            hash_builder_left: self.hash_builder_left.clone(),
+            hash_builder_right: self.hash_builder_right.clone(),
+        }
+    }
+}
+
+impl<L, R, LS, RS> fmt::Debug for BiIndexMap<L, R, LS, RS>
+where
+    L: fmt::Debug + Eq + Hash,
+    R: fmt::Debug + Eq + Hash,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set()
+            .entries(
+                self.iter()
+                    .map(|(left, right)| EntryDebugger { left, right }),
+            )
+            .finish()
+    }
+}
+
+impl<L, R, LS, RS> Default for BiIndexMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn default() -> BiIndexMap<L, R, LS, RS> {
+        BiIndexMap::with_hashers(LS::default(), RS::default())
+    }
+}
+
+impl<L, R, LS, RS> Eq for BiIndexMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+}
+
+impl<L, R, LS, RS> FromIterator<(L, R)> for BiIndexMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn from_iter<I>(iter: I) -> BiIndexMap<L, R, LS, RS>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let iter = iter.into_iter();
+        let mut bimap =
+            BiIndexMap::with_capacity_and_hashers(iter.size_hint().0, LS::default(), RS::default());
+        bimap.extend(iter);
+        bimap
+    }
+}
+
+impl<'a, L, R, LS, RS> IntoIterator for &'a BiIndexMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R>;
+
+    fn into_iter(self) -> Iter<'a, L, R> {
+        self.iter()
+    }
+}
+
+impl<L, R, LS, RS> IntoIterator for BiIndexMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    type Item = (L, R);
+    type IntoIter = IntoIter<L, R>;
+
+    fn into_iter(self) -> IntoIter<L, R> {
+        IntoIter {
+            inner: self.lefts.into_iter().zip(self.rights),
+        }
+    }
+}
+
+impl<L, R, LS, RS> Extend<(L, R)> for BiIndexMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(move |(l, r)| {
+            self.insert(l, r);
+        });
+    }
+}
+
+/// Compares the pairs of two bimaps regardless of their order.
+///
+/// To also compare the order, compare the iterators of the bimaps instead.
+impl<L, R, LS, RS> PartialEq for BiIndexMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(left, right)| other.get_by_left(left) == Some(right))
+    }
+}
+
+/// An owning iterator over the left-right pairs in a `BiIndexMap`.
+pub struct IntoIter<L, R> {
+    inner: iter::Zip<vec::IntoIter<L>, vec::IntoIter<R>>,
+}
+
+impl<L, R> DoubleEndedIterator for IntoIter<L, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<L, R> ExactSizeIterator for IntoIter<L, R> {}
+
+impl<L, R> FusedIterator for IntoIter<L, R> {}
+
+impl<L, R> Iterator for IntoIter<L, R> {
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the left-right pairs in a `BiIndexMap`.
+///
+/// This struct is created by the [`iter`] method of `BiIndexMap`.
+///
+/// [`iter`]: BiIndexMap::iter
+#[derive(Clone)]
+pub struct Iter<'a, L, R> {
+    inner: iter::Zip<slice::Iter<'a, L>, slice::Iter<'a, R>>,
+}
+
+impl<'a, L, R> DoubleEndedIterator for Iter<'a, L, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, L, R> ExactSizeIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> Iterator for Iter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the left values in a `BiIndexMap`.
+///
+/// This struct is created by the [`left_values`] method of `BiIndexMap`.
+///
+/// [`left_values`]: BiIndexMap::left_values
+#[derive(Clone)]
+pub struct LeftValues<'a, L> {
+    inner: slice::Iter<'a, L>,
+}
+
+impl<'a, L> DoubleEndedIterator for LeftValues<'a, L> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, L> ExactSizeIterator for LeftValues<'a, L> {}
+
+impl<'a, L> FusedIterator for LeftValues<'a, L> {}
+
+impl<'a, L> Iterator for LeftValues<'a, L> {
+    type Item = &'a L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the right values in a `BiIndexMap`.
+///
+/// This struct is created by the [`right_values`] method of `BiIndexMap`.
+///
+/// [`right_values`]: BiIndexMap::right_values
+#[derive(Clone)]
+pub struct RightValues<'a, R> {
+    inner: slice::Iter<'a, R>,
+}
+
+impl<'a, R> DoubleEndedIterator for RightValues<'a, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, R> ExactSizeIterator for RightValues<'a, R> {}
+
+impl<'a, R> FusedIterator for RightValues<'a, R> {}
+
+impl<'a, R> Iterator for RightValues<'a, R> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that both tables point every value at its position.
+    fn assert_consistent<L, R>(bimap: &BiIndexMap<L, R>)
+    where
+        L: Eq + Hash + fmt::Debug,
+        R: Eq + Hash + fmt::Debug,
+    {
+        assert_eq!(bimap.lefts.len(), bimap.rights.len());
+        assert_eq!(bimap.left2right.len(), bimap.len());
+        assert_eq!(bimap.right2left.len(), bimap.len());
+        for (index, (left, right)) in bimap.iter().enumerate() {
+            assert_eq!(bimap.index_of_left(left), Some(index));
+            assert_eq!(bimap.index_of_right(right), Some(index));
+        }
+    }
+
+    fn lefts<L: Clone + Eq + Hash, R: Eq + Hash>(bimap: &BiIndexMap<L, R>) -> Vec<L> {
+        bimap.left_values().cloned().collect()
+    }
+
+    #[test]
+    fn insert_keeps_positions() {
+        let mut bimap = BiIndexMap::new();
+        for (left, right) in "abcde".chars().zip(0..) {
+            assert_eq!(bimap.insert(left, right), Overwritten::Neither);
+        }
+        assert_eq!(bimap.insert('c', 2), Overwritten::Pair('c', 2));
+        assert_eq!(bimap.insert('c', 10), Overwritten::Left('c', 2));
+        assert_eq!(bimap.insert('x', 0), Overwritten::Right('a', 0));
+        assert_consistent(&bimap);
+        assert_eq!(lefts(&bimap), ['x', 'b', 'c', 'd', 'e']);
+
+        // the right value's pair comes first
+        assert_eq!(bimap.insert('e', 1), Overwritten::Both(('e', 4), ('b', 1)));
+        assert_consistent(&bimap);
+        assert_eq!(lefts(&bimap), ['x', 'c', 'd', 'e']);
+
+        // the right value's pair comes last
+        assert_eq!(bimap.insert('c', 1), Overwritten::Both(('c', 10), ('e', 1)));
+        assert_consistent(&bimap);
+        assert_eq!(lefts(&bimap), ['x', 'c', 'd']);
+        assert_eq!(bimap.get_index(1), Some((&'c', &1)));
+
+        assert_eq!(bimap.insert_no_overwrite('y', 1), Err(('y', 1)));
+        assert_eq!(bimap.insert_no_overwrite('y', 5), Ok(()));
+        assert_eq!(bimap.index_of_left(&'y'), Some(3));
+    }
+
+    #[test]
+    fn removals() {
+        let mut bimap: BiIndexMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+
+        assert_eq!(bimap.shift_remove_by_left(&2), Some((2, 20)));
+        assert_eq!(bimap.shift_remove_by_right(&50), Some((5, 50)));
+        assert_consistent(&bimap);
+        assert_eq!(lefts(&bimap), [0, 1, 3, 4, 6, 7, 8, 9]);
+
+        assert_eq!(bimap.swap_remove_by_left(&0), Some((0, 0)));
+        assert_eq!(bimap.swap_remove_by_right(&40), Some((4, 40)));
+        assert_consistent(&bimap);
+        assert_eq!(lefts(&bimap), [9, 1, 3, 8, 6, 7]);
+
+        assert_eq!(bimap.swap_remove_index(5), Some((7, 70)));
+        assert_eq!(bimap.shift_remove_index(5), None);
+        assert_eq!(bimap.swap_remove_by_left(&7), None);
+        assert_eq!(bimap.shift_remove_by_right(&70), None);
+        assert_eq!(bimap.shift_remove_index(0), Some((9, 90)));
+        assert_consistent(&bimap);
+        assert_eq!(lefts(&bimap), [1, 3, 8, 6]);
+    }
+
+    #[test]
+    fn move_index() {
+        let mut bimap: BiIndexMap<_, _> = (0..6).map(|i| (i, i)).collect();
+        bimap.move_index(1, 4);
+        assert_consistent(&bimap);
+        assert_eq!(lefts(&bimap), [0, 2, 3, 4, 1, 5]);
+        bimap.move_index(5, 0);
+        assert_consistent(&bimap);
+        assert_eq!(lefts(&bimap), [5, 0, 2, 3, 4, 1]);
+        bimap.move_index(3, 3);
+        bimap.move_index(0, 5);
+        assert_consistent(&bimap);
+        assert_eq!(lefts(&bimap), [0, 2, 3, 4, 1, 5]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn move_index_out_of_bounds() {
+        let mut bimap: BiIndexMap<_, _> = (0..3).map(|i| (i, i)).collect();
+        bimap.move_index(0, 3);
+    }
+
+    #[test]
+    fn sort() {
+        let pairs = vec![(5, 'b'), (3, 'e'), (8, 'a'), (1, 'd'), (4, 'c')];
+        let mut bimap: BiIndexMap<_, _> = pairs.into_iter().collect();
+
+        bimap.sort_by_left();
+        assert_consistent(&bimap);
+        assert_eq!(lefts(&bimap), [1, 3, 4, 5, 8]);
+
+        bimap.sort_by_right();
+        assert_consistent(&bimap);
+        let rights: String = bimap.right_values().collect();
+        assert_eq!(rights, "abcde");
+
+        // the order is untouched if the comparison panics
+        let before = lefts(&bimap);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            bimap.sort_by(|_, _, _, _| panic!("comparison failed"));
+        }));
+        assert!(result.is_err());
+        assert_consistent(&bimap);
+        assert_eq!(lefts(&bimap), before);
+    }
+
+    #[test]
+    fn iterators() {
+        let bimap: BiIndexMap<_, _> = vec![('c', 3), ('a', 1), ('b', 2)].into_iter().collect();
+
+        let mut iter = bimap.iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next_back(), Some((&'b', &2)));
+        assert_eq!(iter.clone().collect::<Vec<_>>(), [(&'c', &3), (&'a', &1)]);
+        assert_eq!(bimap.right_values().rev().collect::<Vec<_>>(), [&2, &1, &3]);
+        assert_eq!((&bimap).into_iter().count(), 3);
+
+        let mut into_iter = bimap.clone().into_iter();
+        assert_eq!(into_iter.next_back(), Some(('b', 2)));
+        assert_eq!(into_iter.collect::<Vec<_>>(), [('c', 3), ('a', 1)]);
+    }
+
+    #[test]
+    fn clone_eq_debug() {
+        let mut bimap = BiIndexMap::new();
+        assert_eq!(format!("{:?}", bimap), "{}");
+        bimap.insert('b', 2);
+        bimap.insert('a', 1);
+        assert_eq!(format!("{:?}", bimap), "{'b' <> 2, 'a' <> 1}");
+
+        let clone = bimap.clone();
+        assert_consistent(&clone);
+        assert_eq!(clone, bimap);
+
+        // equality ignores the order of the pairs
+        let other: BiIndexMap<_, _> = vec![('a', 1), ('b', 2)].into_iter().collect();
+        assert_eq!(other, bimap);
+        assert!(!other.iter().eq(bimap.iter()));
+    }
+}
@@ -0,0 +1,1119 @@
+//! A map in which every left value has exactly one right value, but a right
+//! value may be shared by any number of left values.
+//!
+//! Many mappings are functions rather than bijections: each left value maps
+//! to a single right value, while several left values may map to the same
+//! right value (files and the directories they live in, users and their
+//! roles). A `HashMap` models such a mapping well in one direction, but
+//! finding all keys of a given value requires a linear scan. An
+//! [`InjectiveMap`] keeps a reverse index from each right value to the set of
+//! left values mapping to it, so lookups are fast in both directions.
+//!
+//! Inserting a pair whose left value is already in the map reassigns that left
+//! value to the new right value. Since the previous right value may still be
+//! shared by other left values, [`insert`] reports what happened to it with a
+//! [`Previous`], in the spirit of [`Overwritten`] for bimaps.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::injective::{InjectiveMap, Previous};
+//!
+//! // files and the directories they live in
+//! let mut dirs = InjectiveMap::new();
+//! dirs.insert("lib.rs", "src");
+//! dirs.insert("main.rs", "src");
+//! dirs.insert("README.md", "docs");
+//!
+//! assert_eq!(dirs.get_by_left(&"lib.rs"), Some(&"src"));
+//! let mut files: Vec<_> = dirs.get_lefts_by_right(&"src").collect();
+//! files.sort();
+//! assert_eq!(files, [&"lib.rs", &"main.rs"]);
+//!
+//! // "src" still contains main.rs, so it stays in the map
+//! assert_eq!(dirs.insert("lib.rs", "docs"), Previous::Retained);
+//! // "src" has no files left, so it is removed from the map and returned
+//! assert_eq!(dirs.insert("main.rs", "docs"), Previous::Removed("src"));
+//! assert!(!dirs.contains_right(&"src"));
+//! assert_eq!(dirs.get_lefts_by_right(&"docs").len(), 3);
+//! ```
+//!
+//! [`insert`]: InjectiveMap::insert
+//! [`Overwritten`]: crate::Overwritten
+
+use crate::hash::{erase_index, replace_index, EntryDebugger};
+use hashbrown::hash_table::{self, HashTable};
+use std::{
+    borrow::Borrow,
+    collections::hash_map,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::{Extend, FromIterator, FusedIterator},
+    marker::PhantomData,
+    slice, vec,
+};
+
+/// A map in which every left value has exactly one right value, with a
+/// reverse index from each right value to its left values.
+///
+/// The left values are stored in a dense vector, each together with the
+/// position of its right value. The distinct right values are stored once in
+/// a second dense vector, each together with a hash table of the positions of
+/// its left values. Two more hash tables index both vectors by the hashes of
+/// their values.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// [module-level documentation]: crate::injective
+#[derive(Clone)]
+pub struct InjectiveMap<L, R, LS = hash_map::RandomState, RS = hash_map::RandomState> {
+    // each left value together with the position of its group in `rights`
+    lefts: Vec<(L, usize)>,
+    rights: Vec<Group<R>>,
+    left2right: HashTable<usize>,
+    right2left: HashTable<usize>,
+    hash_builder_left: LS,
+    hash_builder_right: RS,
+}
+
+/// A distinct right value together with the positions of the left values
+/// paired with it, which are never empty.
+#[derive(Clone)]
+struct Group<R> {
+    right: R,
+    // keyed by the hashes of the left values
+    lefts: HashTable<usize>,
+}
+
+/// The right value a left value was previously paired with, as reported by
+/// the [`insert`] and [`remove_by_left`] methods of an `InjectiveMap`.
+///
+/// [`insert`]: InjectiveMap::insert
+/// [`remove_by_left`]: InjectiveMap::remove_by_left
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Previous<R> {
+    /// The left value was not in the map.
+    Vacant,
+
+    /// The left value was already paired with an equal right value, so the
+    /// map is unchanged.
+    Unchanged,
+
+    /// The left value was paired with a different right value, which stays in
+    /// the map because other left values are still paired with it.
+    Retained,
+
+    /// The left value was paired with a different right value, which is
+    /// returned because no other left values were paired with it.
+    Removed(R),
+}
+
+impl<R> Previous<R> {
+    /// Returns a boolean indicating if the left value was moved away from a
+    /// different right value.
+    ///
+    /// This method is `true` for the `Retained` and `Removed` variants.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::InjectiveMap;
+    ///
+    /// let mut map = InjectiveMap::new();
+    /// assert!(!map.insert('a', 1).did_reassign());
+    /// assert!(!map.insert('a', 1).did_reassign());
+    /// assert!(map.insert('a', 2).did_reassign());
+    /// ```
+    pub fn did_reassign(&self) -> bool {
+        matches!(self, Previous::Retained | Previous::Removed(_))
+    }
+}
+
+impl<L, R> InjectiveMap<L, R, hash_map::RandomState, hash_map::RandomState>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    /// Creates an empty `InjectiveMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::InjectiveMap;
+    ///
+    /// let map = InjectiveMap::<char, i32>::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hashers(hash_map::RandomState::new(), hash_map::RandomState::new())
+    }
+}
+
+impl<L, R, LS, RS> InjectiveMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    /// Returns the number of left-right pairs in the map, which is the number
+    /// of left values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::InjectiveMap;
+    ///
+    /// let mut map = InjectiveMap::new();
+    /// map.insert('a', 1);
+    /// map.insert('b', 1);
+    /// map.insert('c', 2);
+    /// assert_eq!(map.len(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.lefts.len()
+    }
+
+    /// Returns `true` if the map contains no left-right pairs, and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::InjectiveMap;
+    ///
+    /// let mut map = InjectiveMap::new();
+    /// assert!(map.is_empty());
+    /// map.insert('a', 1);
+    /// assert!(!map.is_empty());
+    /// map.remove_by_left(&'a');
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.lefts.is_empty()
+    }
+
+    /// Removes all left-right pairs from the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::InjectiveMap;
+    ///
+    /// let mut map = InjectiveMap::new();
+    /// map.insert('a', 1);
+    /// map.insert('b', 1);
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.lefts.clear();
+        self.rights.clear();
+        self.left2right.clear();
+        self.right2left.clear();
+    }
+
+    /// Creates an iterator over the left-right pairs in the map in arbitrary
+    /// order.
+    ///
+    /// The iterator element type is `(&L, &R)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::InjectiveMap;
+    ///
+    /// let mut map = InjectiveMap::new();
+    /// map.insert('a', 1);
+    /// map.insert('b', 1);
+    /// map.insert('c', 2);
+    ///
+    /// for (left, right) in map.iter() {
+    ///     println!("({}, {})", left, right);
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, L, R> {
+        Iter {
+            inner: self.lefts.iter(),
+            rights: &self.rights,
+        }
+    }
+
+    /// Creates an iterator over the left values in the map in arbitrary order.
+    ///
+    /// The iterator element type is `&L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::InjectiveMap;
+    ///
+    /// let mut map = InjectiveMap::new();
+    /// map.insert('a', 1);
+    /// map.insert('b', 1);
+    /// assert_eq!(map.left_values().len(), 2);
+    /// ```
+    pub fn left_values(&self) -> LeftValues<'_, L, R> {
+        LeftValues {
+            inner: self.lefts.iter(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Creates an iterator over the distinct right values in the map in
+    /// arbitrary order.
+    ///
+    /// The iterator element type is `&R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::InjectiveMap;
+    ///
+    /// let mut map = InjectiveMap::new();
+    /// map.insert('a', 1);
+    /// map.insert('b', 1);
+    /// map.insert('c', 2);
+    /// assert_eq!(map.right_values().len(), 2);
+    /// ```
+    pub fn right_values(&self) -> RightValues<'_, L, R> {
+        RightValues {
+            inner: self.rights.iter(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<L, R, LS, RS> InjectiveMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Creates a new empty `InjectiveMap` using `hash_builder_left` to hash
+    /// left values and `hash_builder_right` to hash right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::InjectiveMap;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let s_left = RandomState::new();
+    /// let s_right = RandomState::new();
+    /// let mut map = InjectiveMap::<char, i32, _, _>::with_hashers(s_left, s_right);
+    /// map.insert('a', 42);
+    /// ```
+    pub fn with_hashers(hash_builder_left: LS, hash_builder_right: RS) -> Self {
+        Self {
+            lefts: Vec::new(),
+            rights: Vec::new(),
+            left2right: HashTable::new(),
+            right2left: HashTable::new(),
+            hash_builder_left,
+            hash_builder_right,
+        }
+    }
+
+    /// Returns a reference to the right value paired with the given left
+    /// value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::InjectiveMap;
+    ///
+    /// let mut map = InjectiveMap::new();
+    /// map.insert('a', 1);
+    /// assert_eq!(map.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(map.get_by_left(&'z'), None);
+    /// ```
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_left(left)
+            .map(|index| &self.rights[self.lefts[index].1].right)
+    }
+
+    /// Returns an iterator over the left values paired with the given right
+    /// value, which is empty if the right value is not in the map.
+    ///
+    /// The iterator element type is `&L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::InjectiveMap;
+    ///
+    /// let mut map = InjectiveMap::new();
+    /// map.insert('a', 1);
+    /// map.insert('b', 1);
+    /// map.insert('c', 2);
+    ///
+    /// let mut lefts: Vec<_> = map.get_lefts_by_right(&1).collect();
+    /// lefts.sort();
+    /// assert_eq!(lefts, [&'a', &'b']);
+    /// assert_eq!(map.get_lefts_by_right(&3).len(), 0);
+    /// ```
+    pub fn get_lefts_by_right<Q>(&self, right: &Q) -> Lefts<'_, L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        Lefts {
+            inner: self
+                .find_right(right)
+                .map(|group| self.rights[group].lefts.iter()),
+            lefts: &self.lefts,
+        }
+    }
+
+    /// Returns `true` if the map contains the given left value and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::InjectiveMap;
+    ///
+    /// let mut map = InjectiveMap::new();
+    /// map.insert('a', 1);
+    /// assert!(map.contains_left(&'a'));
+    /// assert!(!map.contains_left(&'b'));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_left(left).is_some()
+    }
+
+    /// Returns `true` if the map contains the given right value and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::InjectiveMap;
+    ///
+    /// let mut map = InjectiveMap::new();
+    /// map.insert('a', 1);
+    /// assert!(map.contains_right(&1));
+    /// assert!(!map.contains_right(&2));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_right(right).is_some()
+    }
+
+    /// Pairs the given left value with the given right value, reassigning the
+    /// left value if it was already paired with a different right value.
+    ///
+    /// Other left values paired with either right value are never affected.
+    /// The returned [`Previous`] describes the right value the left value was
+    /// paired with before; it is only returned by value if no other left
+    /// values are paired with it, since it stays in the map otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::injective::{InjectiveMap, Previous};
+    ///
+    /// let mut map = InjectiveMap::new();
+    /// assert_eq!(map.insert('a', 1), Previous::Vacant);
+    /// assert_eq!(map.insert('b', 1), Previous::Vacant);
+    /// assert_eq!(map.insert('b', 1), Previous::Unchanged);
+    ///
+    /// // 1 is still paired with 'a'
+    /// assert_eq!(map.insert('b', 2), Previous::Retained);
+    /// // 1 is not paired with anything anymore
+    /// assert_eq!(map.insert('a', 2), Previous::Removed(1));
+    ///
+    /// assert_eq!(map.len(), 2);
+    /// assert_eq!(map.get_lefts_by_right(&2).len(), 2);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Previous<R> {
+        let left_hash = self.hash_builder_left.hash_one(&left);
+        let lefts = &self.lefts;
+        let found = self
+            .left2right
+            .find(left_hash, |&index| lefts[index].0 == left)
+            .copied();
+        match found {
+            Some(index) => {
+                let previous = self.lefts[index].1;
+                if self.rights[previous].right == right {
+                    return Previous::Unchanged;
+                }
+                // keep the stored left value, like `HashMap::insert` does
+                erase_index(&mut self.rights[previous].lefts, left_hash, index);
+                let group = self.find_or_insert_right(right);
+                self.attach(index, left_hash, group);
+                if self.rights[previous].lefts.is_empty() {
+                    Previous::Removed(self.remove_group(previous))
+                } else {
+                    Previous::Retained
+                }
+            }
+            None => {
+                let group = self.find_or_insert_right(right);
+                let index = self.lefts.len();
+                self.lefts.push((left, group));
+                self.left2right.insert_unique(
+                    left_hash,
+                    index,
+                    left_rehasher(&self.lefts, &self.hash_builder_left),
+                );
+                self.attach(index, left_hash, group);
+                Previous::Vacant
+            }
+        }
+    }
+
+    /// Removes the given left value from the map, returning it together with
+    /// a [`Previous`] describing the right value it was paired with.
+    ///
+    /// The right value is only returned by value if no other left values are
+    /// paired with it, in which case it is removed from the map as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::injective::{InjectiveMap, Previous};
+    ///
+    /// let mut map = InjectiveMap::new();
+    /// map.insert('a', 1);
+    /// map.insert('b', 1);
+    ///
+    /// assert_eq!(map.remove_by_left(&'a'), Some(('a', Previous::Retained)));
+    /// assert_eq!(map.remove_by_left(&'a'), None);
+    /// assert_eq!(map.remove_by_left(&'b'), Some(('b', Previous::Removed(1))));
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, Previous<R>)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let index = self.find_left(left)?;
+        let (left, group) = self.remove_index(index);
+        let previous = if self.rights[group].lefts.is_empty() {
+            Previous::Removed(self.remove_group(group))
+        } else {
+            Previous::Retained
+        };
+        Some((left, previous))
+    }
+
+    /// Removes the given right value and all left values paired with it from
+    /// the map, returning the right value and the left values in arbitrary
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::InjectiveMap;
+    ///
+    /// let mut map = InjectiveMap::new();
+    /// map.insert('a', 1);
+    /// map.insert('b', 1);
+    /// map.insert('c', 2);
+    ///
+    /// let (right, mut lefts) = map.remove_by_right(&1).unwrap();
+    /// lefts.sort();
+    /// assert_eq!((right, lefts), (1, vec!['a', 'b']));
+    /// assert_eq!(map.len(), 1);
+    /// assert_eq!(map.remove_by_right(&1), None);
+    /// ```
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(R, Vec<L>)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let group = self.find_right(right)?;
+        let mut indices: Vec<_> = self.rights[group].lefts.iter().copied().collect();
+        // removing the highest positions first keeps the remaining ones valid,
+        // since only the last left value is ever moved
+        indices.sort_unstable_by(|a, b| b.cmp(a));
+        let lefts = indices
+            .into_iter()
+            .map(|index| self.remove_index(index).0)
+            .collect();
+        Some((self.remove_group(group), lefts))
+    }
+
+    /// Returns the position of the given left value.
+    fn find_left<Q>(&self, left: &Q) -> Option<usize>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder_left.hash_one(left);
+        let lefts = &self.lefts;
+        self.left2right
+            .find(hash, |&index| lefts[index].0.borrow() == left)
+            .copied()
+    }
+
+    /// Returns the position of the group of the given right value.
+    fn find_right<Q>(&self, right: &Q) -> Option<usize>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder_right.hash_one(right);
+        let rights = &self.rights;
+        self.right2left
+            .find(hash, |&group| rights[group].right.borrow() == right)
+            .copied()
+    }
+
+    /// Returns the position of the group of the given right value, creating
+    /// an empty group if the right value is not in the map.
+    fn find_or_insert_right(&mut self, right: R) -> usize {
+        let hash = self.hash_builder_right.hash_one(&right);
+        let rights = &self.rights;
+        if let Some(&group) = self
+            .right2left
+            .find(hash, |&group| rights[group].right == right)
+        {
+            return group;
+        }
+        let group = self.rights.len();
+        self.rights.push(Group {
+            right,
+            lefts: HashTable::new(),
+        });
+        let (rights, hash_builder_right) = (&self.rights, &self.hash_builder_right);
+        self.right2left.insert_unique(hash, group, |&group| {
+            hash_builder_right.hash_one(&rights[group].right)
+        });
+        group
+    }
+
+    /// Pairs the left value at the given position with the right value of the
+    /// given group.
+    fn attach(&mut self, index: usize, left_hash: u64, group: usize) {
+        self.lefts[index].1 = group;
+        self.rights[group].lefts.insert_unique(
+            left_hash,
+            index,
+            left_rehasher(&self.lefts, &self.hash_builder_left),
+        );
+    }
+
+    /// Removes the left value at the given position from the map and from its
+    /// group, and returns it together with the position of its group.
+    ///
+    /// The last left value is moved into the vacated position to keep the left
+    /// values dense.
+    fn remove_index(&mut self, index: usize) -> (L, usize) {
+        let hash = self.hash_builder_left.hash_one(&self.lefts[index].0);
+        let group = self.lefts[index].1;
+        erase_index(&mut self.left2right, hash, index);
+        erase_index(&mut self.rights[group].lefts, hash, index);
+
+        let last = self.lefts.len() - 1;
+        if index != last {
+            let hash = self.hash_builder_left.hash_one(&self.lefts[last].0);
+            let group = self.lefts[last].1;
+            replace_index(&mut self.left2right, hash, last, index);
+            replace_index(&mut self.rights[group].lefts, hash, last, index);
+        }
+        self.lefts.swap_remove(index)
+    }
+
+    /// Removes the group at the given position, which must not contain any left
+    /// values, and returns its right value.
+    ///
+    /// The last group is moved into the vacated position to keep the groups
+    /// dense.
+    fn remove_group(&mut self, group: usize) -> R {
+        let hash = self.hash_builder_right.hash_one(&self.rights[group].right);
+        erase_index(&mut self.right2left, hash, group);
+
+        let last = self.rights.len() - 1;
+        if group != last {
+            let hash = self.hash_builder_right.hash_one(&self.rights[last].right);
+            replace_index(&mut self.right2left, hash, last, group);
+            for &index in self.rights[last].lefts.iter() {
+                self.lefts[index].1 = group;
+            }
+        }
+        self.rights.swap_remove(group).right
+    }
+}
+
+/// Returns a function that recomputes the hash of the left value at a
+/// position, for use when a hash table of positions is resized.
+fn left_rehasher<'a, L, S>(
+    lefts: &'a [(L, usize)],
+    hash_builder: &'a S,
+) -> impl Fn(&usize) -> u64 + 'a
+where
+    L: Hash,
+    S: BuildHasher,
+{
+    move |&index| hash_builder.hash_one(&lefts[index].0)
+}
+
+impl<L, R, LS, RS> fmt::Debug for InjectiveMap<L, R, LS, RS>
+where
+    L: fmt::Debug + Eq + Hash,
+    R: fmt::Debug + Eq + Hash,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set()
+            .entries(
+                self.iter()
+                    .map(|(left, right)| EntryDebugger { left, right }),
+            )
+            .finish()
+    }
+}
+
+impl<L, R, LS, RS> Default for InjectiveMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn default() -> InjectiveMap<L, R, LS, RS> {
+        InjectiveMap::with_hashers(LS::default(), RS::default())
+    }
+}
+
+impl<L, R, LS, RS> Eq for InjectiveMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+}
+
+impl<L, R, LS, RS> FromIterator<(L, R)> for InjectiveMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn from_iter<I>(iter: I) -> InjectiveMap<L, R, LS, RS>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let mut map = InjectiveMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<'a, L, R, LS, RS> IntoIterator for &'a InjectiveMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R>;
+
+    fn into_iter(self) -> Iter<'a, L, R> {
+        self.iter()
+    }
+}
+
+/// Consumes the map, yielding its left-right pairs in arbitrary order.
+///
+/// Right values that are shared by several left values are cloned for all but
+/// the last pair they are yielded in.
+impl<L, R, LS, RS> IntoIterator for InjectiveMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    type Item = (L, R);
+    type IntoIter = IntoIter<L, R>;
+
+    fn into_iter(self) -> IntoIter<L, R> {
+        IntoIter {
+            inner: self.lefts.into_iter(),
+            rights: self
+                .rights
+                .into_iter()
+                .map(|group| (Some(group.right), group.lefts.len()))
+                .collect(),
+        }
+    }
+}
+
+impl<L, R, LS, RS> Extend<(L, R)> for InjectiveMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(move |(l, r)| {
+            self.insert(l, r);
+        });
+    }
+}
+
+impl<L, R, LS, RS> PartialEq for InjectiveMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(left, right)| other.get_by_left(left) == Some(right))
+    }
+}
+
+/// An owning iterator over the left-right pairs in an `InjectiveMap`.
+pub struct IntoIter<L, R> {
+    inner: vec::IntoIter<(L, usize)>,
+    // each right value, until its last pair takes it, together with the number
+    // of its pairs that have not been yielded yet
+    rights: Vec<(Option<R>, usize)>,
+}
+
+impl<L, R: Clone> ExactSizeIterator for IntoIter<L, R> {}
+
+impl<L, R: Clone> FusedIterator for IntoIter<L, R> {}
+
+impl<L, R: Clone> Iterator for IntoIter<L, R> {
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (left, group) = self.inner.next()?;
+        let (right, remaining) = &mut self.rights[group];
+        *remaining -= 1;
+        let right = if *remaining == 0 {
+            right.take().unwrap()
+        } else {
+            R::clone(right.as_ref().unwrap())
+        };
+        Some((left, right))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the left-right pairs in an `InjectiveMap`.
+///
+/// This struct is created by the [`iter`] method of `InjectiveMap`.
+///
+/// [`iter`]: InjectiveMap::iter
+pub struct Iter<'a, L, R> {
+    inner: slice::Iter<'a, (L, usize)>,
+    rights: &'a [Group<R>],
+}
+
+impl<'a, L, R> Clone for Iter<'a, L, R> {
+    fn clone(&self) -> Self {
+        Iter {
+            inner: self.inner.clone(),
+            rights: self.rights,
+        }
+    }
+}
+
+impl<'a, L, R> ExactSizeIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> Iterator for Iter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rights = self.rights;
+        self.inner
+            .next()
+            .map(|(left, group)| (left, &rights[*group].right))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the left values in an `InjectiveMap`.
+///
+/// This struct is created by the [`left_values`] method of `InjectiveMap`.
+///
+/// [`left_values`]: InjectiveMap::left_values
+pub struct LeftValues<'a, L, R> {
+    inner: slice::Iter<'a, (L, usize)>,
+    marker: PhantomData<&'a R>,
+}
+
+impl<'a, L, R> ExactSizeIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for LeftValues<'a, L, R> {
+    type Item = &'a L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(left, _)| left)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the distinct right values in an `InjectiveMap`.
+///
+/// This struct is created by the [`right_values`] method of `InjectiveMap`.
+///
+/// [`right_values`]: InjectiveMap::right_values
+pub struct RightValues<'a, L, R> {
+    inner: slice::Iter<'a, Group<R>>,
+    marker: PhantomData<&'a L>,
+}
+
+impl<'a, L, R> ExactSizeIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for RightValues<'a, L, R> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|group| &group.right)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the left values paired with a right value in an
+/// `InjectiveMap`.
+///
+/// This struct is created by the [`get_lefts_by_right`] method of
+/// `InjectiveMap`.
+///
+/// [`get_lefts_by_right`]: InjectiveMap::get_lefts_by_right
+pub struct Lefts<'a, L> {
+    inner: Option<hash_table::Iter<'a, usize>>,
+    lefts: &'a [(L, usize)],
+}
+
+impl<'a, L> Clone for Lefts<'a, L> {
+    fn clone(&self) -> Self {
+        Lefts {
+            inner: self.inner.clone(),
+            lefts: self.lefts,
+        }
+    }
+}
+
+impl<'a, L> ExactSizeIterator for Lefts<'a, L> {}
+
+impl<'a, L> FusedIterator for Lefts<'a, L> {}
+
+impl<'a, L> Iterator for Lefts<'a, L> {
+    type Item = &'a L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let lefts = self.lefts;
+        self.inner.as_mut()?.next().map(|&index| &lefts[index].0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner
+            .as_ref()
+            .map_or((0, Some(0)), |inner| inner.size_hint())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted<T: Ord, I: IntoIterator<Item = T>>(iter: I) -> Vec<T> {
+        let mut values: Vec<_> = iter.into_iter().collect();
+        values.sort();
+        values
+    }
+
+    /// Checks that the reverse index describes the same pairs as the map.
+    fn assert_consistent<L, R>(map: &InjectiveMap<L, R>)
+    where
+        L: Eq + Hash + fmt::Debug,
+        R: Eq + Hash + fmt::Debug,
+    {
+        let mut len = 0;
+        for (position, group) in map.rights.iter().enumerate() {
+            assert!(!group.lefts.is_empty());
+            for &index in group.lefts.iter() {
+                let (left, other) = &map.lefts[index];
+                assert_eq!(*other, position);
+                assert_eq!(map.get_by_left(left), Some(&group.right));
+                len += 1;
+            }
+        }
+        assert_eq!(len, map.len());
+        assert_eq!(map.left2right.len(), map.lefts.len());
+        assert_eq!(map.right2left.len(), map.rights.len());
+        for (left, right) in map.iter() {
+            assert!(map.get_lefts_by_right(right).any(|other| other == left));
+        }
+    }
+
+    #[test]
+    fn send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<InjectiveMap<String, i32>>();
+    }
+
+    #[test]
+    fn insert_reports_previous_right() {
+        let mut roles = InjectiveMap::new();
+        assert_eq!(roles.insert("alice", "admin"), Previous::Vacant);
+        assert_eq!(roles.insert("bob", "user"), Previous::Vacant);
+        assert_eq!(roles.insert("carol", "user"), Previous::Vacant);
+        assert_eq!(roles.insert("carol", "user"), Previous::Unchanged);
+        assert_consistent(&roles);
+
+        assert_eq!(roles.insert("bob", "admin"), Previous::Retained);
+        assert_consistent(&roles);
+        assert_eq!(
+            sorted(roles.get_lefts_by_right("admin")),
+            [&"alice", &"bob"]
+        );
+        assert_eq!(sorted(roles.get_lefts_by_right("user")), [&"carol"]);
+
+        assert_eq!(roles.insert("carol", "guest"), Previous::Removed("user"));
+        assert_consistent(&roles);
+        assert!(!roles.contains_right("user"));
+        assert_eq!(roles.len(), 3);
+        assert_eq!(roles.right_values().len(), 2);
+    }
+
+    #[test]
+    fn removals() {
+        let mut map: InjectiveMap<_, _> = vec![('a', 1), ('b', 1), ('c', 2), ('d', 3)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(map.remove_by_left(&'a'), Some(('a', Previous::Retained)));
+        assert_eq!(map.remove_by_left(&'a'), None);
+        assert_consistent(&map);
+        assert_eq!(map.remove_by_left(&'c'), Some(('c', Previous::Removed(2))));
+        assert!(!map.contains_right(&2));
+        assert_consistent(&map);
+
+        map.insert('e', 1);
+        let (right, lefts) = map.remove_by_right(&1).unwrap();
+        assert_eq!((right, sorted(lefts)), (1, vec!['b', 'e']));
+        assert_eq!(map.remove_by_right(&1), None);
+        assert_consistent(&map);
+        assert_eq!(map.iter().collect::<Vec<_>>(), [(&'d', &3)]);
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.right_values().next(), None);
+    }
+
+    #[test]
+    fn matches_hash_map() {
+        let mut map = InjectiveMap::new();
+        let mut model = hash_map::HashMap::new();
+        for i in 0..500u32 {
+            let (left, right) = (i * 7 % 31, i * 11 % 13);
+            if i % 5 == 0 {
+                assert_eq!(
+                    map.remove_by_left(&left).is_some(),
+                    model.remove(&left).is_some()
+                );
+            } else if i % 17 == 0 {
+                let removed = map
+                    .remove_by_right(&right)
+                    .map_or(0, |(_, lefts)| lefts.len());
+                let before = model.len();
+                model.retain(|_, other| *other != right);
+                assert_eq!(removed, before - model.len());
+            } else {
+                map.insert(left, right);
+                model.insert(left, right);
+            }
+            assert_consistent(&map);
+            assert_eq!(map.len(), model.len());
+            assert!(model
+                .iter()
+                .all(|(left, right)| map.get_by_left(left) == Some(right)));
+        }
+    }
+
+    #[test]
+    fn iterators() {
+        let pairs = vec![(0, 'a'), (1, 'a'), (2, 'b'), (3, 'a')];
+        let map: InjectiveMap<_, _> = pairs.iter().copied().collect();
+
+        assert_eq!(map.iter().len(), 4);
+        assert_eq!(sorted(map.iter().map(|(&l, &r)| (l, r))), pairs);
+        assert_eq!(sorted(&map).len(), 4);
+        assert_eq!(sorted(map.left_values()), [&0, &1, &2, &3]);
+        assert_eq!(sorted(map.right_values()), [&'a', &'b']);
+
+        let mut into_iter = map.clone().into_iter();
+        assert_eq!(into_iter.len(), 4);
+        into_iter.next();
+        assert_eq!(into_iter.len(), 3);
+        assert_eq!(sorted(map.into_iter()), pairs);
+    }
+
+    #[test]
+    fn clone_eq_debug() {
+        let mut map = InjectiveMap::new();
+        assert_eq!(format!("{:?}", map), "{}");
+        map.insert('a', 1);
+        assert_eq!(format!("{:?}", map), "{'a' <> 1}");
+        map.insert('b', 1);
+
+        let clone = map.clone();
+        assert_consistent(&clone);
+        assert_eq!(clone, map);
+
+        let mut other = InjectiveMap::new();
+        other.extend(vec![('b', 1), ('a', 2), ('a', 1)]);
+        assert_eq!(other, map);
+        other.insert('b', 2);
+        assert_ne!(other, map);
+    }
+
+    #[test]
+    fn borrowed_lookups() {
+        let mut map = InjectiveMap::new();
+        map.insert("a".to_string(), vec![1]);
+        map.insert("b".to_string(), vec![1]);
+        assert!(map.contains_left("a"));
+        assert!(map.contains_right(&[1][..]));
+        assert_eq!(map.get_by_left("b"), Some(&vec![1]));
+        assert_eq!(map.get_lefts_by_right(&[1][..]).len(), 2);
+        assert_eq!(
+            map.remove_by_left("a"),
+            Some(("a".to_string(), Previous::Retained))
+        );
+        assert_eq!(
+            map.remove_by_right(&[1][..]),
+            Some((vec![1], vec!["b".to_string()]))
+        );
+    }
+}
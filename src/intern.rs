@@ -0,0 +1,726 @@
+//! An interner that assigns dense ids to values.
+//!
+//! An [`Interner`] hands out ids counting up from zero in the order values are
+//! first interned, and resolves them in both directions: from an id to its
+//! value with [`resolve`](Interner::resolve), and from a value to its id with
+//! [`lookup`](Interner::lookup). Values can never be removed, so an id stays
+//! valid for the whole lifetime of the interner.
+//!
+//! Once all values are known, the interner can be turned into a read-only
+//! [`FrozenInterner`] with [`freeze`](Interner::freeze).
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::Interner;
+//!
+//! let mut symbols = Interner::<String>::new();
+//! let alpha = symbols.intern_ref("alpha");
+//! let beta = symbols.intern("beta".to_string());
+//!
+//! // interning a value again returns its existing id
+//! assert_eq!(symbols.intern_ref("alpha"), alpha);
+//! assert_eq!((alpha, beta), (0, 1));
+//!
+//! assert_eq!(symbols.lookup("beta"), Some(beta));
+//! assert_eq!(symbols.resolve(alpha).map(String::as_str), Some("alpha"));
+//!
+//! let symbols = symbols.freeze();
+//! assert_eq!(symbols.lookup("gamma"), None);
+//! ```
+
+use crate::{hash::rehasher, vec::Idx};
+use hashbrown::HashTable;
+use std::{
+    borrow::{Borrow, ToOwned},
+    collections::hash_map,
+    convert::TryFrom,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::{Enumerate, Extend, FromIterator, FusedIterator},
+    marker::PhantomData,
+    slice,
+};
+
+/// An interner that assigns dense ids to values.
+///
+/// `Id` is the type of the ids, which must be able to hold every position
+/// from zero up to the number of interned values. It defaults to `u32`.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// [module-level documentation]: crate::intern
+#[derive(Clone)]
+pub struct Interner<T, Id = u32, S = hash_map::RandomState> {
+    values: Values<T, S>,
+    marker: PhantomData<fn() -> Id>,
+}
+
+/// A read-only [`Interner`].
+///
+/// This struct is created by the [`freeze`](Interner::freeze) method of
+/// `Interner`.
+#[derive(Clone)]
+pub struct FrozenInterner<T, Id = u32, S = hash_map::RandomState> {
+    values: Values<T, S>,
+    marker: PhantomData<fn() -> Id>,
+}
+
+/// The values of an interner, shared by `Interner` and `FrozenInterner`.
+#[derive(Clone)]
+struct Values<T, S> {
+    // the value with id `i` is at position `i`
+    values: Vec<T>,
+    // the positions of the values, hashed by value
+    indices: HashTable<usize>,
+    hash_builder: S,
+}
+
+impl<T, Id> Interner<T, Id, hash_map::RandomState>
+where
+    T: Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+{
+    /// Creates an empty `Interner`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let interner = Interner::<String>::new();
+    /// assert!(interner.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hasher(hash_map::RandomState::new())
+    }
+
+    /// Creates an empty `Interner` with room for at least `capacity` values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let interner = Interner::<String, u16>::with_capacity(10);
+    /// assert!(interner.is_empty());
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, hash_map::RandomState::new())
+    }
+}
+
+impl<T, Id, S> Interner<T, Id, S>
+where
+    T: Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher,
+{
+    /// Creates an empty `Interner` using `hash_builder` to hash values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut interner = Interner::<&str, u8, _>::with_hasher(RandomState::new());
+    /// assert_eq!(interner.intern("a"), 0);
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    /// Creates an empty `Interner` with room for at least `capacity` values,
+    /// using `hash_builder` to hash values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let interner = Interner::<&str, u8, _>::with_capacity_and_hasher(10, RandomState::new());
+    /// assert!(interner.is_empty());
+    /// ```
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            values: Values {
+                values: Vec::with_capacity(capacity),
+                indices: HashTable::with_capacity(capacity),
+                hash_builder,
+            },
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, Id, S> Interner<T, Id, S>
+where
+    T: Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher,
+{
+    /// Returns the number of interned values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let mut interner = Interner::<&str>::new();
+    /// interner.intern("a");
+    /// interner.intern("b");
+    /// interner.intern("a");
+    /// assert_eq!(interner.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.values.values.len()
+    }
+
+    /// Returns `true` if no values have been interned, and `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let mut interner = Interner::<&str>::new();
+    /// assert!(interner.is_empty());
+    /// interner.intern("a");
+    /// assert!(!interner.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.values.values.is_empty()
+    }
+
+    /// Returns the id of the given value, interning it with the next id if it
+    /// wasn't interned yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the next id doesn't fit in `Id`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let mut interner = Interner::<&str>::new();
+    /// assert_eq!(interner.intern("a"), 0);
+    /// assert_eq!(interner.intern("b"), 1);
+    /// assert_eq!(interner.intern("a"), 0);
+    /// ```
+    pub fn intern(&mut self, value: T) -> Id {
+        match self.lookup(&value) {
+            Some(id) => id,
+            None => self.push(value),
+        }
+    }
+
+    /// Returns the id of the given borrowed value, interning an owned copy of
+    /// it with the next id if it wasn't interned yet.
+    ///
+    /// Unlike [`intern`](Interner::intern), this only creates an owned value
+    /// when it is actually inserted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the next id doesn't fit in `Id`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let mut interner = Interner::<String>::new();
+    /// assert_eq!(interner.intern_ref("a"), 0);
+    /// assert_eq!(interner.intern_ref("a"), 0);
+    /// assert_eq!(interner.resolve(0), Some(&"a".to_string()));
+    /// ```
+    pub fn intern_ref<Q>(&mut self, value: &Q) -> Id
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ToOwned<Owned = T> + ?Sized,
+    {
+        match self.lookup(value) {
+            Some(id) => id,
+            None => self.push(value.to_owned()),
+        }
+    }
+
+    /// Returns the id of the given value, or `None` if it wasn't interned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let mut interner = Interner::<String>::new();
+    /// interner.intern_ref("a");
+    /// assert_eq!(interner.lookup("a"), Some(0));
+    /// assert_eq!(interner.lookup("b"), None);
+    /// ```
+    pub fn lookup<Q>(&self, value: &Q) -> Option<Id>
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.values.find(value).map(id)
+    }
+
+    /// Returns the value with the given id, or `None` if no value has that id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let mut interner = Interner::<&str>::new();
+    /// interner.intern("a");
+    /// assert_eq!(interner.resolve(0), Some(&"a"));
+    /// assert_eq!(interner.resolve(1), None);
+    /// ```
+    pub fn resolve(&self, id: Id) -> Option<&T> {
+        self.values.values.get(id.index())
+    }
+
+    /// Creates an iterator over the ids and values in the interner, in the
+    /// order of their ids.
+    ///
+    /// The iterator element type is `(Id, &T)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let interner: Interner<_> = vec!["b", "a", "b"].into_iter().collect();
+    /// let entries: Vec<_> = interner.iter().collect();
+    /// assert_eq!(entries, [(0, &"b"), (1, &"a")]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T, Id> {
+        Iter {
+            inner: self.values.values.iter().enumerate(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Turns the interner into a read-only [`FrozenInterner`], which keeps
+    /// all assigned ids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let mut interner = Interner::<&str>::new();
+    /// interner.intern("a");
+    ///
+    /// let frozen = interner.freeze();
+    /// assert_eq!(frozen.lookup(&"a"), Some(0));
+    /// ```
+    pub fn freeze(self) -> FrozenInterner<T, Id, S> {
+        FrozenInterner {
+            values: self.values,
+            marker: PhantomData,
+        }
+    }
+
+    /// Interns a value that isn't interned yet with the next id.
+    fn push(&mut self, value: T) -> Id {
+        let values = &mut self.values;
+        let index = values.values.len();
+        let id = id(index);
+        let hash = values.hash_builder.hash_one(&value);
+        values.values.push(value);
+        values
+            .indices
+            .insert_unique(hash, index, rehasher(&values.values, &values.hash_builder));
+        id
+    }
+}
+
+impl<T, Id, S> FrozenInterner<T, Id, S>
+where
+    T: Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher,
+{
+    /// Returns the number of interned values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let interner: Interner<_> = vec!["a", "b"].into_iter().collect();
+    /// assert_eq!(interner.freeze().len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.values.values.len()
+    }
+
+    /// Returns `true` if the interner contains no values, and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let interner = Interner::<&str>::new();
+    /// assert!(interner.freeze().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.values.values.is_empty()
+    }
+
+    /// Returns the id of the given value, or `None` if it wasn't interned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let interner: Interner<String> = vec!["a".to_string()].into_iter().collect();
+    /// let frozen = interner.freeze();
+    /// assert_eq!(frozen.lookup("a"), Some(0));
+    /// assert_eq!(frozen.lookup("b"), None);
+    /// ```
+    pub fn lookup<Q>(&self, value: &Q) -> Option<Id>
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.values.find(value).map(id)
+    }
+
+    /// Returns the value with the given id, or `None` if no value has that id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let interner: Interner<_> = vec!["a"].into_iter().collect();
+    /// let frozen = interner.freeze();
+    /// assert_eq!(frozen.resolve(0), Some(&"a"));
+    /// assert_eq!(frozen.resolve(1), None);
+    /// ```
+    pub fn resolve(&self, id: Id) -> Option<&T> {
+        self.values.values.get(id.index())
+    }
+
+    /// Creates an iterator over the ids and values in the interner, in the
+    /// order of their ids.
+    ///
+    /// The iterator element type is `(Id, &T)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let interner: Interner<_> = vec!["b", "a"].into_iter().collect();
+    /// let frozen = interner.freeze();
+    /// assert_eq!(frozen.iter().collect::<Vec<_>>(), [(0, &"b"), (1, &"a")]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T, Id> {
+        Iter {
+            inner: self.values.values.iter().enumerate(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Turns the frozen interner back into an [`Interner`] that new values
+    /// can be interned into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::Interner;
+    ///
+    /// let interner: Interner<_> = vec!["a"].into_iter().collect();
+    /// let mut interner = interner.freeze().thaw();
+    /// assert_eq!(interner.intern("b"), 1);
+    /// ```
+    pub fn thaw(self) -> Interner<T, Id, S> {
+        Interner {
+            values: self.values,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, S> Values<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns the position of the given value.
+    fn find<Q>(&self, value: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder.hash_one(value);
+        let values = &self.values;
+        self.indices
+            .find(hash, |&index| values[index].borrow() == value)
+            .copied()
+    }
+}
+
+/// Returns the id of the value at the given position.
+///
+/// # Panics
+///
+/// Panics if the position doesn't fit in `Id`.
+fn id<Id: TryFrom<usize>>(index: usize) -> Id {
+    Id::try_from(index)
+        .ok()
+        .expect("ran out of ids for the interner")
+}
+
+impl<T, Id, S> fmt::Debug for Interner<T, Id, S>
+where
+    T: fmt::Debug + Eq + Hash,
+    Id: fmt::Debug + Idx + TryFrom<usize> + Copy + Eq + Hash,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T, Id, S> fmt::Debug for FrozenInterner<T, Id, S>
+where
+    T: fmt::Debug + Eq + Hash,
+    Id: fmt::Debug + Idx + TryFrom<usize> + Copy + Eq + Hash,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T, Id, S> Default for Interner<T, Id, S>
+where
+    T: Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+/// Two interners are equal if they assign the same ids to the same values.
+impl<T, Id, S> PartialEq for Interner<T, Id, S>
+where
+    T: Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.values.values == other.values.values
+    }
+}
+
+impl<T, Id, S> Eq for Interner<T, Id, S>
+where
+    T: Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher,
+{
+}
+
+/// Two interners are equal if they assign the same ids to the same values.
+impl<T, Id, S> PartialEq for FrozenInterner<T, Id, S>
+where
+    T: Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.values.values == other.values.values
+    }
+}
+
+impl<T, Id, S> Eq for FrozenInterner<T, Id, S>
+where
+    T: Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher,
+{
+}
+
+impl<T, Id, S> Extend<T> for Interner<T, Id, S>
+where
+    T: Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(move |value| {
+            self.intern(value);
+        });
+    }
+}
+
+impl<T, Id, S> FromIterator<T> for Interner<T, Id, S>
+where
+    T: Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut interner = Self::with_capacity_and_hasher(iter.size_hint().0, S::default());
+        interner.extend(iter);
+        interner
+    }
+}
+
+impl<'a, T, Id, S> IntoIterator for &'a Interner<T, Id, S>
+where
+    T: Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher,
+{
+    type Item = (Id, &'a T);
+    type IntoIter = Iter<'a, T, Id>;
+
+    fn into_iter(self) -> Iter<'a, T, Id> {
+        self.iter()
+    }
+}
+
+impl<'a, T, Id, S> IntoIterator for &'a FrozenInterner<T, Id, S>
+where
+    T: Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher,
+{
+    type Item = (Id, &'a T);
+    type IntoIter = Iter<'a, T, Id>;
+
+    fn into_iter(self) -> Iter<'a, T, Id> {
+        self.iter()
+    }
+}
+
+/// An iterator over the ids and values in an `Interner` or `FrozenInterner`.
+///
+/// This struct is created by the [`iter`](Interner::iter) method of
+/// `Interner` and of `FrozenInterner`.
+#[derive(Clone)]
+pub struct Iter<'a, T, Id> {
+    inner: Enumerate<slice::Iter<'a, T>>,
+    marker: PhantomData<fn() -> Id>,
+}
+
+impl<'a, T, Id: TryFrom<usize>> DoubleEndedIterator for Iter<'a, T, Id> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|(index, value)| (id(index), value))
+    }
+}
+
+impl<'a, T, Id: TryFrom<usize>> ExactSizeIterator for Iter<'a, T, Id> {}
+
+impl<'a, T, Id: TryFrom<usize>> FusedIterator for Iter<'a, T, Id> {}
+
+impl<'a, T, Id: TryFrom<usize>> Iterator for Iter<'a, T, Id> {
+    type Item = (Id, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(index, value)| (id(index), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intern_and_resolve() {
+        let mut interner = Interner::<String, u8>::new();
+        assert_eq!(interner.intern("b".to_string()), 0);
+        assert_eq!(interner.intern_ref("a"), 1);
+        assert_eq!(interner.intern_ref("b"), 0);
+        assert_eq!(interner.intern("a".to_string()), 1);
+        assert_eq!(interner.len(), 2);
+
+        assert_eq!(interner.lookup("a"), Some(1));
+        assert_eq!(interner.lookup("c"), None);
+        assert_eq!(interner.resolve(0).map(String::as_str), Some("b"));
+        assert_eq!(interner.resolve(2), None);
+        for (id, value) in &interner {
+            assert_eq!(interner.lookup(value), Some(id));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ran out of ids")]
+    fn intern_out_of_ids() {
+        let mut interner = Interner::<u16, u8>::new();
+        interner.extend(0..=256);
+    }
+
+    #[test]
+    fn freeze_and_thaw() {
+        let interner: Interner<_> = "hello".chars().collect();
+        let frozen = interner.clone().freeze();
+        assert_eq!(frozen.len(), 4);
+        assert_eq!(frozen.lookup(&'l'), Some(2));
+        assert_eq!(frozen.resolve(3), Some(&'o'));
+        assert_eq!(frozen.iter().next_back(), Some((3, &'o')));
+
+        let mut thawed = frozen.thaw();
+        assert_eq!(thawed, interner);
+        assert_eq!(thawed.intern('!'), 4);
+        assert_ne!(thawed, interner);
+    }
+
+    #[test]
+    fn id_without_hash() {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Sym(u8);
+
+        impl Idx for Sym {
+            fn index(&self) -> usize {
+                self.0.into()
+            }
+        }
+
+        impl TryFrom<usize> for Sym {
+            type Error = <u8 as TryFrom<usize>>::Error;
+
+            fn try_from(index: usize) -> Result<Self, Self::Error> {
+                u8::try_from(index).map(Sym)
+            }
+        }
+
+        let mut interner = Interner::<&str, Sym>::new();
+        assert_eq!(interner.intern("a"), Sym(0));
+        assert_eq!(interner.intern("b"), Sym(1));
+        assert_eq!(interner.lookup("b"), Some(Sym(1)));
+        assert_eq!(interner.resolve(Sym(0)), Some(&"a"));
+    }
+
+    #[test]
+    fn eq_and_debug() {
+        let interner: Interner<_> = vec!["a", "b"].into_iter().collect();
+        let other: Interner<_> = vec!["b", "a"].into_iter().collect();
+        assert_ne!(interner, other);
+        assert_eq!(format!("{:?}", interner), r#"{0: "a", 1: "b"}"#);
+        assert_eq!(format!("{:?}", other.freeze()), r#"{0: "b", 1: "a"}"#);
+    }
+}
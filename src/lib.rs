@@ -7,10 +7,11 @@
 //! value but a value can be associated with more than one key.
 //!
 //! This crate provides two kinds of bimap: a [`BiHashMap`] and a
-//! [`BiBTreeMap`]. Internally, each one is composed of two maps, one for the
-//! left-to-right direction and one for right-to-left. As such, the big-O
-//! performance of the `get`, `remove`, `insert`, and `contains` methods are the
-//! same as those of the backing map.
+//! [`BiBTreeMap`]. Internally, a `BiBTreeMap` is composed of two maps, one for
+//! the left-to-right direction and one for right-to-left, while a `BiHashMap`
+//! stores each pair once in a dense vector and keeps two hash tables of indices
+//! into it. As such, the big-O performance of the `get`, `remove`, `insert`,
+//! and `contains` methods are the same as those of the backing map.
 //!
 //! For convenience, the type definition [`BiMap`] corresponds to a `BiHashMap`.
 //! If you're using this crate without the standard library, it instead
@@ -77,6 +78,15 @@
 //! return type of `insert_no_overwrite` is a `Result` indicating if the
 //! insertion was successful.
 //!
+//! When the caller needs to know why an insertion failed, [`try_insert`] works
+//! like `insert_no_overwrite` but returns an [`InsertError`] describing which
+//! existing pairs the new pair collided with.
+//!
+//! A batch of insertions and removals can be made through a
+//! [`transaction`](BiHashMap::transaction), which keeps the overwritten and
+//! removed pairs in an undo log and rolls the whole batch back unless it is
+//! committed.
+//!
 //! This is especially important when dealing with types that can be equal while
 //! having different data. Unlike a `HashMap` or `BTreeMap`, which [doesn't
 //! update an equal key upon insertion], a bimap updates both the left values
@@ -175,12 +185,83 @@
 //! is disabled. If you choose to do this, only `BiBTreeMap` is available, not
 //! `BiHashMap`.
 //!
+//! ## Custom orderings
+//!
+//! A `BiBTreeMap` orders its left and right values by their `Ord`
+//! implementations unless it is given other comparators, which can be used to
+//! sort one side in reverse or to look up strings case-insensitively. Lookups,
+//! ranges and iteration all respect these orderings. See the [`compare`]
+//! module for examples.
+//!
+//! ## One-to-many and many-to-many relations
+//!
+//! The [`multi`] module provides multimaps, which relate each value to any
+//! number of values on the other side instead of exactly one. Inserting a pair
+//! into a multimap never overwrites other pairs.
+//!
+//! ## Many-to-one relations
+//!
+//! An [`InjectiveMap`] pairs every left value with exactly one right value
+//! while allowing several left values to share a right value, and keeps a
+//! reverse index so that all left values of a right value can be looked up
+//! with [`get_lefts_by_right`]. Reassigning a left value reports its previous
+//! right value with a [`Previous`](injective::Previous). This type requires
+//! the `std` feature.
+//!
+//! [`get_lefts_by_right`]: InjectiveMap::get_lefts_by_right
+//!
+//! ## Insertion order
+//!
+//! The iteration order of a `BiHashMap` is unspecified. A [`BiIndexMap`]
+//! instead keeps its pairs in the order they were inserted in, so they can also
+//! be accessed by position with [`get_index`](BiIndexMap::get_index), moved
+//! around and sorted. This type requires the `std` feature.
+//!
+//! ## Persistent bimaps
+//!
+//! A [`PersistentBiMap`] is never modified in place. Its `insert` and
+//! `remove_*` methods return a new version of the bimap that shares most of
+//! its structure with the original, and cloning a version takes constant time,
+//! which makes it cheap to keep old versions around. This type requires the
+//! `std` feature.
+//!
+//! ## Interning
+//!
+//! An [`Interner`] assigns dense ids to values in the order they are first
+//! interned, and resolves ids to values and values to ids. It can be frozen
+//! into a read-only [`FrozenInterner`](intern::FrozenInterner), and with the
+//! `serde` feature it round-trips with the same ids. This type requires the
+//! `std` feature.
+//!
+//! ## Dense integer ids
+//!
+//! When both sides of a bimap are small integers, such as in a renumbering of
+//! node ids, a [`BiVecMap`] stores each direction in a vector indexed by the
+//! values themselves instead of in hash tables or trees. Any type implementing
+//! [`Idx`](vec::Idx) can be used on either side. `BiVecMap` is available
+//! without the standard library.
+//!
+//! ## Sharing a bimap between threads
+//!
+//! A [`sync::ConcurrentBiHashMap`] can be modified through a shared reference
+//! from many threads at once. It splits its pairs over several independently
+//! locked shards instead of putting a single lock around the whole bimap,
+//! while still updating both directions of every pair atomically.
+//!
 //! ## serde compatibility
 //!
 //! When the `serde` feature is enabled, implementations of `Serialize` and
-//! `Deserialize` are provided for [`BiHashMap`] and [`BiBTreeMap`], allowing
-//! them to be serialized or deserialized painlessly. See the [`serde`] module
-//! for examples and more information.
+//! `Deserialize` are provided for [`BiHashMap`], [`BiBTreeMap`] and
+//! [`BiIndexMap`], allowing them to be serialized or deserialized painlessly.
+//! See the [`serde`] module for examples and more information.
+//!
+//! ## rayon compatibility
+//!
+//! When the `rayon` feature is enabled, [`BiHashMap`] and [`BiBTreeMap`] can be
+//! iterated over in parallel with `par_iter`, `par_left_values` and
+//! `par_right_values`, and built or extended from parallel iterators. Parallel
+//! construction inserts the pairs in the iterator's order, so conflicting pairs
+//! overwrite each other exactly as they would sequentially.
 //!
 //! [bijective map]: https://en.wikipedia.org/wiki/Bijection
 //! [doesn't update an equal key upon insertion]:
@@ -189,6 +270,7 @@
 //! [`BTreeMap`]: https://doc.rust-lang.org/std/collections/struct.BTreeMap.html
 //! [`insert`]: BiHashMap::insert
 //! [`insert_no_overwrite`]: BiHashMap::insert_no_overwrite
+//! [`try_insert`]: BiHashMap::try_insert
 
 // Document everything!
 #![deny(missing_docs)]
@@ -199,7 +281,62 @@
 #[macro_use]
 extern crate alloc;
 
+mod mem;
+
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Implements `ParallelIterator` and `IndexedParallelIterator` for a wrapper
+/// around an indexed parallel iterator stored in its `inner` field, requiring
+/// each type parameter to implement the given trait.
+#[cfg(feature = "rayon")]
+macro_rules! delegate_indexed {
+    ($name:ident<$($lt:lifetime,)? $($param:ident),*>: $item:ty, $bound:ident) => {
+        impl<$($lt,)? $($param),*> ::rayon::iter::ParallelIterator for $name<$($lt,)? $($param),*>
+        where
+            $($param: $bound),*
+        {
+            type Item = $item;
+
+            fn drive_unindexed<C>(self, consumer: C) -> C::Result
+            where
+                C: ::rayon::iter::plumbing::UnindexedConsumer<Self::Item>,
+            {
+                ::rayon::iter::ParallelIterator::drive_unindexed(self.inner, consumer)
+            }
+
+            fn opt_len(&self) -> Option<usize> {
+                ::rayon::iter::ParallelIterator::opt_len(&self.inner)
+            }
+        }
+
+        impl<$($lt,)? $($param),*> ::rayon::iter::IndexedParallelIterator for $name<$($lt,)? $($param),*>
+        where
+            $($param: $bound),*
+        {
+            fn drive<C>(self, consumer: C) -> C::Result
+            where
+                C: ::rayon::iter::plumbing::Consumer<Self::Item>,
+            {
+                ::rayon::iter::IndexedParallelIterator::drive(self.inner, consumer)
+            }
+
+            fn len(&self) -> usize {
+                ::rayon::iter::IndexedParallelIterator::len(&self.inner)
+            }
+
+            fn with_producer<CB>(self, callback: CB) -> CB::Output
+            where
+                CB: ::rayon::iter::plumbing::ProducerCallback<Self::Item>,
+            {
+                ::rayon::iter::IndexedParallelIterator::with_producer(self.inner, callback)
+            }
+        }
+    };
+}
+
 pub mod btree;
+pub mod compare;
 pub use btree::BiBTreeMap;
 
 #[cfg(feature = "std")]
@@ -207,6 +344,26 @@ pub mod hash;
 #[cfg(feature = "std")]
 pub use hash::BiHashMap;
 
+#[cfg(feature = "std")]
+pub mod injective;
+#[cfg(feature = "std")]
+pub use injective::InjectiveMap;
+
+#[cfg(feature = "std")]
+pub mod index;
+#[cfg(feature = "std")]
+pub use index::BiIndexMap;
+
+#[cfg(feature = "std")]
+pub mod intern;
+#[cfg(feature = "std")]
+pub use intern::Interner;
+
+#[cfg(feature = "std")]
+pub mod persistent;
+#[cfg(feature = "std")]
+pub use persistent::PersistentBiMap;
+
 /// Type definition for convenience and compatibility with older versions of
 /// this crate.
 #[cfg(feature = "std")]
@@ -217,9 +374,16 @@ pub type BiMap<L, R> = BiHashMap<L, R>;
 #[cfg(not(feature = "std"))]
 pub type BiMap<L, R> = BiBTreeMap<L, R>;
 
+pub mod multi;
+pub mod vec;
+pub use vec::BiVecMap;
+
 #[cfg(all(feature = "serde", feature = "std"))]
 pub mod serde;
 
+#[cfg(feature = "std")]
+pub mod sync;
+
 /// The previous left-right pairs, if any, that were overwritten by a call to
 /// the [`insert`](BiHashMap::insert) method of a bimap.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -262,9 +426,455 @@ impl<L, R> Overwritten<L, R> {
     /// assert!(bimap.insert('a', 2).did_overwrite());
     /// ```
     pub fn did_overwrite(&self) -> bool {
+        !matches!(self, Overwritten::Neither)
+    }
+
+    /// Converts the overwritten pairs of an insertion into a bimap into those
+    /// of the same insertion into the bimap's inverse.
+    pub(crate) fn inverse(self) -> Overwritten<R, L> {
+        match self {
+            Overwritten::Neither => Overwritten::Neither,
+            Overwritten::Left(l, r) => Overwritten::Right(r, l),
+            Overwritten::Right(l, r) => Overwritten::Left(r, l),
+            Overwritten::Pair(l, r) => Overwritten::Pair(r, l),
+            Overwritten::Both((l1, r1), (l2, r2)) => Overwritten::Both((r2, l2), (r1, l1)),
+        }
+    }
+}
+
+/// A modification of a bimap within a transaction, recorded so that it can be
+/// undone when the transaction is rolled back.
+pub(crate) enum Undo<L, R> {
+    /// A pair with the given left value was inserted without overwriting any
+    /// other pairs.
+    Inserted(L),
+
+    /// Pairs were overwritten by an insertion.
+    Overwritten(Overwritten<L, R>),
+
+    /// A pair was removed.
+    Removed(L, R),
+}
+
+impl<L, R> Undo<L, R> {
+    /// Undoes the modification, which must be the last one that wasn't undone
+    /// yet, using the `insert` and `remove_by_left` methods of the bimap.
+    pub(crate) fn undo<M>(
+        self,
+        bimap: &mut M,
+        insert: impl Fn(&mut M, L, R),
+        remove_by_left: impl Fn(&mut M, &L),
+    ) {
+        match self {
+            Undo::Inserted(left) => remove_by_left(bimap, &left),
+            // inserting the overwritten pairs again overwrites the new pair
+            Undo::Overwritten(Overwritten::Neither) => {}
+            Undo::Overwritten(Overwritten::Left(l, r))
+            | Undo::Overwritten(Overwritten::Right(l, r))
+            | Undo::Overwritten(Overwritten::Pair(l, r))
+            | Undo::Removed(l, r) => insert(bimap, l, r),
+            Undo::Overwritten(Overwritten::Both((l1, r1), (l2, r2))) => {
+                insert(bimap, l1, r1);
+                insert(bimap, l2, r2);
+            }
+        }
+    }
+}
+
+/// Panics in debug builds if a mutable guard is dropped after its value was
+/// changed without committing it, unless the thread is already panicking.
+///
+/// Without the `std` feature, the check is skipped since it can't tell whether
+/// the thread is panicking.
+pub(crate) fn debug_assert_committed(changed: bool, guard: &str) {
+    #[cfg(feature = "std")]
+    debug_assert!(
+        !changed || std::thread::panicking(),
+        "a `{}` guard was changed and dropped without being committed",
+        guard
+    );
+    #[cfg(not(feature = "std"))]
+    let _ = (changed, guard);
+}
+
+/// A left-right pair taken out of a bimap, which is inserted back into the
+/// bimap when the guard is dropped, even if a panic unwinds past it.
+pub(crate) struct Reinsert<'a, M, L, R, F>
+where
+    F: FnMut(&mut M, L, R),
+{
+    bimap: &'a mut M,
+    pair: Option<(L, R)>,
+    insert: F,
+}
+
+impl<'a, M, L, R, F> Reinsert<'a, M, L, R, F>
+where
+    F: FnMut(&mut M, L, R),
+{
+    /// Creates a guard that inserts the pair back into the bimap with
+    /// `insert`.
+    pub(crate) fn new(bimap: &'a mut M, pair: (L, R), insert: F) -> Self {
+        Reinsert {
+            bimap,
+            pair: Some(pair),
+            insert,
+        }
+    }
+
+    /// Returns mutable references to the values of the pair.
+    pub(crate) fn pair_mut(&mut self) -> (&mut L, &mut R) {
+        // unwrap is safe because the pair is only taken when dropped
+        let (left, right) = self.pair.as_mut().unwrap();
+        (left, right)
+    }
+}
+
+impl<'a, M, L, R, F> Drop for Reinsert<'a, M, L, R, F>
+where
+    F: FnMut(&mut M, L, R),
+{
+    fn drop(&mut self) {
+        if let Some((left, right)) = self.pair.take() {
+            (self.insert)(self.bimap, left, right);
+        }
+    }
+}
+
+/// The error returned by the [`try_insert`](BiHashMap::try_insert) method of a
+/// bimap when the left or right value of the pair is already in the bimap.
+///
+/// The bimap is left unchanged, and the rejected pair is handed back together
+/// with the existing pairs it collided with.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct InsertError<'a, L, R> {
+    /// The left-right pair that was not inserted.
+    pub pair: (L, R),
+
+    /// The existing pairs that prevented the pair from being inserted.
+    pub collision: Collision<&'a L, &'a R>,
+}
+
+impl<'a, L, R> fmt::Display for InsertError<'a, L, R>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_insert_error(f, &self.pair, self.collision)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, L, R> std::error::Error for InsertError<'a, L, R>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+}
+
+impl<'a, L, R> InsertError<'a, L, R> {
+    /// Converts the error of an insertion into a bimap into that of the same
+    /// insertion into the bimap's inverse.
+    pub(crate) fn inverse(self) -> InsertError<'a, R, L> {
+        let (left, right) = self.pair;
+        InsertError {
+            pair: (right, left),
+            collision: self.collision.inverse(),
+        }
+    }
+}
+
+impl<'a, L, R> InsertError<'a, L, R>
+where
+    L: Clone,
+    R: Clone,
+{
+    /// Clones the colliding pairs out of the bimap, so that the error no longer
+    /// borrows it.
+    ///
+    /// The owned error can outlive the bimap, e.g. to be returned with `?` as a
+    /// `Box<dyn Error>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, Collision, OwnedInsertError};
+    ///
+    /// let error = {
+    ///     let mut bimap = BiBTreeMap::new();
+    ///     bimap.insert('a', 1);
+    ///     bimap.try_insert('a', 2).unwrap_err().into_owned()
+    /// };
+    /// assert_eq!(
+    ///     error,
+    ///     OwnedInsertError {
+    ///         pair: ('a', 2),
+    ///         collision: Collision::Left('a', 1),
+    ///     }
+    /// );
+    /// ```
+    pub fn into_owned(self) -> OwnedInsertError<L, R> {
+        OwnedInsertError {
+            pair: self.pair,
+            collision: self.collision.cloned(),
+        }
+    }
+}
+
+/// An [`InsertError`] that owns clones of the colliding pairs instead of
+/// borrowing them from the bimap.
+///
+/// This struct is created by the [`into_owned`](InsertError::into_owned)
+/// method of `InsertError`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct OwnedInsertError<L, R> {
+    /// The left-right pair that was not inserted.
+    pub pair: (L, R),
+
+    /// The existing pairs that prevented the pair from being inserted.
+    pub collision: Collision<L, R>,
+}
+
+impl<L, R> fmt::Display for OwnedInsertError<L, R>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_insert_error(f, &self.pair, self.collision.as_ref())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<L, R> std::error::Error for OwnedInsertError<L, R>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+}
+
+/// Formats an insertion error from the rejected pair and its collision.
+fn fmt_insert_error<L, R>(
+    f: &mut fmt::Formatter,
+    (left, right): &(L, R),
+    collision: Collision<&L, &R>,
+) -> fmt::Result
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    write!(f, "failed to insert {:?} <> {:?}: ", left, right)?;
+    match collision {
+        Collision::Left(_, right) => {
+            write!(f, "the left value is already paired with {:?}", right)
+        }
+        Collision::Right(left, _) => {
+            write!(f, "the right value is already paired with {:?}", left)
+        }
+        Collision::Pair(..) => f.write_str("the pair is already in the bimap"),
+        Collision::Both((_, right), (left, _)) => write!(
+            f,
+            "the left value is already paired with {:?} and the right value with {:?}",
+            right, left
+        ),
+    }
+}
+
+/// The existing left-right pairs that prevented a pair from being inserted
+/// into a bimap, as reported by an [`InsertError`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Collision<L, R> {
+    /// The left value exists in the bimap, and its pair is given.
+    Left(L, R),
+
+    /// The right value exists in the bimap, and its pair is given.
+    Right(L, R),
+
+    /// The left-right pair already exists in the bimap.
+    Pair(L, R),
+
+    /// Both the left and the right value exist in the bimap, but as part of
+    /// separate pairs. The first tuple is the pair of the left value, and the
+    /// second is the pair of the right value.
+    Both((L, R), (L, R)),
+}
+
+impl<L, R> Collision<L, R> {
+    /// Converts the collision of an insertion into a bimap into that of the
+    /// same insertion into the bimap's inverse.
+    pub(crate) fn inverse(self) -> Collision<R, L> {
+        match self {
+            Collision::Left(l, r) => Collision::Right(r, l),
+            Collision::Right(l, r) => Collision::Left(r, l),
+            Collision::Pair(l, r) => Collision::Pair(r, l),
+            Collision::Both((l1, r1), (l2, r2)) => Collision::Both((r2, l2), (r1, l1)),
+        }
+    }
+
+    /// Converts from `&Collision<L, R>` to `Collision<&L, &R>`.
+    pub(crate) fn as_ref(&self) -> Collision<&L, &R> {
+        match self {
+            Collision::Left(l, r) => Collision::Left(l, r),
+            Collision::Right(l, r) => Collision::Right(l, r),
+            Collision::Pair(l, r) => Collision::Pair(l, r),
+            Collision::Both((l1, r1), (l2, r2)) => Collision::Both((l1, r1), (l2, r2)),
+        }
+    }
+}
+
+impl<'a, L, R> Collision<&'a L, &'a R>
+where
+    L: Clone,
+    R: Clone,
+{
+    /// Maps a `Collision<&L, &R>` to a `Collision<L, R>` by cloning the
+    /// values of the colliding pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, Collision};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// let error = bimap.try_insert('b', 1).unwrap_err();
+    /// assert_eq!(error.collision.cloned(), Collision::Right('a', 1));
+    /// ```
+    pub fn cloned(self) -> Collision<L, R> {
         match self {
-            Overwritten::Neither => false,
-            _ => true,
+            Collision::Left(l, r) => Collision::Left(l.clone(), r.clone()),
+            Collision::Right(l, r) => Collision::Right(l.clone(), r.clone()),
+            Collision::Pair(l, r) => Collision::Pair(l.clone(), r.clone()),
+            Collision::Both((l1, r1), (l2, r2)) => {
+                Collision::Both((l1.clone(), r1.clone()), (l2.clone(), r2.clone()))
+            }
+        }
+    }
+}
+
+impl<'a, L, R> Collision<&'a L, &'a R> {
+    /// Classifies the existing pairs of the left and right value of a pair that
+    /// couldn't be inserted, at least one of which must exist.
+    pub(crate) fn new(
+        left_pair: Option<(&'a L, &'a R)>,
+        right_pair: Option<(&'a L, &'a R)>,
+    ) -> Self {
+        match (left_pair, right_pair) {
+            (Some((left, right)), Some(r_pair)) if core::ptr::eq(left, r_pair.0) => {
+                Collision::Pair(left, right)
+            }
+            (Some(l_pair), Some(r_pair)) => Collision::Both(l_pair, r_pair),
+            (Some((left, right)), None) => Collision::Left(left, right),
+            (None, Some((left, right))) => Collision::Right(left, right),
+            (None, None) => unreachable!("no collision"),
+        }
+    }
+}
+
+/// The error returned by the
+/// [`insert_all_no_overwrite`](BiHashMap::insert_all_no_overwrite) method of a
+/// bimap when pairs of the batch conflict with the bimap or with each other.
+///
+/// The bimap is left unchanged, and the whole batch is handed back.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BatchInsertError<L, R> {
+    /// The pairs of the batch in their original order, none of which were
+    /// inserted.
+    pub pairs: Vec<(L, R)>,
+
+    /// Every conflict found in the batch, in the order of the indices of the
+    /// pairs they were found at.
+    pub conflicts: Vec<BatchConflict>,
+}
+
+impl<L, R> BatchInsertError<L, R> {
+    /// Converts the error of a batch insertion into a bimap into that of the
+    /// same batch inserted into the bimap's inverse.
+    pub(crate) fn inverse(self) -> BatchInsertError<R, L> {
+        let mut conflicts: Vec<_> = self
+            .conflicts
+            .into_iter()
+            .map(BatchConflict::inverse)
+            .collect();
+        // the conflicts of each pair are listed left before right
+        conflicts.sort_by_key(BatchConflict::order);
+        BatchInsertError {
+            pairs: self.pairs.into_iter().map(|(l, r)| (r, l)).collect(),
+            conflicts,
+        }
+    }
+}
+
+impl<L, R> fmt::Display for BatchInsertError<L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let plural = if self.conflicts.len() == 1 { "" } else { "s" };
+        write!(
+            f,
+            "failed to insert a batch of {} pairs: found {} conflict{}",
+            self.pairs.len(),
+            self.conflicts.len(),
+            plural
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl<L, R> std::error::Error for BatchInsertError<L, R>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+}
+
+/// A conflict that prevented a batch of pairs from being inserted into a
+/// bimap, as reported by a [`BatchInsertError`].
+///
+/// Pairs are identified by their index in the batch. A single pair may be
+/// involved in several conflicts.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BatchConflict {
+    /// The left value of the pair at the given index already exists in the
+    /// bimap.
+    Left(usize),
+
+    /// The right value of the pair at the given index already exists in the
+    /// bimap.
+    Right(usize),
+
+    /// The pair at the second index has the same left value as the earlier
+    /// pair at the first index.
+    DuplicateLeft(usize, usize),
+
+    /// The pair at the second index has the same right value as the earlier
+    /// pair at the first index.
+    DuplicateRight(usize, usize),
+}
+
+impl BatchConflict {
+    /// Converts a conflict of a batch inserted into a bimap into that of the
+    /// same batch inserted into the bimap's inverse.
+    fn inverse(self) -> Self {
+        match self {
+            BatchConflict::Left(index) => BatchConflict::Right(index),
+            BatchConflict::Right(index) => BatchConflict::Left(index),
+            BatchConflict::DuplicateLeft(first, index) => {
+                BatchConflict::DuplicateRight(first, index)
+            }
+            BatchConflict::DuplicateRight(first, index) => {
+                BatchConflict::DuplicateLeft(first, index)
+            }
+        }
+    }
+
+    /// Returns the key that orders the conflicts of a `BatchInsertError`: by
+    /// the index of the pair they were found at, then by kind.
+    fn order(&self) -> (usize, u8) {
+        match *self {
+            BatchConflict::Left(index) => (index, 0),
+            BatchConflict::Right(index) => (index, 1),
+            BatchConflict::DuplicateLeft(_, index) => (index, 2),
+            BatchConflict::DuplicateRight(_, index) => (index, 3),
         }
     }
 }
@@ -273,12 +883,127 @@ impl<L, R> Overwritten<L, R> {
 mod tests {
     use super::*;
 
+    #[cfg(not(feature = "std"))]
+    use alloc::string::ToString;
+
     #[test]
     fn did_overwrite() {
-        assert_eq!(Overwritten::<char, i32>::Neither.did_overwrite(), false);
-        assert_eq!(Overwritten::Left('a', 1).did_overwrite(), true);
-        assert_eq!(Overwritten::Right('a', 1).did_overwrite(), true);
-        assert_eq!(Overwritten::Pair('a', 1).did_overwrite(), true);
-        assert_eq!(Overwritten::Both(('a', 1), ('b', 2)).did_overwrite(), true);
+        assert!(!Overwritten::<char, i32>::Neither.did_overwrite());
+        assert!(Overwritten::Left('a', 1).did_overwrite());
+        assert!(Overwritten::Right('a', 1).did_overwrite());
+        assert!(Overwritten::Pair('a', 1).did_overwrite());
+        assert!(Overwritten::Both(('a', 1), ('b', 2)).did_overwrite());
+    }
+
+    #[test]
+    fn inverse() {
+        assert_eq!(
+            Overwritten::<char, i32>::Neither.inverse(),
+            Overwritten::Neither
+        );
+        assert_eq!(
+            Overwritten::Left('a', 1).inverse(),
+            Overwritten::Right(1, 'a')
+        );
+        assert_eq!(
+            Overwritten::Right('a', 1).inverse(),
+            Overwritten::Left(1, 'a')
+        );
+        assert_eq!(
+            Overwritten::Pair('a', 1).inverse(),
+            Overwritten::Pair(1, 'a')
+        );
+        assert_eq!(
+            Overwritten::Both(('a', 1), ('b', 2)).inverse(),
+            Overwritten::Both((2, 'b'), (1, 'a'))
+        );
+    }
+
+    #[test]
+    fn insert_error_display() {
+        let error = InsertError {
+            pair: ('a', 1),
+            collision: Collision::Left(&'a', &2),
+        };
+        assert_eq!(
+            error.to_string(),
+            "failed to insert 'a' <> 1: the left value is already paired with 2"
+        );
+        let error = InsertError {
+            pair: ('a', 1),
+            collision: Collision::Right(&'b', &1),
+        };
+        assert_eq!(
+            error.to_string(),
+            "failed to insert 'a' <> 1: the right value is already paired with 'b'"
+        );
+        let error = InsertError {
+            pair: ('a', 1),
+            collision: Collision::Pair(&'a', &1),
+        };
+        assert_eq!(
+            error.to_string(),
+            "failed to insert 'a' <> 1: the pair is already in the bimap"
+        );
+        let error = InsertError {
+            pair: ('a', 1),
+            collision: Collision::Both((&'a', &2), (&'b', &1)),
+        };
+        assert_eq!(
+            error.to_string(),
+            "failed to insert 'a' <> 1: the left value is already paired with 2 and the right \
+             value with 'b'"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn insert_error_into_owned() {
+        fn insert_all(
+            bimap: &mut BiHashMap<char, i32>,
+            pairs: &[(char, i32)],
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            for &(left, right) in pairs {
+                bimap
+                    .try_insert(left, right)
+                    .map_err(InsertError::into_owned)?;
+            }
+            Ok(())
+        }
+
+        let mut bimap = BiHashMap::new();
+        assert!(insert_all(&mut bimap, &[('a', 1), ('b', 2)]).is_ok());
+        let error = insert_all(&mut bimap, &[('c', 3), ('a', 2)]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "failed to insert 'a' <> 2: the left value is already paired with 1 and the \
+             right value with 'b'"
+        );
+        let error = error.downcast::<OwnedInsertError<char, i32>>().unwrap();
+        assert_eq!(
+            *error,
+            OwnedInsertError {
+                pair: ('a', 2),
+                collision: Collision::Both(('a', 1), ('b', 2)),
+            }
+        );
+        assert_eq!(bimap.len(), 3);
+    }
+
+    #[test]
+    fn batch_insert_error_display() {
+        let mut error = BatchInsertError {
+            pairs: vec![('a', 1), ('a', 2)],
+            conflicts: vec![BatchConflict::DuplicateLeft(0, 1)],
+        };
+        assert_eq!(
+            error.to_string(),
+            "failed to insert a batch of 2 pairs: found 1 conflict"
+        );
+        error.conflicts.push(BatchConflict::Right(1));
+        assert_eq!(
+            error.to_string(),
+            "failed to insert a batch of 2 pairs: found 2 conflicts"
+        );
     }
 }
@@ -0,0 +1,166 @@
+//! Internal types for sharing values between the two maps inside a bimap.
+
+use crate::compare::{Compare, Natural};
+use alloc::sync::{Arc, Weak};
+use core::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::Deref,
+};
+
+/// An atomically reference-counted value that is shared between the
+/// left-to-right and right-to-left maps of a bimap, ordered by the comparator
+/// `C`.
+///
+/// The comparator is only a marker, so it does not affect whether the bimap is
+/// `Send` or `Sync`.
+///
+/// `Arc<T>` only implements `Borrow<T>`, so a map keyed by `Arc<T>` can only be
+/// queried with a `&T`. A `Ref<T>` can instead be borrowed as a `Wrapper<Q>`
+/// for any `Q` that `T` can be borrowed as, which allows the bimaps to look up
+/// values by any of their borrowed forms.
+pub(crate) struct Ref<T, C = Natural>(pub(crate) Arc<T>, PhantomData<fn() -> C>);
+
+impl<T, C> Ref<T, C> {
+    /// Creates a new shared value.
+    pub(crate) fn new(value: T) -> Self {
+        Self(Arc::new(value), PhantomData)
+    }
+
+    /// Returns the inner value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is still shared, which never happens once the pair
+    /// has been removed from both maps of a bimap.
+    pub(crate) fn into_inner(self) -> T {
+        Arc::try_unwrap(self.0).ok().unwrap()
+    }
+
+    /// Returns the inner value, cloning it if it is still shared.
+    pub(crate) fn into_inner_or_clone(self) -> T
+    where
+        T: Clone,
+    {
+        Arc::try_unwrap(self.0).unwrap_or_else(|value| (*value).clone())
+    }
+
+    /// Creates a weak reference to the shared value.
+    pub(crate) fn downgrade(&self) -> WeakRef<T, C> {
+        WeakRef(Arc::downgrade(&self.0), PhantomData)
+    }
+}
+
+impl<T, C> Clone for Ref<T, C> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
+    }
+}
+
+/// A weak reference to a value shared by the two maps of a bimap.
+///
+/// Unlike a `Ref`, it does not keep the value alive, so it never makes
+/// [`Ref::into_inner`] panic.
+pub(crate) struct WeakRef<T, C = Natural>(Weak<T>, PhantomData<fn() -> C>);
+
+impl<T, C> WeakRef<T, C> {
+    /// Returns the shared value, or `None` if it has already been dropped.
+    pub(crate) fn upgrade(&self) -> Option<Ref<T, C>> {
+        self.0.upgrade().map(|value| Ref(value, PhantomData))
+    }
+}
+
+impl<T: fmt::Debug, C> fmt::Debug for Ref<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T, C> Deref for Ref<T, C> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Hash> Hash for Ref<T, Natural> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl<T, C: Compare<T>> Ord for Ref<T, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        C::compare(&self.0, &other.0)
+    }
+}
+
+impl<T, C: Compare<T>> PartialOrd for Ref<T, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, C: Compare<T>> Eq for Ref<T, C> {}
+
+impl<T, C: Compare<T>> PartialEq for Ref<T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K, Q, C> Borrow<Wrapper<Q, C>> for Ref<K, C>
+where
+    K: Borrow<Q>,
+    Q: ?Sized,
+{
+    fn borrow(&self) -> &Wrapper<Q, C> {
+        let key: &K = &self.0;
+        Wrapper::wrap(key.borrow())
+    }
+}
+
+/// A transparent wrapper around a borrowed form of a value stored in a bimap,
+/// ordered by the comparator `C`.
+///
+/// A blanket `impl<K: Borrow<Q>, Q> Borrow<Q> for Ref<K>` would conflict with
+/// the standard library's `impl<T> Borrow<T> for T`, so lookups go through this
+/// wrapper instead. Its `Eq` and `Ord` implementations forward to the
+/// comparator, which keeps them consistent with those of `Ref<K, C>`.
+#[repr(transparent)]
+pub(crate) struct Wrapper<T: ?Sized, C = Natural> {
+    marker: PhantomData<C>,
+    value: T,
+}
+
+impl<T: ?Sized, C> Wrapper<T, C> {
+    /// Wraps a reference without copying the referenced value.
+    pub(crate) fn wrap(value: &T) -> &Self {
+        // safe because Wrapper<T, C> is #[repr(transparent)] over T
+        unsafe { &*(value as *const T as *const Self) }
+    }
+}
+
+impl<T: ?Sized, C: Compare<T>> Ord for Wrapper<T, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        C::compare(&self.value, &other.value)
+    }
+}
+
+impl<T: ?Sized, C: Compare<T>> PartialOrd for Wrapper<T, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: ?Sized, C: Compare<T>> Eq for Wrapper<T, C> {}
+
+impl<T: ?Sized, C: Compare<T>> PartialEq for Wrapper<T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
@@ -0,0 +1,66 @@
+//! Bidirectional maps that relate each value to any number of values on the
+//! other side.
+//!
+//! A bimap enforces a one-to-one relation: inserting a pair removes any pairs
+//! that share its left or right value. A multimap instead stores a set of
+//! left-right pairs in which every value may appear any number of times, which
+//! models one-to-many relations (a user and their sessions) as well as
+//! many-to-many relations (tags and the documents carrying them). Each pair
+//! appears at most once.
+//!
+//! Like the bimaps, the multimaps come in two flavors. [`BiHashMultiMap`] is
+//! backed by hash tables and requires the standard library, while
+//! [`BiBTreeMultiMap`] is backed by `BTreeMap`s and `BTreeSet`s, keeps its
+//! values sorted and is available without the standard library. The
+//! [`BiMultiMap`] type definition picks the hash-backed flavor when the `std`
+//! feature is enabled, mirroring [`BiMap`].
+//!
+//! Every value is stored once, no matter how many pairs it belongs to.
+//! Looking up a value returns an iterator over the values it is paired with.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::multi::BiBTreeMultiMap;
+//!
+//! // a many-to-many relation between tags and documents
+//! let mut tags = BiBTreeMultiMap::new();
+//! tags.insert("rust", "intro.md");
+//! tags.insert("rust", "traits.md");
+//! tags.insert("beginner", "intro.md");
+//!
+//! // inserting a pair twice has no effect
+//! assert!(!tags.insert("rust", "intro.md"));
+//! assert_eq!(tags.len(), 3);
+//!
+//! let docs: Vec<_> = tags.get_by_left(&"rust").collect();
+//! assert_eq!(docs, [&"intro.md", &"traits.md"]);
+//! let intro_tags: Vec<_> = tags.get_by_right(&"intro.md").collect();
+//! assert_eq!(intro_tags, [&"beginner", &"rust"]);
+//!
+//! // removing a single pair leaves the other pairs of both values alone
+//! assert!(tags.remove_pair(&"rust", &"intro.md"));
+//! assert!(tags.contains_right(&"intro.md"));
+//!
+//! // removing a left value removes all of its pairs at once
+//! assert_eq!(tags.remove_all_by_left(&"rust"), Some(("rust", vec!["traits.md"])));
+//! assert!(!tags.contains_right(&"traits.md"));
+//! ```
+//!
+//! [`BiMap`]: crate::BiMap
+
+pub mod btree;
+pub use btree::BiBTreeMultiMap;
+
+#[cfg(feature = "std")]
+pub mod hash;
+#[cfg(feature = "std")]
+pub use hash::BiHashMultiMap;
+
+/// Type definition for convenience, mirroring [`BiMap`](crate::BiMap).
+#[cfg(feature = "std")]
+pub type BiMultiMap<L, R> = BiHashMultiMap<L, R>;
+
+/// Type definition for convenience, mirroring [`BiMap`](crate::BiMap).
+#[cfg(not(feature = "std"))]
+pub type BiMultiMap<L, R> = BiBTreeMultiMap<L, R>;
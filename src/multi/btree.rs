@@ -0,0 +1,1046 @@
+//! A multimap backed by two `BTreeMap`s of `BTreeSet`s.
+
+use crate::mem::{Ref, Wrapper};
+use alloc::{
+    collections::{btree_map, btree_set, BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use core::{
+    borrow::Borrow,
+    fmt,
+    iter::{Extend, FromIterator, FusedIterator},
+};
+
+/// A multimap backed by two `BTreeMap`s of `BTreeSet`s.
+///
+/// Each map sends a value to the set of values it is paired with. Every value
+/// is stored once behind an `Arc` that both maps share.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// [module-level documentation]: crate::multi
+pub struct BiBTreeMultiMap<L, R> {
+    // the sets are never empty
+    left2right: BTreeMap<Ref<L>, BTreeSet<Ref<R>>>,
+    right2left: BTreeMap<Ref<R>, BTreeSet<Ref<L>>>,
+    // the number of left-right pairs
+    len: usize,
+}
+
+impl<L, R> BiBTreeMultiMap<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    /// Creates an empty `BiBTreeMultiMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let multimap = BiBTreeMultiMap::<char, i32>::new();
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            left2right: BTreeMap::new(),
+            right2left: BTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of left-right pairs in the multimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('a', 2);
+    /// multimap.insert('b', 2);
+    /// assert_eq!(multimap.len(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the multimap contains no left-right pairs, and
+    /// `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// assert!(multimap.is_empty());
+    /// multimap.insert('a', 1);
+    /// assert!(!multimap.is_empty());
+    /// multimap.remove_pair(&'a', &1);
+    /// assert!(multimap.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all left-right pairs from the multimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('b', 1);
+    /// multimap.clear();
+    /// assert!(multimap.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.left2right.clear();
+        self.right2left.clear();
+        self.len = 0;
+    }
+
+    /// Creates an iterator over the left-right pairs in the multimap, ordered
+    /// by left value and then by right value.
+    ///
+    /// The iterator element type is `(&L, &R)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// multimap.insert('b', 1);
+    /// multimap.insert('a', 2);
+    /// multimap.insert('a', 1);
+    ///
+    /// let pairs: Vec<_> = multimap.iter().collect();
+    /// assert_eq!(pairs, [(&'a', &1), (&'a', &2), (&'b', &1)]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, L, R> {
+        Iter {
+            groups: self.left2right.iter(),
+            current: None,
+            remaining: self.len,
+        }
+    }
+
+    /// Creates an iterator over the distinct left values in the multimap in
+    /// ascending order.
+    ///
+    /// The iterator element type is `&L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// multimap.insert('b', 1);
+    /// multimap.insert('a', 2);
+    /// multimap.insert('a', 1);
+    ///
+    /// let lefts: Vec<_> = multimap.left_values().collect();
+    /// assert_eq!(lefts, [&'a', &'b']);
+    /// ```
+    pub fn left_values(&self) -> LeftValues<'_, L, R> {
+        LeftValues {
+            inner: self.left2right.keys(),
+        }
+    }
+
+    /// Creates an iterator over the distinct right values in the multimap in
+    /// ascending order.
+    ///
+    /// The iterator element type is `&R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// multimap.insert('b', 1);
+    /// multimap.insert('a', 2);
+    /// multimap.insert('a', 1);
+    ///
+    /// let rights: Vec<_> = multimap.right_values().collect();
+    /// assert_eq!(rights, [&1, &2]);
+    /// ```
+    pub fn right_values(&self) -> RightValues<'_, L, R> {
+        RightValues {
+            inner: self.right2left.keys(),
+        }
+    }
+
+    /// Returns an iterator over the right values paired with the given left
+    /// value in ascending order, which is empty if the left value is not in
+    /// the multimap.
+    ///
+    /// The iterator element type is `&R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// multimap.insert('a', 2);
+    /// multimap.insert('a', 1);
+    /// multimap.insert('b', 2);
+    ///
+    /// let rights: Vec<_> = multimap.get_by_left(&'a').collect();
+    /// assert_eq!(rights, [&1, &2]);
+    /// assert_eq!(multimap.get_by_left(&'c').len(), 0);
+    /// ```
+    pub fn get_by_left<Q>(&self, left: &Q) -> Rights<'_, R>
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        Rights {
+            inner: self
+                .left2right
+                .get(Wrapper::wrap(left))
+                .map(|rights| rights.iter()),
+        }
+    }
+
+    /// Returns an iterator over the left values paired with the given right
+    /// value in ascending order, which is empty if the right value is not in
+    /// the multimap.
+    ///
+    /// The iterator element type is `&L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// multimap.insert('b', 2);
+    /// multimap.insert('a', 2);
+    /// multimap.insert('a', 1);
+    ///
+    /// let lefts: Vec<_> = multimap.get_by_right(&2).collect();
+    /// assert_eq!(lefts, [&'a', &'b']);
+    /// assert_eq!(multimap.get_by_right(&3).len(), 0);
+    /// ```
+    pub fn get_by_right<Q>(&self, right: &Q) -> Lefts<'_, L>
+    where
+        R: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        Lefts {
+            inner: self
+                .right2left
+                .get(Wrapper::wrap(right))
+                .map(|lefts| lefts.iter()),
+        }
+    }
+
+    /// Returns `true` if the multimap contains the given left value and
+    /// `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// assert!(multimap.contains_left(&'a'));
+    /// assert!(!multimap.contains_left(&'b'));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.left2right.contains_key(Wrapper::wrap(left))
+    }
+
+    /// Returns `true` if the multimap contains the given right value and
+    /// `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// assert!(multimap.contains_right(&1));
+    /// assert!(!multimap.contains_right(&2));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.right2left.contains_key(Wrapper::wrap(right))
+    }
+
+    /// Returns `true` if the multimap contains the given left-right pair and
+    /// `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('b', 2);
+    /// assert!(multimap.contains_pair(&'a', &1));
+    /// assert!(!multimap.contains_pair(&'a', &2));
+    /// ```
+    pub fn contains_pair<QL, QR>(&self, left: &QL, right: &QR) -> bool
+    where
+        L: Borrow<QL>,
+        R: Borrow<QR>,
+        QL: Ord + ?Sized,
+        QR: Ord + ?Sized,
+    {
+        self.left2right
+            .get(Wrapper::wrap(left))
+            .is_some_and(|rights| rights.contains(Wrapper::wrap(right)))
+    }
+
+    /// Inserts the given left-right pair into the multimap.
+    ///
+    /// Returns `true` if the pair was inserted, and `false` if the multimap
+    /// already contained it. Other pairs containing the left or right value are
+    /// never affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// assert!(multimap.insert('a', 1));
+    /// assert!(multimap.insert('a', 2));
+    /// assert!(multimap.insert('b', 1));
+    /// assert!(!multimap.insert('a', 1));
+    /// assert_eq!(multimap.len(), 3);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> bool {
+        self.insert_by_left(left, Some(right)) == 1
+    }
+
+    /// Inserts a pair for each of the given right values, all sharing the same
+    /// left value, and returns the number of pairs that were inserted.
+    pub(crate) fn insert_by_left<I>(&mut self, left: L, rights: I) -> usize
+    where
+        I: IntoIterator<Item = R>,
+    {
+        let left = match self.left2right.get_key_value(Wrapper::wrap(&left)) {
+            Some((left, _)) => left.clone(),
+            None => Ref::new(left),
+        };
+        let mut inserted = 0;
+        for right in rights {
+            let right = match self.right2left.get_key_value(Wrapper::wrap(&right)) {
+                Some((right, lefts)) => {
+                    if lefts.contains(&left) {
+                        continue;
+                    }
+                    right.clone()
+                }
+                None => Ref::new(right),
+            };
+            self.left2right
+                .entry(left.clone())
+                .or_default()
+                .insert(right.clone());
+            self.right2left
+                .entry(right)
+                .or_default()
+                .insert(left.clone());
+            self.len += 1;
+            inserted += 1;
+        }
+        inserted
+    }
+
+    /// Removes the given left-right pair from the multimap.
+    ///
+    /// Returns `true` if the pair was removed, and `false` if the multimap
+    /// didn't contain it. Other pairs containing the left or right value are
+    /// never affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('a', 2);
+    ///
+    /// assert!(multimap.remove_pair(&'a', &1));
+    /// assert!(!multimap.remove_pair(&'a', &1));
+    /// assert!(!multimap.contains_right(&1));
+    /// assert!(multimap.contains_left(&'a'));
+    /// ```
+    pub fn remove_pair<QL, QR>(&mut self, left: &QL, right: &QR) -> bool
+    where
+        L: Borrow<QL>,
+        R: Borrow<QR>,
+        QL: Ord + ?Sized,
+        QR: Ord + ?Sized,
+    {
+        let removed = detach(
+            &mut self.left2right,
+            Wrapper::wrap(left),
+            Wrapper::wrap(right),
+        );
+        if removed.is_none() {
+            return false;
+        }
+        detach(
+            &mut self.right2left,
+            Wrapper::wrap(right),
+            Wrapper::wrap(left),
+        );
+        self.len -= 1;
+        true
+    }
+
+    /// Removes all pairs containing the given left value from the multimap,
+    /// returning the left value and the right values it was paired with in
+    /// ascending order.
+    ///
+    /// Right values that are still paired with other left values stay in the
+    /// multimap, so they are cloned into the returned vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('a', 2);
+    /// multimap.insert('b', 2);
+    ///
+    /// assert_eq!(multimap.remove_all_by_left(&'a'), Some(('a', vec![1, 2])));
+    /// assert_eq!(multimap.len(), 1);
+    /// assert!(!multimap.contains_right(&1));
+    /// assert!(multimap.contains_pair(&'b', &2));
+    /// assert_eq!(multimap.remove_all_by_left(&'a'), None);
+    /// ```
+    pub fn remove_all_by_left<Q>(&mut self, left: &Q) -> Option<(L, Vec<R>)>
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: Clone,
+    {
+        let (left, rights) = self.left2right.remove_entry(Wrapper::wrap(left))?;
+        let rights = remove_group(&mut self.right2left, rights, &left);
+        self.len -= rights.len();
+        Some((left.into_inner(), rights))
+    }
+
+    /// Removes all pairs containing the given right value from the multimap,
+    /// returning the right value and the left values it was paired with in
+    /// ascending order.
+    ///
+    /// Left values that are still paired with other right values stay in the
+    /// multimap, so they are cloned into the returned vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiBTreeMultiMap;
+    ///
+    /// let mut multimap = BiBTreeMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('b', 1);
+    /// multimap.insert('b', 2);
+    ///
+    /// assert_eq!(multimap.remove_all_by_right(&1), Some((1, vec!['a', 'b'])));
+    /// assert_eq!(multimap.len(), 1);
+    /// assert!(!multimap.contains_left(&'a'));
+    /// assert!(multimap.contains_pair(&'b', &2));
+    /// assert_eq!(multimap.remove_all_by_right(&1), None);
+    /// ```
+    pub fn remove_all_by_right<Q>(&mut self, right: &Q) -> Option<(R, Vec<L>)>
+    where
+        R: Borrow<Q>,
+        Q: Ord + ?Sized,
+        L: Clone,
+    {
+        let (right, lefts) = self.right2left.remove_entry(Wrapper::wrap(right))?;
+        let lefts = remove_group(&mut self.left2right, lefts, &right);
+        self.len -= lefts.len();
+        Some((right.into_inner(), lefts))
+    }
+}
+
+/// Removes a value from the set of a key, dropping the set once it is empty.
+fn detach<K, V, QK, QV>(
+    map: &mut BTreeMap<Ref<K>, BTreeSet<Ref<V>>>,
+    key: &Wrapper<QK>,
+    value: &Wrapper<QV>,
+) -> Option<Ref<V>>
+where
+    K: Ord + Borrow<QK>,
+    V: Ord + Borrow<QV>,
+    QK: Ord + ?Sized,
+    QV: Ord + ?Sized,
+{
+    let values = map.get_mut(key)?;
+    let value = values.take(value)?;
+    if values.is_empty() {
+        map.remove(key);
+    }
+    Some(value)
+}
+
+/// Detaches a removed key from the sets of all the values it was paired with,
+/// and returns those values.
+fn remove_group<K, V>(
+    map: &mut BTreeMap<Ref<V>, BTreeSet<Ref<K>>>,
+    values: BTreeSet<Ref<V>>,
+    key: &Ref<K>,
+) -> Vec<V>
+where
+    K: Ord,
+    V: Ord + Clone,
+{
+    values
+        .into_iter()
+        .map(|value| {
+            detach::<V, K, V, K>(map, Wrapper::wrap(&*value), Wrapper::wrap(&**key));
+            // the value is only shared if it is still paired with other keys
+            value.into_inner_or_clone()
+        })
+        .collect()
+}
+
+impl<L, R> Clone for BiBTreeMultiMap<L, R>
+where
+    L: Clone + Ord,
+    R: Clone + Ord,
+{
+    fn clone(&self) -> BiBTreeMultiMap<L, R> {
+        let mut multimap = BiBTreeMultiMap::new();
+        for (left, rights) in &self.left2right {
+            let rights = rights.iter().map(|right| R::clone(right));
+            multimap.insert_by_left(L::clone(left), rights);
+        }
+        multimap
+    }
+}
+
+impl<L, R> fmt::Debug for BiBTreeMultiMap<L, R>
+where
+    L: fmt::Debug + Ord,
+    R: fmt::Debug + Ord,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{")?;
+        for (i, (left, right)) in self.iter().enumerate() {
+            let comma = if i == 0 { "" } else { ", " };
+            write!(f, "{}{:?} <> {:?}", comma, left, right)?;
+        }
+        write!(f, "}}")?;
+        Ok(())
+    }
+}
+
+impl<L, R> Default for BiBTreeMultiMap<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    fn default() -> BiBTreeMultiMap<L, R> {
+        BiBTreeMultiMap::new()
+    }
+}
+
+impl<L, R> Eq for BiBTreeMultiMap<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+}
+
+impl<L, R> FromIterator<(L, R)> for BiBTreeMultiMap<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    fn from_iter<I>(iter: I) -> BiBTreeMultiMap<L, R>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let mut multimap = BiBTreeMultiMap::new();
+        multimap.extend(iter);
+        multimap
+    }
+}
+
+impl<'a, L, R> IntoIterator for &'a BiBTreeMultiMap<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R>;
+
+    fn into_iter(self) -> Iter<'a, L, R> {
+        self.iter()
+    }
+}
+
+/// Consumes the multimap, yielding its left-right pairs ordered by left value
+/// and then by right value.
+///
+/// Values that belong to several pairs are cloned for all but the last pair
+/// they are yielded in.
+impl<L, R> IntoIterator for BiBTreeMultiMap<L, R>
+where
+    L: Clone + Ord,
+    R: Clone + Ord,
+{
+    type Item = (L, R);
+    type IntoIter = IntoIter<L, R>;
+
+    fn into_iter(self) -> IntoIter<L, R> {
+        // afterwards, the left-to-right map holds the only references
+        drop(self.right2left);
+        IntoIter {
+            groups: self.left2right.into_iter(),
+            current: None,
+            remaining: self.len,
+        }
+    }
+}
+
+impl<L, R> Extend<(L, R)> for BiBTreeMultiMap<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(move |(l, r)| {
+            self.insert(l, r);
+        });
+    }
+}
+
+impl<L, R> PartialEq for BiBTreeMultiMap<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.left2right == other.left2right
+    }
+}
+
+/// An owning iterator over the left-right pairs in a `BiBTreeMultiMap`.
+pub struct IntoIter<L, R> {
+    groups: btree_map::IntoIter<Ref<L>, BTreeSet<Ref<R>>>,
+    current: Option<(Ref<L>, btree_set::IntoIter<Ref<R>>)>,
+    remaining: usize,
+}
+
+impl<L: Clone, R: Clone> ExactSizeIterator for IntoIter<L, R> {}
+
+impl<L: Clone, R: Clone> FusedIterator for IntoIter<L, R> {}
+
+impl<L: Clone, R: Clone> Iterator for IntoIter<L, R> {
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((left, rights)) = &mut self.current {
+                if let Some(right) = rights.next() {
+                    self.remaining -= 1;
+                    let left = if rights.len() == 0 {
+                        // the last pair takes the left value out of its set
+                        let (left, _) = self.current.take().unwrap();
+                        left.into_inner_or_clone()
+                    } else {
+                        L::clone(left)
+                    };
+                    return Some((left, right.into_inner_or_clone()));
+                }
+            }
+            let (left, rights) = self.groups.next()?;
+            self.current = Some((left, rights.into_iter()));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// An iterator over the left-right pairs in a `BiBTreeMultiMap`.
+///
+/// This struct is created by the [`iter`] method of `BiBTreeMultiMap`.
+///
+/// [`iter`]: BiBTreeMultiMap::iter
+pub struct Iter<'a, L, R> {
+    groups: btree_map::Iter<'a, Ref<L>, BTreeSet<Ref<R>>>,
+    current: Option<(&'a L, btree_set::Iter<'a, Ref<R>>)>,
+    remaining: usize,
+}
+
+impl<'a, L, R> Clone for Iter<'a, L, R> {
+    fn clone(&self) -> Self {
+        Iter {
+            groups: self.groups.clone(),
+            current: self.current.clone(),
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<'a, L, R> ExactSizeIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> Iterator for Iter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((left, rights)) = &mut self.current {
+                if let Some(right) = rights.next() {
+                    self.remaining -= 1;
+                    return Some((*left, &**right));
+                }
+            }
+            let (left, rights) = self.groups.next()?;
+            self.current = Some((&**left, rights.iter()));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// An iterator over the distinct left values in a `BiBTreeMultiMap`.
+///
+/// This struct is created by the [`left_values`] method of `BiBTreeMultiMap`.
+///
+/// [`left_values`]: BiBTreeMultiMap::left_values
+pub struct LeftValues<'a, L, R> {
+    inner: btree_map::Keys<'a, Ref<L>, BTreeSet<Ref<R>>>,
+}
+
+impl<'a, L, R> DoubleEndedIterator for LeftValues<'a, L, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|left| &**left)
+    }
+}
+
+impl<'a, L, R> ExactSizeIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for LeftValues<'a, L, R> {
+    type Item = &'a L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|left| &**left)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the distinct right values in a `BiBTreeMultiMap`.
+///
+/// This struct is created by the [`right_values`] method of `BiBTreeMultiMap`.
+///
+/// [`right_values`]: BiBTreeMultiMap::right_values
+pub struct RightValues<'a, L, R> {
+    inner: btree_map::Keys<'a, Ref<R>, BTreeSet<Ref<L>>>,
+}
+
+impl<'a, L, R> DoubleEndedIterator for RightValues<'a, L, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|right| &**right)
+    }
+}
+
+impl<'a, L, R> ExactSizeIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for RightValues<'a, L, R> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|right| &**right)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the right values paired with a left value in a
+/// `BiBTreeMultiMap`.
+///
+/// This struct is created by the [`get_by_left`] method of `BiBTreeMultiMap`.
+///
+/// [`get_by_left`]: BiBTreeMultiMap::get_by_left
+pub struct Rights<'a, R> {
+    inner: Option<btree_set::Iter<'a, Ref<R>>>,
+}
+
+impl<'a, R> Clone for Rights<'a, R> {
+    fn clone(&self) -> Self {
+        Rights {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, R> DoubleEndedIterator for Rights<'a, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.as_mut()?.next_back().map(|right| &**right)
+    }
+}
+
+impl<'a, R> ExactSizeIterator for Rights<'a, R> {}
+
+impl<'a, R> FusedIterator for Rights<'a, R> {}
+
+impl<'a, R> Iterator for Rights<'a, R> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.as_mut()?.next().map(|right| &**right)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner
+            .as_ref()
+            .map_or((0, Some(0)), |inner| inner.size_hint())
+    }
+}
+
+/// An iterator over the left values paired with a right value in a
+/// `BiBTreeMultiMap`.
+///
+/// This struct is created by the [`get_by_right`] method of `BiBTreeMultiMap`.
+///
+/// [`get_by_right`]: BiBTreeMultiMap::get_by_right
+pub struct Lefts<'a, L> {
+    inner: Option<btree_set::Iter<'a, Ref<L>>>,
+}
+
+impl<'a, L> Clone for Lefts<'a, L> {
+    fn clone(&self) -> Self {
+        Lefts {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, L> DoubleEndedIterator for Lefts<'a, L> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.as_mut()?.next_back().map(|left| &**left)
+    }
+}
+
+impl<'a, L> ExactSizeIterator for Lefts<'a, L> {}
+
+impl<'a, L> FusedIterator for Lefts<'a, L> {}
+
+impl<'a, L> Iterator for Lefts<'a, L> {
+    type Item = &'a L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.as_mut()?.next().map(|left| &**left)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner
+            .as_ref()
+            .map_or((0, Some(0)), |inner| inner.size_hint())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "std"))]
+    use alloc::{
+        format,
+        string::{String, ToString},
+        vec,
+    };
+
+    /// Checks that both maps describe the same set of pairs.
+    fn assert_consistent<L, R>(multimap: &BiBTreeMultiMap<L, R>)
+    where
+        L: Ord + fmt::Debug,
+        R: Ord + fmt::Debug,
+    {
+        let mut len = 0;
+        for (right, lefts) in &multimap.right2left {
+            assert!(!lefts.is_empty());
+            for left in lefts {
+                assert!(multimap.contains_pair(&**left, &**right));
+                len += 1;
+            }
+        }
+        assert!(multimap
+            .left2right
+            .values()
+            .all(|rights| !rights.is_empty()));
+        assert_eq!(len, multimap.len());
+        assert_eq!(multimap.iter().count(), multimap.len());
+    }
+
+    #[test]
+    fn send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<BiBTreeMultiMap<String, i32>>();
+    }
+
+    #[test]
+    fn one_to_many() {
+        let mut sessions = BiBTreeMultiMap::new();
+        assert!(sessions.insert("alice", 1));
+        assert!(sessions.insert("alice", 2));
+        assert!(sessions.insert("bob", 3));
+        assert!(!sessions.insert("alice", 2));
+        assert_consistent(&sessions);
+
+        assert_eq!(sessions.len(), 3);
+        assert!(sessions.get_by_left(&"alice").eq(&[1, 2]));
+        assert!(sessions.get_by_left(&"alice").rev().eq(&[2, 1]));
+        assert!(sessions.get_by_right(&3).eq(&["bob"]));
+        assert_eq!(sessions.get_by_left(&"carol").len(), 0);
+        assert_eq!(sessions.get_by_right(&4).next_back(), None);
+
+        assert!(sessions.remove_pair(&"alice", &1));
+        assert!(!sessions.remove_pair(&"alice", &1));
+        assert!(!sessions.remove_pair(&"bob", &2));
+        assert_consistent(&sessions);
+        assert!(!sessions.contains_right(&1));
+        assert!(sessions.contains_left(&"alice"));
+
+        assert!(sessions.remove_pair(&"alice", &2));
+        assert!(!sessions.contains_left(&"alice"));
+        assert_eq!(sessions.len(), 1);
+        assert_consistent(&sessions);
+    }
+
+    #[test]
+    fn many_to_many() {
+        let mut tags: BiBTreeMultiMap<String, String> = vec![
+            ("rust", "intro"),
+            ("rust", "traits"),
+            ("beginner", "intro"),
+            ("beginner", "setup"),
+            ("advanced", "traits"),
+        ]
+        .into_iter()
+        .map(|(l, r)| (l.to_string(), r.to_string()))
+        .collect();
+        assert_consistent(&tags);
+        assert_eq!(tags.len(), 5);
+        assert!(tags.left_values().eq(&["advanced", "beginner", "rust"]));
+        assert!(tags.right_values().rev().eq(&["traits", "setup", "intro"]));
+        assert!(tags.get_by_right("intro").eq(&["beginner", "rust"]));
+
+        assert_eq!(
+            tags.remove_all_by_left("rust"),
+            Some((
+                "rust".to_string(),
+                vec!["intro".to_string(), "traits".to_string()]
+            ))
+        );
+        assert_consistent(&tags);
+        assert_eq!(tags.len(), 3);
+        assert!(tags.contains_pair("beginner", "intro"));
+        assert!(tags.contains_pair("advanced", "traits"));
+        assert_eq!(tags.remove_all_by_left("rust"), None);
+
+        assert_eq!(
+            tags.remove_all_by_right("traits"),
+            Some(("traits".to_string(), vec!["advanced".to_string()]))
+        );
+        assert!(!tags.contains_left("advanced"));
+        assert_consistent(&tags);
+
+        tags.clear();
+        assert!(tags.is_empty());
+        assert_eq!(tags.iter().next(), None);
+    }
+
+    #[test]
+    fn iterators() {
+        let pairs = vec![(0, 'a'), (0, 'b'), (1, 'a'), (2, 'a'), (2, 'c')];
+        let multimap: BiBTreeMultiMap<_, _> = pairs.iter().rev().copied().collect();
+
+        let iter = multimap.iter();
+        assert_eq!(iter.len(), 5);
+        assert!(iter.map(|(&l, &r)| (l, r)).eq(pairs.iter().copied()));
+        assert_eq!((&multimap).into_iter().count(), 5);
+
+        let mut into_iter = multimap.clone().into_iter();
+        assert_eq!(into_iter.len(), 5);
+        into_iter.next();
+        assert_eq!(into_iter.len(), 4);
+        assert_eq!(multimap.into_iter().collect::<Vec<_>>(), pairs);
+    }
+
+    #[test]
+    fn clone_eq_debug() {
+        let mut multimap = BiBTreeMultiMap::new();
+        assert_eq!(format!("{:?}", multimap), "{}");
+        multimap.insert('a', 2);
+        multimap.insert('b', 2);
+        multimap.insert('a', 1);
+        assert_eq!(format!("{:?}", multimap), "{'a' <> 1, 'a' <> 2, 'b' <> 2}");
+
+        let clone = multimap.clone();
+        assert_consistent(&clone);
+        assert_eq!(clone, multimap);
+
+        let mut other = BiBTreeMultiMap::new();
+        other.extend(vec![('b', 2), ('a', 1), ('a', 2), ('a', 2)]);
+        assert_eq!(other, multimap);
+        other.remove_pair(&'a', &1);
+        other.insert('b', 1);
+        assert_ne!(other, multimap);
+    }
+
+    #[test]
+    fn owned_values_are_shared() {
+        let mut multimap = BiBTreeMultiMap::new();
+        multimap.insert_by_left("a".to_string(), vec![1, 2, 3]);
+        multimap.insert_by_left("b".to_string(), vec![3, 4]);
+        assert_consistent(&multimap);
+        // every value is stored once, no matter how many pairs it belongs to
+        for (left, rights) in &multimap.right2left {
+            for right in rights {
+                let (key, _) = multimap.left2right.get_key_value(right).unwrap();
+                assert!(alloc::sync::Arc::ptr_eq(&key.0, &right.0));
+            }
+            let (key, _) = multimap.right2left.get_key_value(left).unwrap();
+            assert!(alloc::sync::Arc::ptr_eq(&key.0, &left.0));
+        }
+    }
+}
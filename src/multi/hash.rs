@@ -0,0 +1,1218 @@
+//! A multimap backed by two hash tables.
+
+use crate::hash::{erase_index, replace_index, EntryDebugger};
+use hashbrown::hash_table::{self, HashTable};
+use std::{
+    borrow::Borrow,
+    collections::hash_map,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::{Extend, FromIterator, FusedIterator},
+    marker::PhantomData,
+    slice, vec,
+};
+
+/// A multimap backed by two hash tables.
+///
+/// The distinct left values and the distinct right values are each stored
+/// once, in two dense vectors of groups. Each group holds a hash table of the
+/// positions of the values on the other side that its value is paired with,
+/// and two more hash tables index both vectors by the hashes of their values.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// [module-level documentation]: crate::multi
+#[derive(Clone)]
+pub struct BiHashMultiMap<L, R, LS = hash_map::RandomState, RS = hash_map::RandomState> {
+    left: Side<L, LS>,
+    right: Side<R, RS>,
+    // the number of left-right pairs
+    len: usize,
+}
+
+/// The distinct values on one side of a multimap.
+#[derive(Clone)]
+struct Side<K, S> {
+    groups: Vec<Group<K>>,
+    // the positions of the groups, keyed by the hashes of their values
+    index: HashTable<usize>,
+    hash_builder: S,
+}
+
+/// A value together with the positions of the groups of the values it is
+/// paired with, which are never empty.
+#[derive(Clone)]
+struct Group<K> {
+    key: K,
+    // keyed by the hashes of the values on the other side
+    values: HashTable<usize>,
+}
+
+impl<L, R> BiHashMultiMap<L, R, hash_map::RandomState, hash_map::RandomState>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    /// Creates an empty `BiHashMultiMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let multimap = BiHashMultiMap::<char, i32>::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hashers(hash_map::RandomState::new(), hash_map::RandomState::new())
+    }
+}
+
+impl<L, R, LS, RS> BiHashMultiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    /// Returns the number of left-right pairs in the multimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('a', 2);
+    /// multimap.insert('b', 2);
+    /// assert_eq!(multimap.len(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the multimap contains no left-right pairs, and
+    /// `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// assert!(multimap.is_empty());
+    /// multimap.insert('a', 1);
+    /// assert!(!multimap.is_empty());
+    /// multimap.remove_pair(&'a', &1);
+    /// assert!(multimap.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all left-right pairs from the multimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('b', 1);
+    /// multimap.clear();
+    /// assert!(multimap.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.left.clear();
+        self.right.clear();
+        self.len = 0;
+    }
+
+    /// Creates an iterator over the left-right pairs in the multimap in
+    /// arbitrary order.
+    ///
+    /// The iterator element type is `(&L, &R)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('a', 2);
+    /// multimap.insert('b', 2);
+    ///
+    /// for (left, right) in multimap.iter() {
+    ///     println!("({}, {})", left, right);
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, L, R> {
+        Iter {
+            groups: self.left.groups.iter(),
+            rights: &self.right.groups,
+            current: None,
+            remaining: self.len,
+        }
+    }
+
+    /// Creates an iterator over the distinct left values in the multimap in
+    /// arbitrary order.
+    ///
+    /// The iterator element type is `&L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('a', 2);
+    /// multimap.insert('b', 2);
+    /// assert_eq!(multimap.left_values().len(), 2);
+    /// ```
+    pub fn left_values(&self) -> LeftValues<'_, L, R> {
+        LeftValues {
+            inner: self.left.groups.iter(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Creates an iterator over the distinct right values in the multimap in
+    /// arbitrary order.
+    ///
+    /// The iterator element type is `&R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('a', 2);
+    /// multimap.insert('b', 2);
+    /// assert_eq!(multimap.right_values().len(), 2);
+    /// ```
+    pub fn right_values(&self) -> RightValues<'_, L, R> {
+        RightValues {
+            inner: self.right.groups.iter(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<L, R, LS, RS> BiHashMultiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Creates a new empty `BiHashMultiMap` using `hash_builder_left` to hash
+    /// left values and `hash_builder_right` to hash right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let s_left = RandomState::new();
+    /// let s_right = RandomState::new();
+    /// let mut multimap = BiHashMultiMap::<char, i32, _, _>::with_hashers(s_left, s_right);
+    /// multimap.insert('a', 42);
+    /// ```
+    pub fn with_hashers(hash_builder_left: LS, hash_builder_right: RS) -> Self {
+        Self {
+            left: Side::new(hash_builder_left),
+            right: Side::new(hash_builder_right),
+            len: 0,
+        }
+    }
+
+    /// Returns an iterator over the right values paired with the given left
+    /// value, which is empty if the left value is not in the multimap.
+    ///
+    /// The iterator element type is `&R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('a', 2);
+    /// multimap.insert('b', 2);
+    ///
+    /// let mut rights: Vec<_> = multimap.get_by_left(&'a').collect();
+    /// rights.sort();
+    /// assert_eq!(rights, [&1, &2]);
+    /// assert_eq!(multimap.get_by_left(&'c').len(), 0);
+    /// ```
+    pub fn get_by_left<Q>(&self, left: &Q) -> Rights<'_, R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        Rights {
+            inner: self
+                .left
+                .find(left)
+                .map(|index| self.left.groups[index].values.iter()),
+            rights: &self.right.groups,
+        }
+    }
+
+    /// Returns an iterator over the left values paired with the given right
+    /// value, which is empty if the right value is not in the multimap.
+    ///
+    /// The iterator element type is `&L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('a', 2);
+    /// multimap.insert('b', 2);
+    ///
+    /// let mut lefts: Vec<_> = multimap.get_by_right(&2).collect();
+    /// lefts.sort();
+    /// assert_eq!(lefts, [&'a', &'b']);
+    /// assert_eq!(multimap.get_by_right(&3).len(), 0);
+    /// ```
+    pub fn get_by_right<Q>(&self, right: &Q) -> Lefts<'_, L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        Lefts {
+            inner: self
+                .right
+                .find(right)
+                .map(|index| self.right.groups[index].values.iter()),
+            lefts: &self.left.groups,
+        }
+    }
+
+    /// Returns `true` if the multimap contains the given left value and
+    /// `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// assert!(multimap.contains_left(&'a'));
+    /// assert!(!multimap.contains_left(&'b'));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.left.find(left).is_some()
+    }
+
+    /// Returns `true` if the multimap contains the given right value and
+    /// `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// assert!(multimap.contains_right(&1));
+    /// assert!(!multimap.contains_right(&2));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.right.find(right).is_some()
+    }
+
+    /// Returns `true` if the multimap contains the given left-right pair and
+    /// `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('b', 2);
+    /// assert!(multimap.contains_pair(&'a', &1));
+    /// assert!(!multimap.contains_pair(&'a', &2));
+    /// ```
+    pub fn contains_pair<QL, QR>(&self, left: &QL, right: &QR) -> bool
+    where
+        L: Borrow<QL>,
+        R: Borrow<QR>,
+        QL: Eq + Hash + ?Sized,
+        QR: Eq + Hash + ?Sized,
+    {
+        self.left.find(left).is_some_and(|index| {
+            let hash = self.right.hash_builder.hash_one(right);
+            let rights = &self.right.groups;
+            self.left.groups[index]
+                .values
+                .find(hash, |&other| rights[other].key.borrow() == right)
+                .is_some()
+        })
+    }
+
+    /// Inserts the given left-right pair into the multimap.
+    ///
+    /// Returns `true` if the pair was inserted, and `false` if the multimap
+    /// already contained it. Other pairs containing the left or right value are
+    /// never affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// assert!(multimap.insert('a', 1));
+    /// assert!(multimap.insert('a', 2));
+    /// assert!(multimap.insert('b', 1));
+    /// assert!(!multimap.insert('a', 1));
+    /// assert_eq!(multimap.len(), 3);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> bool {
+        self.insert_by_left(left, Some(right)) == 1
+    }
+
+    /// Inserts a pair for each of the given right values, all sharing the same
+    /// left value, and returns the number of pairs that were inserted.
+    pub(crate) fn insert_by_left<I>(&mut self, left: L, rights: I) -> usize
+    where
+        I: IntoIterator<Item = R>,
+    {
+        let left_hash = self.left.hash_builder.hash_one(&left);
+        let index = self.left.find_or_insert(left_hash, left);
+        let mut inserted = 0;
+        for right in rights {
+            let right_hash = self.right.hash_builder.hash_one(&right);
+            let other = self.right.find_or_insert(right_hash, right);
+            let values = &mut self.left.groups[index].values;
+            if values.find(right_hash, |&i| i == other).is_some() {
+                continue;
+            }
+            values.insert_unique(right_hash, other, self.right.rehasher());
+            self.right.groups[other]
+                .values
+                .insert_unique(left_hash, index, self.left.rehasher());
+            self.len += 1;
+            inserted += 1;
+        }
+        if self.left.groups[index].values.is_empty() {
+            // the left value is new, but there were no right values to pair
+            self.left.remove(index, &mut self.right.groups);
+        }
+        inserted
+    }
+
+    /// Removes the given left-right pair from the multimap.
+    ///
+    /// Returns `true` if the pair was removed, and `false` if the multimap
+    /// didn't contain it. Other pairs containing the left or right value are
+    /// never affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('a', 2);
+    ///
+    /// assert!(multimap.remove_pair(&'a', &1));
+    /// assert!(!multimap.remove_pair(&'a', &1));
+    /// assert!(!multimap.contains_right(&1));
+    /// assert!(multimap.contains_left(&'a'));
+    /// ```
+    pub fn remove_pair<QL, QR>(&mut self, left: &QL, right: &QR) -> bool
+    where
+        L: Borrow<QL>,
+        R: Borrow<QR>,
+        QL: Eq + Hash + ?Sized,
+        QR: Eq + Hash + ?Sized,
+    {
+        let (index, other) = match (self.left.find(left), self.right.find(right)) {
+            (Some(index), Some(other)) => (index, other),
+            _ => return false,
+        };
+        let right_hash = self.right.hash_builder.hash_one(right);
+        match self.left.groups[index]
+            .values
+            .find_entry(right_hash, |&i| i == other)
+        {
+            Ok(entry) => entry.remove(),
+            Err(_) => return false,
+        };
+        let left_hash = self.left.hash_builder.hash_one(left);
+        erase_index(&mut self.right.groups[other].values, left_hash, index);
+        self.len -= 1;
+
+        // removing a left group never moves a right group, and vice versa
+        if self.left.groups[index].values.is_empty() {
+            self.left.remove(index, &mut self.right.groups);
+        }
+        if self.right.groups[other].values.is_empty() {
+            self.right.remove(other, &mut self.left.groups);
+        }
+        true
+    }
+
+    /// Removes all pairs containing the given left value from the multimap,
+    /// returning the left value and the right values it was paired with in
+    /// arbitrary order.
+    ///
+    /// Right values that are still paired with other left values stay in the
+    /// multimap, so they are cloned into the returned vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('a', 2);
+    /// multimap.insert('b', 2);
+    ///
+    /// let (left, mut rights) = multimap.remove_all_by_left(&'a').unwrap();
+    /// rights.sort();
+    /// assert_eq!((left, rights), ('a', vec![1, 2]));
+    /// assert_eq!(multimap.len(), 1);
+    /// assert!(!multimap.contains_right(&1));
+    /// assert!(multimap.contains_pair(&'b', &2));
+    /// assert_eq!(multimap.remove_all_by_left(&'a'), None);
+    /// ```
+    pub fn remove_all_by_left<Q>(&mut self, left: &Q) -> Option<(L, Vec<R>)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        R: Clone,
+    {
+        let index = self.left.find(left)?;
+        let (left, rights) = self.left.remove_all(index, &mut self.right);
+        self.len -= rights.len();
+        Some((left, rights))
+    }
+
+    /// Removes all pairs containing the given right value from the multimap,
+    /// returning the right value and the left values it was paired with in
+    /// arbitrary order.
+    ///
+    /// Left values that are still paired with other right values stay in the
+    /// multimap, so they are cloned into the returned vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::multi::BiHashMultiMap;
+    ///
+    /// let mut multimap = BiHashMultiMap::new();
+    /// multimap.insert('a', 1);
+    /// multimap.insert('b', 1);
+    /// multimap.insert('b', 2);
+    ///
+    /// let (right, mut lefts) = multimap.remove_all_by_right(&1).unwrap();
+    /// lefts.sort();
+    /// assert_eq!((right, lefts), (1, vec!['a', 'b']));
+    /// assert_eq!(multimap.len(), 1);
+    /// assert!(!multimap.contains_left(&'a'));
+    /// assert!(multimap.contains_pair(&'b', &2));
+    /// assert_eq!(multimap.remove_all_by_right(&1), None);
+    /// ```
+    pub fn remove_all_by_right<Q>(&mut self, right: &Q) -> Option<(R, Vec<L>)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        L: Clone,
+    {
+        let index = self.right.find(right)?;
+        let (right, lefts) = self.right.remove_all(index, &mut self.left);
+        self.len -= lefts.len();
+        Some((right, lefts))
+    }
+}
+
+impl<K, S> Side<K, S> {
+    fn new(hash_builder: S) -> Self {
+        Side {
+            groups: Vec::new(),
+            index: HashTable::new(),
+            hash_builder,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.groups.clear();
+        self.index.clear();
+    }
+}
+
+impl<K, S> Side<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns the position of the group of the given value.
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        let groups = &self.groups;
+        self.index
+            .find(hash, |&index| groups[index].key.borrow() == key)
+            .copied()
+    }
+
+    /// Returns the position of the group of the given value, creating an empty
+    /// group if the value is not in the multimap.
+    fn find_or_insert(&mut self, hash: u64, key: K) -> usize {
+        let groups = &self.groups;
+        if let Some(&index) = self.index.find(hash, |&index| groups[index].key == key) {
+            return index;
+        }
+        let index = self.groups.len();
+        self.groups.push(Group {
+            key,
+            values: HashTable::new(),
+        });
+        let (groups, hash_builder) = (&self.groups, &self.hash_builder);
+        self.index.insert_unique(hash, index, |&index| {
+            hash_builder.hash_one(&groups[index].key)
+        });
+        index
+    }
+
+    /// Returns a function that recomputes the hash of the value of the group
+    /// at a position, for use when a hash table of positions is resized.
+    fn rehasher(&self) -> impl Fn(&usize) -> u64 + '_ {
+        move |&index| self.hash_builder.hash_one(&self.groups[index].key)
+    }
+
+    /// Removes the group at the given position and returns it, without
+    /// detaching it from the groups of the values it is paired with.
+    ///
+    /// The last group is moved into the vacated position to keep the groups
+    /// dense, and its new position is updated in the groups of its values.
+    fn remove<V>(&mut self, index: usize, others: &mut [Group<V>]) -> Group<K> {
+        let hash = self.hash_builder.hash_one(&self.groups[index].key);
+        erase_index(&mut self.index, hash, index);
+
+        let last = self.groups.len() - 1;
+        if index != last {
+            let hash = self.hash_builder.hash_one(&self.groups[last].key);
+            replace_index(&mut self.index, hash, last, index);
+            for &other in self.groups[last].values.iter() {
+                replace_index(&mut others[other].values, hash, last, index);
+            }
+        }
+        self.groups.swap_remove(index)
+    }
+
+    /// Removes the group at the given position together with all of its pairs,
+    /// and returns its value and the values it was paired with.
+    ///
+    /// Values that are still paired with other values stay in the multimap, so
+    /// they are cloned into the returned vector.
+    fn remove_all<V, T>(&mut self, index: usize, others: &mut Side<V, T>) -> (K, Vec<V>)
+    where
+        V: Clone + Eq + Hash,
+        T: BuildHasher,
+    {
+        let hash = self.hash_builder.hash_one(&self.groups[index].key);
+        let mut positions: Vec<_> = self.groups[index].values.iter().copied().collect();
+        for &other in &positions {
+            erase_index(&mut others.groups[other].values, hash, index);
+        }
+        let key = self.remove(index, &mut others.groups).key;
+
+        // removing the highest positions first keeps the remaining ones valid,
+        // since only the last group is ever moved
+        positions.sort_unstable_by(|a, b| b.cmp(a));
+        let mut values = Vec::with_capacity(positions.len());
+        for other in positions {
+            values.push(if others.groups[other].values.is_empty() {
+                others.remove(other, &mut self.groups).key
+            } else {
+                others.groups[other].key.clone()
+            });
+        }
+        (key, values)
+    }
+}
+
+/// Takes a value out of its slot if no other pairs will be yielded with it,
+/// and clones it otherwise.
+fn take_or_clone<T: Clone>((value, remaining): &mut (Option<T>, usize)) -> T {
+    *remaining -= 1;
+    if *remaining == 0 {
+        value.take().unwrap()
+    } else {
+        T::clone(value.as_ref().unwrap())
+    }
+}
+
+impl<L, R, LS, RS> fmt::Debug for BiHashMultiMap<L, R, LS, RS>
+where
+    L: fmt::Debug + Eq + Hash,
+    R: fmt::Debug + Eq + Hash,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set()
+            .entries(
+                self.iter()
+                    .map(|(left, right)| EntryDebugger { left, right }),
+            )
+            .finish()
+    }
+}
+
+impl<L, R, LS, RS> Default for BiHashMultiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn default() -> BiHashMultiMap<L, R, LS, RS> {
+        BiHashMultiMap::with_hashers(LS::default(), RS::default())
+    }
+}
+
+impl<L, R, LS, RS> Eq for BiHashMultiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+}
+
+impl<L, R, LS, RS> FromIterator<(L, R)> for BiHashMultiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn from_iter<I>(iter: I) -> BiHashMultiMap<L, R, LS, RS>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let mut multimap = BiHashMultiMap::default();
+        multimap.extend(iter);
+        multimap
+    }
+}
+
+impl<'a, L, R, LS, RS> IntoIterator for &'a BiHashMultiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R>;
+
+    fn into_iter(self) -> Iter<'a, L, R> {
+        self.iter()
+    }
+}
+
+/// Consumes the multimap, yielding its left-right pairs in arbitrary order.
+///
+/// Values that belong to several pairs are cloned for all but the last pair
+/// they are yielded in.
+impl<L, R, LS, RS> IntoIterator for BiHashMultiMap<L, R, LS, RS>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    type Item = (L, R);
+    type IntoIter = IntoIter<L, R>;
+
+    fn into_iter(self) -> IntoIter<L, R> {
+        IntoIter {
+            groups: self.left.groups.into_iter(),
+            rights: self
+                .right
+                .groups
+                .into_iter()
+                .map(|group| (Some(group.key), group.values.len()))
+                .collect(),
+            current: None,
+            remaining: self.len,
+        }
+    }
+}
+
+impl<L, R, LS, RS> Extend<(L, R)> for BiHashMultiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(move |(l, r)| {
+            self.insert(l, r);
+        });
+    }
+}
+
+impl<L, R, LS, RS> PartialEq for BiHashMultiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(left, right)| other.contains_pair(left, right))
+    }
+}
+
+/// An owning iterator over the left-right pairs in a `BiHashMultiMap`.
+pub struct IntoIter<L, R> {
+    groups: vec::IntoIter<Group<L>>,
+    // each right value, until its last pair takes it, together with the number
+    // of its pairs that have not been yielded yet
+    rights: Vec<(Option<R>, usize)>,
+    current: Option<(L, hash_table::IntoIter<usize>)>,
+    remaining: usize,
+}
+
+impl<L: Clone, R: Clone> ExactSizeIterator for IntoIter<L, R> {}
+
+impl<L: Clone, R: Clone> FusedIterator for IntoIter<L, R> {}
+
+impl<L: Clone, R: Clone> Iterator for IntoIter<L, R> {
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((left, values)) = &mut self.current {
+                if let Some(other) = values.next() {
+                    self.remaining -= 1;
+                    let left = if values.len() == 0 {
+                        // the last pair takes the left value out of its group
+                        self.current.take().unwrap().0
+                    } else {
+                        L::clone(left)
+                    };
+                    return Some((left, take_or_clone(&mut self.rights[other])));
+                }
+            }
+            let group = self.groups.next()?;
+            self.current = Some((group.key, group.values.into_iter()));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// An iterator over the left-right pairs in a `BiHashMultiMap`.
+///
+/// This struct is created by the [`iter`] method of `BiHashMultiMap`.
+///
+/// [`iter`]: BiHashMultiMap::iter
+pub struct Iter<'a, L, R> {
+    groups: slice::Iter<'a, Group<L>>,
+    rights: &'a [Group<R>],
+    current: Option<(&'a L, hash_table::Iter<'a, usize>)>,
+    remaining: usize,
+}
+
+impl<'a, L, R> Clone for Iter<'a, L, R> {
+    fn clone(&self) -> Self {
+        Iter {
+            groups: self.groups.clone(),
+            rights: self.rights,
+            current: self.current.clone(),
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<'a, L, R> ExactSizeIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> Iterator for Iter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((left, values)) = &mut self.current {
+                if let Some(&other) = values.next() {
+                    self.remaining -= 1;
+                    return Some((*left, &self.rights[other].key));
+                }
+            }
+            let group = self.groups.next()?;
+            self.current = Some((&group.key, group.values.iter()));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// An iterator over the distinct left values in a `BiHashMultiMap`.
+///
+/// This struct is created by the [`left_values`] method of `BiHashMultiMap`.
+///
+/// [`left_values`]: BiHashMultiMap::left_values
+pub struct LeftValues<'a, L, R> {
+    inner: slice::Iter<'a, Group<L>>,
+    marker: PhantomData<&'a R>,
+}
+
+impl<'a, L, R> ExactSizeIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for LeftValues<'a, L, R> {
+    type Item = &'a L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|group| &group.key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the distinct right values in a `BiHashMultiMap`.
+///
+/// This struct is created by the [`right_values`] method of `BiHashMultiMap`.
+///
+/// [`right_values`]: BiHashMultiMap::right_values
+pub struct RightValues<'a, L, R> {
+    inner: slice::Iter<'a, Group<R>>,
+    marker: PhantomData<&'a L>,
+}
+
+impl<'a, L, R> ExactSizeIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for RightValues<'a, L, R> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|group| &group.key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the right values paired with a left value in a
+/// `BiHashMultiMap`.
+///
+/// This struct is created by the [`get_by_left`] method of `BiHashMultiMap`.
+///
+/// [`get_by_left`]: BiHashMultiMap::get_by_left
+pub struct Rights<'a, R> {
+    inner: Option<hash_table::Iter<'a, usize>>,
+    rights: &'a [Group<R>],
+}
+
+impl<'a, R> Clone for Rights<'a, R> {
+    fn clone(&self) -> Self {
+        Rights {
+            inner: self.inner.clone(),
+            rights: self.rights,
+        }
+    }
+}
+
+impl<'a, R> ExactSizeIterator for Rights<'a, R> {}
+
+impl<'a, R> FusedIterator for Rights<'a, R> {}
+
+impl<'a, R> Iterator for Rights<'a, R> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rights = self.rights;
+        self.inner.as_mut()?.next().map(|&other| &rights[other].key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner
+            .as_ref()
+            .map_or((0, Some(0)), |inner| inner.size_hint())
+    }
+}
+
+/// An iterator over the left values paired with a right value in a
+/// `BiHashMultiMap`.
+///
+/// This struct is created by the [`get_by_right`] method of `BiHashMultiMap`.
+///
+/// [`get_by_right`]: BiHashMultiMap::get_by_right
+pub struct Lefts<'a, L> {
+    inner: Option<hash_table::Iter<'a, usize>>,
+    lefts: &'a [Group<L>],
+}
+
+impl<'a, L> Clone for Lefts<'a, L> {
+    fn clone(&self) -> Self {
+        Lefts {
+            inner: self.inner.clone(),
+            lefts: self.lefts,
+        }
+    }
+}
+
+impl<'a, L> ExactSizeIterator for Lefts<'a, L> {}
+
+impl<'a, L> FusedIterator for Lefts<'a, L> {}
+
+impl<'a, L> Iterator for Lefts<'a, L> {
+    type Item = &'a L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let lefts = self.lefts;
+        self.inner.as_mut()?.next().map(|&other| &lefts[other].key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner
+            .as_ref()
+            .map_or((0, Some(0)), |inner| inner.size_hint())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted<T: Ord, I: IntoIterator<Item = T>>(iter: I) -> Vec<T> {
+        let mut values: Vec<_> = iter.into_iter().collect();
+        values.sort();
+        values
+    }
+
+    /// Checks that both tables describe the same set of pairs.
+    fn assert_consistent<L, R>(multimap: &BiHashMultiMap<L, R>)
+    where
+        L: Eq + Hash + fmt::Debug,
+        R: Eq + Hash + fmt::Debug,
+    {
+        let (lefts, rights) = (&multimap.left.groups, &multimap.right.groups);
+        let mut len = 0;
+        for (position, group) in rights.iter().enumerate() {
+            assert!(!group.values.is_empty());
+            for &index in group.values.iter() {
+                assert!(lefts[index].values.iter().any(|&i| i == position));
+                assert!(multimap.contains_pair(&lefts[index].key, &group.key));
+                len += 1;
+            }
+        }
+        let left_len: usize = lefts.iter().map(|group| group.values.len()).sum();
+        assert!(lefts.iter().all(|group| !group.values.is_empty()));
+        assert_eq!(multimap.left.index.len(), lefts.len());
+        assert_eq!(multimap.right.index.len(), rights.len());
+        assert_eq!(left_len, multimap.len());
+        assert_eq!(len, multimap.len());
+        assert_eq!(multimap.iter().count(), multimap.len());
+    }
+
+    #[test]
+    fn send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<BiHashMultiMap<String, i32>>();
+    }
+
+    #[test]
+    fn matches_hash_set() {
+        let mut multimap = BiHashMultiMap::new();
+        let mut model = std::collections::HashSet::new();
+        for i in 0..500u32 {
+            let (left, right) = (i * 7 % 23, i * 11 % 19);
+            if i % 13 == 0 {
+                let removed = multimap
+                    .remove_all_by_left(&left)
+                    .map_or(0, |(_, r)| r.len());
+                let before = model.len();
+                model.retain(|&(l, _)| l != left);
+                assert_eq!(removed, before - model.len());
+            } else if i % 17 == 0 {
+                let removed = multimap
+                    .remove_all_by_right(&right)
+                    .map_or(0, |(_, l)| l.len());
+                let before = model.len();
+                model.retain(|&(_, r)| r != right);
+                assert_eq!(removed, before - model.len());
+            } else if i % 3 == 0 {
+                assert_eq!(
+                    multimap.remove_pair(&left, &right),
+                    model.remove(&(left, right))
+                );
+            } else {
+                assert_eq!(multimap.insert(left, right), model.insert((left, right)));
+            }
+            assert_consistent(&multimap);
+            assert_eq!(multimap.len(), model.len());
+            assert!(model.iter().all(|(l, r)| multimap.contains_pair(l, r)));
+        }
+    }
+
+    #[test]
+    fn one_to_many() {
+        let mut sessions = BiHashMultiMap::new();
+        assert!(sessions.insert("alice", 1));
+        assert!(sessions.insert("alice", 2));
+        assert!(sessions.insert("bob", 3));
+        assert!(!sessions.insert("alice", 2));
+        assert_consistent(&sessions);
+
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(sorted(sessions.get_by_left("alice")), [&1, &2]);
+        assert_eq!(sorted(sessions.get_by_right(&3)), [&"bob"]);
+        assert_eq!(sessions.get_by_left("carol").len(), 0);
+        assert_eq!(sessions.get_by_right(&4).next(), None);
+
+        assert!(sessions.remove_pair("alice", &1));
+        assert!(!sessions.remove_pair("alice", &1));
+        assert!(!sessions.remove_pair("bob", &2));
+        assert_consistent(&sessions);
+        assert!(!sessions.contains_right(&1));
+        assert!(sessions.contains_left("alice"));
+
+        assert!(sessions.remove_pair("alice", &2));
+        assert!(!sessions.contains_left("alice"));
+        assert_eq!(sessions.len(), 1);
+        assert_consistent(&sessions);
+
+        assert_eq!(sessions.insert_by_left("carol", None), 0);
+        assert!(!sessions.contains_left("carol"));
+        assert_consistent(&sessions);
+    }
+
+    #[test]
+    fn many_to_many() {
+        let mut tags: BiHashMultiMap<String, String> = vec![
+            ("rust", "intro"),
+            ("rust", "traits"),
+            ("beginner", "intro"),
+            ("beginner", "setup"),
+            ("advanced", "traits"),
+        ]
+        .into_iter()
+        .map(|(l, r)| (l.to_string(), r.to_string()))
+        .collect();
+        assert_consistent(&tags);
+        assert_eq!(tags.len(), 5);
+        assert_eq!(tags.left_values().len(), 3);
+        assert_eq!(tags.right_values().len(), 3);
+        assert_eq!(sorted(tags.get_by_right("intro")), ["beginner", "rust"]);
+
+        let (left, rights) = tags.remove_all_by_left("rust").unwrap();
+        assert_eq!(
+            (left.as_str(), sorted(rights)),
+            ("rust", vec!["intro".to_string(), "traits".to_string()])
+        );
+        assert_consistent(&tags);
+        assert_eq!(tags.len(), 3);
+        assert!(tags.contains_pair("beginner", "intro"));
+        assert!(tags.contains_pair("advanced", "traits"));
+        assert_eq!(tags.remove_all_by_left("rust"), None);
+
+        let (right, lefts) = tags.remove_all_by_right("traits").unwrap();
+        assert_eq!(
+            (right.as_str(), lefts),
+            ("traits", vec!["advanced".to_string()])
+        );
+        assert!(!tags.contains_left("advanced"));
+        assert_consistent(&tags);
+
+        tags.clear();
+        assert!(tags.is_empty());
+        assert_eq!(tags.iter().next(), None);
+    }
+
+    #[test]
+    fn iterators() {
+        let pairs = vec![(0, 'a'), (0, 'b'), (1, 'a'), (2, 'a'), (2, 'c')];
+        let multimap: BiHashMultiMap<_, _> = pairs.iter().copied().collect();
+
+        let iter = multimap.iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(sorted(iter.map(|(&l, &r)| (l, r))), pairs);
+        assert_eq!(sorted(&multimap).len(), 5);
+        assert_eq!(sorted(multimap.left_values()), [&0, &1, &2]);
+        assert_eq!(sorted(multimap.right_values()), [&'a', &'b', &'c']);
+
+        let mut into_iter = multimap.clone().into_iter();
+        assert_eq!(into_iter.len(), 5);
+        into_iter.next();
+        assert_eq!(into_iter.len(), 4);
+        assert_eq!(sorted(multimap.into_iter()), pairs);
+    }
+
+    #[test]
+    fn clone_eq_debug() {
+        let mut multimap = BiHashMultiMap::new();
+        assert_eq!(format!("{:?}", multimap), "{}");
+        multimap.insert('a', 1);
+        assert_eq!(format!("{:?}", multimap), "{'a' <> 1}");
+        multimap.insert('a', 2);
+        multimap.insert('b', 2);
+
+        let clone = multimap.clone();
+        assert_consistent(&clone);
+        assert_eq!(clone, multimap);
+
+        let mut other = BiHashMultiMap::new();
+        other.extend(vec![('b', 2), ('a', 1), ('a', 2), ('a', 2)]);
+        assert_eq!(other, multimap);
+        other.remove_pair(&'a', &1);
+        other.insert('b', 1);
+        assert_ne!(other, multimap);
+    }
+
+    #[test]
+    fn borrowed_lookups() {
+        let mut multimap = BiHashMultiMap::new();
+        multimap.insert("a".to_string(), vec![1]);
+        multimap.insert("a".to_string(), vec![2]);
+        assert!(multimap.contains_left("a"));
+        assert!(multimap.contains_right(&[1][..]));
+        assert!(multimap.contains_pair("a", &[2][..]));
+        assert_eq!(sorted(multimap.get_by_right(&[1][..])), [&"a".to_string()]);
+        assert!(multimap.remove_pair("a", &[1][..]));
+        assert_eq!(multimap.len(), 1);
+    }
+}
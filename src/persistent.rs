@@ -0,0 +1,1141 @@
+//! A persistent bimap whose versions share their structure.
+//!
+//! A [`PersistentBiMap`] never changes once it is created. Instead, its
+//! [`insert`] and `remove_*` methods return a new version of the bimap and
+//! leave the original untouched. Both versions share everything but the
+//! modified path of the underlying hash array mapped tries, so creating a
+//! version takes logarithmic time and memory, and [`clone`] takes constant
+//! time. This makes it cheap to keep a history of versions, e.g. for an undo
+//! stack.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::{Overwritten, PersistentBiMap};
+//!
+//! let v0 = PersistentBiMap::new();
+//! let (v1, _) = v0.insert('a', 1);
+//! let (v2, overwritten) = v1.insert('b', 1);
+//! assert_eq!(overwritten, Overwritten::Right(&'a', &1));
+//!
+//! // every version is still available
+//! assert!(v0.is_empty());
+//! assert_eq!(v1.get_by_right(&1), Some(&'a'));
+//! assert_eq!(v2.get_by_right(&1), Some(&'b'));
+//!
+//! let (v3, removed) = v2.remove_by_left(&'b');
+//! assert_eq!(removed, Some((&'b', &1)));
+//! assert!(v3.is_empty());
+//! ```
+//!
+//! [`insert`]: PersistentBiMap::insert
+//! [`clone`]: Clone::clone
+
+use crate::{hash::EntryDebugger, Overwritten};
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    collections::hash_map,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::{Extend, FromIterator, FusedIterator},
+    slice,
+    sync::Arc,
+};
+
+/// A persistent bimap whose versions share their structure.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// [module-level documentation]: crate::persistent
+pub struct PersistentBiMap<L, R, LS = hash_map::RandomState, RS = hash_map::RandomState> {
+    // both tries hold the same pairs, keyed by the hash of either value
+    left2right: Hamt<Arc<(L, R)>>,
+    right2left: Hamt<Arc<(L, R)>>,
+    hash_builder_left: LS,
+    hash_builder_right: RS,
+}
+
+impl<L, R> PersistentBiMap<L, R, hash_map::RandomState, hash_map::RandomState>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    /// Creates an empty `PersistentBiMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap = PersistentBiMap::<char, i32>::new();
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hashers(hash_map::RandomState::new(), hash_map::RandomState::new())
+    }
+}
+
+impl<L, R, LS, RS> PersistentBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    /// Creates an empty `PersistentBiMap` using `hash_builder_left` to hash
+    /// left values and `hash_builder_right` to hash right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let s_left = RandomState::new();
+    /// let s_right = RandomState::new();
+    /// let bimap = PersistentBiMap::<char, i32, _, _>::with_hashers(s_left, s_right);
+    /// let (bimap, _) = bimap.insert('a', 42);
+    /// ```
+    pub fn with_hashers(hash_builder_left: LS, hash_builder_right: RS) -> Self {
+        Self {
+            left2right: Hamt::new(),
+            right2left: Hamt::new(),
+            hash_builder_left,
+            hash_builder_right,
+        }
+    }
+
+    /// Returns the number of left-right pairs in the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap: PersistentBiMap<_, _> = vec![('a', 1), ('b', 2)].into_iter().collect();
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.left2right.len
+    }
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap = PersistentBiMap::new();
+    /// assert!(bimap.is_empty());
+    /// let (bimap, _) = bimap.insert('a', 1);
+    /// assert!(!bimap.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Creates an iterator over the left-right pairs in the bimap in arbitrary
+    /// order.
+    ///
+    /// The iterator element type is `(&L, &R)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap: PersistentBiMap<_, _> = vec![('a', 1), ('b', 2)].into_iter().collect();
+    /// let mut pairs: Vec<_> = bimap.iter().collect();
+    /// pairs.sort();
+    /// assert_eq!(pairs, [(&'a', &1), (&'b', &2)]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, L, R> {
+        Iter {
+            inner: self.left2right.iter(),
+        }
+    }
+
+    /// Creates an iterator over the left values in the bimap in arbitrary
+    /// order.
+    ///
+    /// The iterator element type is `&L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap: PersistentBiMap<_, _> = vec![('a', 1), ('b', 2)].into_iter().collect();
+    /// let mut lefts: Vec<_> = bimap.left_values().collect();
+    /// lefts.sort();
+    /// assert_eq!(lefts, [&'a', &'b']);
+    /// ```
+    pub fn left_values(&self) -> LeftValues<'_, L, R> {
+        LeftValues { inner: self.iter() }
+    }
+
+    /// Creates an iterator over the right values in the bimap in arbitrary
+    /// order.
+    ///
+    /// The iterator element type is `&R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap: PersistentBiMap<_, _> = vec![('a', 1), ('b', 2)].into_iter().collect();
+    /// let mut rights: Vec<_> = bimap.right_values().collect();
+    /// rights.sort();
+    /// assert_eq!(rights, [&1, &2]);
+    /// ```
+    pub fn right_values(&self) -> RightValues<'_, L, R> {
+        RightValues { inner: self.iter() }
+    }
+
+    /// Returns `true` if both bimaps are the same version or were cloned
+    /// from the same version, and `false` otherwise.
+    ///
+    /// This takes constant time, and can return `false` for bimaps that hold
+    /// equal pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let (v1, _) = PersistentBiMap::new().insert('a', 1);
+    /// let (v2, _) = v1.insert('a', 1);
+    /// assert!(v1.ptr_eq(&v1.clone()));
+    /// assert!(!v1.ptr_eq(&v2));
+    /// assert_eq!(v1, v2);
+    /// ```
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.left2right.root, &other.left2right.root)
+            && Arc::ptr_eq(&self.right2left.root, &other.right2left.root)
+    }
+}
+
+impl<L, R, LS, RS> PersistentBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Returns a reference to the right value corresponding to the given left
+    /// value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let (bimap, _) = PersistentBiMap::new().insert('a', 1);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.get_by_left(&'z'), None);
+    /// ```
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.pair_by_left(left).map(|pair| &pair.1)
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let (bimap, _) = PersistentBiMap::new().insert('a', 1);
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+    /// assert_eq!(bimap.get_by_right(&2), None);
+    /// ```
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.pair_by_right(right).map(|pair| &pair.0)
+    }
+
+    /// Returns `true` if the bimap contains the given left value and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let (bimap, _) = PersistentBiMap::new().insert('a', 1);
+    /// assert!(bimap.contains_left(&'a'));
+    /// assert!(!bimap.contains_left(&'b'));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.pair_by_left(left).is_some()
+    }
+
+    /// Returns `true` if the bimap contains the given right value and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let (bimap, _) = PersistentBiMap::new().insert('a', 1);
+    /// assert!(bimap.contains_right(&1));
+    /// assert!(!bimap.contains_right(&2));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.pair_by_right(right).is_some()
+    }
+
+    fn pair_by_left<Q>(&self, left: &Q) -> Option<&Arc<(L, R)>>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder_left.hash_one(left);
+        self.left2right.get(hash, |pair| pair.0.borrow() == left)
+    }
+
+    fn pair_by_right<Q>(&self, right: &Q) -> Option<&Arc<(L, R)>>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder_right.hash_one(right);
+        self.right2left.get(hash, |pair| pair.1.borrow() == right)
+    }
+}
+
+impl<L, R, LS, RS> PersistentBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Clone,
+    RS: BuildHasher + Clone,
+{
+    /// Returns a new version of the bimap with the given left-right pair
+    /// inserted, along with the pairs of this version, if any, that were
+    /// overwritten.
+    ///
+    /// The overwritten pairs are reported like in
+    /// [`BiHashMap::insert`](crate::BiHashMap::insert), but as references
+    /// into this version, which still contains them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{Overwritten, PersistentBiMap};
+    ///
+    /// let v0 = PersistentBiMap::new();
+    /// let (v1, overwritten) = v0.insert('a', 1);
+    /// assert_eq!(overwritten, Overwritten::Neither);
+    /// let (v2, _) = v1.insert('b', 2);
+    ///
+    /// assert_eq!(v2.insert('a', 1).1, Overwritten::Pair(&'a', &1));
+    /// assert_eq!(v2.insert('a', 3).1, Overwritten::Left(&'a', &1));
+    /// assert_eq!(v2.insert('c', 1).1, Overwritten::Right(&'a', &1));
+    /// assert_eq!(v2.insert('a', 2).1, Overwritten::Both((&'a', &1), (&'b', &2)));
+    /// ```
+    pub fn insert(&self, left: L, right: R) -> (Self, Overwritten<&L, &R>) {
+        let l_pair = self.pair_by_left(&left);
+        let r_pair = self.pair_by_right(&right);
+        let overwritten = match (l_pair, r_pair) {
+            (None, None) => Overwritten::Neither,
+            (Some(pair), None) => Overwritten::Left(&pair.0, &pair.1),
+            (None, Some(pair)) => Overwritten::Right(&pair.0, &pair.1),
+            (Some(l_pair), Some(r_pair)) if Arc::ptr_eq(l_pair, r_pair) => {
+                Overwritten::Pair(&l_pair.0, &l_pair.1)
+            }
+            (Some(l_pair), Some(r_pair)) => {
+                Overwritten::Both((&l_pair.0, &l_pair.1), (&r_pair.0, &r_pair.1))
+            }
+        };
+
+        let mut bimap = self.clone();
+        if let Some(pair) = l_pair {
+            bimap = bimap.without(pair);
+        }
+        if let Some(pair) = r_pair {
+            if !matches!(overwritten, Overwritten::Pair(..)) {
+                bimap = bimap.without(pair);
+            }
+        }
+        (bimap.with(Arc::new((left, right))), overwritten)
+    }
+
+    /// Returns a new version of the bimap with the given left-right pair
+    /// inserted, unless either value is already in the bimap.
+    ///
+    /// If either value exists in the bimap, `Err((left, right)` is returned
+    /// with the attempted left-right pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let v0 = PersistentBiMap::new();
+    /// let v1 = v0.insert_no_overwrite('a', 1).unwrap();
+    /// assert_eq!(v1.insert_no_overwrite('a', 2), Err(('a', 2)));
+    /// assert_eq!(v1.insert_no_overwrite('b', 1), Err(('b', 1)));
+    /// assert!(v1.insert_no_overwrite('b', 2).is_ok());
+    /// ```
+    pub fn insert_no_overwrite(&self, left: L, right: R) -> Result<Self, (L, R)> {
+        if self.contains_left(&left) || self.contains_right(&right) {
+            Err((left, right))
+        } else {
+            Ok(self.with(Arc::new((left, right))))
+        }
+    }
+
+    /// Returns a new version of the bimap without the left-right pair
+    /// corresponding to the given left value, along with that pair if it was
+    /// in this version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let v0: PersistentBiMap<_, _> = vec![('a', 1), ('b', 2)].into_iter().collect();
+    /// let (v1, removed) = v0.remove_by_left(&'a');
+    /// assert_eq!(removed, Some((&'a', &1)));
+    /// assert_eq!(v1.len(), 1);
+    /// assert_eq!(v1.remove_by_left(&'a').1, None);
+    /// ```
+    pub fn remove_by_left<Q>(&self, left: &Q) -> (Self, Option<(&L, &R)>)
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        match self.pair_by_left(left) {
+            Some(pair) => (self.without(pair), Some((&pair.0, &pair.1))),
+            None => (self.clone(), None),
+        }
+    }
+
+    /// Returns a new version of the bimap without the left-right pair
+    /// corresponding to the given right value, along with that pair if it was
+    /// in this version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let v0: PersistentBiMap<_, _> = vec![('a', 1), ('b', 2)].into_iter().collect();
+    /// let (v1, removed) = v0.remove_by_right(&2);
+    /// assert_eq!(removed, Some((&'b', &2)));
+    /// assert_eq!(v1.len(), 1);
+    /// assert_eq!(v1.remove_by_right(&2).1, None);
+    /// ```
+    pub fn remove_by_right<Q>(&self, right: &Q) -> (Self, Option<(&L, &R)>)
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        match self.pair_by_right(right) {
+            Some(pair) => (self.without(pair), Some((&pair.0, &pair.1))),
+            None => (self.clone(), None),
+        }
+    }
+
+    /// Returns a new version of the bimap with the given pair, which must not
+    /// conflict with any pair in this version.
+    fn with(&self, pair: Arc<(L, R)>) -> Self {
+        let left_hash = self.hash_builder_left.hash_one(&pair.0);
+        let right_hash = self.hash_builder_right.hash_one(&pair.1);
+        Self {
+            left2right: self.left2right.insert(left_hash, Arc::clone(&pair)),
+            right2left: self.right2left.insert(right_hash, pair),
+            hash_builder_left: self.hash_builder_left.clone(),
+            hash_builder_right: self.hash_builder_right.clone(),
+        }
+    }
+
+    /// Returns a new version of the bimap without the given pair, which must
+    /// be in this version.
+    fn without(&self, pair: &Arc<(L, R)>) -> Self {
+        let left_hash = self.hash_builder_left.hash_one(&pair.0);
+        let right_hash = self.hash_builder_right.hash_one(&pair.1);
+        let is_pair = |other: &Arc<(L, R)>| Arc::ptr_eq(other, pair);
+        Self {
+            left2right: self.left2right.remove(left_hash, is_pair),
+            right2left: self.right2left.remove(right_hash, is_pair),
+            hash_builder_left: self.hash_builder_left.clone(),
+            hash_builder_right: self.hash_builder_right.clone(),
+        }
+    }
+}
+
+/// Takes constant time, since the clone shares all of its pairs with the
+/// original.
+impl<L, R, LS, RS> Clone for PersistentBiMap<L, R, LS, RS>
+where
+    LS: Clone,
+    RS: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            left2right: self.left2right.clone(),
+            right2left: self.right2left.clone(),
+            hash_builder_left: self.hash_builder_left.clone(),
+            hash_builder_right: self.hash_builder_right.clone(),
+        }
+    }
+}
+
+impl<L, R, LS, RS> fmt::Debug for PersistentBiMap<L, R, LS, RS>
+where
+    L: fmt::Debug + Eq + Hash,
+    R: fmt::Debug + Eq + Hash,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set()
+            .entries(
+                self.iter()
+                    .map(|(left, right)| EntryDebugger { left, right }),
+            )
+            .finish()
+    }
+}
+
+impl<L, R, LS, RS> Default for PersistentBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_hashers(LS::default(), RS::default())
+    }
+}
+
+impl<L, R, LS, RS> Eq for PersistentBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+}
+
+impl<L, R, LS, RS> PartialEq for PersistentBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(left, right)| other.get_by_left(left) == Some(right))
+    }
+}
+
+impl<L, R, LS, RS> Extend<(L, R)> for PersistentBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Clone,
+    RS: BuildHasher + Clone,
+{
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(move |(l, r)| {
+            *self = self.insert(l, r).0;
+        });
+    }
+}
+
+impl<L, R, LS, RS> FromIterator<(L, R)> for PersistentBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Clone + Default,
+    RS: BuildHasher + Clone + Default,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let mut bimap = Self::default();
+        bimap.extend(iter);
+        bimap
+    }
+}
+
+impl<'a, L, R, LS, RS> IntoIterator for &'a PersistentBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R>;
+
+    fn into_iter(self) -> Iter<'a, L, R> {
+        self.iter()
+    }
+}
+
+/// An iterator over the left-right pairs in a `PersistentBiMap`.
+///
+/// This struct is created by the [`iter`] method of `PersistentBiMap`.
+///
+/// [`iter`]: PersistentBiMap::iter
+pub struct Iter<'a, L, R> {
+    inner: HamtIter<'a, Arc<(L, R)>>,
+}
+
+impl<'a, L, R> Clone for Iter<'a, L, R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, L, R> ExactSizeIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> Iterator for Iter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|pair| (&pair.0, &pair.1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the left values in a `PersistentBiMap`.
+///
+/// This struct is created by the [`left_values`] method of `PersistentBiMap`.
+///
+/// [`left_values`]: PersistentBiMap::left_values
+pub struct LeftValues<'a, L, R> {
+    inner: Iter<'a, L, R>,
+}
+
+impl<'a, L, R> Clone for LeftValues<'a, L, R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, L, R> ExactSizeIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for LeftValues<'a, L, R> {
+    type Item = &'a L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(l, _)| l)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the right values in a `PersistentBiMap`.
+///
+/// This struct is created by the [`right_values`] method of `PersistentBiMap`.
+///
+/// [`right_values`]: PersistentBiMap::right_values
+pub struct RightValues<'a, L, R> {
+    inner: Iter<'a, L, R>,
+}
+
+impl<'a, L, R> Clone for RightValues<'a, L, R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, L, R> ExactSizeIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for RightValues<'a, L, R> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, r)| r)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// The number of hash bits consumed by each level of a trie.
+const BITS: u32 = 5;
+
+/// Returns the bit for the given hash in the bitmap of a branch at the given
+/// shift.
+fn bit(hash: u64, shift: u32) -> u32 {
+    1 << ((hash >> shift) & ((1 << BITS) - 1))
+}
+
+/// A hash array mapped trie holding values that are looked up by their hash.
+///
+/// Every node is shared through an `Arc`, and updates copy the path from the
+/// root to the modified node while sharing everything else.
+struct Hamt<T> {
+    root: Arc<Branch<T>>,
+    len: usize,
+}
+
+/// A node of a `Hamt`, with one entry for each bit set in the bitmap.
+///
+/// Every branch except the root has either at least two entries or a single
+/// branch entry.
+struct Branch<T> {
+    bitmap: u32,
+    entries: Vec<Entry<T>>,
+}
+
+enum Entry<T> {
+    Leaf(u64, T),
+    // at least two values that have the same hash
+    Collision(u64, Arc<Vec<T>>),
+    Branch(Arc<Branch<T>>),
+}
+
+impl<T> Clone for Hamt<T> {
+    fn clone(&self) -> Self {
+        Self {
+            root: Arc::clone(&self.root),
+            len: self.len,
+        }
+    }
+}
+
+impl<T: Clone> Clone for Branch<T> {
+    fn clone(&self) -> Self {
+        Self {
+            bitmap: self.bitmap,
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<T: Clone> Clone for Entry<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Entry::Leaf(hash, value) => Entry::Leaf(*hash, value.clone()),
+            Entry::Collision(hash, values) => Entry::Collision(*hash, Arc::clone(values)),
+            Entry::Branch(branch) => Entry::Branch(Arc::clone(branch)),
+        }
+    }
+}
+
+impl<T> Hamt<T> {
+    fn new() -> Self {
+        Self {
+            root: Arc::new(Branch {
+                bitmap: 0,
+                entries: Vec::new(),
+            }),
+            len: 0,
+        }
+    }
+
+    fn get<F>(&self, hash: u64, is_match: F) -> Option<&T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut branch = &*self.root;
+        let mut shift = 0;
+        loop {
+            let bit = bit(hash, shift);
+            if branch.bitmap & bit == 0 {
+                return None;
+            }
+            match &branch.entries[branch.position(bit)] {
+                Entry::Leaf(h, value) => {
+                    return Some(value).filter(|value| *h == hash && is_match(value));
+                }
+                Entry::Collision(h, values) => {
+                    return values.iter().find(|value| *h == hash && is_match(value));
+                }
+                Entry::Branch(child) => {
+                    branch = child;
+                    shift += BITS;
+                }
+            }
+        }
+    }
+
+    fn iter(&self) -> HamtIter<'_, T> {
+        HamtIter {
+            stack: vec![self.root.entries.iter()],
+            bucket: [].iter(),
+            remaining: self.len,
+        }
+    }
+}
+
+impl<T: Clone> Hamt<T> {
+    /// Returns a new trie with the given value, which must not be in this
+    /// trie.
+    fn insert(&self, hash: u64, value: T) -> Self {
+        Self {
+            root: Arc::new(self.root.insert(0, hash, value)),
+            len: self.len + 1,
+        }
+    }
+
+    /// Returns a new trie without the value matching the given predicate,
+    /// which must be in this trie.
+    fn remove<F>(&self, hash: u64, is_match: F) -> Self
+    where
+        F: Fn(&T) -> bool,
+    {
+        let root = self
+            .root
+            .remove(0, hash, &is_match)
+            .expect("value to remove is not in the trie");
+        Self {
+            root: Arc::new(root),
+            len: self.len - 1,
+        }
+    }
+}
+
+impl<T> Branch<T> {
+    /// Returns the position of the entry for the given bit.
+    fn position(&self, bit: u32) -> usize {
+        (self.bitmap & (bit - 1)).count_ones() as usize
+    }
+}
+
+impl<T: Clone> Branch<T> {
+    /// Returns a branch at the given shift holding two leaf or collision
+    /// entries with different hashes.
+    fn pair(shift: u32, (hash_a, a): (u64, Entry<T>), (hash_b, b): (u64, Entry<T>)) -> Self {
+        let (bit_a, bit_b) = (bit(hash_a, shift), bit(hash_b, shift));
+        let entries = match bit_a.cmp(&bit_b) {
+            Ordering::Equal => vec![Entry::Branch(Arc::new(Self::pair(
+                shift + BITS,
+                (hash_a, a),
+                (hash_b, b),
+            )))],
+            Ordering::Less => vec![a, b],
+            Ordering::Greater => vec![b, a],
+        };
+        Self {
+            bitmap: bit_a | bit_b,
+            entries,
+        }
+    }
+
+    fn insert(&self, shift: u32, hash: u64, value: T) -> Self {
+        let bit = bit(hash, shift);
+        let position = self.position(bit);
+        let mut branch = self.clone();
+        if self.bitmap & bit == 0 {
+            branch.bitmap |= bit;
+            branch.entries.insert(position, Entry::Leaf(hash, value));
+            return branch;
+        }
+        branch.entries[position] = match &self.entries[position] {
+            Entry::Leaf(h, other) if *h == hash => {
+                Entry::Collision(hash, Arc::new(vec![other.clone(), value]))
+            }
+            Entry::Collision(h, values) if *h == hash => {
+                let mut values = Vec::clone(values);
+                values.push(value);
+                Entry::Collision(hash, Arc::new(values))
+            }
+            Entry::Branch(child) => {
+                Entry::Branch(Arc::new(child.insert(shift + BITS, hash, value)))
+            }
+            Entry::Leaf(h, _) | Entry::Collision(h, _) => Entry::Branch(Arc::new(Self::pair(
+                shift + BITS,
+                (*h, self.entries[position].clone()),
+                (hash, Entry::Leaf(hash, value)),
+            ))),
+        };
+        branch
+    }
+
+    fn remove<F>(&self, shift: u32, hash: u64, is_match: &F) -> Option<Self>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let bit = bit(hash, shift);
+        if self.bitmap & bit == 0 {
+            return None;
+        }
+        let position = self.position(bit);
+        let entry = match &self.entries[position] {
+            Entry::Leaf(h, value) if *h == hash && is_match(value) => None,
+            Entry::Leaf(..) => return None,
+            Entry::Collision(h, values) => {
+                let index = values
+                    .iter()
+                    .position(|value| *h == hash && is_match(value))?;
+                let mut values = Vec::clone(values);
+                values.remove(index);
+                if values.len() == 1 {
+                    Some(Entry::Leaf(hash, values.remove(0)))
+                } else {
+                    Some(Entry::Collision(hash, Arc::new(values)))
+                }
+            }
+            Entry::Branch(child) => {
+                let mut child = child.remove(shift + BITS, hash, is_match)?;
+                match child.entries.len() {
+                    0 => None,
+                    // pull a lone leaf or collision up to keep the trie shallow
+                    1 if !matches!(child.entries[0], Entry::Branch(_)) => child.entries.pop(),
+                    _ => Some(Entry::Branch(Arc::new(child))),
+                }
+            }
+        };
+
+        let mut branch = self.clone();
+        match entry {
+            Some(entry) => branch.entries[position] = entry,
+            None => {
+                branch.bitmap &= !bit;
+                branch.entries.remove(position);
+            }
+        }
+        Some(branch)
+    }
+}
+
+/// An iterator over the values in a `Hamt`.
+struct HamtIter<'a, T> {
+    stack: Vec<slice::Iter<'a, Entry<T>>>,
+    bucket: slice::Iter<'a, T>,
+    remaining: usize,
+}
+
+impl<'a, T> Clone for HamtIter<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            stack: self.stack.clone(),
+            bucket: self.bucket.clone(),
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<'a, T> Iterator for HamtIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            if let Some(value) = self.bucket.next() {
+                self.remaining -= 1;
+                return Some(value);
+            }
+            match self.stack.last_mut()?.next() {
+                None => {
+                    self.stack.pop();
+                }
+                Some(Entry::Leaf(_, value)) => {
+                    self.remaining -= 1;
+                    return Some(value);
+                }
+                Some(Entry::Collision(_, values)) => self.bucket = values.iter(),
+                Some(Entry::Branch(branch)) => self.stack.push(branch.entries.iter()),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BiHashMap;
+    use quickcheck::quickcheck;
+    use std::hash::{BuildHasherDefault, Hasher};
+
+    /// Hashes integers to themselves shifted into the top bits, so that all
+    /// hashes share their low bits and the tries get as deep as possible.
+    #[derive(Default)]
+    struct Deep(u64);
+
+    impl Hasher for Deep {
+        fn finish(&self) -> u64 {
+            self.0 << 56
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.0 = self.0 << 8 | u64::from(byte);
+            }
+        }
+    }
+
+    /// Hashes everything into four hashes, so that most values collide.
+    #[derive(Default)]
+    struct Colliding(u64);
+
+    impl Hasher for Colliding {
+        fn finish(&self) -> u64 {
+            self.0 % 4
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.0 = self.0.wrapping_mul(31).wrapping_add(u64::from(byte));
+            }
+        }
+    }
+
+    fn owned<L: Clone, R: Clone>(overwritten: Overwritten<&L, &R>) -> Overwritten<L, R> {
+        match overwritten {
+            Overwritten::Neither => Overwritten::Neither,
+            Overwritten::Left(l, r) => Overwritten::Left(l.clone(), r.clone()),
+            Overwritten::Right(l, r) => Overwritten::Right(l.clone(), r.clone()),
+            Overwritten::Pair(l, r) => Overwritten::Pair(l.clone(), r.clone()),
+            Overwritten::Both((l1, r1), (l2, r2)) => {
+                Overwritten::Both((l1.clone(), r1.clone()), (l2.clone(), r2.clone()))
+            }
+        }
+    }
+
+    /// Applies the operations to a persistent bimap and a `BiHashMap`, and
+    /// checks that every version matches the `BiHashMap` at that point.
+    fn matches_bihashmap<S>(ops: Vec<(u8, u8, u8)>) -> bool
+    where
+        S: BuildHasher + Clone + Default,
+    {
+        let mut versions = vec![PersistentBiMap::<u8, u8, S, S>::default()];
+        let mut snapshots = vec![BiHashMap::new()];
+        for (op, left, right) in ops {
+            let current = versions.last().unwrap();
+            let mut expected = snapshots.last().unwrap().clone();
+            let next = match op % 4 {
+                0 | 1 => {
+                    let (next, overwritten) = current.insert(left, right);
+                    if owned(overwritten) != expected.insert(left, right) {
+                        return false;
+                    }
+                    next
+                }
+                2 => {
+                    let (next, removed) = current.remove_by_left(&left);
+                    if removed.map(|(l, r)| (*l, *r)) != expected.remove_by_left(&left) {
+                        return false;
+                    }
+                    next
+                }
+                _ => {
+                    let (next, removed) = current.remove_by_right(&right);
+                    if removed.map(|(l, r)| (*l, *r)) != expected.remove_by_right(&right) {
+                        return false;
+                    }
+                    next
+                }
+            };
+            versions.push(next);
+            snapshots.push(expected);
+        }
+        versions.iter().zip(&snapshots).all(|(version, snapshot)| {
+            version.len() == snapshot.len()
+                && version.iter().count() == snapshot.len()
+                && snapshot.iter().all(|(l, r)| {
+                    version.get_by_left(l) == Some(r) && version.get_by_right(r) == Some(l)
+                })
+        })
+    }
+
+    quickcheck! {
+        fn versions_match_bihashmap(ops: Vec<(u8, u8, u8)>) -> bool {
+            matches_bihashmap::<hash_map::RandomState>(ops)
+        }
+
+        fn versions_match_bihashmap_deep(ops: Vec<(u8, u8, u8)>) -> bool {
+            matches_bihashmap::<BuildHasherDefault<Deep>>(ops)
+        }
+
+        fn versions_match_bihashmap_colliding(ops: Vec<(u8, u8, u8)>) -> bool {
+            matches_bihashmap::<BuildHasherDefault<Colliding>>(ops)
+        }
+    }
+
+    #[test]
+    fn versions_share_structure() {
+        let v0: PersistentBiMap<u32, u32> = (0..1000).map(|i| (i, i + 1)).collect();
+        let (v1, _) = v0.insert(0, 5000);
+        let (v2, _) = v1.remove_by_right(&500);
+        assert_eq!(v0.get_by_left(&0), Some(&1));
+        assert_eq!(v1.get_by_left(&0), Some(&5000));
+        assert_eq!(v2.len(), 999);
+
+        // only the pairs on the modified paths are different
+        let shared = v0
+            .left2right
+            .iter()
+            .filter(|pair| {
+                v2.pair_by_left(&pair.0)
+                    .is_some_and(|p| Arc::ptr_eq(p, pair))
+            })
+            .count();
+        assert_eq!(shared, 998);
+        assert!(Arc::ptr_eq(
+            &v0.left2right.root,
+            &v0.clone().left2right.root
+        ));
+    }
+
+    #[test]
+    fn removing_everything_empties_the_tries() {
+        type Map = PersistentBiMap<u16, u16, BuildHasherDefault<Deep>, BuildHasherDefault<Deep>>;
+        let mut bimap: Map = (0..300).map(|i| (i, 300 - i)).collect();
+        for i in 0..300 {
+            bimap = bimap.remove_by_left(&i).0;
+        }
+        assert!(bimap.is_empty());
+        assert!(bimap.left2right.root.entries.is_empty());
+        assert!(bimap.right2left.root.entries.is_empty());
+    }
+
+    #[test]
+    fn debug_and_eq() {
+        let (bimap, _) = PersistentBiMap::new().insert('a', 1);
+        assert_eq!(format!("{:?}", bimap), "{'a' <> 1}");
+        let (other, _) = bimap.insert('b', 2);
+        assert_ne!(bimap, other);
+        assert_eq!(bimap, other.remove_by_right(&2).0);
+        assert_eq!(format!("{:?}", PersistentBiMap::<char, i32>::new()), "{}");
+    }
+}
@@ -1,5 +1,6 @@
 //! Implementations of `serde::Serialize` and `serde::Deserialize` for
-//! `BiHashMap` and `BiBTreeMap`.
+//! `BiHashMap`, `BiBTreeMap` and `BiIndexMap`, as well as for the multimaps in
+//! the [`multi`](crate::multi) module.
 //!
 //! You do not need to import anything from this module to use this
 //! functionality, simply enable the `serde` feature in your dependency
@@ -54,6 +55,26 @@
 //! assert_eq!(map, map2);
 //! ```
 //!
+//! A `BiHashMap` with custom hashers can be deserialized as long as the hashers
+//! implement `Default`. Hashers that must be built at runtime can be passed in
+//! with a [`BiHashMapSeed`] instead.
+//!
+//! A [`BiIndexMap`] is serialized in its insertion order, and deserializing it
+//! inserts the pairs in the order they appear in the input.
+//!
+//! ```
+//! # use bimap::BiIndexMap;
+//! let mut map = BiIndexMap::new();
+//! map.insert('C', 3);
+//! map.insert('A', 1);
+//!
+//! let json = serde_json::to_string(&map).unwrap();
+//! assert_eq!(json, r#"{"C":3,"A":1}"#);
+//!
+//! let map2: BiIndexMap<char, i32> = serde_json::from_str(&json).unwrap();
+//! assert!(map.iter().eq(map2.iter()));
+//! ```
+//!
 //! Of course, this is only possible for bimaps where the values also implement
 //! `Serialize` and `Deserialize` respectively:
 //!
@@ -73,6 +94,61 @@
 //! let json = serde_json::to_string(&map).unwrap();
 //! ```
 //!
+//! # Alternate representations
+//!
+//! The submodules of this module can be used with `#[serde(with = "...")]` to
+//! (de)serialize a bimap field differently:
+//!
+//! - [`strict`] rejects input with duplicated left or right values, and
+//!   [`lenient`] explicitly keeps the default overwriting behavior.
+//! - [`pairs`] uses a sequence of `[left, right]` pairs and [`records`] a
+//!   sequence of `{left, right}` records, which both work with formats such as
+//!   JSON that only allow some types as map keys.
+//! - [`by_right`] uses a map keyed by the right values.
+//!
+//! # Multimaps
+//!
+//! [`BiHashMultiMap`] and [`BiBTreeMultiMap`] are serialized as a map from each
+//! left value to the sequence of right values it is paired with, which is how
+//! a one-to-many relation is usually written down by hand. Left values mapped
+//! to an empty sequence are skipped when deserializing, and a left value that
+//! appears several times keeps the pairs from all of its entries.
+//!
+//! ```
+//! # use bimap::multi::BiBTreeMultiMap;
+//! let mut sessions = BiBTreeMultiMap::new();
+//! sessions.insert("alice".to_string(), 1);
+//! sessions.insert("alice".to_string(), 2);
+//! sessions.insert("bob".to_string(), 3);
+//!
+//! let json = serde_json::to_string(&sessions).unwrap();
+//! assert_eq!(json, r#"{"alice":[1,2],"bob":[3]}"#);
+//!
+//! let sessions2 = serde_json::from_str(&json).unwrap();
+//! assert_eq!(sessions, sessions2);
+//! ```
+//!
+//! # Interners
+//!
+//! An [`Interner`] is serialized as the sequence of its values in the order of
+//! their ids, so that deserializing it assigns every value the same id again.
+//! Input containing a value twice is rejected, since it can't be deserialized
+//! without changing the ids.
+//!
+//! ```
+//! # use bimap::Interner;
+//! let mut symbols = Interner::<String>::new();
+//! symbols.intern_ref("beta");
+//! symbols.intern_ref("alpha");
+//!
+//! let json = serde_json::to_string(&symbols).unwrap();
+//! assert_eq!(json, r#"["beta","alpha"]"#);
+//!
+//! let symbols2: Interner<String> = serde_json::from_str(&json).unwrap();
+//! assert_eq!(symbols2.lookup("alpha"), Some(1));
+//! assert!(serde_json::from_str::<Interner<String>>(r#"["a","a"]"#).is_err());
+//! ```
+//!
 //! # Implementation details
 //!
 //! Bimaps are serialized and deserialized as a map data type in serde.
@@ -82,7 +158,8 @@
 //!
 //! For example, a bimap can be deserialized from the serialized form of a
 //! standard [`HashMap`]. However, *deserializing a bimap silently overwrites
-//! any conflicting pairs*, leading to non-deterministic results.
+//! any conflicting pairs*, leading to non-deterministic results. Use the
+//! [`strict`] module to reject such input instead.
 //! ```
 //! # use std::collections::HashMap;
 //! # use bimap::BiHashMap;
@@ -142,22 +219,158 @@
 //! ```
 //! [`BiHashMap`]: crate::BiHashMap
 //! [`BiBTreeMap`]: crate::BiBTreeMap
+//! [`BiIndexMap`]: crate::BiIndexMap
+//! [`Interner`]: crate::Interner
+//! [`BiHashMultiMap`]: crate::multi::BiHashMultiMap
+//! [`BiBTreeMultiMap`]: crate::multi::BiBTreeMultiMap
 //! [`HashMap`]: https://doc.rust-lang.org/std/collections/struct.HashMap.html
 
-use crate::{BiBTreeMap, BiHashMap};
+pub mod by_right;
+pub mod lenient;
+pub mod pairs;
+pub mod records;
+pub mod strict;
+
+use crate::{
+    compare::Compare,
+    intern::{FrozenInterner, Interner},
+    multi::{BiBTreeMultiMap, BiHashMultiMap},
+    vec::Idx,
+    BiBTreeMap, BiHashMap, BiIndexMap,
+};
 use serde::{
-    de::{MapAccess, Visitor},
+    de::{DeserializeSeed, Error, MapAccess, SeqAccess, Visitor},
     Deserialize, Deserializer, Serialize, Serializer,
 };
 use std::{
+    convert::TryFrom,
     default::Default,
     fmt::{Formatter, Result as FmtResult},
-    hash::Hash,
+    hash::{BuildHasher, Hash},
     marker::PhantomData,
 };
 
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A bimap that the helper modules in this module can deserialize into.
+///
+/// This trait is sealed and implemented for [`BiHashMap`], [`BiBTreeMap`] and
+/// [`BiIndexMap`].
+pub trait BijectiveMap: sealed::Sealed + Sized {
+    /// The type of the left values.
+    type Left;
+
+    /// The type of the right values.
+    type Right;
+
+    /// Creates an empty bimap with room for at least `capacity` pairs, if the
+    /// bimap supports preallocation.
+    fn with_capacity(capacity: usize) -> Self;
+
+    /// Returns `true` if the bimap contains the given left value.
+    fn contains_left(&self, left: &Self::Left) -> bool;
+
+    /// Inserts the given pair, overwriting any conflicting pairs.
+    fn insert(&mut self, left: Self::Left, right: Self::Right);
+
+    /// Inserts the given pair unless it conflicts with an existing pair, in
+    /// which case it is returned.
+    fn insert_no_overwrite(
+        &mut self,
+        left: Self::Left,
+        right: Self::Right,
+    ) -> Result<(), (Self::Left, Self::Right)>;
+}
+
+impl<L, R, LS, RS> sealed::Sealed for BiHashMap<L, R, LS, RS> {}
+
+impl<L, R, LS, RS> BijectiveMap for BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    type Left = L;
+    type Right = R;
+
+    fn with_capacity(capacity: usize) -> Self {
+        BiHashMap::with_capacity_and_hashers(capacity, LS::default(), RS::default())
+    }
+
+    fn contains_left(&self, left: &L) -> bool {
+        self.contains_left(left)
+    }
+
+    fn insert(&mut self, left: L, right: R) {
+        self.insert(left, right);
+    }
+
+    fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        self.insert_no_overwrite(left, right)
+    }
+}
+
+impl<L, R, LC, RC> sealed::Sealed for BiBTreeMap<L, R, LC, RC> {}
+
+impl<L, R, LC, RC> BijectiveMap for BiBTreeMap<L, R, LC, RC>
+where
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    type Left = L;
+    type Right = R;
+
+    fn with_capacity(_capacity: usize) -> Self {
+        BiBTreeMap::default()
+    }
+
+    fn contains_left(&self, left: &L) -> bool {
+        self.contains_left(left)
+    }
+
+    fn insert(&mut self, left: L, right: R) {
+        self.insert(left, right);
+    }
+
+    fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        self.insert_no_overwrite(left, right)
+    }
+}
+
+impl<L, R, LS, RS> sealed::Sealed for BiIndexMap<L, R, LS, RS> {}
+
+impl<L, R, LS, RS> BijectiveMap for BiIndexMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    type Left = L;
+    type Right = R;
+
+    fn with_capacity(capacity: usize) -> Self {
+        BiIndexMap::with_capacity_and_hashers(capacity, LS::default(), RS::default())
+    }
+
+    fn contains_left(&self, left: &L) -> bool {
+        self.contains_left(left)
+    }
+
+    fn insert(&mut self, left: L, right: R) {
+        self.insert(left, right);
+    }
+
+    fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        self.insert_no_overwrite(left, right)
+    }
+}
+
 /// Serializer for `BiHashMap`
-impl<L, R> Serialize for BiHashMap<L, R>
+impl<L, R, LS, RS> Serialize for BiHashMap<L, R, LS, RS>
 where
     L: Serialize + Eq + Hash,
     R: Serialize + Eq + Hash,
@@ -167,26 +380,84 @@ where
     }
 }
 
+/// Deserializes a `BiHashMap` that uses the given hashers.
+///
+/// This is useful when the hashers can't be created with `Default`, e.g.
+/// because they are seeded at runtime.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{serde::BiHashMapSeed, BiHashMap};
+/// use serde::de::DeserializeSeed;
+/// use std::collections::hash_map::RandomState;
+///
+/// let mut bimap = BiHashMap::new();
+/// bimap.insert('a', 1);
+/// bimap.insert('b', 2);
+/// let json = serde_json::to_string(&bimap).unwrap();
+///
+/// let seed = BiHashMapSeed::new(RandomState::new(), RandomState::new());
+/// let mut de = serde_json::Deserializer::from_str(&json);
+/// let bimap2: BiHashMap<char, i32> = seed.deserialize(&mut de).unwrap();
+/// assert_eq!(bimap, bimap2);
+/// ```
+pub struct BiHashMapSeed<L, R, LS, RS> {
+    hash_builder_left: LS,
+    hash_builder_right: RS,
+    marker: PhantomData<fn() -> (L, R)>,
+}
+
+impl<L, R, LS, RS> BiHashMapSeed<L, R, LS, RS> {
+    /// Creates a seed for deserializing a `BiHashMap` that uses
+    /// `hash_builder_left` to hash left values and `hash_builder_right` to
+    /// hash right values.
+    pub fn new(hash_builder_left: LS, hash_builder_right: RS) -> Self {
+        BiHashMapSeed {
+            hash_builder_left,
+            hash_builder_right,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, L, R, LS, RS> DeserializeSeed<'de> for BiHashMapSeed<L, R, LS, RS>
+where
+    L: Deserialize<'de> + Eq + Hash,
+    R: Deserialize<'de> + Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    type Value = BiHashMap<L, R, LS, RS>;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_map(BiHashMapVisitor { seed: self })
+    }
+}
+
 /// Visitor to construct `BiHashMap` from serialized map entries
-struct BiHashMapVisitor<L, R> {
-    marker: PhantomData<BiHashMap<L, R>>,
+struct BiHashMapVisitor<L, R, LS, RS> {
+    seed: BiHashMapSeed<L, R, LS, RS>,
 }
 
-impl<'de, L, R> Visitor<'de> for BiHashMapVisitor<L, R>
+impl<'de, L, R, LS, RS> Visitor<'de> for BiHashMapVisitor<L, R, LS, RS>
 where
     L: Deserialize<'de> + Eq + Hash,
     R: Deserialize<'de> + Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
 {
     fn expecting(&self, f: &mut Formatter) -> FmtResult {
         write!(f, "a map")
     }
 
-    type Value = BiHashMap<L, R>;
+    type Value = BiHashMap<L, R, LS, RS>;
     fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Self::Value, A::Error> {
-        let mut map = match entries.size_hint() {
-            Some(s) => BiHashMap::with_capacity(s),
-            None => BiHashMap::new(),
-        };
+        let mut map = BiHashMap::with_capacity_and_hashers(
+            entries.size_hint().unwrap_or(0),
+            self.seed.hash_builder_left,
+            self.seed.hash_builder_right,
+        );
         while let Some((l, r)) = entries.next_entry()? {
             map.insert(l, r);
         }
@@ -195,23 +466,81 @@ where
 }
 
 /// Deserializer for `BiHashMap`
-impl<'de, L, R> Deserialize<'de> for BiHashMap<L, R>
+impl<'de, L, R, LS, RS> Deserialize<'de> for BiHashMap<L, R, LS, RS>
 where
     L: Deserialize<'de> + Eq + Hash,
     R: Deserialize<'de> + Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
 {
     fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
-        de.deserialize_map(BiHashMapVisitor {
-            marker: PhantomData::default(),
+        BiHashMapSeed::new(LS::default(), RS::default()).deserialize(de)
+    }
+}
+
+/// Serializer for `BiIndexMap`
+impl<L, R, LS, RS> Serialize for BiIndexMap<L, R, LS, RS>
+where
+    L: Serialize + Eq + Hash,
+    R: Serialize + Eq + Hash,
+{
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_map(self.iter())
+    }
+}
+
+/// Visitor to construct `BiIndexMap` from serialized map entries
+struct BiIndexMapVisitor<L, R, LS, RS> {
+    marker: PhantomData<BiIndexMap<L, R, LS, RS>>,
+}
+
+impl<'de, L, R, LS, RS> Visitor<'de> for BiIndexMapVisitor<L, R, LS, RS>
+where
+    L: Deserialize<'de> + Eq + Hash,
+    R: Deserialize<'de> + Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a map")
+    }
+
+    type Value = BiIndexMap<L, R, LS, RS>;
+    fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Self::Value, A::Error> {
+        let mut map = BiIndexMap::with_capacity_and_hashers(
+            entries.size_hint().unwrap_or(0),
+            LS::default(),
+            RS::default(),
+        );
+        while let Some((l, r)) = entries.next_entry()? {
+            map.insert(l, r);
+        }
+        Ok(map)
+    }
+}
+
+/// Deserializer for `BiIndexMap`
+impl<'de, L, R, LS, RS> Deserialize<'de> for BiIndexMap<L, R, LS, RS>
+where
+    L: Deserialize<'de> + Eq + Hash,
+    R: Deserialize<'de> + Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_map(BiIndexMapVisitor {
+            marker: PhantomData,
         })
     }
 }
 
 /// Serializer for `BiBTreeMap`
-impl<L, R> Serialize for BiBTreeMap<L, R>
+impl<L, R, LC, RC> Serialize for BiBTreeMap<L, R, LC, RC>
 where
-    L: Serialize + Ord,
-    R: Serialize + Ord,
+    L: Serialize,
+    R: Serialize,
+    LC: Compare<L>,
+    RC: Compare<R>,
 {
     fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
         ser.collect_map(self.iter())
@@ -219,22 +548,24 @@ where
 }
 
 /// Visitor to construct `BiBTreeMap` from serialized map entries
-struct BiBTreeMapVisitor<L, R> {
-    marker: PhantomData<BiBTreeMap<L, R>>,
+struct BiBTreeMapVisitor<L, R, LC, RC> {
+    marker: PhantomData<BiBTreeMap<L, R, LC, RC>>,
 }
 
-impl<'de, L, R> Visitor<'de> for BiBTreeMapVisitor<L, R>
+impl<'de, L, R, LC, RC> Visitor<'de> for BiBTreeMapVisitor<L, R, LC, RC>
 where
-    L: Deserialize<'de> + Ord,
-    R: Deserialize<'de> + Ord,
+    L: Deserialize<'de>,
+    R: Deserialize<'de>,
+    LC: Compare<L>,
+    RC: Compare<R>,
 {
     fn expecting(&self, f: &mut Formatter) -> FmtResult {
         write!(f, "a map")
     }
 
-    type Value = BiBTreeMap<L, R>;
+    type Value = BiBTreeMap<L, R, LC, RC>;
     fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Self::Value, A::Error> {
-        let mut map = BiBTreeMap::new();
+        let mut map = BiBTreeMap::default();
         while let Some((l, r)) = entries.next_entry()? {
             map.insert(l, r);
         }
@@ -243,14 +574,226 @@ where
 }
 
 /// Deserializer for `BiBTreeMap`
-impl<'de, L, R> Deserialize<'de> for BiBTreeMap<L, R>
+impl<'de, L, R, LC, RC> Deserialize<'de> for BiBTreeMap<L, R, LC, RC>
+where
+    L: Deserialize<'de>,
+    R: Deserialize<'de>,
+    LC: Compare<L>,
+    RC: Compare<R>,
+{
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_map(BiBTreeMapVisitor {
+            marker: PhantomData,
+        })
+    }
+}
+
+/// Serializer for `Interner`
+impl<T, Id, S> Serialize for Interner<T, Id, S>
+where
+    T: Serialize + Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher,
+{
+    fn serialize<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        ser.collect_seq(self.iter().map(|(_, value)| value))
+    }
+}
+
+/// Visitor to construct `Interner` from a serialized sequence of values
+struct InternerVisitor<T, Id, S> {
+    marker: PhantomData<Interner<T, Id, S>>,
+}
+
+impl<'de, T, Id, S> Visitor<'de> for InternerVisitor<T, Id, S>
+where
+    T: Deserialize<'de> + Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher + Default,
+{
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a sequence of distinct values")
+    }
+
+    type Value = Interner<T, Id, S>;
+    fn visit_seq<A: SeqAccess<'de>>(self, mut values: A) -> Result<Self::Value, A::Error> {
+        let mut interner =
+            Interner::with_capacity_and_hasher(values.size_hint().unwrap_or(0), S::default());
+        while let Some(value) = values.next_element()? {
+            let id = interner.len();
+            if interner.lookup(&value).is_some() {
+                return Err(A::Error::custom(format_args!(
+                    "duplicate value at id {}",
+                    id
+                )));
+            }
+            if Id::try_from(id).is_err() {
+                return Err(A::Error::custom(format_args!(
+                    "id {} is out of range for the id type",
+                    id
+                )));
+            }
+            interner.intern(value);
+        }
+        Ok(interner)
+    }
+}
+
+/// Deserializer for `Interner`
+impl<'de, T, Id, S> Deserialize<'de> for Interner<T, Id, S>
+where
+    T: Deserialize<'de> + Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_seq(InternerVisitor {
+            marker: PhantomData,
+        })
+    }
+}
+
+/// Serializer for `FrozenInterner`
+impl<T, Id, S> Serialize for FrozenInterner<T, Id, S>
+where
+    T: Serialize + Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher,
+{
+    fn serialize<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        ser.collect_seq(self.iter().map(|(_, value)| value))
+    }
+}
+
+/// Deserializer for `FrozenInterner`
+impl<'de, T, Id, S> Deserialize<'de> for FrozenInterner<T, Id, S>
+where
+    T: Deserialize<'de> + Eq + Hash,
+    Id: Idx + TryFrom<usize> + Copy,
+    S: BuildHasher + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        Interner::deserialize(de).map(Interner::freeze)
+    }
+}
+
+/// Serializes the items of a cloneable iterator as a sequence.
+struct Seq<I>(I);
+
+impl<I> Serialize for Seq<I>
+where
+    I: Iterator + Clone,
+    I::Item: Serialize,
+{
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_seq(self.0.clone())
+    }
+}
+
+/// Serializer for `BiHashMultiMap`
+impl<L, R, LS, RS> Serialize for BiHashMultiMap<L, R, LS, RS>
+where
+    L: Serialize + Eq + Hash,
+    R: Serialize + Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_map(
+            self.left_values()
+                .map(|left| (left, Seq(self.get_by_left(left)))),
+        )
+    }
+}
+
+/// Visitor to construct `BiHashMultiMap` from serialized map entries
+struct BiHashMultiMapVisitor<L, R, LS, RS> {
+    marker: PhantomData<BiHashMultiMap<L, R, LS, RS>>,
+}
+
+impl<'de, L, R, LS, RS> Visitor<'de> for BiHashMultiMapVisitor<L, R, LS, RS>
+where
+    L: Deserialize<'de> + Eq + Hash,
+    R: Deserialize<'de> + Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a map of sequences")
+    }
+
+    type Value = BiHashMultiMap<L, R, LS, RS>;
+    fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Self::Value, A::Error> {
+        let mut map = BiHashMultiMap::default();
+        while let Some((left, rights)) = entries.next_entry::<L, Vec<R>>()? {
+            map.insert_by_left(left, rights);
+        }
+        Ok(map)
+    }
+}
+
+/// Deserializer for `BiHashMultiMap`
+impl<'de, L, R, LS, RS> Deserialize<'de> for BiHashMultiMap<L, R, LS, RS>
+where
+    L: Deserialize<'de> + Eq + Hash,
+    R: Deserialize<'de> + Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_map(BiHashMultiMapVisitor {
+            marker: PhantomData,
+        })
+    }
+}
+
+/// Serializer for `BiBTreeMultiMap`
+impl<L, R> Serialize for BiBTreeMultiMap<L, R>
+where
+    L: Serialize + Ord,
+    R: Serialize + Ord,
+{
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_map(
+            self.left_values()
+                .map(|left| (left, Seq(self.get_by_left(left)))),
+        )
+    }
+}
+
+/// Visitor to construct `BiBTreeMultiMap` from serialized map entries
+struct BiBTreeMultiMapVisitor<L, R> {
+    marker: PhantomData<BiBTreeMultiMap<L, R>>,
+}
+
+impl<'de, L, R> Visitor<'de> for BiBTreeMultiMapVisitor<L, R>
+where
+    L: Deserialize<'de> + Ord,
+    R: Deserialize<'de> + Ord,
+{
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a map of sequences")
+    }
+
+    type Value = BiBTreeMultiMap<L, R>;
+    fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Self::Value, A::Error> {
+        let mut map = BiBTreeMultiMap::new();
+        while let Some((left, rights)) = entries.next_entry::<L, Vec<R>>()? {
+            map.insert_by_left(left, rights);
+        }
+        Ok(map)
+    }
+}
+
+/// Deserializer for `BiBTreeMultiMap`
+impl<'de, L, R> Deserialize<'de> for BiBTreeMultiMap<L, R>
 where
     L: Deserialize<'de> + Ord,
     R: Deserialize<'de> + Ord,
 {
     fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
-        de.deserialize_map(BiBTreeMapVisitor {
-            marker: PhantomData::default(),
+        de.deserialize_map(BiBTreeMultiMapVisitor {
+            marker: PhantomData,
         })
     }
 }
@@ -259,6 +802,10 @@ where
 mod tests {
     use super::*;
     use serde::de::value::Error;
+    use std::{
+        collections::hash_map::{DefaultHasher, RandomState},
+        hash::BuildHasherDefault,
+    };
 
     #[test]
     fn serde_hash() {
@@ -286,23 +833,149 @@ mod tests {
         assert_eq!(bimap, bimap2);
     }
 
+    #[test]
+    fn serde_index_preserves_order() {
+        let mut bimap = BiIndexMap::new();
+        bimap.insert('c', 3);
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+
+        let json = serde_json::to_string(&bimap).unwrap();
+        assert_eq!(json, r#"{"c":3,"a":1,"b":2}"#);
+        let bimap2: BiIndexMap<char, i32> = serde_json::from_str(&json).unwrap();
+
+        assert!(bimap.iter().eq(bimap2.iter()));
+    }
+
+    #[test]
+    fn serde_interner_keeps_ids() {
+        let mut interner = Interner::<String, u8>::new();
+        for value in ["c", "a", "b", "a"].iter() {
+            interner.intern_ref(*value);
+        }
+
+        let json = serde_json::to_string(&interner).unwrap();
+        assert_eq!(json, r#"["c","a","b"]"#);
+        let interner2: Interner<String, u8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(interner, interner2);
+        assert_eq!(interner2.lookup("b"), Some(2));
+
+        let frozen: FrozenInterner<String, u8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&frozen).unwrap(), json);
+        assert_eq!(frozen, interner.freeze());
+
+        let err = serde_json::from_str::<Interner<String>>(r#"["a","b","a"]"#).unwrap_err();
+        assert!(err.to_string().contains("duplicate value at id 2"));
+        let values: Vec<_> = (0..=256).collect();
+        let json = serde_json::to_string(&values).unwrap();
+        let err = serde_json::from_str::<Interner<u16, u8>>(&json).unwrap_err();
+        assert!(err.to_string().contains("id 256 is out of range"));
+    }
+
+    #[test]
+    fn serde_hash_custom_hashers() {
+        type Hasher = BuildHasherDefault<DefaultHasher>;
+
+        let mut bimap = BiHashMap::<_, _, Hasher, Hasher>::default();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        let json = serde_json::to_string(&bimap).unwrap();
+        let bimap2: BiHashMap<_, _, Hasher, Hasher> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(bimap, bimap2);
+    }
+
+    #[test]
+    fn serde_hash_seed() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        let json = serde_json::to_string(&bimap).unwrap();
+        let seed = BiHashMapSeed::new(RandomState::new(), RandomState::new());
+        let bimap2 = seed
+            .deserialize(&mut serde_json::Deserializer::from_str(&json))
+            .unwrap();
+
+        assert_eq!(bimap, bimap2);
+    }
+
     #[test]
     fn expecting_btree() {
         let visitor = BiBTreeMapVisitor {
             marker: PhantomData::<BiBTreeMap<char, i32>>,
         };
-        let error_str = format!("{:?}", visitor.visit_bool::<Error>(true));
-        let expected = "Err(Error { err: \"invalid type: boolean `true`, expected a map\" })";
-        assert_eq!(error_str, expected);
+        let error = visitor.visit_bool::<Error>(true).unwrap_err();
+        let expected = "invalid type: boolean `true`, expected a map";
+        assert_eq!(error.to_string(), expected);
     }
 
     #[test]
     fn expecting_hash() {
         let visitor = BiHashMapVisitor {
-            marker: PhantomData::<BiHashMap<char, i32>>,
+            seed: BiHashMapSeed::<char, i32, _, _>::new(RandomState::new(), RandomState::new()),
+        };
+        let error = visitor.visit_bool::<Error>(true).unwrap_err();
+        let expected = "invalid type: boolean `true`, expected a map";
+        assert_eq!(error.to_string(), expected);
+    }
+
+    #[test]
+    fn serde_hash_multimap() {
+        let mut multimap = BiHashMultiMap::new();
+        multimap.insert('a', 1);
+        multimap.insert('a', 2);
+        multimap.insert('b', 2);
+
+        let json = serde_json::to_string(&multimap).unwrap();
+        let multimap2 = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(multimap, multimap2);
+    }
+
+    #[test]
+    fn serde_btree_multimap() {
+        let mut multimap = BiBTreeMultiMap::new();
+        multimap.insert('b', 2);
+        multimap.insert('a', 2);
+        multimap.insert('a', 1);
+
+        let json = serde_json::to_string(&multimap).unwrap();
+        assert_eq!(json, r#"{"a":[1,2],"b":[2]}"#);
+        let multimap2 = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(multimap, multimap2);
+    }
+
+    #[test]
+    fn deserialize_multimap_merges_entries() {
+        let json = r#"{"a":[1,2,1],"b":[],"c":[2],"a":[3]}"#;
+        let multimap: BiBTreeMultiMap<char, i32> = serde_json::from_str(json).unwrap();
+        let pairs: Vec<_> = multimap.into_iter().collect();
+        assert_eq!(pairs, [('a', 1), ('a', 2), ('a', 3), ('c', 2)]);
+
+        let multimap: BiHashMultiMap<char, i32> = serde_json::from_str(json).unwrap();
+        assert_eq!(multimap.len(), 4);
+        assert!(!multimap.contains_left(&'b'));
+        assert!(multimap.contains_pair(&'a', &3));
+    }
+
+    #[test]
+    fn expecting_multimaps() {
+        let expected = "invalid type: boolean `true`, expected a map of sequences";
+        let visitor = BiBTreeMultiMapVisitor {
+            marker: PhantomData::<BiBTreeMultiMap<char, i32>>,
+        };
+        let error = visitor.visit_bool::<Error>(true).unwrap_err();
+        assert_eq!(error.to_string(), expected);
+
+        let visitor = BiHashMultiMapVisitor {
+            marker: PhantomData::<BiHashMultiMap<char, i32>>,
         };
-        let error_str = format!("{:?}", visitor.visit_bool::<Error>(true));
-        let expected = "Err(Error { err: \"invalid type: boolean `true`, expected a map\" })";
-        assert_eq!(error_str, expected);
+        let error = visitor.visit_bool::<Error>(true).unwrap_err();
+        assert_eq!(error.to_string(), expected);
     }
 }
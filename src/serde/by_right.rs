@@ -0,0 +1,132 @@
+//! (De)serialization of bimaps as maps keyed by their right values, for use
+//! with `#[serde(with = "...")]`.
+//!
+//! This is the inverse of the representation used by the bimaps' `Serialize`
+//! and `Deserialize` implementations, which is useful when only the right
+//! values can be map keys, e.g. because they are strings while the left values
+//! are not. Deserialized pairs overwrite earlier conflicting pairs, just like
+//! [`insert`] does.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::BiBTreeMap;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, PartialEq, Serialize)]
+//! struct Names {
+//!     #[serde(with = "bimap::serde::by_right")]
+//!     ids: BiBTreeMap<(u32, u32), String>,
+//! }
+//!
+//! let mut names = Names { ids: BiBTreeMap::new() };
+//! names.ids.insert((0, 1), String::from("alice"));
+//! names.ids.insert((1, 0), String::from("bob"));
+//!
+//! let json = serde_json::to_string(&names).unwrap();
+//! assert_eq!(json, r#"{"ids":{"alice":[0,1],"bob":[1,0]}}"#);
+//! assert_eq!(serde_json::from_str::<Names>(&json).unwrap(), names);
+//! ```
+//!
+//! [`insert`]: crate::BiHashMap::insert
+
+use super::BijectiveMap;
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    fmt::{Formatter, Result as FmtResult},
+    marker::PhantomData,
+};
+
+/// Serializes a bimap as a map from right values to left values.
+pub fn serialize<'a, M, L, R, S>(map: &'a M, ser: S) -> Result<S::Ok, S::Error>
+where
+    &'a M: IntoIterator<Item = (&'a L, &'a R)>,
+    L: Serialize + 'a,
+    R: Serialize + 'a,
+    S: Serializer,
+{
+    ser.collect_map(map.into_iter().map(|(left, right)| (right, left)))
+}
+
+/// Deserializes a bimap from a map from right values to left values.
+pub fn deserialize<'de, M, D>(de: D) -> Result<M, D::Error>
+where
+    M: BijectiveMap,
+    M::Left: Deserialize<'de>,
+    M::Right: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    de.deserialize_map(ByRightVisitor {
+        marker: PhantomData,
+    })
+}
+
+/// Visitor to construct a bimap from serialized map entries keyed by right
+/// values
+struct ByRightVisitor<M> {
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<'de, M> Visitor<'de> for ByRightVisitor<M>
+where
+    M: BijectiveMap,
+    M::Left: Deserialize<'de>,
+    M::Right: Deserialize<'de>,
+{
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a map")
+    }
+
+    type Value = M;
+    fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<M, A::Error> {
+        let mut map = M::with_capacity(entries.size_hint().unwrap_or(0));
+        while let Some((r, l)) = entries.next_entry()? {
+            map.insert(l, r);
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BiBTreeMap, BiHashMap};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct ByRight {
+        #[serde(with = "super")]
+        hash: BiHashMap<(u8, u8), String>,
+        #[serde(with = "super")]
+        btree: BiBTreeMap<(u8, u8), String>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let by_right = ByRight {
+            hash: vec![((0, 1), String::from("a")), ((1, 0), String::from("b"))]
+                .into_iter()
+                .collect(),
+            btree: vec![((3, 2), String::from("c")), ((2, 3), String::from("d"))]
+                .into_iter()
+                .collect(),
+        };
+        let json = serde_json::to_string(&by_right).unwrap();
+        assert!(json.ends_with(r#""btree":{"d":[2,3],"c":[3,2]}}"#));
+        assert_eq!(serde_json::from_str::<ByRight>(&json).unwrap(), by_right);
+    }
+
+    #[test]
+    fn conflicts_overwrite() {
+        let json = r#"{"hash":{"a":[0,0],"b":[0,0]},"btree":{"a":[0,0],"a":[0,1]}}"#;
+        let by_right = serde_json::from_str::<ByRight>(json).unwrap();
+        assert_eq!(
+            by_right.hash.get_by_left(&(0, 0)).map(String::as_str),
+            Some("b")
+        );
+        assert_eq!(by_right.btree.get_by_right("a"), Some(&(0, 1)));
+        assert_eq!(by_right.btree.len(), 1);
+    }
+}
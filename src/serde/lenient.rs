@@ -0,0 +1,47 @@
+//! Lenient (de)serialization of bimaps, for use with `#[serde(with = "...")]`.
+//!
+//! This is the behavior of the `Serialize` and `Deserialize` implementations of
+//! the bimaps: a pair whose left or right value was already deserialized
+//! overwrites the earlier pair, just like [`insert`] does. This module makes
+//! that choice explicit, as opposed to the [`strict`] module.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::BiBTreeMap;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! struct Config {
+//!     #[serde(with = "bimap::serde::lenient")]
+//!     aliases: BiBTreeMap<String, u32>,
+//! }
+//!
+//! let config: Config = serde_json::from_str(r#"{"aliases":{"a":1,"b":1}}"#).unwrap();
+//! assert_eq!(config.aliases.len(), 1);
+//! assert_eq!(config.aliases.get_by_right(&1).map(String::as_str), Some("b"));
+//! ```
+//!
+//! [`insert`]: crate::BiHashMap::insert
+//! [`strict`]: super::strict
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serializes a bimap as a map from left values to right values.
+pub fn serialize<M, S>(map: &M, ser: S) -> Result<S::Ok, S::Error>
+where
+    M: Serialize,
+    S: Serializer,
+{
+    map.serialize(ser)
+}
+
+/// Deserializes a bimap from a map, overwriting earlier pairs with later
+/// conflicting ones.
+pub fn deserialize<'de, M, D>(de: D) -> Result<M, D::Error>
+where
+    M: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    M::deserialize(de)
+}
@@ -0,0 +1,129 @@
+//! (De)serialization of bimaps as sequences of `[left, right]` pairs, for use
+//! with `#[serde(with = "...")]`.
+//!
+//! Unlike maps, sequences of pairs can be represented in formats that restrict
+//! the types of map keys, such as JSON, whose object keys must be strings.
+//! Deserialized pairs overwrite earlier conflicting pairs, just like
+//! [`insert`] does.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::BiBTreeMap;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, PartialEq, Serialize)]
+//! struct Grid {
+//!     #[serde(with = "bimap::serde::pairs")]
+//!     cells: BiBTreeMap<(u32, u32), char>,
+//! }
+//!
+//! let mut grid = Grid { cells: BiBTreeMap::new() };
+//! grid.cells.insert((0, 0), 'a');
+//! grid.cells.insert((0, 1), 'b');
+//!
+//! let json = serde_json::to_string(&grid).unwrap();
+//! assert_eq!(json, r#"{"cells":[[[0,0],"a"],[[0,1],"b"]]}"#);
+//! assert_eq!(serde_json::from_str::<Grid>(&json).unwrap(), grid);
+//! ```
+//!
+//! [`insert`]: crate::BiHashMap::insert
+
+use super::BijectiveMap;
+use serde::{
+    de::{SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    fmt::{Formatter, Result as FmtResult},
+    marker::PhantomData,
+};
+
+/// Serializes a bimap as a sequence of `[left, right]` pairs.
+pub fn serialize<'a, M, L, R, S>(map: &'a M, ser: S) -> Result<S::Ok, S::Error>
+where
+    &'a M: IntoIterator<Item = (&'a L, &'a R)>,
+    L: Serialize + 'a,
+    R: Serialize + 'a,
+    S: Serializer,
+{
+    ser.collect_seq(map)
+}
+
+/// Deserializes a bimap from a sequence of `[left, right]` pairs.
+pub fn deserialize<'de, M, D>(de: D) -> Result<M, D::Error>
+where
+    M: BijectiveMap,
+    M::Left: Deserialize<'de>,
+    M::Right: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    de.deserialize_seq(PairsVisitor {
+        marker: PhantomData,
+    })
+}
+
+/// Visitor to construct a bimap from a serialized sequence of pairs
+struct PairsVisitor<M> {
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<'de, M> Visitor<'de> for PairsVisitor<M>
+where
+    M: BijectiveMap,
+    M::Left: Deserialize<'de>,
+    M::Right: Deserialize<'de>,
+{
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a sequence of pairs")
+    }
+
+    type Value = M;
+    fn visit_seq<A: SeqAccess<'de>>(self, mut pairs: A) -> Result<M, A::Error> {
+        let mut map = M::with_capacity(pairs.size_hint().unwrap_or(0));
+        while let Some((l, r)) = pairs.next_element()? {
+            map.insert(l, r);
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BiBTreeMap, BiHashMap};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Pairs {
+        #[serde(with = "super")]
+        hash: BiHashMap<(u8, u8), String>,
+        #[serde(with = "super")]
+        btree: BiBTreeMap<(u8, u8), String>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let pairs = Pairs {
+            hash: vec![((0, 1), String::from("a")), ((1, 0), String::from("b"))]
+                .into_iter()
+                .collect(),
+            btree: vec![((2, 3), String::from("c")), ((3, 2), String::from("d"))]
+                .into_iter()
+                .collect(),
+        };
+        let json = serde_json::to_string(&pairs).unwrap();
+        assert!(json.ends_with(r#""btree":[[[2,3],"c"],[[3,2],"d"]]}"#));
+        assert_eq!(serde_json::from_str::<Pairs>(&json).unwrap(), pairs);
+    }
+
+    #[test]
+    fn conflicts_overwrite() {
+        let json = r#"{"hash":[[[0,0],"a"],[[0,1],"a"]],"btree":[[[0,0],"a"],[[0,0],"b"]]}"#;
+        let pairs = serde_json::from_str::<Pairs>(json).unwrap();
+        assert_eq!(pairs.hash.get_by_right("a"), Some(&(0, 1)));
+        assert_eq!(
+            pairs.btree.get_by_left(&(0, 0)).map(String::as_str),
+            Some("b")
+        );
+    }
+}
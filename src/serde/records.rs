@@ -0,0 +1,256 @@
+//! (De)serialization of bimaps as sequences of `{left, right}` records, for use
+//! with `#[serde(with = "...")]`.
+//!
+//! Each pair is represented as a struct with `left` and `right` fields, which
+//! is the most self-describing representation and works with any left and
+//! right value types. Deserialized pairs overwrite earlier conflicting pairs,
+//! just like [`insert`] does.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::BiBTreeMap;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, PartialEq, Serialize)]
+//! struct Grid {
+//!     #[serde(with = "bimap::serde::records")]
+//!     cells: BiBTreeMap<(u32, u32), char>,
+//! }
+//!
+//! let mut grid = Grid { cells: BiBTreeMap::new() };
+//! grid.cells.insert((0, 0), 'a');
+//!
+//! let json = serde_json::to_string(&grid).unwrap();
+//! assert_eq!(json, r#"{"cells":[{"left":[0,0],"right":"a"}]}"#);
+//! assert_eq!(serde_json::from_str::<Grid>(&json).unwrap(), grid);
+//! ```
+//!
+//! [`insert`]: crate::BiHashMap::insert
+
+use super::BijectiveMap;
+use serde::{
+    de::{Error, MapAccess, SeqAccess, Visitor},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    fmt::{Formatter, Result as FmtResult},
+    marker::PhantomData,
+};
+
+const FIELDS: &[&str] = &["left", "right"];
+
+/// Serializes a bimap as a sequence of `{left, right}` records.
+pub fn serialize<'a, M, L, R, S>(map: &'a M, ser: S) -> Result<S::Ok, S::Error>
+where
+    &'a M: IntoIterator<Item = (&'a L, &'a R)>,
+    L: Serialize + 'a,
+    R: Serialize + 'a,
+    S: Serializer,
+{
+    ser.collect_seq(map.into_iter().map(|(left, right)| Record { left, right }))
+}
+
+/// Deserializes a bimap from a sequence of `{left, right}` records.
+pub fn deserialize<'de, M, D>(de: D) -> Result<M, D::Error>
+where
+    M: BijectiveMap,
+    M::Left: Deserialize<'de>,
+    M::Right: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    de.deserialize_seq(RecordsVisitor {
+        marker: PhantomData,
+    })
+}
+
+/// A left-right pair with named fields
+struct Record<L, R> {
+    left: L,
+    right: R,
+}
+
+impl<L, R> Serialize for Record<L, R>
+where
+    L: Serialize,
+    R: Serialize,
+{
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        let mut record = ser.serialize_struct("Record", 2)?;
+        record.serialize_field("left", &self.left)?;
+        record.serialize_field("right", &self.right)?;
+        record.end()
+    }
+}
+
+impl<'de, L, R> Deserialize<'de> for Record<L, R>
+where
+    L: Deserialize<'de>,
+    R: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_struct(
+            "Record",
+            FIELDS,
+            RecordVisitor {
+                marker: PhantomData,
+            },
+        )
+    }
+}
+
+/// The name of a field of a `Record`
+enum Field {
+    Left,
+    Right,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_identifier(FieldVisitor)
+    }
+}
+
+/// Visitor to identify the fields of a `Record`
+struct FieldVisitor;
+
+impl<'de> Visitor<'de> for FieldVisitor {
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "`left` or `right`")
+    }
+
+    type Value = Field;
+    fn visit_str<E: Error>(self, name: &str) -> Result<Field, E> {
+        match name {
+            "left" => Ok(Field::Left),
+            "right" => Ok(Field::Right),
+            _ => Err(E::unknown_field(name, FIELDS)),
+        }
+    }
+}
+
+/// Visitor to construct a `Record` from its serialized fields
+struct RecordVisitor<L, R> {
+    marker: PhantomData<fn() -> (L, R)>,
+}
+
+impl<'de, L, R> Visitor<'de> for RecordVisitor<L, R>
+where
+    L: Deserialize<'de>,
+    R: Deserialize<'de>,
+{
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a record with `left` and `right` fields")
+    }
+
+    type Value = Record<L, R>;
+    fn visit_seq<A: SeqAccess<'de>>(self, mut fields: A) -> Result<Self::Value, A::Error> {
+        let left = fields
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let right = fields
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        Ok(Record { left, right })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut fields: A) -> Result<Self::Value, A::Error> {
+        let mut left = None;
+        let mut right = None;
+        while let Some(field) = fields.next_key()? {
+            match field {
+                Field::Left if left.is_some() => return Err(A::Error::duplicate_field("left")),
+                Field::Left => left = Some(fields.next_value()?),
+                Field::Right if right.is_some() => return Err(A::Error::duplicate_field("right")),
+                Field::Right => right = Some(fields.next_value()?),
+            }
+        }
+        Ok(Record {
+            left: left.ok_or_else(|| A::Error::missing_field("left"))?,
+            right: right.ok_or_else(|| A::Error::missing_field("right"))?,
+        })
+    }
+}
+
+/// Visitor to construct a bimap from a serialized sequence of records
+struct RecordsVisitor<M> {
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<'de, M> Visitor<'de> for RecordsVisitor<M>
+where
+    M: BijectiveMap,
+    M::Left: Deserialize<'de>,
+    M::Right: Deserialize<'de>,
+{
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a sequence of records")
+    }
+
+    type Value = M;
+    fn visit_seq<A: SeqAccess<'de>>(self, mut records: A) -> Result<M, A::Error> {
+        let mut map = M::with_capacity(records.size_hint().unwrap_or(0));
+        while let Some(Record { left, right }) = records.next_element()? {
+            map.insert(left, right);
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BiBTreeMap, BiHashMap};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Records {
+        #[serde(with = "super")]
+        hash: BiHashMap<(u8, u8), String>,
+        #[serde(with = "super")]
+        btree: BiBTreeMap<(u8, u8), String>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let records = Records {
+            hash: vec![((0, 1), String::from("a")), ((1, 0), String::from("b"))]
+                .into_iter()
+                .collect(),
+            btree: vec![((2, 3), String::from("c"))].into_iter().collect(),
+        };
+        let json = serde_json::to_string(&records).unwrap();
+        assert!(json.ends_with(r#""btree":[{"left":[2,3],"right":"c"}]}"#));
+        assert_eq!(serde_json::from_str::<Records>(&json).unwrap(), records);
+    }
+
+    #[test]
+    fn field_order_and_conflicts() {
+        let json = r#"{
+            "hash": [{"right": "a", "left": [0, 0]}, {"left": [0, 1], "right": "a"}],
+            "btree": [[[0, 0], "a"]]
+        }"#;
+        let records = serde_json::from_str::<Records>(json).unwrap();
+        assert_eq!(records.hash.len(), 1);
+        assert_eq!(records.hash.get_by_right("a"), Some(&(0, 1)));
+        assert_eq!(
+            records.btree.get_by_left(&(0, 0)).map(String::as_str),
+            Some("a")
+        );
+    }
+
+    #[test]
+    fn invalid_records() {
+        let missing = r#"{"hash": [{"left": [0, 0]}], "btree": []}"#;
+        let error = serde_json::from_str::<Records>(missing).unwrap_err();
+        assert!(error.to_string().starts_with("missing field `right`"));
+
+        let unknown = r#"{"hash": [{"left": [0, 0], "middle": 1}], "btree": []}"#;
+        let error = serde_json::from_str::<Records>(unknown).unwrap_err();
+        assert!(error.to_string().starts_with("unknown field `middle`"));
+
+        let duplicate = r#"{"hash": [{"left": [0, 0], "left": [0, 1]}], "btree": []}"#;
+        let error = serde_json::from_str::<Records>(duplicate).unwrap_err();
+        assert!(error.to_string().starts_with("duplicate field `left`"));
+    }
+}
@@ -0,0 +1,136 @@
+//! Strict (de)serialization of bimaps, for use with `#[serde(with = "...")]`.
+//!
+//! Bimaps are serialized as maps, just like with their `Serialize`
+//! implementations. When deserializing, a left or right value that appears more
+//! than once is reported as an error naming the duplicated value, instead of
+//! silently overwriting the earlier pair.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::BiHashMap;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Deserialize, Serialize)]
+//! struct Config {
+//!     #[serde(with = "bimap::serde::strict")]
+//!     aliases: BiHashMap<String, u32>,
+//! }
+//!
+//! let config: Config = serde_json::from_str(r#"{"aliases":{"a":1,"b":2}}"#).unwrap();
+//! assert_eq!(config.aliases.get_by_right(&2).map(String::as_str), Some("b"));
+//!
+//! let error = serde_json::from_str::<Config>(r#"{"aliases":{"a":1,"b":1}}"#).unwrap_err();
+//! assert!(error.to_string().starts_with("duplicate right value 1"));
+//! ```
+
+use super::BijectiveMap;
+use serde::{
+    de::{Error, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    fmt::{Debug, Formatter, Result as FmtResult},
+    marker::PhantomData,
+};
+
+/// Serializes a bimap as a map from left values to right values.
+pub fn serialize<M, S>(map: &M, ser: S) -> Result<S::Ok, S::Error>
+where
+    M: Serialize,
+    S: Serializer,
+{
+    map.serialize(ser)
+}
+
+/// Deserializes a bimap from a map, returning an error if a left or right
+/// value appears more than once.
+pub fn deserialize<'de, M, D>(de: D) -> Result<M, D::Error>
+where
+    M: BijectiveMap,
+    M::Left: Deserialize<'de> + Debug,
+    M::Right: Deserialize<'de> + Debug,
+    D: Deserializer<'de>,
+{
+    de.deserialize_map(StrictVisitor {
+        marker: PhantomData,
+    })
+}
+
+/// Visitor to construct a bimap from serialized map entries, rejecting
+/// duplicates
+struct StrictVisitor<M> {
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<'de, M> Visitor<'de> for StrictVisitor<M>
+where
+    M: BijectiveMap,
+    M::Left: Deserialize<'de> + Debug,
+    M::Right: Deserialize<'de> + Debug,
+{
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a map without duplicate keys or values")
+    }
+
+    type Value = M;
+    fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<M, A::Error> {
+        let mut map = M::with_capacity(entries.size_hint().unwrap_or(0));
+        while let Some((l, r)) = entries.next_entry()? {
+            if let Err((l, r)) = map.insert_no_overwrite(l, r) {
+                return Err(if map.contains_left(&l) {
+                    A::Error::custom(format_args!("duplicate left value {:?}", l))
+                } else {
+                    A::Error::custom(format_args!("duplicate right value {:?}", r))
+                });
+            }
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BiBTreeMap, BiHashMap};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Strict {
+        #[serde(with = "super")]
+        hash: BiHashMap<String, i32>,
+        #[serde(with = "super")]
+        btree: BiBTreeMap<String, i32>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let strict = Strict {
+            hash: vec![(String::from("a"), 1), (String::from("b"), 2)]
+                .into_iter()
+                .collect(),
+            btree: vec![(String::from("c"), 3)].into_iter().collect(),
+        };
+        let json = serde_json::to_string(&strict).unwrap();
+        assert_eq!(serde_json::from_str::<Strict>(&json).unwrap(), strict);
+    }
+
+    #[test]
+    fn duplicate_left() {
+        let json = r#"{"hash":{"a":1,"a":2},"btree":{}}"#;
+        let error = serde_json::from_str::<Strict>(json).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"duplicate left value "a" at line 1 column 21"#
+        );
+    }
+
+    #[test]
+    fn duplicate_right() {
+        let json = r#"{"hash":{},"btree":{"a":1,"b":1}}"#;
+        let error = serde_json::from_str::<Strict>(json).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "duplicate right value 1 at line 1 column 32"
+        );
+    }
+}
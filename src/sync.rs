@@ -0,0 +1,1196 @@
+//! A bimap that can be shared between threads.
+
+use crate::{hash::EntryDebugger, BiHashMap, Overwritten};
+use hashbrown::HashTable;
+use std::{
+    borrow::Borrow,
+    collections::hash_map,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::{Extend, FromIterator},
+    ptr,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    thread,
+};
+
+const POISONED: &str = "a thread panicked while modifying the ConcurrentBiHashMap";
+
+/// A bimap that can be shared between threads, backed by lock-striped hash
+/// tables.
+///
+/// The bimap is split into a fixed number of shards, each protected by its own
+/// read-write lock. Every left value is indexed in the shard selected by its
+/// hash and every right value in the shard selected by its own hash, so pairs
+/// with unrelated values rarely contend for the same lock.
+///
+/// Lookups lock a single shard for reading. Operations that modify the bimap
+/// lock every shard holding a value they change for writing, in order of
+/// increasing shard index, before changing anything. Each operation therefore
+/// takes effect atomically: no thread can observe a left value without its
+/// right value or vice versa, or a pair that is only partly overwritten.
+///
+/// Since other threads can modify the bimap at any time, lookups return clones
+/// of values instead of references. A consistent copy of the whole bimap can
+/// be taken with [`snapshot`].
+///
+/// # Panics
+///
+/// If a `Hash` or `Eq` implementation panics while the bimap is locked, the
+/// locked shards are poisoned and every later operation using them panics.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::sync::ConcurrentBiHashMap;
+/// use std::{sync::Arc, thread};
+///
+/// let handles = Arc::new(ConcurrentBiHashMap::new());
+///
+/// let workers: Vec<_> = (0..4)
+///     .map(|id| {
+///         let handles = Arc::clone(&handles);
+///         thread::spawn(move || {
+///             handles.insert(id, format!("worker-{}", id));
+///         })
+///     })
+///     .collect();
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
+///
+/// assert_eq!(handles.len(), 4);
+/// assert_eq!(handles.get_by_right("worker-2"), Some(2));
+/// ```
+///
+/// [`snapshot`]: ConcurrentBiHashMap::snapshot
+pub struct ConcurrentBiHashMap<L, R, LS = hash_map::RandomState, RS = hash_map::RandomState> {
+    shards: Box<[RwLock<Shard<L, R>>]>,
+    // shards are selected by the bits right below the top 7 bits of a hash,
+    // which hashbrown uses within each table
+    shift: u32,
+    hash_builder_left: LS,
+    hash_builder_right: RS,
+}
+
+/// The part of a `ConcurrentBiHashMap` protected by a single lock, indexing
+/// the left values and right values whose hashes select it.
+struct Shard<L, R> {
+    lefts: HashTable<Slot<L, R>>,
+    rights: HashTable<Slot<R, L>>,
+}
+
+/// A value indexed in a shard, together with the value it is paired with.
+///
+/// Both hashes are stored so that tables can be resized and the other half of
+/// a pair can be found without calling any `Hash` or `Eq` implementation.
+struct Slot<K, V> {
+    key: Arc<K>,
+    value: Arc<V>,
+    key_hash: u64,
+    value_hash: u64,
+}
+
+impl<K, V> Slot<K, V> {
+    fn locate(&self) -> Found<K, V> {
+        Found {
+            key: Arc::as_ptr(&self.key),
+            value: Arc::as_ptr(&self.value),
+            value_hash: self.value_hash,
+        }
+    }
+}
+
+/// The location of a pair found in a locked shard, which stays valid as long as
+/// the shards holding it remain locked.
+struct Found<K, V> {
+    key: *const K,
+    value: *const V,
+    value_hash: u64,
+}
+
+/// A sorted set of the indices of at most four shards, which is the most that
+/// a single operation needs to lock.
+#[derive(Clone, Copy)]
+struct ShardSet {
+    indices: [usize; 4],
+    len: usize,
+}
+
+impl ShardSet {
+    fn new(indices: &[usize]) -> Self {
+        let mut set = ShardSet {
+            indices: [0; 4],
+            len: 0,
+        };
+        for &index in indices {
+            if !set.as_slice().contains(&index) {
+                set.indices[set.len] = index;
+                set.len += 1;
+            }
+        }
+        set.indices[..set.len].sort_unstable();
+        set
+    }
+
+    fn as_slice(&self) -> &[usize] {
+        &self.indices[..self.len]
+    }
+
+    fn is_superset(&self, other: &ShardSet) -> bool {
+        other
+            .as_slice()
+            .iter()
+            .all(|index| self.as_slice().contains(index))
+    }
+}
+
+/// Write guards for a set of shards.
+struct Locked<'a, L, R> {
+    set: ShardSet,
+    guards: [Option<RwLockWriteGuard<'a, Shard<L, R>>>; 4],
+    shift: u32,
+}
+
+impl<'a, L, R> Locked<'a, L, R> {
+    /// Returns the locked shard selected by a hash.
+    fn shard(&mut self, hash: u64) -> &mut Shard<L, R> {
+        let index = shard_index(self.shift, hash);
+        // unwraps are safe because operations only look up values in shards
+        // they have locked
+        let position = self.set.as_slice().iter().position(|&i| i == index);
+        self.guards[position.unwrap()].as_mut().unwrap()
+    }
+
+    /// Removes a pair from the locked shards.
+    fn remove_pair(
+        &mut self,
+        left_hash: u64,
+        left: *const L,
+        right_hash: u64,
+        right: *const R,
+    ) -> (L, R) {
+        // unwraps are safe because every pair is indexed in both directions
+        let (slot, _) = self
+            .shard(left_hash)
+            .lefts
+            .find_entry(left_hash, |slot| ptr::eq(Arc::as_ptr(&slot.key), left))
+            .ok()
+            .unwrap()
+            .remove();
+        self.shard(right_hash)
+            .rights
+            .find_entry(right_hash, |slot| ptr::eq(Arc::as_ptr(&slot.key), right))
+            .ok()
+            .unwrap()
+            .remove();
+        (
+            Arc::try_unwrap(slot.key).ok().unwrap(),
+            Arc::try_unwrap(slot.value).ok().unwrap(),
+        )
+    }
+
+    /// Inserts a pair into the locked shards without checking for conflicts.
+    fn insert_pair(&mut self, left: L, left_hash: u64, right: R, right_hash: u64) {
+        let left = Arc::new(left);
+        let right = Arc::new(right);
+        let right_slot = Slot {
+            key: Arc::clone(&right),
+            value: Arc::clone(&left),
+            key_hash: right_hash,
+            value_hash: left_hash,
+        };
+        self.shard(right_hash)
+            .rights
+            .insert_unique(right_hash, right_slot, |slot| slot.key_hash);
+        let left_slot = Slot {
+            key: left,
+            value: right,
+            key_hash: left_hash,
+            value_hash: right_hash,
+        };
+        self.shard(left_hash)
+            .lefts
+            .insert_unique(left_hash, left_slot, |slot| slot.key_hash);
+    }
+}
+
+/// Returns the index of the shard selected by a hash.
+fn shard_index(shift: u32, hash: u64) -> usize {
+    (hash << 7).checked_shr(shift).unwrap_or(0) as usize
+}
+
+impl<L, R> ConcurrentBiHashMap<L, R, hash_map::RandomState, hash_map::RandomState>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    /// Creates an empty `ConcurrentBiHashMap` with four shards per available
+    /// CPU.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let bimap = ConcurrentBiHashMap::<char, i32>::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hashers(hash_map::RandomState::new(), hash_map::RandomState::new())
+    }
+
+    /// Creates an empty `ConcurrentBiHashMap` with the given number of shards,
+    /// rounded up to a power of two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let bimap = ConcurrentBiHashMap::<char, i32>::with_shards(16);
+    /// ```
+    pub fn with_shards(shards: usize) -> Self {
+        Self::with_shards_and_hashers(
+            shards,
+            hash_map::RandomState::new(),
+            hash_map::RandomState::new(),
+        )
+    }
+}
+
+impl<L, R, LS, RS> ConcurrentBiHashMap<L, R, LS, RS> {
+    /// Returns the number of left-right pairs in the bimap.
+    ///
+    /// This locks every shard, so it should be used sparingly while other
+    /// threads are modifying the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let bimap = ConcurrentBiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// assert_eq!(bimap.len(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.read_all().iter().map(|shard| shard.lefts.len()).sum()
+    }
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let bimap = ConcurrentBiHashMap::new();
+    /// assert!(bimap.is_empty());
+    /// bimap.insert('a', 1);
+    /// assert!(!bimap.is_empty());
+    /// bimap.remove_by_right(&1);
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all pairs from the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let bimap = ConcurrentBiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.clear();
+    /// assert!(bimap.len() == 0);
+    /// ```
+    pub fn clear(&self) {
+        let mut shards: Vec<_> = self
+            .shards
+            .iter()
+            .map(|shard| shard.write().expect(POISONED))
+            .collect();
+        for shard in &mut shards {
+            shard.rights.clear();
+        }
+        for shard in &mut shards {
+            shard.lefts.clear();
+        }
+    }
+
+    /// Consumes the `ConcurrentBiHashMap` and returns its pairs in a
+    /// [`BiHashMap`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let bimap = ConcurrentBiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let bimap = bimap.into_bimap();
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    /// ```
+    pub fn into_bimap(self) -> BiHashMap<L, R, LS, RS>
+    where
+        L: Eq + Hash,
+        R: Eq + Hash,
+        LS: BuildHasher,
+        RS: BuildHasher,
+    {
+        let mut shards: Vec<_> = self
+            .shards
+            .into_vec()
+            .into_iter()
+            .map(|shard| shard.into_inner().expect(POISONED))
+            .collect();
+        let len = shards.iter().map(|shard| shard.lefts.len()).sum();
+        let mut bimap = BiHashMap::with_capacity_and_hashers(
+            len,
+            self.hash_builder_left,
+            self.hash_builder_right,
+        );
+        // drop the right-to-left slots first so that the left-to-right slots
+        // hold the only references to the values
+        for shard in &mut shards {
+            shard.rights.clear();
+        }
+        for slot in shards.into_iter().flat_map(|shard| shard.lefts) {
+            bimap.insert(
+                Arc::try_unwrap(slot.key).ok().unwrap(),
+                Arc::try_unwrap(slot.value).ok().unwrap(),
+            );
+        }
+        bimap
+    }
+
+    /// Locks every shard for reading, in order of increasing index.
+    fn read_all(&self) -> Vec<RwLockReadGuard<'_, Shard<L, R>>> {
+        self.shards
+            .iter()
+            .map(|shard| shard.read().expect(POISONED))
+            .collect()
+    }
+}
+
+impl<L, R, LS, RS> ConcurrentBiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Creates an empty `ConcurrentBiHashMap` with four shards per available
+    /// CPU, using `hash_builder_left` to hash left values and
+    /// `hash_builder_right` to hash right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let s_left = RandomState::new();
+    /// let s_right = RandomState::new();
+    /// let bimap = ConcurrentBiHashMap::<char, i32>::with_hashers(s_left, s_right);
+    /// bimap.insert('a', 42);
+    /// ```
+    pub fn with_hashers(hash_builder_left: LS, hash_builder_right: RS) -> Self {
+        let shards = thread::available_parallelism().map_or(1, usize::from) * 4;
+        Self::with_shards_and_hashers(shards, hash_builder_left, hash_builder_right)
+    }
+
+    /// Creates an empty `ConcurrentBiHashMap` with the given number of shards,
+    /// rounded up to a power of two, using `hash_builder_left` to hash left
+    /// values and `hash_builder_right` to hash right values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let s_left = RandomState::new();
+    /// let s_right = RandomState::new();
+    /// let bimap = ConcurrentBiHashMap::<char, i32>::with_shards_and_hashers(16, s_left, s_right);
+    /// bimap.insert('a', 42);
+    /// ```
+    pub fn with_shards_and_hashers(
+        shards: usize,
+        hash_builder_left: LS,
+        hash_builder_right: RS,
+    ) -> Self {
+        assert!(shards > 0, "a ConcurrentBiHashMap needs at least one shard");
+        let shards = shards.next_power_of_two();
+        Self {
+            shards: (0..shards)
+                .map(|_| {
+                    RwLock::new(Shard {
+                        lefts: HashTable::new(),
+                        rights: HashTable::new(),
+                    })
+                })
+                .collect(),
+            shift: 64 - shards.trailing_zeros(),
+            hash_builder_left,
+            hash_builder_right,
+        }
+    }
+
+    /// Returns a clone of the right value corresponding to the given left
+    /// value.
+    ///
+    /// The left value may be any borrowed form of the bimap's left value
+    /// type, but `Hash` and `Eq` on the borrowed form *must* match those for
+    /// the left value type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let bimap = ConcurrentBiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(1));
+    /// assert_eq!(bimap.get_by_left(&'z'), None);
+    /// ```
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        R: Clone,
+    {
+        let hash = self.hash_builder_left.hash_one(left);
+        self.read(hash)
+            .lefts
+            .find(hash, |slot| L::borrow(&slot.key) == left)
+            .map(|slot| R::clone(&slot.value))
+    }
+
+    /// Returns a clone of the left value corresponding to the given right
+    /// value.
+    ///
+    /// The right value may be any borrowed form of the bimap's right value
+    /// type, but `Hash` and `Eq` on the borrowed form *must* match those for
+    /// the right value type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let bimap = ConcurrentBiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_by_right(&1), Some('a'));
+    /// assert_eq!(bimap.get_by_right(&2), None);
+    /// ```
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        L: Clone,
+    {
+        let hash = self.hash_builder_right.hash_one(right);
+        self.read(hash)
+            .rights
+            .find(hash, |slot| R::borrow(&slot.key) == right)
+            .map(|slot| L::clone(&slot.value))
+    }
+
+    /// Returns `true` if the bimap contains the given left value and `false`
+    /// otherwise.
+    ///
+    /// The left value may be any borrowed form of the bimap's left value
+    /// type, but `Hash` and `Eq` on the borrowed form *must* match those for
+    /// the left value type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let bimap = ConcurrentBiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// assert!(bimap.contains_left(&'a'));
+    /// assert!(!bimap.contains_left(&'b'));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder_left.hash_one(left);
+        self.read(hash)
+            .lefts
+            .find(hash, |slot| L::borrow(&slot.key) == left)
+            .is_some()
+    }
+
+    /// Returns `true` if the bimap contains the given right value and `false`
+    /// otherwise.
+    ///
+    /// The right value may be any borrowed form of the bimap's right value
+    /// type, but `Hash` and `Eq` on the borrowed form *must* match those for
+    /// the right value type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let bimap = ConcurrentBiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// assert!(bimap.contains_right(&1));
+    /// assert!(!bimap.contains_right(&2));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = self.hash_builder_right.hash_one(right);
+        self.read(hash)
+            .rights
+            .find(hash, |slot| R::borrow(&slot.key) == right)
+            .is_some()
+    }
+
+    /// Removes the left-right pair corresponding to the given left value.
+    ///
+    /// Returns the previous left-right pair if the map contained the left value
+    /// and `None` otherwise.
+    ///
+    /// The left value may be any borrowed form of the bimap's left value
+    /// type, but `Hash` and `Eq` on the borrowed form *must* match those for
+    /// the left value type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let bimap = ConcurrentBiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.remove_by_left(&'b'), Some(('b', 2)));
+    /// assert_eq!(bimap.remove_by_left(&'b'), None);
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    pub fn remove_by_left<Q>(&self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let left_hash = self.hash_builder_left.hash_one(left);
+        let mut set = self.shard_set(&[left_hash]);
+        loop {
+            let mut locked = self.lock(set);
+            let found = locked
+                .shard(left_hash)
+                .lefts
+                .find(left_hash, |slot| L::borrow(&slot.key) == left)
+                .map(Slot::locate)?;
+            // the shard of the right value may not be locked yet, in which
+            // case the lookup has to be repeated once it is
+            let needed = self.shard_set(&[left_hash, found.value_hash]);
+            if set.is_superset(&needed) {
+                return Some(locked.remove_pair(
+                    left_hash,
+                    found.key,
+                    found.value_hash,
+                    found.value,
+                ));
+            }
+            set = needed;
+        }
+    }
+
+    /// Removes the left-right pair corresponding to the given right value.
+    ///
+    /// Returns the previous left-right pair if the map contained the right
+    /// value and `None` otherwise.
+    ///
+    /// The right value may be any borrowed form of the bimap's right value
+    /// type, but `Hash` and `Eq` on the borrowed form *must* match those for
+    /// the right value type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let bimap = ConcurrentBiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.remove_by_right(&2), Some(('b', 2)));
+    /// assert_eq!(bimap.remove_by_right(&2), None);
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    pub fn remove_by_right<Q>(&self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let right_hash = self.hash_builder_right.hash_one(right);
+        let mut set = self.shard_set(&[right_hash]);
+        loop {
+            let mut locked = self.lock(set);
+            let found = locked
+                .shard(right_hash)
+                .rights
+                .find(right_hash, |slot| R::borrow(&slot.key) == right)
+                .map(Slot::locate)?;
+            let needed = self.shard_set(&[right_hash, found.value_hash]);
+            if set.is_superset(&needed) {
+                return Some(locked.remove_pair(
+                    found.value_hash,
+                    found.value,
+                    right_hash,
+                    found.key,
+                ));
+            }
+            set = needed;
+        }
+    }
+
+    /// Inserts the given left-right pair into the bimap.
+    ///
+    /// Returns an enum `Overwritten` representing any left-right pairs that
+    /// were overwritten by the call to `insert`, exactly like
+    /// [`BiHashMap::insert`]. The removal of the overwritten pairs and the
+    /// insertion of the new pair happen atomically.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{sync::ConcurrentBiHashMap, Overwritten};
+    ///
+    /// let bimap = ConcurrentBiHashMap::new();
+    ///
+    /// assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
+    /// assert_eq!(bimap.insert('a', 2), Overwritten::Left('a', 1));
+    /// assert_eq!(bimap.insert('b', 2), Overwritten::Right('a', 2));
+    /// assert_eq!(bimap.insert('b', 2), Overwritten::Pair('b', 2));
+    ///
+    /// bimap.insert('c', 3);
+    /// assert_eq!(bimap.insert('b', 3), Overwritten::Both(('b', 2), ('c', 3)));
+    /// ```
+    pub fn insert(&self, left: L, right: R) -> Overwritten<L, R> {
+        let left_hash = self.hash_builder_left.hash_one(&left);
+        let right_hash = self.hash_builder_right.hash_one(&right);
+        let mut set = self.shard_set(&[left_hash, right_hash]);
+        loop {
+            let mut locked = self.lock(set);
+            let old_right = locked
+                .shard(left_hash)
+                .lefts
+                .find(left_hash, |slot| *slot.key == left)
+                .map(Slot::locate);
+            let old_left = locked
+                .shard(right_hash)
+                .rights
+                .find(right_hash, |slot| *slot.key == right)
+                .map(Slot::locate);
+
+            // the shards holding the other halves of the overwritten pairs may
+            // not be locked yet, in which case the lookups have to be repeated
+            // once they are
+            let needed = self.shard_set(&[
+                left_hash,
+                right_hash,
+                old_right
+                    .as_ref()
+                    .map_or(left_hash, |found| found.value_hash),
+                old_left
+                    .as_ref()
+                    .map_or(right_hash, |found| found.value_hash),
+            ]);
+            if !set.is_superset(&needed) {
+                set = needed;
+                continue;
+            }
+
+            let overwritten = match (old_right, old_left) {
+                (None, None) => Overwritten::Neither,
+                (Some(old), None) => {
+                    let (l, r) = locked.remove_pair(left_hash, old.key, old.value_hash, old.value);
+                    Overwritten::Left(l, r)
+                }
+                (None, Some(old)) => {
+                    let (l, r) = locked.remove_pair(old.value_hash, old.value, right_hash, old.key);
+                    Overwritten::Right(l, r)
+                }
+                (Some(old_right), Some(old_left)) if ptr::eq(old_right.key, old_left.value) => {
+                    let (l, r) =
+                        locked.remove_pair(left_hash, old_right.key, right_hash, old_right.value);
+                    Overwritten::Pair(l, r)
+                }
+                (Some(old_right), Some(old_left)) => {
+                    let left_pair = locked.remove_pair(
+                        left_hash,
+                        old_right.key,
+                        old_right.value_hash,
+                        old_right.value,
+                    );
+                    let right_pair = locked.remove_pair(
+                        old_left.value_hash,
+                        old_left.value,
+                        right_hash,
+                        old_left.key,
+                    );
+                    Overwritten::Both(left_pair, right_pair)
+                }
+            };
+            locked.insert_pair(left, left_hash, right, right_hash);
+            return overwritten;
+        }
+    }
+
+    /// Inserts the given left-right pair into the bimap without overwriting any
+    /// existing values.
+    ///
+    /// Returns `Ok(())` if the pair was successfully inserted into the bimap.
+    /// If either value exists in the map, `Err((left, right))` is returned
+    /// with the attempted left-right pair and the map is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let bimap = ConcurrentBiHashMap::new();
+    /// assert_eq!(bimap.insert_no_overwrite('a', 1), Ok(()));
+    /// assert_eq!(bimap.insert_no_overwrite('b', 2), Ok(()));
+    /// assert_eq!(bimap.insert_no_overwrite('a', 3), Err(('a', 3)));
+    /// assert_eq!(bimap.insert_no_overwrite('c', 2), Err(('c', 2)));
+    /// ```
+    pub fn insert_no_overwrite(&self, left: L, right: R) -> Result<(), (L, R)> {
+        let left_hash = self.hash_builder_left.hash_one(&left);
+        let right_hash = self.hash_builder_right.hash_one(&right);
+        let mut locked = self.lock(self.shard_set(&[left_hash, right_hash]));
+        let conflict = locked
+            .shard(left_hash)
+            .lefts
+            .find(left_hash, |slot| *slot.key == left)
+            .is_some()
+            || locked
+                .shard(right_hash)
+                .rights
+                .find(right_hash, |slot| *slot.key == right)
+                .is_some();
+        if conflict {
+            Err((left, right))
+        } else {
+            locked.insert_pair(left, left_hash, right, right_hash);
+            Ok(())
+        }
+    }
+
+    /// Returns a [`BiHashMap`] containing clones of the pairs of the bimap.
+    ///
+    /// Every shard is locked while the pairs are cloned, so the snapshot
+    /// reflects the bimap at a single point in time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::sync::ConcurrentBiHashMap;
+    ///
+    /// let bimap = ConcurrentBiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let snapshot = bimap.snapshot();
+    /// bimap.clear();
+    /// assert_eq!(snapshot.len(), 2);
+    /// assert_eq!(snapshot.get_by_right(&1), Some(&'a'));
+    /// ```
+    pub fn snapshot(&self) -> BiHashMap<L, R, LS, RS>
+    where
+        L: Clone,
+        R: Clone,
+        LS: Clone,
+        RS: Clone,
+    {
+        let shards = self.read_all();
+        let len = shards.iter().map(|shard| shard.lefts.len()).sum();
+        let mut bimap = BiHashMap::with_capacity_and_hashers(
+            len,
+            self.hash_builder_left.clone(),
+            self.hash_builder_right.clone(),
+        );
+        for slot in shards.iter().flat_map(|shard| shard.lefts.iter()) {
+            bimap.insert(L::clone(&slot.key), R::clone(&slot.value));
+        }
+        bimap
+    }
+
+    /// Returns the set of shards selected by the given hashes.
+    fn shard_set(&self, hashes: &[u64]) -> ShardSet {
+        let mut indices = [0; 4];
+        for (index, &hash) in indices.iter_mut().zip(hashes) {
+            *index = shard_index(self.shift, hash);
+        }
+        ShardSet::new(&indices[..hashes.len()])
+    }
+
+    /// Locks the shard selected by a hash for reading.
+    fn read(&self, hash: u64) -> RwLockReadGuard<'_, Shard<L, R>> {
+        self.shards[shard_index(self.shift, hash)]
+            .read()
+            .expect(POISONED)
+    }
+
+    /// Locks a set of shards for writing, in order of increasing index.
+    fn lock(&self, set: ShardSet) -> Locked<'_, L, R> {
+        let mut guards = [None, None, None, None];
+        for (guard, &index) in guards.iter_mut().zip(set.as_slice()) {
+            *guard = Some(self.shards[index].write().expect(POISONED));
+        }
+        Locked {
+            set,
+            guards,
+            shift: self.shift,
+        }
+    }
+}
+
+impl<L, R, LS, RS> fmt::Debug for ConcurrentBiHashMap<L, R, LS, RS>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let shards = self.read_all();
+        f.debug_set()
+            .entries(
+                shards
+                    .iter()
+                    .flat_map(|shard| shard.lefts.iter())
+                    .map(|slot| EntryDebugger {
+                        left: &*slot.key,
+                        right: &*slot.value,
+                    }),
+            )
+            .finish()
+    }
+}
+
+impl<L, R, LS, RS> Default for ConcurrentBiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn default() -> ConcurrentBiHashMap<L, R, LS, RS> {
+        ConcurrentBiHashMap::with_hashers(LS::default(), RS::default())
+    }
+}
+
+impl<L, R, LS, RS> FromIterator<(L, R)> for ConcurrentBiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn from_iter<I>(iter: I) -> ConcurrentBiHashMap<L, R, LS, RS>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let mut bimap = ConcurrentBiHashMap::default();
+        bimap.extend(iter);
+        bimap
+    }
+}
+
+impl<L, R, LS, RS> Extend<(L, R)> for ConcurrentBiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(move |(l, r)| {
+            self.insert(l, r);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Checks that every pair is indexed exactly once in both directions.
+    fn assert_consistent<L, R, LS, RS>(bimap: &ConcurrentBiHashMap<L, R, LS, RS>) {
+        let shards = bimap.read_all();
+        let mut len = 0;
+        for slot in shards.iter().flat_map(|shard| shard.lefts.iter()) {
+            let shard = &shards[shard_index(bimap.shift, slot.value_hash)];
+            let matching = shard
+                .rights
+                .iter()
+                .filter(|other| Arc::ptr_eq(&other.key, &slot.value))
+                .collect::<Vec<_>>();
+            assert_eq!(matching.len(), 1);
+            assert!(Arc::ptr_eq(&matching[0].value, &slot.key));
+            assert_eq!(matching[0].value_hash, slot.key_hash);
+            assert_eq!(Arc::strong_count(&slot.key), 2);
+            assert_eq!(Arc::strong_count(&slot.value), 2);
+            len += 1;
+        }
+        let rights: usize = shards.iter().map(|shard| shard.rights.len()).sum();
+        assert_eq!(rights, len);
+    }
+
+    /// A small xorshift generator, so that the stress tests are reproducible.
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % n
+        }
+    }
+
+    #[test]
+    fn shard_sets() {
+        let set = ShardSet::new(&[3, 1, 3, 2]);
+        assert_eq!(set.as_slice(), &[1, 2, 3]);
+        assert!(set.is_superset(&ShardSet::new(&[2, 1])));
+        assert!(!set.is_superset(&ShardSet::new(&[0, 1])));
+    }
+
+    #[test]
+    fn shard_counts() {
+        for &shards in &[1, 2, 3, 8, 100] {
+            let bimap = ConcurrentBiHashMap::with_shards(shards);
+            assert_eq!(bimap.shards.len(), shards.next_power_of_two());
+            for i in 0..100 {
+                bimap.insert(i, i * 2);
+            }
+            assert_eq!(bimap.len(), 100);
+            assert_consistent(&bimap);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_shards() {
+        ConcurrentBiHashMap::<u32, u32>::with_shards(0);
+    }
+
+    #[test]
+    fn insert_overwrites() {
+        for &shards in &[1, 64] {
+            let bimap = ConcurrentBiHashMap::with_shards(shards);
+            assert_eq!(bimap.insert(0, 'a'), Overwritten::Neither);
+            assert_eq!(bimap.insert(1, 'b'), Overwritten::Neither);
+            assert_eq!(bimap.insert(0, 'c'), Overwritten::Left(0, 'a'));
+            assert_eq!(bimap.insert(2, 'c'), Overwritten::Right(0, 'c'));
+            assert_eq!(bimap.insert(2, 'c'), Overwritten::Pair(2, 'c'));
+            assert_eq!(bimap.insert(1, 'c'), Overwritten::Both((1, 'b'), (2, 'c')));
+            assert_eq!(bimap.len(), 1);
+            assert_eq!(bimap.get_by_left(&1), Some('c'));
+            assert_eq!(bimap.get_by_right(&'c'), Some(1));
+            assert_consistent(&bimap);
+        }
+    }
+
+    #[test]
+    fn borrowed_lookups() {
+        let bimap = ConcurrentBiHashMap::new();
+        bimap.insert(String::from("a"), String::from("b"));
+        assert_eq!(bimap.get_by_left("a").as_deref(), Some("b"));
+        assert_eq!(bimap.get_by_right("b").as_deref(), Some("a"));
+        assert!(bimap.contains_left("a"));
+        assert!(bimap.contains_right("b"));
+        assert_eq!(
+            bimap.remove_by_right("b"),
+            Some((String::from("a"), String::from("b")))
+        );
+        assert!(bimap.is_empty());
+    }
+
+    #[test]
+    fn from_iter_and_into_bimap() {
+        let bimap: ConcurrentBiHashMap<_, _> = (0..50).map(|i| (i, i + 100)).collect();
+        assert_consistent(&bimap);
+        let bimap = bimap.into_bimap();
+        assert_eq!(bimap.len(), 50);
+        assert!((0..50).all(|i| bimap.get_by_left(&i) == Some(&(i + 100))));
+    }
+
+    #[test]
+    fn debug() {
+        let bimap = ConcurrentBiHashMap::new();
+        assert_eq!(format!("{:?}", bimap), "{}");
+        bimap.insert('a', 1);
+        assert_eq!(format!("{:?}", bimap), "{'a' <> 1}");
+    }
+
+    #[test]
+    fn concurrent_operations_stay_consistent() {
+        const THREADS: u64 = 8;
+        const OPERATIONS: u64 = 10_000;
+        const VALUES: u64 = 32;
+
+        let bimap = Arc::new(ConcurrentBiHashMap::with_shards(8));
+        let done = Arc::new(AtomicBool::new(false));
+
+        let checkers: Vec<_> = (0..2)
+            .map(|_| {
+                let bimap = Arc::clone(&bimap);
+                let done = Arc::clone(&done);
+                thread::spawn(move || {
+                    while !done.load(Ordering::Relaxed) {
+                        assert_consistent(&bimap);
+                        for left in 0..VALUES {
+                            if let Some(right) = bimap.get_by_left(&left) {
+                                assert!(right < VALUES);
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let writers: Vec<_> = (0..THREADS)
+            .map(|id| {
+                let bimap = Arc::clone(&bimap);
+                thread::spawn(move || {
+                    let mut rng = Rng(id.wrapping_mul(0x9e37_79b9_7f4a_7c15) + 1);
+                    // the change in the number of pairs caused by this thread
+                    let mut delta = 0i64;
+                    for _ in 0..OPERATIONS {
+                        let left = rng.below(VALUES);
+                        let right = rng.below(VALUES);
+                        match rng.below(10) {
+                            0..=5 => match bimap.insert(left, right) {
+                                Overwritten::Neither => delta += 1,
+                                Overwritten::Left(l, _) => assert_eq!(l, left),
+                                Overwritten::Right(_, r) => assert_eq!(r, right),
+                                Overwritten::Pair(l, r) => assert_eq!((l, r), (left, right)),
+                                Overwritten::Both((l1, r1), (l2, r2)) => {
+                                    assert_eq!(l1, left);
+                                    assert_eq!(r2, right);
+                                    assert!(r1 != right && l2 != left);
+                                    delta -= 1;
+                                }
+                            },
+                            6 => {
+                                if bimap.insert_no_overwrite(left, right).is_ok() {
+                                    delta += 1;
+                                }
+                            }
+                            7 | 8 => {
+                                if let Some((l, _)) = bimap.remove_by_left(&left) {
+                                    assert_eq!(l, left);
+                                    delta -= 1;
+                                }
+                            }
+                            _ => {
+                                if let Some((_, r)) = bimap.remove_by_right(&right) {
+                                    assert_eq!(r, right);
+                                    delta -= 1;
+                                }
+                            }
+                        }
+                    }
+                    delta
+                })
+            })
+            .collect();
+
+        let delta: i64 = writers.into_iter().map(|w| w.join().unwrap()).sum();
+        done.store(true, Ordering::Relaxed);
+        for checker in checkers {
+            checker.join().unwrap();
+        }
+
+        assert_consistent(&bimap);
+        assert_eq!(bimap.len() as i64, delta);
+        let bimap = Arc::try_unwrap(bimap).ok().unwrap().into_bimap();
+        assert_eq!(bimap.len() as i64, delta);
+    }
+
+    #[test]
+    fn concurrent_readers_see_whole_pairs() {
+        const VALUES: u64 = 16;
+
+        // every pair inserted has a right value that is a multiple of its left
+        // value plus one, so a torn pair would be noticed by the readers
+        let bimap = Arc::new(ConcurrentBiHashMap::with_shards(4));
+        let done = Arc::new(AtomicBool::new(false));
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let bimap = Arc::clone(&bimap);
+                let done = Arc::clone(&done);
+                thread::spawn(move || {
+                    while !done.load(Ordering::Relaxed) {
+                        for value in 0..VALUES * VALUES {
+                            if let Some(right) = bimap.get_by_left(&value) {
+                                assert_eq!(right % (value + 1), 0);
+                            }
+                            if let Some(left) = bimap.get_by_right(&value) {
+                                assert_eq!(value % (left + 1), 0);
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let writers: Vec<_> = (0..4)
+            .map(|id| {
+                let bimap = Arc::clone(&bimap);
+                thread::spawn(move || {
+                    let mut rng = Rng(id + 1);
+                    for _ in 0..20_000 {
+                        let left = rng.below(VALUES);
+                        let right = (left + 1) * rng.below(VALUES);
+                        if rng.below(4) == 0 {
+                            bimap.remove_by_right(&right);
+                        } else {
+                            bimap.insert(left, right);
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        done.store(true, Ordering::Relaxed);
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_consistent(&bimap);
+    }
+}
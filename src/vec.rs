@@ -0,0 +1,916 @@
+//! A bimap backed by two vectors, for dense integer ids on both sides.
+//!
+//! A [`BiVecMap`] stores the right value of each left value at the left
+//! value's index in one vector, and the left value of each right value at the
+//! right value's index in another. Lookups, insertions and removals are plain
+//! indexing operations without any hashing or comparisons, and each pair
+//! occupies a single slot in each vector. This makes it well suited to
+//! remappings between small integer ids, such as renumbering the nodes of a
+//! graph, as long as the ids are dense: each vector is as long as the largest
+//! index on its side.
+//!
+//! Values are converted to indices by the [`Idx`] trait, which is implemented
+//! for the unsigned integer types and can be implemented for newtype ids.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::BiVecMap;
+//!
+//! // renumber the nodes 10, 20 and 30 to 0, 1 and 2
+//! let mut renumbering = BiVecMap::<u32, u32>::new();
+//! renumbering.insert(10, 0);
+//! renumbering.insert(20, 1);
+//! renumbering.insert(30, 2);
+//!
+//! assert_eq!(renumbering.get_by_left(&20), Some(&1));
+//! assert_eq!(renumbering.get_by_right(&2), Some(&30));
+//!
+//! // pairs are iterated over in ascending order by left value
+//! let pairs: Vec<_> = renumbering.iter().collect();
+//! assert_eq!(pairs, [(&10, &0), (&20, &1), (&30, &2)]);
+//! ```
+
+use crate::Overwritten;
+use alloc::vec::{self, Vec};
+use core::{
+    convert::TryFrom,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::{Extend, FromIterator, FusedIterator},
+    slice,
+};
+
+/// A value that can be used as an index into a [`BiVecMap`].
+///
+/// Two values must have the same index if and only if they are equal.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{vec::Idx, BiVecMap};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct NodeId(u32);
+///
+/// impl Idx for NodeId {
+///     fn index(&self) -> usize {
+///         self.0 as usize
+///     }
+/// }
+///
+/// let mut bimap = BiVecMap::<NodeId, u8>::new();
+/// bimap.insert(NodeId(3), 0);
+/// assert_eq!(bimap.get_by_right(&0), Some(&NodeId(3)));
+/// ```
+pub trait Idx {
+    /// Returns the index of the value.
+    fn index(&self) -> usize;
+}
+
+macro_rules! impl_idx {
+    ($($t:ty),*) => {
+        $(
+            impl Idx for $t {
+                /// # Panics
+                ///
+                /// Panics if the value doesn't fit in a `usize`.
+                fn index(&self) -> usize {
+                    usize::try_from(*self).expect("index out of range for usize")
+                }
+            }
+        )*
+    };
+}
+
+impl_idx!(u8, u16, u32, u64, usize);
+
+/// A bimap backed by two vectors indexed by the left and right values.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// [module-level documentation]: crate::vec
+pub struct BiVecMap<L, R> {
+    // the right value of each left value, at the index of the left value
+    left2right: Vec<Option<R>>,
+    // the left value of each right value, at the index of the right value
+    right2left: Vec<Option<L>>,
+    len: usize,
+}
+
+impl<L, R> BiVecMap<L, R>
+where
+    L: Idx,
+    R: Idx,
+{
+    /// Creates an empty `BiVecMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let bimap = BiVecMap::<u32, u32>::new();
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            left2right: Vec::new(),
+            right2left: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Creates an empty `BiVecMap` with room for left and right values with
+    /// indices below `capacity` without reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let bimap = BiVecMap::<u32, u32>::with_capacity(10);
+    /// assert!(bimap.capacity() >= 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            left2right: Vec::with_capacity(capacity),
+            right2left: Vec::with_capacity(capacity),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of left-right pairs in the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::<u32, u32>::new();
+    /// bimap.insert(0, 10);
+    /// bimap.insert(5, 3);
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::<u32, u32>::new();
+    /// assert!(bimap.is_empty());
+    /// bimap.insert(0, 10);
+    /// assert!(!bimap.is_empty());
+    /// bimap.remove_by_left(&0);
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a lower bound on the number of indices on each side the bimap
+    /// can hold without reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let bimap = BiVecMap::<u32, u32>::with_capacity(10);
+    /// assert!(bimap.capacity() >= 10);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.left2right.capacity().min(self.right2left.capacity())
+    }
+
+    /// Removes all left-right pairs from the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::<u32, u32>::new();
+    /// bimap.insert(0, 10);
+    /// bimap.insert(1, 11);
+    /// bimap.clear();
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.left2right.clear();
+        self.right2left.clear();
+        self.len = 0;
+    }
+
+    /// Creates an iterator over the left-right pairs in the bimap in ascending
+    /// order by left value.
+    ///
+    /// The iterator element type is `(&L, &R)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::<u32, u32>::new();
+    /// bimap.insert(2, 0);
+    /// bimap.insert(0, 1);
+    /// bimap.insert(1, 2);
+    ///
+    /// let pairs: Vec<_> = bimap.iter().collect();
+    /// assert_eq!(pairs, [(&0, &1), (&1, &2), (&2, &0)]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, L, R> {
+        Iter {
+            inner: self.left2right.iter(),
+            lefts: &self.right2left,
+            remaining: self.len,
+        }
+    }
+
+    /// Creates an iterator over the left values in the bimap in ascending
+    /// order.
+    ///
+    /// The iterator element type is `&L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::<u32, u32>::new();
+    /// bimap.insert(2, 0);
+    /// bimap.insert(0, 1);
+    ///
+    /// let lefts: Vec<_> = bimap.left_values().collect();
+    /// assert_eq!(lefts, [&0, &2]);
+    /// ```
+    pub fn left_values(&self) -> LeftValues<'_, L, R> {
+        LeftValues { inner: self.iter() }
+    }
+
+    /// Creates an iterator over the right values in the bimap in ascending
+    /// order.
+    ///
+    /// The iterator element type is `&R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::<u32, u32>::new();
+    /// bimap.insert(0, 2);
+    /// bimap.insert(1, 0);
+    ///
+    /// let rights: Vec<_> = bimap.right_values().collect();
+    /// assert_eq!(rights, [&0, &2]);
+    /// ```
+    pub fn right_values(&self) -> RightValues<'_, L, R> {
+        RightValues {
+            inner: self.iter().inverse(),
+        }
+    }
+
+    /// Returns a reference to the right value corresponding to the given left
+    /// value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::<u32, u32>::new();
+    /// bimap.insert(0, 10);
+    /// assert_eq!(bimap.get_by_left(&0), Some(&10));
+    /// assert_eq!(bimap.get_by_left(&1), None);
+    /// ```
+    pub fn get_by_left(&self, left: &L) -> Option<&R> {
+        self.left2right.get(left.index())?.as_ref()
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::<u32, u32>::new();
+    /// bimap.insert(0, 10);
+    /// assert_eq!(bimap.get_by_right(&10), Some(&0));
+    /// assert_eq!(bimap.get_by_right(&0), None);
+    /// ```
+    pub fn get_by_right(&self, right: &R) -> Option<&L> {
+        self.right2left.get(right.index())?.as_ref()
+    }
+
+    /// Returns `true` if the bimap contains the given left value and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::<u32, u32>::new();
+    /// bimap.insert(0, 10);
+    /// assert!(bimap.contains_left(&0));
+    /// assert!(!bimap.contains_left(&10));
+    /// ```
+    pub fn contains_left(&self, left: &L) -> bool {
+        self.get_by_left(left).is_some()
+    }
+
+    /// Returns `true` if the bimap contains the given right value and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::<u32, u32>::new();
+    /// bimap.insert(0, 10);
+    /// assert!(bimap.contains_right(&10));
+    /// assert!(!bimap.contains_right(&0));
+    /// ```
+    pub fn contains_right(&self, right: &R) -> bool {
+        self.get_by_right(right).is_some()
+    }
+
+    /// Removes the left-right pair corresponding to the given left value.
+    ///
+    /// Returns the previous left-right pair if the map contained the left value
+    /// and `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::<u32, u32>::new();
+    /// bimap.insert(0, 10);
+    /// bimap.insert(1, 11);
+    ///
+    /// assert_eq!(bimap.remove_by_left(&0), Some((0, 10)));
+    /// assert_eq!(bimap.remove_by_left(&0), None);
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    pub fn remove_by_left(&mut self, left: &L) -> Option<(L, R)> {
+        let right = self.left2right.get_mut(left.index())?.take()?;
+        // unwrap is safe because every pair is stored in both vectors
+        let left = self.right2left[right.index()].take().unwrap();
+        self.len -= 1;
+        trim(&mut self.left2right);
+        trim(&mut self.right2left);
+        Some((left, right))
+    }
+
+    /// Removes the left-right pair corresponding to the given right value.
+    ///
+    /// Returns the previous left-right pair if the map contained the right
+    /// value and `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::<u32, u32>::new();
+    /// bimap.insert(0, 10);
+    /// bimap.insert(1, 11);
+    ///
+    /// assert_eq!(bimap.remove_by_right(&10), Some((0, 10)));
+    /// assert_eq!(bimap.remove_by_right(&10), None);
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    pub fn remove_by_right(&mut self, right: &R) -> Option<(L, R)> {
+        let left = self.right2left.get_mut(right.index())?.take()?;
+        // unwrap is safe because every pair is stored in both vectors
+        let right = self.left2right[left.index()].take().unwrap();
+        self.len -= 1;
+        trim(&mut self.left2right);
+        trim(&mut self.right2left);
+        Some((left, right))
+    }
+
+    /// Inserts the given left-right pair into the bimap.
+    ///
+    /// Returns an enum `Overwritten` representing any left-right pairs that
+    /// were overwritten by the call to `insert`. The example below details
+    /// all possible enum variants that can be returned.
+    ///
+    /// # Warnings
+    ///
+    /// Each vector grows to hold the largest index inserted on its side, so a
+    /// single large index allocates memory proportional to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiVecMap, Overwritten};
+    ///
+    /// let mut bimap = BiVecMap::<u32, u32>::new();
+    /// assert_eq!(bimap.len(), 0); // {}
+    ///
+    /// // no values are overwritten.
+    /// assert_eq!(bimap.insert(0, 1), Overwritten::Neither);
+    /// assert_eq!(bimap.len(), 1); // {0 <> 1}
+    ///
+    /// // no values are overwritten.
+    /// assert_eq!(bimap.insert(2, 3), Overwritten::Neither);
+    /// assert_eq!(bimap.len(), 2); // {0 <> 1, 2 <> 3}
+    ///
+    /// // (0, 1) is overwritten by (0, 5).
+    /// assert_eq!(bimap.insert(0, 5), Overwritten::Left(0, 1));
+    /// assert_eq!(bimap.len(), 2); // {0 <> 5, 2 <> 3}
+    ///
+    /// // (2, 3) is overwritten by (4, 3).
+    /// assert_eq!(bimap.insert(4, 3), Overwritten::Right(2, 3));
+    /// assert_eq!(bimap.len(), 2); // {0 <> 5, 4 <> 3}
+    ///
+    /// // (4, 3) is overwritten by (4, 3).
+    /// assert_eq!(bimap.insert(4, 3), Overwritten::Pair(4, 3));
+    /// assert_eq!(bimap.len(), 2); // {0 <> 5, 4 <> 3}
+    ///
+    /// // both (0, 5) and (4, 3) are overwritten by (0, 3).
+    /// assert_eq!(bimap.insert(0, 3), Overwritten::Both((0, 5), (4, 3)));
+    /// assert_eq!(bimap.len(), 1); // {0 <> 3}
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        let retval = match (self.remove_by_left(&left), self.remove_by_right(&right)) {
+            (None, None) => Overwritten::Neither,
+            (None, Some(r_pair)) => Overwritten::Right(r_pair.0, r_pair.1),
+            (Some(l_pair), None) => {
+                // since remove_by_left() was called first, it's possible the right value was
+                // removed if a duplicate pair is being inserted
+                if l_pair.1.index() == right.index() {
+                    Overwritten::Pair(l_pair.0, l_pair.1)
+                } else {
+                    Overwritten::Left(l_pair.0, l_pair.1)
+                }
+            }
+            (Some(l_pair), Some(r_pair)) => Overwritten::Both(l_pair, r_pair),
+        };
+        self.insert_unchecked(left, right);
+        retval
+    }
+
+    /// Inserts the given left-right pair into the bimap without overwriting any
+    /// existing values.
+    ///
+    /// Returns `Ok(())` if the pair was successfully inserted into the bimap.
+    /// If either value exists in the map, `Err((left, right)` is returned
+    /// with the attempted left-right pair and the map is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::<u32, u32>::new();
+    /// assert_eq!(bimap.insert_no_overwrite(0, 1), Ok(()));
+    /// assert_eq!(bimap.insert_no_overwrite(2, 3), Ok(()));
+    /// assert_eq!(bimap.insert_no_overwrite(0, 4), Err((0, 4)));
+    /// assert_eq!(bimap.insert_no_overwrite(4, 3), Err((4, 3)));
+    /// ```
+    pub fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        if self.contains_left(&left) || self.contains_right(&right) {
+            Err((left, right))
+        } else {
+            self.insert_unchecked(left, right);
+            Ok(())
+        }
+    }
+
+    /// Inserts the given left-right pair into the bimap without checking if
+    /// the pair already exists.
+    fn insert_unchecked(&mut self, left: L, right: R) {
+        let (left_index, right_index) = (left.index(), right.index());
+        *slot(&mut self.left2right, left_index) = Some(right);
+        *slot(&mut self.right2left, right_index) = Some(left);
+        self.len += 1;
+    }
+}
+
+/// Returns the slot at the given index, growing the vector if necessary.
+fn slot<T>(values: &mut Vec<Option<T>>, index: usize) -> &mut Option<T> {
+    if index >= values.len() {
+        values.resize_with(index + 1, || None);
+    }
+    &mut values[index]
+}
+
+/// Removes the empty slots at the end of a vector, so that equal bimaps are
+/// stored in equal vectors.
+fn trim<T>(values: &mut Vec<Option<T>>) {
+    while let Some(None) = values.last() {
+        values.pop();
+    }
+}
+
+impl<L, R> Clone for BiVecMap<L, R>
+where
+    L: Clone,
+    R: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            left2right: self.left2right.clone(),
+            right2left: self.right2left.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<L, R> fmt::Debug for BiVecMap<L, R>
+where
+    L: fmt::Debug + Idx,
+    R: fmt::Debug + Idx,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{")?;
+        for (i, (left, right)) in self.iter().enumerate() {
+            let comma = if i == 0 { "" } else { ", " };
+            write!(f, "{}{:?} <> {:?}", comma, left, right)?;
+        }
+        write!(f, "}}")?;
+        Ok(())
+    }
+}
+
+impl<L, R> Default for BiVecMap<L, R>
+where
+    L: Idx,
+    R: Idx,
+{
+    fn default() -> BiVecMap<L, R> {
+        BiVecMap::new()
+    }
+}
+
+impl<L, R> Eq for BiVecMap<L, R>
+where
+    L: Eq,
+    R: Eq,
+{
+}
+
+impl<L, R> FromIterator<(L, R)> for BiVecMap<L, R>
+where
+    L: Idx,
+    R: Idx,
+{
+    fn from_iter<I>(iter: I) -> BiVecMap<L, R>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let mut bimap = BiVecMap::new();
+        bimap.extend(iter);
+        bimap
+    }
+}
+
+impl<L, R> Hash for BiVecMap<L, R>
+where
+    R: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // the left values are determined by the indices of the right values
+        self.left2right.hash(state);
+    }
+}
+
+impl<'a, L, R> IntoIterator for &'a BiVecMap<L, R>
+where
+    L: Idx,
+    R: Idx,
+{
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R>;
+
+    fn into_iter(self) -> Iter<'a, L, R> {
+        self.iter()
+    }
+}
+
+impl<L, R> IntoIterator for BiVecMap<L, R>
+where
+    L: Idx,
+    R: Idx,
+{
+    type Item = (L, R);
+    type IntoIter = IntoIter<L, R>;
+
+    fn into_iter(self) -> IntoIter<L, R> {
+        IntoIter {
+            inner: self.left2right.into_iter(),
+            lefts: self.right2left,
+            remaining: self.len,
+        }
+    }
+}
+
+impl<L, R> Extend<(L, R)> for BiVecMap<L, R>
+where
+    L: Idx,
+    R: Idx,
+{
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(move |(l, r)| {
+            self.insert(l, r);
+        });
+    }
+}
+
+impl<L, R> PartialEq for BiVecMap<L, R>
+where
+    L: PartialEq,
+    R: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        // trimming guarantees that equal bimaps have equal vectors
+        self.left2right == other.left2right
+    }
+}
+
+/// An owning iterator over the left-right pairs in a `BiVecMap`.
+pub struct IntoIter<L, R> {
+    inner: vec::IntoIter<Option<R>>,
+    lefts: Vec<Option<L>>,
+    remaining: usize,
+}
+
+impl<L: Idx, R: Idx> IntoIter<L, R> {
+    fn pair(&mut self, right: R) -> (L, R) {
+        self.remaining -= 1;
+        // unwrap is safe because every pair is stored in both vectors
+        (self.lefts[right.index()].take().unwrap(), right)
+    }
+}
+
+impl<L: Idx, R: Idx> DoubleEndedIterator for IntoIter<L, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let right = self.inner.by_ref().rev().flatten().next()?;
+        Some(self.pair(right))
+    }
+}
+
+impl<L: Idx, R: Idx> ExactSizeIterator for IntoIter<L, R> {}
+
+impl<L: Idx, R: Idx> FusedIterator for IntoIter<L, R> {}
+
+impl<L: Idx, R: Idx> Iterator for IntoIter<L, R> {
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let right = self.inner.by_ref().flatten().next()?;
+        Some(self.pair(right))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// An iterator over the left-right pairs in a `BiVecMap`.
+///
+/// This struct is created by the [`iter`] method of `BiVecMap`.
+///
+/// [`iter`]: BiVecMap::iter
+pub struct Iter<'a, L, R> {
+    inner: slice::Iter<'a, Option<R>>,
+    lefts: &'a [Option<L>],
+    remaining: usize,
+}
+
+impl<'a, L: Idx, R: Idx> Iter<'a, L, R> {
+    fn pair(&mut self, right: &'a R) -> (&'a L, &'a R) {
+        self.remaining -= 1;
+        // unwrap is safe because every pair is stored in both vectors
+        (self.lefts[right.index()].as_ref().unwrap(), right)
+    }
+
+    /// Turns the iterator into one over the right-left pairs in ascending
+    /// order by right value.
+    fn inverse(self) -> Iter<'a, R, L> {
+        // the vectors are only read, so a fresh iterator over them is equivalent
+        Iter {
+            inner: self.lefts.iter(),
+            lefts: self.inner.as_slice(),
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<'a, L, R> Clone for Iter<'a, L, R> {
+    fn clone(&self) -> Self {
+        Iter {
+            inner: self.inner.clone(),
+            lefts: self.lefts,
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<'a, L: Idx, R: Idx> DoubleEndedIterator for Iter<'a, L, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let right = self.inner.by_ref().rev().flatten().next()?;
+        Some(self.pair(right))
+    }
+}
+
+impl<'a, L: Idx, R: Idx> ExactSizeIterator for Iter<'a, L, R> {}
+
+impl<'a, L: Idx, R: Idx> FusedIterator for Iter<'a, L, R> {}
+
+impl<'a, L: Idx, R: Idx> Iterator for Iter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let right = self.inner.by_ref().flatten().next()?;
+        Some(self.pair(right))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// An iterator over the left values in a `BiVecMap`.
+///
+/// This struct is created by the [`left_values`] method of `BiVecMap`.
+///
+/// [`left_values`]: BiVecMap::left_values
+pub struct LeftValues<'a, L, R> {
+    inner: Iter<'a, L, R>,
+}
+
+impl<'a, L, R> Clone for LeftValues<'a, L, R> {
+    fn clone(&self) -> Self {
+        LeftValues {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, L: Idx, R: Idx> DoubleEndedIterator for LeftValues<'a, L, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(l, _)| l)
+    }
+}
+
+impl<'a, L: Idx, R: Idx> ExactSizeIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L: Idx, R: Idx> FusedIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L: Idx, R: Idx> Iterator for LeftValues<'a, L, R> {
+    type Item = &'a L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(l, _)| l)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the right values in a `BiVecMap`.
+///
+/// This struct is created by the [`right_values`] method of `BiVecMap`.
+///
+/// [`right_values`]: BiVecMap::right_values
+pub struct RightValues<'a, L, R> {
+    inner: Iter<'a, R, L>,
+}
+
+impl<'a, L, R> Clone for RightValues<'a, L, R> {
+    fn clone(&self) -> Self {
+        RightValues {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, L: Idx, R: Idx> DoubleEndedIterator for RightValues<'a, L, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(r, _)| r)
+    }
+}
+
+impl<'a, L: Idx, R: Idx> ExactSizeIterator for RightValues<'a, L, R> {}
+
+impl<'a, L: Idx, R: Idx> FusedIterator for RightValues<'a, L, R> {}
+
+impl<'a, L: Idx, R: Idx> Iterator for RightValues<'a, L, R> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(r, _)| r)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that both vectors describe the same pairs and are trimmed.
+    fn assert_consistent<L: Idx, R: Idx>(bimap: &BiVecMap<L, R>) {
+        let mut len = 0;
+        for (index, right) in bimap.left2right.iter().enumerate() {
+            if let Some(right) = right {
+                let left = bimap.right2left[right.index()].as_ref().unwrap();
+                assert_eq!(left.index(), index);
+                len += 1;
+            }
+        }
+        assert_eq!(bimap.right2left.iter().flatten().count(), len);
+        assert_eq!(bimap.len(), len);
+        assert!(!matches!(bimap.left2right.last(), Some(None)));
+        assert!(!matches!(bimap.right2left.last(), Some(None)));
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut bimap = BiVecMap::<u32, u16>::new();
+        assert_eq!(bimap.insert(3, 0), Overwritten::Neither);
+        assert_eq!(bimap.insert(0, 7), Overwritten::Neither);
+        assert_eq!(bimap.insert(3, 1), Overwritten::Left(3, 0));
+        assert_eq!(bimap.insert(5, 7), Overwritten::Right(0, 7));
+        assert_eq!(bimap.insert(5, 7), Overwritten::Pair(5, 7));
+        assert_eq!(bimap.insert(3, 7), Overwritten::Both((3, 1), (5, 7)));
+        assert_consistent(&bimap);
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.left2right.len(), 4);
+        assert_eq!(bimap.right2left.len(), 8);
+
+        assert_eq!(bimap.insert_no_overwrite(9, 7), Err((9, 7)));
+        assert_eq!(bimap.insert_no_overwrite(1, 1), Ok(()));
+        assert_eq!(bimap.remove_by_right(&7), Some((3, 7)));
+        assert_eq!(bimap.remove_by_left(&3), None);
+        assert_consistent(&bimap);
+        assert_eq!(bimap.left2right.len(), 2);
+        assert_eq!(bimap.right2left.len(), 2);
+
+        assert_eq!(bimap.remove_by_left(&1), Some((1, 1)));
+        assert_consistent(&bimap);
+        assert!(bimap.is_empty());
+        assert!(bimap.left2right.is_empty());
+        assert!(bimap.right2left.is_empty());
+        assert_eq!(bimap.get_by_left(&100), None);
+        assert_eq!(bimap.remove_by_right(&100), None);
+    }
+
+    #[test]
+    fn iterators() {
+        let bimap: BiVecMap<usize, u8> = vec![(4, 1), (0, 3), (2, 0)].into_iter().collect();
+        assert_consistent(&bimap);
+
+        let mut iter = bimap.iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some((&0, &3)));
+        assert_eq!(iter.next_back(), Some((&4, &1)));
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.clone().next(), Some((&2, &0)));
+
+        assert_eq!(bimap.left_values().collect::<Vec<_>>(), [&0, &2, &4]);
+        assert_eq!(bimap.right_values().collect::<Vec<_>>(), [&0, &1, &3]);
+        assert_eq!(bimap.right_values().rev().len(), 3);
+
+        let mut into_iter = bimap.clone().into_iter();
+        assert_eq!(into_iter.next_back(), Some((4, 1)));
+        assert_eq!(into_iter.len(), 2);
+        assert_eq!(into_iter.collect::<Vec<_>>(), [(0, 3), (2, 0)]);
+        assert_eq!((&bimap).into_iter().count(), 3);
+    }
+
+    #[test]
+    fn clone_eq_debug() {
+        let mut bimap = BiVecMap::<u8, u8>::new();
+        assert_eq!(format!("{:?}", bimap), "{}");
+        bimap.insert(1, 2);
+        bimap.insert(0, 5);
+        assert_eq!(format!("{:?}", bimap), "{0 <> 5, 1 <> 2}");
+
+        let mut other = bimap.clone();
+        assert_eq!(other, bimap);
+        other.insert(200, 200);
+        assert_ne!(other, bimap);
+        // removing the large index shrinks the vectors again
+        other.remove_by_left(&200);
+        assert_eq!(other, bimap);
+    }
+}